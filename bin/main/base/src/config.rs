@@ -14,6 +14,7 @@
 
 //! Scheme for config.toml
 
+use primitives::Address;
 use serde::Deserialize;
 use std::path::PathBuf;
 
@@ -29,6 +30,8 @@ pub struct Config {
 #[derive(Deserialize, Debug)]
 pub struct TxPoolConfig {
 	pub pool_capacity: usize,
+	pub sender_capacity: Option<usize>,
+	pub journal: Option<bool>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -36,6 +39,9 @@ pub struct ApiConfig {
 	pub rpc_addr: String,
 	pub rpc_workers: usize,
 	pub rpc_maxconn: usize,
+	pub rpc_admin: Option<bool>,
+	pub rpc_admin_token: Option<String>,
+	pub rpc_checkpoint_dir: Option<PathBuf>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -43,6 +49,10 @@ pub struct DBConfig {
 	pub memory_budget: u64,
 	pub path: Option<PathBuf>,
 	pub partitions: Option<Vec<Partition>>,
+	pub backend: Option<String>,
+	pub pruning: Option<String>,
+	pub pruning_keep_blocks: Option<u64>,
+	pub index: Option<bool>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -55,6 +65,7 @@ pub struct Partition {
 pub struct ConsensusConfig {
 	pub poa: Option<PoaConfig>,
 	pub raft: Option<RaftConfig>,
+	pub instant: Option<InstantConfig>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -70,6 +81,12 @@ pub struct RaftConfig {
 	pub request_proposal_min_interval: Option<u64>,
 }
 
+#[derive(Deserialize, Debug)]
+pub struct InstantConfig {
+	pub secret_key_file: Option<PathBuf>,
+	pub authority: Option<Address>,
+}
+
 #[derive(Deserialize, Debug)]
 pub struct NetworkConfig {
 	pub max_in_peers: u32,
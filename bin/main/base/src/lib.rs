@@ -31,6 +31,7 @@ pub const AUTHOR: &str = "Wingchain";
 pub const CONFIG: &str = "config";
 pub const DATA: &str = "data";
 pub const DB: &str = "db";
+pub const CHECKPOINT: &str = "checkpoint";
 pub const SPEC_FILE: &str = "spec.toml";
 pub const CONFIG_FILE: &str = "config.toml";
 
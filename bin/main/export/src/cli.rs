@@ -0,0 +1,49 @@
+// Copyright 2019, 2020 Wingchain
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::path::PathBuf;
+
+use structopt::StructOpt;
+
+use base::SharedParams;
+
+#[derive(Clone, Debug, StructOpt)]
+pub struct ExportOpt {
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[structopt(
+		long = "from",
+		value_name = "NUMBER",
+		default_value = "1",
+		help = "The first block number to export"
+	)]
+	pub from: u64,
+
+	#[structopt(
+		long = "to",
+		value_name = "NUMBER",
+		help = "The last block number to export, the confirmed number if not specified"
+	)]
+	pub to: Option<u64>,
+
+	#[structopt(
+		long = "file",
+		value_name = "PATH",
+		parse(from_os_str),
+		help = "Path of the block file"
+	)]
+	pub file: PathBuf,
+}
@@ -14,8 +14,15 @@
 
 use structopt::StructOpt;
 
+use export::cli::ExportOpt;
+use import::cli::ImportOpt;
 use init::cli::InitOpt;
 use node::cli::NodeOpt;
+use restore::cli::RestoreOpt;
+use restore_checkpoint::cli::RestoreCheckpointOpt;
+use revert::cli::RevertOpt;
+use snapshot::cli::SnapshotOpt;
+use verify::cli::VerifyOpt;
 
 #[derive(Clone, Debug, StructOpt)]
 pub struct Opt {
@@ -31,4 +38,28 @@ pub enum Subcommand {
 
 	#[structopt(name = "node", about = "Run the wingchain node")]
 	Node(NodeOpt),
+
+	#[structopt(name = "export", about = "Export the blocks to a file")]
+	Export(ExportOpt),
+
+	#[structopt(name = "import", about = "Import the blocks from a file")]
+	Import(ImportOpt),
+
+	#[structopt(name = "snapshot", about = "Create a state snapshot of a block")]
+	Snapshot(SnapshotOpt),
+
+	#[structopt(name = "restore", about = "Initialize the chain from a state snapshot")]
+	Restore(RestoreOpt),
+
+	#[structopt(name = "revert", about = "Revert the chain to an earlier block")]
+	Revert(RevertOpt),
+
+	#[structopt(
+		name = "restore-checkpoint",
+		about = "Restore the db from a checkpoint of a running node"
+	)]
+	RestoreCheckpoint(RestoreCheckpointOpt),
+
+	#[structopt(name = "verify", about = "Verify the integrity of the chain")]
+	Verify(VerifyOpt),
 }
@@ -13,7 +13,7 @@
 // limitations under the License.
 
 //! Wingchain main CLI
-//! Subcommands: init, node
+//! Subcommands: init, node, export, import, snapshot, restore, revert, restore-checkpoint, verify
 
 use structopt::clap::{App, AppSettings};
 use structopt::StructOpt;
@@ -56,6 +56,34 @@ fn run_subcommand(subcommand: Subcommand) -> CommonResult<()> {
 			init_logger(&opt.shared_params.log)?;
 			node::run(opt)?;
 		}
+		Subcommand::Export(opt) => {
+			init_logger(&opt.shared_params.log)?;
+			export::run(opt)?;
+		}
+		Subcommand::Import(opt) => {
+			init_logger(&opt.shared_params.log)?;
+			import::run(opt)?;
+		}
+		Subcommand::Snapshot(opt) => {
+			init_logger(&opt.shared_params.log)?;
+			snapshot::run(opt)?;
+		}
+		Subcommand::Restore(opt) => {
+			init_logger(&opt.shared_params.log)?;
+			restore::run(opt)?;
+		}
+		Subcommand::Revert(opt) => {
+			init_logger(&opt.shared_params.log)?;
+			revert::run(opt)?;
+		}
+		Subcommand::RestoreCheckpoint(opt) => {
+			init_logger(&opt.shared_params.log)?;
+			restore_checkpoint::run(opt)?;
+		}
+		Subcommand::Verify(opt) => {
+			init_logger(&opt.shared_params.log)?;
+			verify::run(opt)?;
+		}
 	}
 	Ok(())
 }
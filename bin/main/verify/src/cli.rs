@@ -0,0 +1,45 @@
+// Copyright 2019, 2020 Wingchain
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use structopt::StructOpt;
+
+use base::SharedParams;
+
+#[derive(Clone, Debug, StructOpt)]
+pub struct VerifyOpt {
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[structopt(
+		long = "from",
+		value_name = "NUMBER",
+		default_value = "0",
+		help = "The first block number to verify"
+	)]
+	pub from: u64,
+
+	#[structopt(
+		long = "to",
+		value_name = "NUMBER",
+		help = "The last block number to verify, the confirmed number if not specified"
+	)]
+	pub to: Option<u64>,
+
+	#[structopt(
+		long = "execute",
+		help = "Execute the blocks again to verify the state roots, the state should not be pruned"
+	)]
+	pub execute: bool,
+}
@@ -120,6 +120,34 @@ impl<C: Context, U: Util> Module<C, U> {
 		Ok(())
 	}
 
+	/// Charge the fee from the payer and pay it to the block author
+	/// the fee will be burned if there is no author
+	pub fn charge_fee(
+		&self,
+		payer: &Address,
+		author: Option<&Address>,
+		fee: Balance,
+	) -> ModuleResult<()> {
+		let payer_balance = self.balance.get(payer)?.unwrap_or(0);
+		if payer_balance < fee {
+			return Err("Insufficient balance for fee".into());
+		}
+		if author == Some(payer) {
+			return Ok(());
+		}
+		self.balance.set(payer, &(payer_balance - fee))?;
+
+		if let Some(author) = author {
+			let author_balance = self.balance.get(author)?.unwrap_or(0);
+			let (author_balance, overflow) = author_balance.overflowing_add(fee);
+			if overflow {
+				return Err("U64 overflow".into());
+			}
+			self.balance.set(author, &author_balance)?;
+		}
+		Ok(())
+	}
+
 	pub fn validate_transfer(
 		&self,
 		_sender: Option<&Address>,
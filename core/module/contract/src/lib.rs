@@ -15,7 +15,6 @@
 #![allow(clippy::too_many_arguments)]
 
 use std::borrow::Cow;
-use std::collections::HashMap;
 use std::rc::Rc;
 use std::sync::Arc;
 
@@ -23,16 +22,21 @@ use serde::{Deserialize, Serialize};
 
 use executor_macro::{call, module};
 use executor_primitives::{
-	errors, errors::ApplicationError, Context, ContextEnv, Module as ModuleT, ModuleError,
-	ModuleResult, OpaqueModuleResult, StorageMap, StorageValue, Util,
+	errors, errors::ApplicationError, Context, ContextEnv, EmptyParams, Module as ModuleT,
+	ModuleError, ModuleResult, OpaqueModuleResult, StorageMap, StorageValue, Util,
+};
+use module_governance::{
+	aggregate_admin, CancelParams, GetProposalParams, Governance, Proposal, ProposalId, VoteParams,
 };
 use node_vm::errors::{ContractError, VMError};
 use node_vm::{LazyCodeProvider, Mode, VMCodeProvider, VMConfig, VMContext, VMContractEnv, VM};
 use primitives::codec::{Decode, Encode};
-use primitives::{codec, Address, Balance, Call, Event, Hash};
+use primitives::{codec, Address, Balance, BlockNumber, Call, Event, Hash, Params};
 
 use crate::vm::DefaultVMContext;
 
+pub use module_governance::Admin;
+
 mod vm;
 
 pub struct Module<C, U>
@@ -50,6 +54,7 @@ where
 	max_share_value_len: StorageValue<Option<u64>, Self>,
 	max_share_size: StorageValue<Option<u64>, Self>,
 	max_nest_depth: StorageValue<Option<u32>, Self>,
+	max_gas: StorageValue<Option<u64>, Self>,
 
 	/// contract address -> admin
 	admin: StorageMap<Address, Admin, Self>,
@@ -60,15 +65,8 @@ where
 	/// (contract address, version) -> code hash
 	code_hash: StorageMap<(Address, u32), Hash, Self>,
 
-	/// contract address -> update admin proposal id
-	update_admin_proposal_id: StorageMap<Address, u32, Self>,
-	/// contract address -> update admin proposal
-	update_admin_proposal: StorageMap<Address, UpdateAdminProposal, Self>,
-
-	/// contract address -> update code proposal id
-	update_code_proposal_id: StorageMap<Address, u32, Self>,
-	/// contract address -> update code proposal
-	update_code_proposal: StorageMap<Address, UpdateCodeProposal, Self>,
+	/// proposals of the contract admins
+	governance: Governance<Address, Self>,
 }
 
 #[module]
@@ -80,21 +78,19 @@ impl<C: Context, U: Util> Module<C, U> {
 		Self {
 			env: context.env(),
 			context: context.clone(),
-			util,
+			util: util.clone(),
 			max_stack_height: StorageValue::new(context.clone(), b"max_stack_height"),
 			initial_memory_pages: StorageValue::new(context.clone(), b"initial_memory_pages"),
 			max_memory_pages: StorageValue::new(context.clone(), b"max_memory_pages"),
 			max_share_value_len: StorageValue::new(context.clone(), b"max_share_value_len"),
 			max_share_size: StorageValue::new(context.clone(), b"max_share_size"),
 			max_nest_depth: StorageValue::new(context.clone(), b"max_nest_depth"),
+			max_gas: StorageValue::new(context.clone(), b"max_gas"),
 			admin: StorageMap::new(context.clone(), b"admin"),
 			version: StorageMap::new(context.clone(), b"version"),
 			code: StorageMap::new(context.clone(), b"code"),
 			code_hash: StorageMap::new(context.clone(), b"code_hash"),
-			update_admin_proposal_id: StorageMap::new(context.clone(), b"update_admin_proposal_id"),
-			update_admin_proposal: StorageMap::new(context.clone(), b"update_admin_proposal"),
-			update_code_proposal_id: StorageMap::new(context.clone(), b"update_code_proposal_id"),
-			update_code_proposal: StorageMap::new(context, b"update_code_proposal"),
+			governance: Governance::new(context, util).with_legacy_event(Self::legacy_event),
 		}
 	}
 
@@ -110,9 +106,14 @@ impl<C: Context, U: Util> Module<C, U> {
 		self.max_share_value_len.set(&params.max_share_value_len)?;
 		self.max_share_size.set(&params.max_share_size)?;
 		self.max_nest_depth.set(&params.max_nest_depth)?;
+		self.max_gas.set(&params.max_gas)?;
 		Ok(())
 	}
 
+	fn on_finalize(&self) -> ModuleResult<()> {
+		self.governance.remove_expired()
+	}
+
 	#[call]
 	fn get_version(
 		&self,
@@ -228,150 +229,178 @@ impl<C: Context, U: Util> Module<C, U> {
 		Ok(contract_address)
 	}
 
-	fn validate_update_admin(
+	#[call]
+	fn get_proposal(
 		&self,
 		_sender: Option<&Address>,
+		params: GetProposalParams,
+	) -> ModuleResult<Option<Proposal<Address>>> {
+		self.governance.get_proposal(params.proposal_id)
+	}
+
+	#[call]
+	fn get_proposals(
+		&self,
+		_sender: Option<&Address>,
+		_params: EmptyParams,
+	) -> ModuleResult<Vec<Proposal<Address>>> {
+		self.governance.get_proposals()
+	}
+
+	/// Propose a call of the module on behalf of the contract admin, executed once passed
+	#[call(write = true)]
+	fn propose(&self, sender: Option<&Address>, params: ProposeParams) -> ModuleResult<ProposalId> {
+		let sender = sender.ok_or(ApplicationError::Unsigned)?;
+		let admin = self.get_contract_admin(&params.contract_address)?;
+		self.governance.propose(
+			self,
+			sender,
+			params.contract_address,
+			params.call,
+			params.expire_block,
+			&admin,
+		)
+	}
+
+	#[call(write = true)]
+	fn vote(&self, sender: Option<&Address>, params: VoteParams) -> ModuleResult<()> {
+		let sender = sender.ok_or(ApplicationError::Unsigned)?;
+		self.governance
+			.vote(self, sender, params.proposal_id, |contract_address| {
+				self.get_contract_admin(contract_address)
+			})
+	}
+
+	#[call(write = true)]
+	fn cancel(&self, sender: Option<&Address>, params: CancelParams) -> ModuleResult<()> {
+		let sender = sender.ok_or(ApplicationError::Unsigned)?;
+		self.governance.cancel(self, sender, params.proposal_id)
+	}
+
+	fn validate_update_admin(
+		&self,
+		sender: Option<&Address>,
 		params: UpdateAdminParams,
 	) -> ModuleResult<()> {
-		for (address, _) in params.admin.members {
-			self.util.validate_address(&address)?;
-		}
-		Ok(())
+		self.validate_set_admin(sender, params)
 	}
 
+	/// Propose to update the admin of the contract
 	#[call(write = true)]
 	fn update_admin(
 		&self,
 		sender: Option<&Address>,
 		params: UpdateAdminParams,
-	) -> ModuleResult<()> {
-		let sender = sender.ok_or(ApplicationError::Unsigned)?;
-		let contract_address = params.contract_address;
-
-		let (old_threshold, old_members) = self.verify_sender(sender, &contract_address)?;
-
-		// create a proposal
-		let new_admin = aggregate_admin(params.admin);
-		let proposal_id = self
-			.update_admin_proposal_id
-			.get(&contract_address)?
-			.unwrap_or(1u32);
-		let mut proposal = UpdateAdminProposal {
-			proposal_id,
-			admin: new_admin,
-			vote: vec![],
+	) -> ModuleResult<ProposalId> {
+		let contract_address = params.contract_address.clone();
+		let call = Call {
+			module: "contract".to_string(),
+			method: "set_admin".to_string(),
+			params: Params(codec::encode(&params)?),
 		};
-		self.context.emit_event(Event::from_data(
-			"UpdateAdminProposalCreated".to_string(),
-			UpdateAdminProposalCreated {
-				contract_address: contract_address.clone(),
-				proposal: proposal.clone(),
+		self.propose(
+			sender,
+			ProposeParams {
+				contract_address,
+				call,
+				expire_block: None,
 			},
-		)?)?;
+		)
+	}
 
-		self.update_admin_vote_and_pass(
+	/// Propose to update the code of the contract
+	#[call(write = true)]
+	fn update_code(
+		&self,
+		sender: Option<&Address>,
+		params: UpdateCodeParams,
+	) -> ModuleResult<ProposalId> {
+		let contract_address = params.contract_address.clone();
+		let call = Call {
+			module: "contract".to_string(),
+			method: "set_code".to_string(),
+			params: Params(codec::encode(&params)?),
+		};
+		self.propose(
 			sender,
-			&contract_address,
-			&mut proposal,
-			old_threshold,
-			&old_members,
+			ProposeParams {
+				contract_address,
+				call,
+				expire_block: None,
+			},
 		)
 	}
 
+	/// Vote for a proposal to update the admin of the contract
 	#[call(write = true)]
 	fn update_admin_vote(
 		&self,
 		sender: Option<&Address>,
 		params: UpdateAdminVoteParams,
 	) -> ModuleResult<()> {
-		let sender = sender.ok_or(ApplicationError::Unsigned)?;
-		let contract_address = params.contract_address;
-
-		let proposal = self.update_admin_proposal.get(&contract_address)?;
-		let mut proposal = proposal.ok_or("Proposal not found")?;
-
-		if proposal.proposal_id != params.proposal_id {
-			return Err("Proposal id not match".into());
-		}
-
-		let (old_threshold, old_members) = self.verify_sender(sender, &contract_address)?;
-
-		self.update_admin_vote_and_pass(
+		self.vote_method(
 			sender,
-			&contract_address,
-			&mut proposal,
-			old_threshold,
-			&old_members,
+			&params.contract_address,
+			params.proposal_id,
+			"set_admin",
 		)
 	}
 
+	/// Vote for a proposal to update the code of the contract
 	#[call(write = true)]
-	fn update_code(&self, sender: Option<&Address>, params: UpdateCodeParams) -> ModuleResult<()> {
-		let sender = sender.ok_or(ApplicationError::Unsigned)?;
-		let contract_address = params.contract_address;
-
-		let (old_threshold, old_members) = self.verify_sender(sender, &contract_address)?;
-
-		let code = params.code;
-
-		// create a proposal
-		let proposal_id = self
-			.update_code_proposal_id
-			.get(&contract_address)?
-			.unwrap_or(1u32);
-		let code_hash = self.util.hash(&code)?;
-		let mut proposal = UpdateCodeProposal {
-			proposal_id,
-			code,
-			vote: vec![],
-		};
-
-		self.context.emit_event(Event::from_data(
-			"UpdateCodeProposalCreated".to_string(),
-			UpdateCodeProposalCreated {
-				contract_address: contract_address.clone(),
-				proposal: UpdateCodeProposalForEvent::from(&proposal, &code_hash),
-			},
-		)?)?;
-
-		self.update_code_vote_and_pass(
+	fn update_code_vote(
+		&self,
+		sender: Option<&Address>,
+		params: UpdateCodeVoteParams,
+	) -> ModuleResult<()> {
+		self.vote_method(
 			sender,
-			&contract_address,
-			&mut proposal,
-			&code_hash,
-			old_threshold,
-			&old_members,
+			&params.contract_address,
+			params.proposal_id,
+			"set_code",
 		)
 	}
 
-	#[call(write = true)]
-	fn update_code_vote(
+	fn validate_set_admin(
 		&self,
-		sender: Option<&Address>,
-		params: UpdateCodeVoteParams,
+		_sender: Option<&Address>,
+		params: UpdateAdminParams,
 	) -> ModuleResult<()> {
-		let sender = sender.ok_or(ApplicationError::Unsigned)?;
-		let contract_address = params.contract_address;
+		for (address, _) in params.admin.members {
+			self.util.validate_address(&address)?;
+		}
+		Ok(())
+	}
 
-		let proposal = self.update_code_proposal.get(&contract_address)?;
-		let mut proposal = proposal.ok_or("Proposal not found")?;
+	/// Set the admin of the contract, only allowed by a passed proposal of the contract
+	#[call(write = true)]
+	fn set_admin(&self, _sender: Option<&Address>, params: UpdateAdminParams) -> ModuleResult<()> {
+		let contract_address = params.contract_address;
+		self.governance.verify_executing(&contract_address)?;
 
-		if proposal.proposal_id != params.proposal_id {
-			return Err("Proposal id not match".into());
-		}
+		self.admin
+			.set(&contract_address, &aggregate_admin(params.admin))?;
+		Ok(())
+	}
 
-		let (old_threshold, old_members) = self.verify_sender(sender, &contract_address)?;
+	/// Set the code of the contract as a new version, only allowed by a passed proposal of the contract
+	#[call(write = true)]
+	fn set_code(&self, _sender: Option<&Address>, params: UpdateCodeParams) -> ModuleResult<()> {
+		let contract_address = params.contract_address;
+		self.governance.verify_executing(&contract_address)?;
 
-		let code_hash = self.util.hash(&proposal.code)?;
+		let version = self.version.get(&contract_address)?;
+		let version = version.ok_or("Contract version not found")?;
 
-		self.update_code_vote_and_pass(
-			sender,
-			&contract_address,
-			&mut proposal,
-			&code_hash,
-			old_threshold,
-			&old_members,
-		)
+		let code = params.code;
+		let code_hash = self.util.hash(&code)?;
+		let new_version = version + 1;
+		self.version.set(&contract_address, &new_version)?;
+		self.code
+			.set(&(contract_address.clone(), new_version), &code)?;
+		self.code_hash
+			.set(&(contract_address, new_version), &code_hash)?;
+		Ok(())
 	}
 
 	fn validate_execute(
@@ -444,132 +473,73 @@ impl<C: Context, U: Util> Module<C, U> {
 		)
 	}
 
-	fn verify_sender(
+	fn vote_method(
 		&self,
-		sender: &Address,
-		contract_address: &Address,
-	) -> ModuleResult<(u32, HashMap<Address, u32>)> {
-		let admin = self.admin.get(&contract_address)?;
-		let admin = admin.ok_or("Contract admin not found")?;
-
-		let threshold = admin.threshold;
-		let members = admin.members.into_iter().collect::<HashMap<_, _>>();
-		if !members.contains_key(sender) {
-			return Err("Not admin".into());
-		}
-
-		Ok((threshold, members))
-	}
-
-	fn update_admin_vote_and_pass(
-		&self,
-		sender: &Address,
+		sender: Option<&Address>,
 		contract_address: &Address,
-		proposal: &mut UpdateAdminProposal,
-		old_threshold: u32,
-		old_members: &HashMap<Address, u32>,
+		proposal_id: ProposalId,
+		method: &str,
 	) -> ModuleResult<()> {
-		// vote for the proposal
-		if !proposal.vote.contains(sender) {
-			proposal.vote.push(sender.clone());
-		}
-		self.context.emit_event(Event::from_data(
-			"UpdateAdminProposalVoted".to_string(),
-			UpdateAdminProposalVoted {
-				contract_address: contract_address.clone(),
-				proposal: proposal.clone(),
-			},
-		)?)?;
-
-		// pass a proposal
-		let sum = proposal
-			.vote
-			.iter()
-			.fold(0u32, |x, v| x + *old_members.get(v).unwrap_or(&0u32));
-		let mut pass = false;
-		if sum >= old_threshold {
-			self.admin.set(&contract_address, &proposal.admin)?;
-			pass = true;
-
-			self.context.emit_event(Event::from_data(
-				"UpdateAdminProposalPassed".to_string(),
-				UpdateAdminProposalPassed {
-					contract_address: contract_address.clone(),
-					proposal: proposal.clone(),
-				},
-			)?)?;
-		}
-
-		if pass {
-			self.update_admin_proposal.delete(&contract_address)?;
-		} else {
-			self.update_admin_proposal
-				.set(&contract_address, &proposal)?;
-		};
-		self.update_admin_proposal_id
-			.set(&contract_address, &(proposal.proposal_id + 1))?;
-
-		Ok(())
+		let sender = sender.ok_or(ApplicationError::Unsigned)?;
+		self.governance
+			.vote_method(self, sender, proposal_id, method, |subject| {
+				if subject != contract_address {
+					return Err("Contract address not match".into());
+				}
+				self.get_contract_admin(subject)
+			})
 	}
 
-	fn update_code_vote_and_pass(
+	/// Events of the former admin and code proposals of the contracts
+	fn legacy_event(
 		&self,
-		sender: &Address,
-		contract_address: &Address,
-		proposal: &mut UpdateCodeProposal,
-		code_hash: &Hash,
-		old_threshold: u32,
-		old_members: &HashMap<Address, u32>,
-	) -> ModuleResult<()> {
-		// vote for the proposal
-		if !proposal.vote.contains(sender) {
-			proposal.vote.push(sender.clone());
-		}
-		self.context.emit_event(Event::from_data(
-			"UpdateCodeProposalVoted".to_string(),
-			UpdateCodeProposalVoted {
-				contract_address: contract_address.clone(),
-				proposal: UpdateCodeProposalForEvent::from(proposal, code_hash),
-			},
-		)?)?;
-
-		// pass a proposal
-		let sum = proposal
-			.vote
-			.iter()
-			.fold(0u32, |x, v| x + *old_members.get(v).unwrap_or(&0u32));
-		let mut pass = false;
-		if sum >= old_threshold {
-			let version = self.version.get(&contract_address)?;
-			let version = version.ok_or("Contract version not found")?;
-
-			let new_version = version + 1;
-			self.version.set(&contract_address, &new_version)?;
-			self.code
-				.set(&(contract_address.clone(), new_version), &proposal.code)?;
-			self.code_hash
-				.set(&(contract_address.clone(), new_version), &code_hash)?;
-			pass = true;
-
-			self.context.emit_event(Event::from_data(
-				"UpdateCodeProposalPassed".to_string(),
-				UpdateCodeProposalPassed {
-					contract_address: contract_address.clone(),
-					proposal: UpdateCodeProposalForEvent::from(proposal, code_hash),
-				},
-			)?)?;
+		name: &str,
+		proposal: &Proposal<Address>,
+	) -> ModuleResult<Option<Event>> {
+		let call = &proposal.call;
+		if name == "ProposalCancelled" || call.module.as_bytes() != Self::STORAGE_KEY {
+			return Ok(None);
 		}
-
-		if pass {
-			self.update_code_proposal.delete(&contract_address)?;
-		} else {
-			self.update_code_proposal
-				.set(&contract_address, &proposal)?;
+		let proposal_id = proposal.proposal_id;
+		let vote = proposal.vote.clone();
+		let event = match call.method.as_str() {
+			"set_admin" => {
+				let params: UpdateAdminParams = codec::decode(&mut &call.params.0[..])?;
+				Event::from_data(
+					format!("UpdateAdmin{}", name),
+					ContractProposalEvent {
+						contract_address: params.contract_address,
+						proposal: UpdateAdminProposal {
+							proposal_id,
+							admin: aggregate_admin(params.admin),
+							vote,
+						},
+					},
+				)?
+			}
+			"set_code" => {
+				let params: UpdateCodeParams = codec::decode(&mut &call.params.0[..])?;
+				Event::from_data(
+					format!("UpdateCode{}", name),
+					ContractProposalEvent {
+						contract_address: params.contract_address,
+						proposal: UpdateCodeProposalForEvent {
+							proposal_id,
+							code_hash: self.util.hash(&params.code)?,
+							vote,
+						},
+					},
+				)?
+			}
+			_ => return Ok(None),
 		};
-		self.update_code_proposal_id
-			.set(&contract_address, &(proposal.proposal_id + 1))?;
+		Ok(Some(event))
+	}
 
-		Ok(())
+	fn get_contract_admin(&self, contract_address: &Address) -> ModuleResult<Admin> {
+		let admin = self.admin.get(contract_address)?;
+		let admin = admin.ok_or("Contract admin not found")?;
+		Ok(admin)
 	}
 
 	fn inner_get_version(
@@ -652,6 +622,11 @@ impl<C: Context, U: Util> Module<C, U> {
 				.get()?
 				.ok_or("Unexpected none")?
 				.unwrap_or(default_vm_config.max_nest_depth),
+			max_gas: self
+				.max_gas
+				.get()?
+				.ok_or("Unexpected none")?
+				.unwrap_or(default_vm_config.max_gas),
 		};
 		Ok(vm_config)
 	}
@@ -712,6 +687,10 @@ impl<C: Context, U: Util> Module<C, U> {
 			.execute(code, &vm_context, mode, &method, &params, pay_value)
 			.map_err(vm_to_module_error);
 
+		// gas is charged whether the execution succeeds or not
+		let gas_used = vm_context.gas_used_get().map_err(vm_to_module_error)?;
+		self.context.add_gas_used(gas_used)?;
+
 		match result {
 			Ok(result) => {
 				vm_context
@@ -737,27 +716,6 @@ impl<C: Context, U: Util> Module<C, U> {
 	}
 }
 
-fn aggregate_admin(admin: Admin) -> Admin {
-	let threshold = admin.threshold;
-	let members = admin.members;
-	let mut new_members = Vec::<(Address, u32)>::new();
-	for (address, weight) in members {
-		if weight > 0 {
-			match new_members.iter().position(|x| x.0 == address) {
-				Some(position) => {
-					let find = new_members.get_mut(position).unwrap();
-					find.1 += weight;
-				}
-				None => new_members.push((address, weight)),
-			}
-		}
-	}
-	Admin {
-		threshold,
-		members: new_members,
-	}
-}
-
 fn vm_to_module_error(e: VMError) -> ModuleError {
 	match e {
 		VMError::System(e) => ModuleError::System(e),
@@ -816,6 +774,7 @@ pub struct InitParams {
 	pub max_share_value_len: Option<u64>,
 	pub max_share_size: Option<u64>,
 	pub max_nest_depth: Option<u32>,
+	pub max_gas: Option<u64>,
 }
 
 #[derive(Encode, Decode, Debug, PartialEq)]
@@ -846,17 +805,14 @@ pub struct GetCodeHashParams {
 	pub version: Option<u32>,
 }
 
-#[derive(Encode, Decode, Debug, PartialEq, Clone, Serialize, Deserialize)]
-pub struct Admin {
-	pub threshold: u32,
-	pub members: Vec<(Address, u32)>,
-}
-
-#[derive(Encode, Decode, Debug, PartialEq, Clone, Serialize, Deserialize)]
-pub struct UpdateAdminProposal {
-	pub proposal_id: u32,
-	pub admin: Admin,
-	pub vote: Vec<Address>,
+#[derive(Encode, Decode, Debug, PartialEq)]
+pub struct ProposeParams {
+	/// contract address
+	pub contract_address: Address,
+	/// call of the contract module
+	pub call: Call,
+	/// the last block the proposal can be voted in, none for the default period
+	pub expire_block: Option<BlockNumber>,
 }
 
 #[derive(Encode, Decode, Debug, PartialEq)]
@@ -868,19 +824,19 @@ pub struct UpdateAdminParams {
 }
 
 #[derive(Encode, Decode, Debug, PartialEq)]
-pub struct UpdateAdminVoteParams {
+pub struct UpdateCodeParams {
 	/// contract address
 	pub contract_address: Address,
-	/// proposal id
-	pub proposal_id: u32,
+	/// wasm code
+	pub code: Vec<u8>,
 }
 
 #[derive(Encode, Decode, Debug, PartialEq)]
-pub struct UpdateCodeParams {
+pub struct UpdateAdminVoteParams {
 	/// contract address
 	pub contract_address: Address,
-	/// wasm code
-	pub code: Vec<u8>,
+	/// proposal id
+	pub proposal_id: ProposalId,
 }
 
 #[derive(Encode, Decode, Debug, PartialEq)]
@@ -888,94 +844,26 @@ pub struct UpdateCodeVoteParams {
 	/// contract address
 	pub contract_address: Address,
 	/// proposal id
-	pub proposal_id: u32,
+	pub proposal_id: ProposalId,
 }
 
-#[derive(Encode, Decode, Debug, PartialEq, Clone)]
-pub struct UpdateCodeProposal {
-	pub proposal_id: u32,
-	pub code: Vec<u8>,
+#[derive(Encode, Decode, Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct UpdateAdminProposal {
+	pub proposal_id: ProposalId,
+	pub admin: Admin,
 	pub vote: Vec<Address>,
 }
 
 #[derive(Encode, Decode, Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct UpdateCodeProposalForEvent {
-	pub proposal_id: u32,
+	pub proposal_id: ProposalId,
 	pub code_hash: Hash,
 	pub vote: Vec<Address>,
 }
 
-impl UpdateCodeProposalForEvent {
-	fn from(proposal: &UpdateCodeProposal, code_hash: &Hash) -> Self {
-		Self {
-			proposal_id: proposal.proposal_id,
-			code_hash: code_hash.clone(),
-			vote: proposal.vote.clone(),
-		}
-	}
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-pub struct UpdateAdminProposalCreated {
-	pub contract_address: Address,
-	pub proposal: UpdateAdminProposal,
-}
-
+/// Event of the former proposals of a contract
 #[derive(Serialize, Deserialize, Debug)]
-pub struct UpdateAdminProposalVoted {
+pub struct ContractProposalEvent<P> {
 	pub contract_address: Address,
-	pub proposal: UpdateAdminProposal,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-pub struct UpdateAdminProposalPassed {
-	pub contract_address: Address,
-	pub proposal: UpdateAdminProposal,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-pub struct UpdateCodeProposalCreated {
-	pub contract_address: Address,
-	pub proposal: UpdateCodeProposalForEvent,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-pub struct UpdateCodeProposalVoted {
-	pub contract_address: Address,
-	pub proposal: UpdateCodeProposalForEvent,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-pub struct UpdateCodeProposalPassed {
-	pub contract_address: Address,
-	pub proposal: UpdateCodeProposalForEvent,
-}
-
-#[cfg(test)]
-mod tests {
-	use super::*;
-
-	#[test]
-	fn test_aggregate_admin() {
-		let admin = Admin {
-			threshold: 2,
-			members: vec![
-				(Address(vec![1, 1, 1, 1]), 2),
-				(Address(vec![2, 2, 2, 2]), 3),
-				(Address(vec![1, 1, 1, 1]), 4),
-				(Address(vec![3, 3, 3, 3]), 0),
-			],
-		};
-		let admin = aggregate_admin(admin);
-		assert_eq!(
-			admin,
-			Admin {
-				threshold: 2,
-				members: vec![
-					(Address(vec![1, 1, 1, 1]), 6),
-					(Address(vec![2, 2, 2, 2]), 3)
-				],
-			}
-		)
-	}
+	pub proposal: P,
 }
@@ -13,7 +13,7 @@
 // limitations under the License.
 
 use std::borrow::Cow;
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::collections::{HashMap, VecDeque};
 use std::rc::Rc;
 use std::sync::Arc;
@@ -137,6 +137,19 @@ impl<EC: Context> Context for StackedExecutorContext<EC> {
 		}
 		Ok(())
 	}
+	fn meta_iter_prefix(&self, prefix: &[u8]) -> ModuleResult<Vec<(DBKey, DBValue)>> {
+		let items = self.executor_context.meta_iter_prefix(prefix)?;
+		let buffers = self
+			.meta_buffer_stack
+			.iter()
+			.map(|buffer| buffer.borrow())
+			.collect::<Vec<_>>();
+		Ok(executor_primitives::overlay_prefix(
+			items,
+			buffers.iter().map(|buffer| &**buffer),
+			prefix,
+		))
+	}
 	fn payload_get(&self, key: &[u8]) -> ModuleResult<Option<DBValue>> {
 		for buffer in self.payload_buffer_stack.iter().rev() {
 			if let Some(value) = buffer.borrow().get(&DBKey::from_slice(key)) {
@@ -179,6 +192,19 @@ impl<EC: Context> Context for StackedExecutorContext<EC> {
 		}
 		Ok(())
 	}
+	fn payload_iter_prefix(&self, prefix: &[u8]) -> ModuleResult<Vec<(DBKey, DBValue)>> {
+		let items = self.executor_context.payload_iter_prefix(prefix)?;
+		let buffers = self
+			.payload_buffer_stack
+			.iter()
+			.map(|buffer| buffer.borrow())
+			.collect::<Vec<_>>();
+		Ok(executor_primitives::overlay_prefix(
+			items,
+			buffers.iter().map(|buffer| &**buffer),
+			prefix,
+		))
+	}
 	fn emit_event(&self, event: Event) -> ModuleResult<()> {
 		let events = self
 			.events_stack
@@ -212,6 +238,12 @@ impl<EC: Context> Context for StackedExecutorContext<EC> {
 		}
 		Ok(())
 	}
+	fn add_gas_used(&self, gas_used: u64) -> ModuleResult<()> {
+		self.executor_context.add_gas_used(gas_used)
+	}
+	fn drain_tx_gas_used(&self) -> ModuleResult<u64> {
+		self.executor_context.drain_tx_gas_used()
+	}
 }
 
 pub struct DefaultVMContext<M: Module> {
@@ -219,6 +251,7 @@ pub struct DefaultVMContext<M: Module> {
 	env: Rc<VMContextEnv>,
 	call_env: Rc<VMCallEnv>,
 	contract_env: Rc<VMContractEnv>,
+	gas_used: Rc<Cell<u64>>,
 	executor_util: M::U,
 	base_context: StackedExecutorContext<M::C>,
 	module_context: StackedExecutorContext<M::C>,
@@ -236,11 +269,19 @@ impl<M: Module> DefaultVMContext<M> {
 		executor_util: M::U,
 	) -> Self {
 		let base_context = StackedExecutorContext::new(executor_context);
-		Self::new_with_base_context(Rc::new(config), contract_env, base_context, executor_util)
+		let gas_used = Rc::new(Cell::new(0));
+		Self::new_with_base_context(
+			Rc::new(config),
+			contract_env,
+			gas_used,
+			base_context,
+			executor_util,
+		)
 	}
 	fn new_with_base_context(
 		config: Rc<VMConfig>,
 		contract_env: Rc<VMContractEnv>,
+		gas_used: Rc<Cell<u64>>,
 		base_context: StackedExecutorContext<M::C>,
 		executor_util: M::U,
 	) -> Self {
@@ -269,6 +310,7 @@ impl<M: Module> DefaultVMContext<M> {
 			env,
 			call_env,
 			contract_env,
+			gas_used,
 			executor_util,
 			base_context,
 			module_context,
@@ -360,6 +402,13 @@ impl<M: Module> VMContext for DefaultVMContext<M> {
 	fn contract_env(&self) -> Rc<VMContractEnv> {
 		self.contract_env.clone()
 	}
+	fn gas_used_get(&self) -> VMResult<u64> {
+		Ok(self.gas_used.get())
+	}
+	fn gas_used_set(&self, gas_used: u64) -> VMResult<()> {
+		self.gas_used.set(gas_used);
+		Ok(())
+	}
 	fn payload_get(&self, key: &[u8]) -> VMResult<Option<DBValue>> {
 		let key = &self.vm_to_module_key(key)?;
 		let result = self
@@ -501,6 +550,7 @@ impl<M: Module> VMContext for DefaultVMContext<M> {
 				inner: Rc::new(DefaultVMContext::new_with_base_context(
 					self.config.clone(),
 					contract_env,
+					self.gas_used.clone(),
 					base_context,
 					self.executor_util.clone(),
 				)),
@@ -594,6 +644,12 @@ impl<M: Module> VMContext for ClonableDefaultVMContext<M> {
 	fn contract_env(&self) -> Rc<VMContractEnv> {
 		self.inner.contract_env()
 	}
+	fn gas_used_get(&self) -> VMResult<u64> {
+		self.inner.gas_used_get()
+	}
+	fn gas_used_set(&self, gas_used: u64) -> VMResult<()> {
+		self.inner.gas_used_set(gas_used)
+	}
 	fn payload_get(&self, key: &[u8]) -> VMResult<Option<DBValue>> {
 		self.inner.payload_get(key)
 	}
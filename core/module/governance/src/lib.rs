@@ -0,0 +1,461 @@
+// Copyright 2019, 2020 Wingchain
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Governance for the modules managed by an admin
+//!
+//! A module embeds `Governance` to let the admin members propose calls,
+//! a proposed call is executed once the sum of the vote weights reaches the admin threshold.
+//! The proposals are saved under the storage key of the module,
+//! so that they live in the same state (meta or payload) as the module.
+//! A passed call is dispatched on behalf of the proposer rather than the deciding voter,
+//! the privileged calls of another module are still guarded by its own governance.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use executor_primitives::{
+	Context, ContextEnv, Module, ModuleError, ModuleResult, StorageMap, StorageValue, Util,
+};
+use primitives::codec::{Decode, Encode};
+use primitives::{Address, BlockNumber, Call, Event};
+use serde::{Deserialize, Serialize};
+
+pub type ProposalId = u32;
+
+/// Number of blocks a proposal is open for voting if the expire block is not specified
+pub const DEFAULT_PROPOSAL_PERIOD: BlockNumber = 100_000;
+
+/// Build the event of a proposal named after its method for the clients of the former per-method proposals,
+/// e.g. `UpdateAdminProposalCreated` for `ProposalCreated` of a `set_admin` proposal,
+/// none if the proposal has no such event
+pub type LegacyEvent<K, M> = fn(&M, &str, &Proposal<K>) -> ModuleResult<Option<Event>>;
+
+/// K: the subject managed by an admin, e.g. `()` for a module, the contract address for a contract
+/// M: the module embedding the governance
+pub struct Governance<K, M>
+where
+	K: Encode + Decode + Clone + PartialEq + Serialize,
+	M: Module,
+{
+	env: Arc<ContextEnv>,
+	context: M::C,
+	util: M::U,
+	/// id of the next proposal
+	next_proposal_id: StorageValue<ProposalId, M>,
+	/// proposal id -> pending proposal
+	proposal: StorageMap<ProposalId, Proposal<K>, M>,
+	/// subject of the proposal being executed
+	executing: RefCell<Option<K>>,
+	legacy_event: Option<LegacyEvent<K, M>>,
+}
+
+impl<K, M> Governance<K, M>
+where
+	K: Encode + Decode + Clone + PartialEq + Serialize,
+	M: Module,
+{
+	pub fn new(context: M::C, util: M::U) -> Self {
+		Self {
+			env: context.env(),
+			context: context.clone(),
+			util,
+			next_proposal_id: StorageValue::new(context.clone(), b"next_proposal_id"),
+			proposal: StorageMap::new(context, b"proposal"),
+			executing: RefCell::new(None),
+			legacy_event: None,
+		}
+	}
+
+	/// Emit the legacy events along with the proposal events
+	pub fn with_legacy_event(mut self, legacy_event: LegacyEvent<K, M>) -> Self {
+		self.legacy_event = Some(legacy_event);
+		self
+	}
+
+	/// Get a pending proposal
+	pub fn get_proposal(&self, proposal_id: ProposalId) -> ModuleResult<Option<Proposal<K>>> {
+		self.proposal.get(&proposal_id)
+	}
+
+	/// Get all the pending proposals in the order of proposal id
+	pub fn get_proposals(&self) -> ModuleResult<Vec<Proposal<K>>> {
+		let mut proposals = self
+			.proposal
+			.iter()?
+			.into_iter()
+			.map(|(_, proposal)| proposal)
+			.collect::<Vec<_>>();
+		proposals.sort_by_key(|proposal| proposal.proposal_id);
+		Ok(proposals)
+	}
+
+	/// Propose a call on behalf of the admin of the subject
+	/// the call should be a write call of the module, or of another module in the same state
+	/// the proposer votes for the proposal at once
+	pub fn propose(
+		&self,
+		module: &M,
+		sender: &Address,
+		subject: K,
+		call: Call,
+		expire_block: Option<BlockNumber>,
+		admin: &Admin,
+	) -> ModuleResult<ProposalId> {
+		verify_member(admin, sender)?;
+
+		if call.module.as_bytes() == M::STORAGE_KEY {
+			M::check_call(&call).map_err(to_application_error)?;
+			if !M::is_write_call(&call).map_err(to_application_error)? {
+				return Err("Not write call".into());
+			}
+			module.validate_call(Some(sender), &call)?;
+		} else {
+			// the meta state and the payload state are executed separately
+			let meta = self
+				.util
+				.is_meta_call(&call)
+				.map_err(to_application_error)?;
+			if meta != M::META_MODULE {
+				return Err("Call module not in the same state".into());
+			}
+			if !self
+				.util
+				.is_write_call(&call)
+				.map_err(to_application_error)?
+			{
+				return Err("Not write call".into());
+			}
+			self.util
+				.validate_call(&self.context, Some(sender), &call)
+				.map_err(to_application_error)?;
+		}
+
+		let number = self.env.number;
+		let expire_block = match expire_block {
+			Some(expire_block) if expire_block < number => {
+				return Err("Invalid expire block".into());
+			}
+			Some(expire_block) => expire_block,
+			None => number.saturating_add(DEFAULT_PROPOSAL_PERIOD),
+		};
+
+		let proposal_id = self.next_proposal_id.get()?.unwrap_or(1u32);
+		let next_proposal_id = proposal_id.checked_add(1).ok_or("Proposal id overflow")?;
+		self.next_proposal_id.set(&next_proposal_id)?;
+
+		let mut proposal = Proposal {
+			proposal_id,
+			subject,
+			call,
+			proposer: sender.clone(),
+			vote: vec![],
+			expire_block,
+		};
+		self.emit_event(module, "ProposalCreated", &proposal)?;
+
+		self.vote_and_pass(module, sender, &mut proposal, admin)?;
+
+		Ok(proposal_id)
+	}
+
+	/// Vote for a pending proposal
+	/// the weight is counted by the current admin of the subject
+	pub fn vote<F>(
+		&self,
+		module: &M,
+		sender: &Address,
+		proposal_id: ProposalId,
+		get_admin: F,
+	) -> ModuleResult<()>
+	where
+		F: Fn(&K) -> ModuleResult<Admin>,
+	{
+		let mut proposal = self.get_open_proposal(proposal_id)?;
+
+		let admin = get_admin(&proposal.subject)?;
+		verify_member(&admin, sender)?;
+
+		self.vote_and_pass(module, sender, &mut proposal, &admin)
+	}
+
+	/// Vote for a pending proposal of a certain method of the module
+	/// for the vote calls of the former per-method proposals, e.g. `update_admin_vote`
+	pub fn vote_method<F>(
+		&self,
+		module: &M,
+		sender: &Address,
+		proposal_id: ProposalId,
+		method: &str,
+		get_admin: F,
+	) -> ModuleResult<()>
+	where
+		F: Fn(&K) -> ModuleResult<Admin>,
+	{
+		let proposal = self.get_open_proposal(proposal_id)?;
+
+		let call = &proposal.call;
+		if call.module.as_bytes() != M::STORAGE_KEY || call.method != method {
+			return Err("Proposal method not match".into());
+		}
+
+		self.vote(module, sender, proposal_id, get_admin)
+	}
+
+	/// Cancel a pending proposal, only the proposer can cancel it
+	pub fn cancel(
+		&self,
+		module: &M,
+		sender: &Address,
+		proposal_id: ProposalId,
+	) -> ModuleResult<()> {
+		let proposal = self.get_open_proposal(proposal_id)?;
+
+		if &proposal.proposer != sender {
+			return Err("Not proposer".into());
+		}
+
+		self.proposal.delete(&proposal_id)?;
+		self.emit_event(module, "ProposalCancelled", &proposal)?;
+
+		Ok(())
+	}
+
+	/// Remove the proposals expiring in the current block
+	/// should be called in the on_finalize hook of the module
+	pub fn remove_expired(&self) -> ModuleResult<()> {
+		let number = self.env.number;
+		for (proposal_id, proposal) in self.proposal.iter()? {
+			if proposal.expire_block <= number {
+				self.proposal.delete(&proposal_id)?;
+			}
+		}
+		Ok(())
+	}
+
+	/// Verify that the current call is executed by a passed proposal of the subject
+	/// privileged calls of the module should be guarded by this
+	pub fn verify_executing(&self, subject: &K) -> ModuleResult<()> {
+		match &*self.executing.borrow() {
+			Some(executing) if executing == subject => Ok(()),
+			_ => Err("Not executed by governance".into()),
+		}
+	}
+
+	fn get_open_proposal(&self, proposal_id: ProposalId) -> ModuleResult<Proposal<K>> {
+		let proposal = self.proposal.get(&proposal_id)?;
+		let proposal = proposal.ok_or("Proposal not found")?;
+
+		if proposal.expire_block < self.env.number {
+			return Err("Proposal expired".into());
+		}
+
+		Ok(proposal)
+	}
+
+	fn vote_and_pass(
+		&self,
+		module: &M,
+		sender: &Address,
+		proposal: &mut Proposal<K>,
+		admin: &Admin,
+	) -> ModuleResult<()> {
+		// vote for the proposal
+		if !proposal.vote.contains(sender) {
+			proposal.vote.push(sender.clone());
+		}
+		self.emit_event(module, "ProposalVoted", proposal)?;
+
+		// pass the proposal
+		let members = admin.members.iter().cloned().collect::<HashMap<_, _>>();
+		let sum = proposal.vote.iter().fold(0u32, |x, v| {
+			x.saturating_add(*members.get(v).unwrap_or(&0u32))
+		});
+		if sum < admin.threshold {
+			self.proposal.set(&proposal.proposal_id, proposal)?;
+			return Ok(());
+		}
+
+		self.proposal.delete(&proposal.proposal_id)?;
+		self.emit_event(module, "ProposalPassed", proposal)?;
+
+		// execute the call on behalf of the proposer, a failed call fails the passing vote as well
+		let call = &proposal.call;
+		let proposer = &proposal.proposer;
+		if call.module.as_bytes() == M::STORAGE_KEY {
+			*self.executing.borrow_mut() = Some(proposal.subject.clone());
+			let result = module
+				.validate_call(Some(proposer), call)
+				.and_then(|_| module.execute_call(Some(proposer), call));
+			*self.executing.borrow_mut() = None;
+			result?;
+		} else {
+			self.util
+				.validate_call(&self.context, Some(proposer), call)
+				.and_then(|_| self.util.execute_call(&self.context, Some(proposer), call))?;
+		}
+
+		Ok(())
+	}
+
+	fn emit_event(&self, module: &M, name: &str, proposal: &Proposal<K>) -> ModuleResult<()> {
+		self.context.emit_event(Event::from_data(
+			name.to_string(),
+			ProposalEvent {
+				proposal: ProposalForEvent::from(proposal),
+			},
+		)?)?;
+		if let Some(legacy_event) = self.legacy_event {
+			if let Some(event) = legacy_event(module, name, proposal)? {
+				self.context.emit_event(event)?;
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Merge the duplicated members and remove the members without weight
+pub fn aggregate_admin(admin: Admin) -> Admin {
+	let threshold = admin.threshold;
+	let members = admin.members;
+	let mut new_members = Vec::<(Address, u32)>::new();
+	for (address, weight) in members {
+		if weight > 0 {
+			match new_members.iter().position(|x| x.0 == address) {
+				Some(position) => {
+					let find = new_members.get_mut(position).unwrap();
+					find.1 += weight;
+				}
+				None => new_members.push((address, weight)),
+			}
+		}
+	}
+	Admin {
+		threshold,
+		members: new_members,
+	}
+}
+
+fn verify_member(admin: &Admin, sender: &Address) -> ModuleResult<()> {
+	if !admin.members.iter().any(|(address, _)| address == sender) {
+		return Err("Not admin".into());
+	}
+	Ok(())
+}
+
+/// The proposed call is provided by the sender,
+/// so a malformed call is an application error rather than a system error
+fn to_application_error(e: ModuleError) -> ModuleError {
+	match e {
+		ModuleError::System(e) => format!("Invalid call: {}", e).into(),
+		e => e,
+	}
+}
+
+#[derive(Encode, Decode, Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct Admin {
+	pub threshold: u32,
+	pub members: Vec<(Address, u32)>,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq, Clone)]
+pub struct Proposal<K> {
+	pub proposal_id: ProposalId,
+	pub subject: K,
+	pub call: Call,
+	pub proposer: Address,
+	pub vote: Vec<Address>,
+	/// the last block the proposal can be voted in
+	pub expire_block: BlockNumber,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct ProposalForEvent<K> {
+	pub proposal_id: ProposalId,
+	pub subject: K,
+	pub module: String,
+	pub method: String,
+	pub proposer: Address,
+	pub vote: Vec<Address>,
+	pub expire_block: BlockNumber,
+}
+
+impl<K: Clone> ProposalForEvent<K> {
+	fn from(proposal: &Proposal<K>) -> Self {
+		Self {
+			proposal_id: proposal.proposal_id,
+			subject: proposal.subject.clone(),
+			module: proposal.call.module.clone(),
+			method: proposal.call.method.clone(),
+			proposer: proposal.proposer.clone(),
+			vote: proposal.vote.clone(),
+			expire_block: proposal.expire_block,
+		}
+	}
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ProposalEvent<K> {
+	pub proposal: ProposalForEvent<K>,
+}
+
+/// Event of the former per-method proposals, see `LegacyEvent`
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LegacyProposalEvent<P> {
+	pub proposal: P,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+pub struct VoteParams {
+	pub proposal_id: ProposalId,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+pub struct CancelParams {
+	pub proposal_id: ProposalId,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+pub struct GetProposalParams {
+	pub proposal_id: ProposalId,
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_aggregate_admin() {
+		let admin = Admin {
+			threshold: 2,
+			members: vec![
+				(Address(vec![1, 1, 1, 1]), 2),
+				(Address(vec![2, 2, 2, 2]), 3),
+				(Address(vec![1, 1, 1, 1]), 4),
+				(Address(vec![3, 3, 3, 3]), 0),
+			],
+		};
+		let admin = aggregate_admin(admin);
+		assert_eq!(
+			admin,
+			Admin {
+				threshold: 2,
+				members: vec![
+					(Address(vec![1, 1, 1, 1]), 6),
+					(Address(vec![2, 2, 2, 2]), 3)
+				],
+			}
+		)
+	}
+}
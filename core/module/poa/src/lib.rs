@@ -19,10 +19,15 @@ use executor_primitives::{
 	errors, errors::ApplicationError, Context, ContextEnv, EmptyParams, Module as ModuleT,
 	ModuleResult, OpaqueModuleResult, StorageValue, Util,
 };
+use module_governance::{
+	aggregate_admin, CancelParams, GetProposalParams, Governance, LegacyProposalEvent, Proposal,
+	ProposalId, VoteParams,
+};
 use primitives::codec::{Decode, Encode};
-use primitives::{codec, Address, Call, Event};
+use primitives::{codec, Address, BlockNumber, Call, Event, Params};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+
+pub use module_governance::Admin;
 
 pub struct Module<C, U>
 where
@@ -35,17 +40,8 @@ where
 	util: U,
 	block_interval: StorageValue<Option<u64>, Self>,
 	admin: StorageValue<Admin, Self>,
-	authority: StorageValue<Address, Self>,
-
-	/// update admin proposal id
-	update_admin_proposal_id: StorageValue<u32, Self>,
-	/// update admin proposal
-	update_admin_proposal: StorageValue<UpdateAdminProposal, Self>,
-
-	/// update authority proposal id
-	update_authority_proposal_id: StorageValue<u32, Self>,
-	/// update authority proposal
-	update_authority_proposal: StorageValue<UpdateAuthorityProposal, Self>,
+	authorities: StorageValue<Authorities, Self>,
+	governance: Governance<(), Self>,
 }
 
 #[module]
@@ -57,20 +53,11 @@ impl<C: Context, U: Util> Module<C, U> {
 		Self {
 			env: context.env(),
 			context: context.clone(),
-			util,
+			util: util.clone(),
 			block_interval: StorageValue::new(context.clone(), b"block_interval"),
 			admin: StorageValue::new(context.clone(), b"admin"),
-			authority: StorageValue::new(context.clone(), b"authority"),
-			update_admin_proposal_id: StorageValue::new(
-				context.clone(),
-				b"update_admin_proposal_id",
-			),
-			update_admin_proposal: StorageValue::new(context.clone(), b"update_admin_proposal"),
-			update_authority_proposal_id: StorageValue::new(
-				context.clone(),
-				b"update_authority_proposal_id",
-			),
-			update_authority_proposal: StorageValue::new(context, b"update_authority_proposal"),
+			authorities: StorageValue::new(context.clone(), b"authorities"),
+			governance: Governance::new(context, util).with_legacy_event(Self::legacy_event),
 		}
 	}
 
@@ -81,7 +68,7 @@ impl<C: Context, U: Util> Module<C, U> {
 		}
 		self.block_interval.set(&params.block_interval)?;
 		self.admin.set(&params.admin)?;
-		self.authority.set(&params.authority)?;
+		self.authorities.set(&params.authorities)?;
 		Ok(())
 	}
 
@@ -89,8 +76,11 @@ impl<C: Context, U: Util> Module<C, U> {
 		for (address, _) in &params.admin.members {
 			self.util.validate_address(address)?;
 		}
-		self.util.validate_address(&params.authority)?;
-		Ok(())
+		validate_authorities(&self.util, &params.authorities)
+	}
+
+	fn on_finalize(&self) -> ModuleResult<()> {
+		self.governance.remove_expired()
 	}
 
 	#[call]
@@ -102,15 +92,15 @@ impl<C: Context, U: Util> Module<C, U> {
 		Ok(meta)
 	}
 
+	/// Get the ordered authorities, which take turns to seal the blocks slot by slot
 	#[call]
-	fn get_authority(
+	fn get_authorities(
 		&self,
 		_sender: Option<&Address>,
 		_params: EmptyParams,
-	) -> ModuleResult<Address> {
-		let authority = self.authority.get()?;
-		let authority = authority.ok_or("Unexpected none")?;
-		Ok(authority)
+	) -> ModuleResult<Authorities> {
+		let authorities = self.authorities.get()?.ok_or("Unexpected none")?;
+		Ok(authorities)
 	}
 
 	#[call]
@@ -120,244 +110,232 @@ impl<C: Context, U: Util> Module<C, U> {
 		Ok(admin)
 	}
 
+	#[call]
+	fn get_proposal(
+		&self,
+		_sender: Option<&Address>,
+		params: GetProposalParams,
+	) -> ModuleResult<Option<Proposal<()>>> {
+		self.governance.get_proposal(params.proposal_id)
+	}
+
+	#[call]
+	fn get_proposals(
+		&self,
+		_sender: Option<&Address>,
+		_params: EmptyParams,
+	) -> ModuleResult<Vec<Proposal<()>>> {
+		self.governance.get_proposals()
+	}
+
+	/// Propose a call of the module, executed once passed
+	#[call(write = true)]
+	fn propose(&self, sender: Option<&Address>, params: ProposeParams) -> ModuleResult<ProposalId> {
+		let sender = sender.ok_or(ApplicationError::Unsigned)?;
+		let admin = self.admin.get()?.ok_or("Admin not found")?;
+		self.governance
+			.propose(self, sender, (), params.call, params.expire_block, &admin)
+	}
+
+	#[call(write = true)]
+	fn vote(&self, sender: Option<&Address>, params: VoteParams) -> ModuleResult<()> {
+		let sender = sender.ok_or(ApplicationError::Unsigned)?;
+		self.governance.vote(self, sender, params.proposal_id, |_| {
+			let admin = self.admin.get()?;
+			Ok(admin.ok_or("Admin not found")?)
+		})
+	}
+
+	#[call(write = true)]
+	fn cancel(&self, sender: Option<&Address>, params: CancelParams) -> ModuleResult<()> {
+		let sender = sender.ok_or(ApplicationError::Unsigned)?;
+		self.governance.cancel(self, sender, params.proposal_id)
+	}
+
+	/// Propose to update the admin
 	#[call(write = true)]
 	fn update_admin(
 		&self,
 		sender: Option<&Address>,
 		params: UpdateAdminParams,
-	) -> ModuleResult<()> {
-		let sender = sender.ok_or(ApplicationError::Unsigned)?;
-
-		let (old_threshold, old_members) = self.verify_sender(sender)?;
-
-		// create a proposal
-		let new_admin = aggregate_admin(params.admin);
-		let proposal_id = self.update_admin_proposal_id.get()?.unwrap_or(1u32);
-		let mut proposal = UpdateAdminProposal {
-			proposal_id,
-			admin: new_admin,
-			vote: vec![],
+	) -> ModuleResult<ProposalId> {
+		let call = Call {
+			module: "poa".to_string(),
+			method: "set_admin".to_string(),
+			params: Params(codec::encode(&params)?),
 		};
-		self.context.emit_event(Event::from_data(
-			"UpdateAdminProposalCreated".to_string(),
-			UpdateAdminProposalCreated {
-				proposal: proposal.clone(),
+		self.propose(
+			sender,
+			ProposeParams {
+				call,
+				expire_block: None,
 			},
-		)?)?;
-
-		self.update_admin_vote_and_pass(sender, &mut proposal, old_threshold, &old_members)
+		)
 	}
 
-	#[call(write = true)]
-	fn update_admin_vote(
+	fn validate_update_authorities(
 		&self,
-		sender: Option<&Address>,
-		params: UpdateAdminVoteParams,
+		_sender: Option<&Address>,
+		params: UpdateAuthoritiesParams,
 	) -> ModuleResult<()> {
-		let sender = sender.ok_or(ApplicationError::Unsigned)?;
-
-		let proposal = self.update_admin_proposal.get()?;
-		let mut proposal = proposal.ok_or("Proposal not found")?;
-
-		if proposal.proposal_id != params.proposal_id {
-			return Err("Proposal id not match".into());
-		}
-
-		let (old_threshold, old_members) = self.verify_sender(sender)?;
-
-		self.update_admin_vote_and_pass(sender, &mut proposal, old_threshold, &old_members)
+		validate_authorities(&self.util, &params.authorities)
 	}
 
+	/// Propose to update the authorities
 	#[call(write = true)]
-	fn update_authority(
+	fn update_authorities(
 		&self,
 		sender: Option<&Address>,
-		params: UpdateAuthorityParams,
-	) -> ModuleResult<()> {
-		let sender = sender.ok_or(ApplicationError::Unsigned)?;
-
-		let (old_threshold, old_members) = self.verify_sender(sender)?;
-
-		let authority = params.authority;
-
-		// create a proposal
-		let proposal_id = self.update_authority_proposal_id.get()?.unwrap_or(1u32);
-		let mut proposal = UpdateAuthorityProposal {
-			proposal_id,
-			authority,
-			vote: vec![],
+		params: UpdateAuthoritiesParams,
+	) -> ModuleResult<ProposalId> {
+		let call = Call {
+			module: "poa".to_string(),
+			method: "set_authorities".to_string(),
+			params: Params(codec::encode(&params)?),
 		};
-
-		self.context.emit_event(Event::from_data(
-			"UpdateAuthorityProposalCreated".to_string(),
-			UpdateAuthorityProposalCreated {
-				proposal: proposal.clone(),
+		self.propose(
+			sender,
+			ProposeParams {
+				call,
+				expire_block: None,
 			},
-		)?)?;
+		)
+	}
 
-		self.update_authority_vote_and_pass(sender, &mut proposal, old_threshold, &old_members)
+	/// Vote for a proposal to update the admin
+	#[call(write = true)]
+	fn update_admin_vote(
+		&self,
+		sender: Option<&Address>,
+		params: UpdateAdminVoteParams,
+	) -> ModuleResult<()> {
+		self.vote_method(sender, params.proposal_id, "set_admin")
 	}
 
+	/// Vote for a proposal to update the authorities
 	#[call(write = true)]
 	fn update_authority_vote(
 		&self,
 		sender: Option<&Address>,
 		params: UpdateAuthorityVoteParams,
 	) -> ModuleResult<()> {
-		let sender = sender.ok_or(ApplicationError::Unsigned)?;
-
-		let proposal = self.update_authority_proposal.get()?;
-		let mut proposal = proposal.ok_or("Proposal not found")?;
-
-		if proposal.proposal_id != params.proposal_id {
-			return Err("Proposal id not match".into());
-		}
-
-		let (old_threshold, old_members) = self.verify_sender(sender)?;
-
-		self.update_authority_vote_and_pass(sender, &mut proposal, old_threshold, &old_members)
+		self.vote_method(sender, params.proposal_id, "set_authorities")
 	}
 
-	fn verify_sender(&self, sender: &Address) -> ModuleResult<(u32, HashMap<Address, u32>)> {
-		let admin = self.admin.get()?;
-		let admin = admin.ok_or("Admin not found")?;
-
-		let threshold = admin.threshold;
-		let members = admin.members.into_iter().collect::<HashMap<_, _>>();
-		if !members.contains_key(sender) {
-			return Err("Not admin".into());
-		}
-
-		Ok((threshold, members))
+	/// Set the admin, only allowed by a passed proposal
+	#[call(write = true)]
+	fn set_admin(&self, _sender: Option<&Address>, params: UpdateAdminParams) -> ModuleResult<()> {
+		self.governance.verify_executing(&())?;
+		self.admin.set(&aggregate_admin(params.admin))?;
+		Ok(())
 	}
 
-	fn update_admin_vote_and_pass(
+	fn validate_set_authorities(
 		&self,
-		sender: &Address,
-		proposal: &mut UpdateAdminProposal,
-		old_threshold: u32,
-		old_members: &HashMap<Address, u32>,
+		_sender: Option<&Address>,
+		params: UpdateAuthoritiesParams,
 	) -> ModuleResult<()> {
-		// vote for the proposal
-		if !proposal.vote.contains(sender) {
-			proposal.vote.push(sender.clone());
-		}
-		self.context.emit_event(Event::from_data(
-			"UpdateAdminProposalVoted".to_string(),
-			UpdateAdminProposalVoted {
-				proposal: proposal.clone(),
-			},
-		)?)?;
-
-		// pass a proposal
-		let sum = proposal
-			.vote
-			.iter()
-			.fold(0u32, |x, v| x + *old_members.get(v).unwrap_or(&0u32));
-		let mut pass = false;
-		if sum >= old_threshold {
-			self.admin.set(&proposal.admin)?;
-			pass = true;
-
-			self.context.emit_event(Event::from_data(
-				"UpdateAdminProposalPassed".to_string(),
-				UpdateAdminProposalPassed {
-					proposal: proposal.clone(),
-				},
-			)?)?;
-		}
-
-		if pass {
-			self.update_admin_proposal.delete()?;
-		} else {
-			self.update_admin_proposal.set(&proposal)?;
-		};
-		self.update_admin_proposal_id
-			.set(&(proposal.proposal_id + 1))?;
+		validate_authorities(&self.util, &params.authorities)
+	}
 
+	/// Set the authorities, only allowed by a passed proposal
+	#[call(write = true)]
+	fn set_authorities(
+		&self,
+		_sender: Option<&Address>,
+		params: UpdateAuthoritiesParams,
+	) -> ModuleResult<()> {
+		self.governance.verify_executing(&())?;
+		self.authorities.set(&params.authorities)?;
 		Ok(())
 	}
 
-	fn update_authority_vote_and_pass(
+	fn vote_method(
 		&self,
-		sender: &Address,
-		proposal: &mut UpdateAuthorityProposal,
-		old_threshold: u32,
-		old_members: &HashMap<Address, u32>,
+		sender: Option<&Address>,
+		proposal_id: ProposalId,
+		method: &str,
 	) -> ModuleResult<()> {
-		// vote for the proposal
-		if !proposal.vote.contains(sender) {
-			proposal.vote.push(sender.clone());
-		}
-		self.context.emit_event(Event::from_data(
-			"UpdateAuthorityProposalVoted".to_string(),
-			UpdateAuthorityProposalVoted {
-				proposal: proposal.clone(),
-			},
-		)?)?;
-
-		// pass a proposal
-		let sum = proposal
-			.vote
-			.iter()
-			.fold(0u32, |x, v| x + *old_members.get(v).unwrap_or(&0u32));
-		let mut pass = false;
-		if sum >= old_threshold {
-			self.authority.set(&proposal.authority)?;
-			pass = true;
-
-			self.context.emit_event(Event::from_data(
-				"UpdateAuthorityProposalPassed".to_string(),
-				UpdateAuthorityProposalPassed {
-					proposal: proposal.clone(),
-				},
-			)?)?;
-		}
+		let sender = sender.ok_or(ApplicationError::Unsigned)?;
+		self.governance
+			.vote_method(self, sender, proposal_id, method, |_| {
+				let admin = self.admin.get()?;
+				Ok(admin.ok_or("Admin not found")?)
+			})
+	}
 
-		if pass {
-			self.update_authority_proposal.delete()?;
-		} else {
-			self.update_authority_proposal.set(&proposal)?;
+	/// Events of the former admin and authority proposals
+	/// the authority proposal only has a single authority
+	fn legacy_event(&self, name: &str, proposal: &Proposal<()>) -> ModuleResult<Option<Event>> {
+		let call = &proposal.call;
+		if name == "ProposalCancelled" || call.module.as_bytes() != Self::STORAGE_KEY {
+			return Ok(None);
+		}
+		let proposal_id = proposal.proposal_id;
+		let vote = proposal.vote.clone();
+		let event = match call.method.as_str() {
+			"set_admin" => {
+				let params: UpdateAdminParams = codec::decode(&mut &call.params.0[..])?;
+				Event::from_data(
+					format!("UpdateAdmin{}", name),
+					LegacyProposalEvent {
+						proposal: UpdateAdminProposal {
+							proposal_id,
+							admin: aggregate_admin(params.admin),
+							vote,
+						},
+					},
+				)?
+			}
+			"set_authorities" => {
+				let params: UpdateAuthoritiesParams = codec::decode(&mut &call.params.0[..])?;
+				let authority = match params.authorities.members.as_slice() {
+					[authority] => authority.clone(),
+					_ => return Ok(None),
+				};
+				Event::from_data(
+					format!("UpdateAuthority{}", name),
+					LegacyProposalEvent {
+						proposal: UpdateAuthorityProposal {
+							proposal_id,
+							authority,
+							vote,
+						},
+					},
+				)?
+			}
+			_ => return Ok(None),
 		};
-		self.update_authority_proposal_id
-			.set(&(proposal.proposal_id + 1))?;
-
-		Ok(())
+		Ok(Some(event))
 	}
 }
 
-fn aggregate_admin(admin: Admin) -> Admin {
-	let threshold = admin.threshold;
-	let members = admin.members;
-	let mut new_members = Vec::<(Address, u32)>::new();
-	for (address, weight) in members {
-		if weight > 0 {
-			match new_members.iter().position(|x| x.0 == address) {
-				Some(position) => {
-					let find = new_members.get_mut(position).unwrap();
-					find.1 += weight;
-				}
-				None => new_members.push((address, weight)),
-			}
-		}
+/// The authorities should be distinct valid addresses, and at least one
+fn validate_authorities<U: Util>(util: &U, authorities: &Authorities) -> ModuleResult<()> {
+	if authorities.members.is_empty() {
+		return Err("Empty authorities".into());
 	}
-	Admin {
-		threshold,
-		members: new_members,
+	for (i, address) in authorities.members.iter().enumerate() {
+		util.validate_address(address)?;
+		if authorities.members[..i].contains(address) {
+			return Err("Duplicated authority".into());
+		}
 	}
+	Ok(())
 }
 
+/// Ordered authorities, the authority of a slot is the one at `slot % members.len()`,
+/// and the next one is the backup if the slot is missed
 #[derive(Encode, Decode, Debug, PartialEq, Clone, Serialize, Deserialize)]
-pub struct Admin {
-	pub threshold: u32,
-	pub members: Vec<(Address, u32)>,
+pub struct Authorities {
+	pub members: Vec<Address>,
 }
 
 #[derive(Encode, Decode, Debug, PartialEq, Deserialize)]
 pub struct InitParams {
 	pub block_interval: Option<u64>,
 	pub admin: Admin,
-	pub authority: Address,
+	pub authorities: Authorities,
 }
 
 #[derive(Encode, Decode, Debug, PartialEq, Clone, Serialize)]
@@ -365,18 +343,11 @@ pub struct Meta {
 	pub block_interval: Option<u64>,
 }
 
-#[derive(Encode, Decode, Debug, PartialEq, Clone, Serialize, Deserialize)]
-pub struct UpdateAdminProposal {
-	pub proposal_id: u32,
-	pub admin: Admin,
-	pub vote: Vec<Address>,
-}
-
-#[derive(Encode, Decode, Debug, PartialEq, Clone, Serialize, Deserialize)]
-pub struct UpdateAuthorityProposal {
-	pub proposal_id: u32,
-	pub authority: Address,
-	pub vote: Vec<Address>,
+#[derive(Encode, Decode, Debug, PartialEq)]
+pub struct ProposeParams {
+	pub call: Call,
+	/// the last block the proposal can be voted in, none for the default period
+	pub expire_block: Option<BlockNumber>,
 }
 
 #[derive(Encode, Decode, Debug, PartialEq)]
@@ -385,46 +356,30 @@ pub struct UpdateAdminParams {
 }
 
 #[derive(Encode, Decode, Debug, PartialEq)]
-pub struct UpdateAdminVoteParams {
-	pub proposal_id: u32,
+pub struct UpdateAuthoritiesParams {
+	pub authorities: Authorities,
 }
 
 #[derive(Encode, Decode, Debug, PartialEq)]
-pub struct UpdateAuthorityParams {
-	pub authority: Address,
+pub struct UpdateAdminVoteParams {
+	pub proposal_id: ProposalId,
 }
 
 #[derive(Encode, Decode, Debug, PartialEq)]
 pub struct UpdateAuthorityVoteParams {
-	pub proposal_id: u32,
+	pub proposal_id: ProposalId,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct UpdateAdminProposalCreated {
-	pub proposal: UpdateAdminProposal,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-pub struct UpdateAdminProposalVoted {
-	pub proposal: UpdateAdminProposal,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-pub struct UpdateAdminProposalPassed {
-	pub proposal: UpdateAdminProposal,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-pub struct UpdateAuthorityProposalCreated {
-	pub proposal: UpdateAuthorityProposal,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-pub struct UpdateAuthorityProposalVoted {
-	pub proposal: UpdateAuthorityProposal,
+#[derive(Encode, Decode, Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct UpdateAdminProposal {
+	pub proposal_id: ProposalId,
+	pub admin: Admin,
+	pub vote: Vec<Address>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct UpdateAuthorityProposalPassed {
-	pub proposal: UpdateAuthorityProposal,
+#[derive(Encode, Decode, Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct UpdateAuthorityProposal {
+	pub proposal_id: ProposalId,
+	pub authority: Address,
+	pub vote: Vec<Address>,
 }
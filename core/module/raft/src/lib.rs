@@ -19,10 +19,15 @@ use executor_primitives::{
 	errors, errors::ApplicationError, Context, ContextEnv, EmptyParams, Module as ModuleT,
 	ModuleResult, OpaqueModuleResult, StorageValue, Util,
 };
+use module_governance::{
+	aggregate_admin, CancelParams, GetProposalParams, Governance, LegacyProposalEvent, Proposal,
+	ProposalId, VoteParams,
+};
 use primitives::codec::{Decode, Encode};
-use primitives::{codec, Address, Call, Event};
+use primitives::{codec, Address, BlockNumber, Call, Event, Params};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+
+pub use module_governance::Admin;
 
 pub struct Module<C, U>
 where
@@ -39,16 +44,7 @@ where
 	election_timeout_max: StorageValue<u64, Self>,
 	admin: StorageValue<Admin, Self>,
 	authorities: StorageValue<Authorities, Self>,
-
-	/// update admin proposal id
-	update_admin_proposal_id: StorageValue<u32, Self>,
-	/// update admin proposal
-	update_admin_proposal: StorageValue<UpdateAdminProposal, Self>,
-
-	/// update authorities proposal id
-	update_authorities_proposal_id: StorageValue<u32, Self>,
-	/// update authorities proposal
-	update_authorities_proposal: StorageValue<UpdateAuthoritiesProposal, Self>,
+	governance: Governance<(), Self>,
 }
 
 #[module]
@@ -60,23 +56,14 @@ impl<C: Context, U: Util> Module<C, U> {
 		Self {
 			env: context.env(),
 			context: context.clone(),
-			util,
+			util: util.clone(),
 			block_interval: StorageValue::new(context.clone(), b"block_interval"),
 			heartbeat_interval: StorageValue::new(context.clone(), b"heartbeat_interval"),
 			election_timeout_min: StorageValue::new(context.clone(), b"election_timeout_min"),
 			election_timeout_max: StorageValue::new(context.clone(), b"election_timeout_max"),
 			admin: StorageValue::new(context.clone(), b"admin"),
 			authorities: StorageValue::new(context.clone(), b"authorities"),
-			update_admin_proposal_id: StorageValue::new(
-				context.clone(),
-				b"update_admin_proposal_id",
-			),
-			update_admin_proposal: StorageValue::new(context.clone(), b"update_admin_proposal"),
-			update_authorities_proposal_id: StorageValue::new(
-				context.clone(),
-				b"update_authorities_proposal_id",
-			),
-			update_authorities_proposal: StorageValue::new(context, b"update_authorities_proposal"),
+			governance: Governance::new(context, util).with_legacy_event(Self::legacy_event),
 		}
 	}
 
@@ -106,6 +93,10 @@ impl<C: Context, U: Util> Module<C, U> {
 		Ok(())
 	}
 
+	fn on_finalize(&self) -> ModuleResult<()> {
+		self.governance.remove_expired()
+	}
+
 	#[call]
 	fn get_meta(&self, _sender: Option<&Address>, _params: EmptyParams) -> ModuleResult<Meta> {
 		let block_interval = self.block_interval.get()?.ok_or("Unexpected none")?;
@@ -139,239 +130,185 @@ impl<C: Context, U: Util> Module<C, U> {
 		Ok(admin)
 	}
 
-	#[call(write = true)]
-	fn update_admin(
+	#[call]
+	fn get_proposal(
 		&self,
-		sender: Option<&Address>,
-		params: UpdateAdminParams,
-	) -> ModuleResult<()> {
-		let sender = sender.ok_or(ApplicationError::Unsigned)?;
-
-		let (old_threshold, old_members) = self.verify_sender(sender)?;
-
-		// create a proposal
-		let new_admin = aggregate_admin(params.admin);
-		let proposal_id = self.update_admin_proposal_id.get()?.unwrap_or(1u32);
-		let mut proposal = UpdateAdminProposal {
-			proposal_id,
-			admin: new_admin,
-			vote: vec![],
-		};
-		self.context.emit_event(Event::from_data(
-			"UpdateAdminProposalCreated".to_string(),
-			UpdateAdminProposalCreated {
-				proposal: proposal.clone(),
-			},
-		)?)?;
+		_sender: Option<&Address>,
+		params: GetProposalParams,
+	) -> ModuleResult<Option<Proposal<()>>> {
+		self.governance.get_proposal(params.proposal_id)
+	}
 
-		self.update_admin_vote_and_pass(sender, &mut proposal, old_threshold, &old_members)
+	#[call]
+	fn get_proposals(
+		&self,
+		_sender: Option<&Address>,
+		_params: EmptyParams,
+	) -> ModuleResult<Vec<Proposal<()>>> {
+		self.governance.get_proposals()
 	}
 
+	/// Propose a call of the module, executed once passed
 	#[call(write = true)]
-	fn update_admin_vote(
-		&self,
-		sender: Option<&Address>,
-		params: UpdateAdminVoteParams,
-	) -> ModuleResult<()> {
+	fn propose(&self, sender: Option<&Address>, params: ProposeParams) -> ModuleResult<ProposalId> {
 		let sender = sender.ok_or(ApplicationError::Unsigned)?;
+		let admin = self.admin.get()?.ok_or("Admin not found")?;
+		self.governance
+			.propose(self, sender, (), params.call, params.expire_block, &admin)
+	}
 
-		let proposal = self.update_admin_proposal.get()?;
-		let mut proposal = proposal.ok_or("Proposal not found")?;
-
-		if proposal.proposal_id != params.proposal_id {
-			return Err("Proposal id not match".into());
-		}
+	#[call(write = true)]
+	fn vote(&self, sender: Option<&Address>, params: VoteParams) -> ModuleResult<()> {
+		let sender = sender.ok_or(ApplicationError::Unsigned)?;
+		self.governance.vote(self, sender, params.proposal_id, |_| {
+			let admin = self.admin.get()?;
+			Ok(admin.ok_or("Admin not found")?)
+		})
+	}
 
-		let (old_threshold, old_members) = self.verify_sender(sender)?;
+	#[call(write = true)]
+	fn cancel(&self, sender: Option<&Address>, params: CancelParams) -> ModuleResult<()> {
+		let sender = sender.ok_or(ApplicationError::Unsigned)?;
+		self.governance.cancel(self, sender, params.proposal_id)
+	}
 
-		self.update_admin_vote_and_pass(sender, &mut proposal, old_threshold, &old_members)
+	/// Propose to update the admin
+	#[call(write = true)]
+	fn update_admin(
+		&self,
+		sender: Option<&Address>,
+		params: UpdateAdminParams,
+	) -> ModuleResult<ProposalId> {
+		let call = Call {
+			module: "raft".to_string(),
+			method: "set_admin".to_string(),
+			params: Params(codec::encode(&params)?),
+		};
+		self.propose(
+			sender,
+			ProposeParams {
+				call,
+				expire_block: None,
+			},
+		)
 	}
 
+	/// Propose to update the authorities
 	#[call(write = true)]
 	fn update_authorities(
 		&self,
 		sender: Option<&Address>,
 		params: UpdateAuthoritiesParams,
-	) -> ModuleResult<()> {
-		let sender = sender.ok_or(ApplicationError::Unsigned)?;
-
-		let (old_threshold, old_members) = self.verify_sender(sender)?;
-
-		let authorities = params.authorities;
-
-		// create a proposal
-		let proposal_id = self.update_authorities_proposal_id.get()?.unwrap_or(1u32);
-		let mut proposal = UpdateAuthoritiesProposal {
-			proposal_id,
-			authorities,
-			vote: vec![],
+	) -> ModuleResult<ProposalId> {
+		let call = Call {
+			module: "raft".to_string(),
+			method: "set_authorities".to_string(),
+			params: Params(codec::encode(&params)?),
 		};
-
-		self.context.emit_event(Event::from_data(
-			"UpdateAuthoritiesProposalCreated".to_string(),
-			UpdateAuthoritiesProposalCreated {
-				proposal: proposal.clone(),
+		self.propose(
+			sender,
+			ProposeParams {
+				call,
+				expire_block: None,
 			},
-		)?)?;
+		)
+	}
 
-		self.update_authorities_vote_and_pass(sender, &mut proposal, old_threshold, &old_members)
+	/// Vote for a proposal to update the admin
+	#[call(write = true)]
+	fn update_admin_vote(
+		&self,
+		sender: Option<&Address>,
+		params: UpdateAdminVoteParams,
+	) -> ModuleResult<()> {
+		self.vote_method(sender, params.proposal_id, "set_admin")
 	}
 
+	/// Vote for a proposal to update the authorities
 	#[call(write = true)]
 	fn update_authorities_vote(
 		&self,
 		sender: Option<&Address>,
 		params: UpdateAuthoritiesVoteParams,
 	) -> ModuleResult<()> {
-		let sender = sender.ok_or(ApplicationError::Unsigned)?;
-
-		let proposal = self.update_authorities_proposal.get()?;
-		let mut proposal = proposal.ok_or("Proposal not found")?;
-
-		if proposal.proposal_id != params.proposal_id {
-			return Err("Proposal id not match".into());
-		}
-
-		let (old_threshold, old_members) = self.verify_sender(sender)?;
-
-		self.update_authorities_vote_and_pass(sender, &mut proposal, old_threshold, &old_members)
+		self.vote_method(sender, params.proposal_id, "set_authorities")
 	}
 
-	fn verify_sender(&self, sender: &Address) -> ModuleResult<(u32, HashMap<Address, u32>)> {
-		let admin = self.admin.get()?;
-		let admin = admin.ok_or("Admin not found")?;
-
-		let threshold = admin.threshold;
-		let members = admin.members.into_iter().collect::<HashMap<_, _>>();
-		if !members.contains_key(sender) {
-			return Err("Not admin".into());
-		}
-
-		Ok((threshold, members))
+	/// Set the admin, only allowed by a passed proposal
+	#[call(write = true)]
+	fn set_admin(&self, _sender: Option<&Address>, params: UpdateAdminParams) -> ModuleResult<()> {
+		self.governance.verify_executing(&())?;
+		self.admin.set(&aggregate_admin(params.admin))?;
+		Ok(())
 	}
 
-	fn update_admin_vote_and_pass(
+	/// Set the authorities, only allowed by a passed proposal
+	#[call(write = true)]
+	fn set_authorities(
 		&self,
-		sender: &Address,
-		proposal: &mut UpdateAdminProposal,
-		old_threshold: u32,
-		old_members: &HashMap<Address, u32>,
+		_sender: Option<&Address>,
+		params: UpdateAuthoritiesParams,
 	) -> ModuleResult<()> {
-		// vote for the proposal
-		if !proposal.vote.contains(sender) {
-			proposal.vote.push(sender.clone());
-		}
-		self.context.emit_event(Event::from_data(
-			"UpdateAdminProposalVoted".to_string(),
-			UpdateAdminProposalVoted {
-				proposal: proposal.clone(),
-			},
-		)?)?;
-
-		// pass a proposal
-		let sum = proposal
-			.vote
-			.iter()
-			.fold(0u32, |x, v| x + *old_members.get(v).unwrap_or(&0u32));
-		let mut pass = false;
-		if sum >= old_threshold {
-			self.admin.set(&proposal.admin)?;
-			pass = true;
-
-			self.context.emit_event(Event::from_data(
-				"UpdateAdminProposalPassed".to_string(),
-				UpdateAdminProposalPassed {
-					proposal: proposal.clone(),
-				},
-			)?)?;
-		}
-
-		if pass {
-			self.update_admin_proposal.delete()?;
-		} else {
-			self.update_admin_proposal.set(&proposal)?;
-		};
-		self.update_admin_proposal_id
-			.set(&(proposal.proposal_id + 1))?;
-
+		self.governance.verify_executing(&())?;
+		self.authorities.set(&params.authorities)?;
 		Ok(())
 	}
 
-	fn update_authorities_vote_and_pass(
+	fn vote_method(
 		&self,
-		sender: &Address,
-		proposal: &mut UpdateAuthoritiesProposal,
-		old_threshold: u32,
-		old_members: &HashMap<Address, u32>,
+		sender: Option<&Address>,
+		proposal_id: ProposalId,
+		method: &str,
 	) -> ModuleResult<()> {
-		// vote for the proposal
-		if !proposal.vote.contains(sender) {
-			proposal.vote.push(sender.clone());
-		}
-		self.context.emit_event(Event::from_data(
-			"UpdateAuthoritiesProposalVoted".to_string(),
-			UpdateAuthoritiesProposalVoted {
-				proposal: proposal.clone(),
-			},
-		)?)?;
-
-		// pass a proposal
-		let sum = proposal
-			.vote
-			.iter()
-			.fold(0u32, |x, v| x + *old_members.get(v).unwrap_or(&0u32));
-		let mut pass = false;
-		if sum >= old_threshold {
-			self.authorities.set(&proposal.authorities)?;
-			pass = true;
-
-			self.context.emit_event(Event::from_data(
-				"UpdateAuthoritiesProposalPassed".to_string(),
-				UpdateAuthoritiesProposalPassed {
-					proposal: proposal.clone(),
-				},
-			)?)?;
-		}
-
-		if pass {
-			self.update_authorities_proposal.delete()?;
-		} else {
-			self.update_authorities_proposal.set(&proposal)?;
-		};
-		self.update_authorities_proposal_id
-			.set(&(proposal.proposal_id + 1))?;
-
-		Ok(())
+		let sender = sender.ok_or(ApplicationError::Unsigned)?;
+		self.governance
+			.vote_method(self, sender, proposal_id, method, |_| {
+				let admin = self.admin.get()?;
+				Ok(admin.ok_or("Admin not found")?)
+			})
 	}
-}
 
-fn aggregate_admin(admin: Admin) -> Admin {
-	let threshold = admin.threshold;
-	let members = admin.members;
-	let mut new_members = Vec::<(Address, u32)>::new();
-	for (address, weight) in members {
-		if weight > 0 {
-			match new_members.iter().position(|x| x.0 == address) {
-				Some(position) => {
-					let find = new_members.get_mut(position).unwrap();
-					find.1 += weight;
-				}
-				None => new_members.push((address, weight)),
-			}
+	/// Events of the former admin and authorities proposals
+	fn legacy_event(&self, name: &str, proposal: &Proposal<()>) -> ModuleResult<Option<Event>> {
+		let call = &proposal.call;
+		if name == "ProposalCancelled" || call.module.as_bytes() != Self::STORAGE_KEY {
+			return Ok(None);
 		}
-	}
-	Admin {
-		threshold,
-		members: new_members,
+		let proposal_id = proposal.proposal_id;
+		let vote = proposal.vote.clone();
+		let event = match call.method.as_str() {
+			"set_admin" => {
+				let params: UpdateAdminParams = codec::decode(&mut &call.params.0[..])?;
+				Event::from_data(
+					format!("UpdateAdmin{}", name),
+					LegacyProposalEvent {
+						proposal: UpdateAdminProposal {
+							proposal_id,
+							admin: aggregate_admin(params.admin),
+							vote,
+						},
+					},
+				)?
+			}
+			"set_authorities" => {
+				let params: UpdateAuthoritiesParams = codec::decode(&mut &call.params.0[..])?;
+				Event::from_data(
+					format!("UpdateAuthorities{}", name),
+					LegacyProposalEvent {
+						proposal: UpdateAuthoritiesProposal {
+							proposal_id,
+							authorities: params.authorities,
+							vote,
+						},
+					},
+				)?
+			}
+			_ => return Ok(None),
+		};
+		Ok(Some(event))
 	}
 }
 
-#[derive(Encode, Decode, Debug, PartialEq, Clone, Serialize, Deserialize)]
-pub struct Admin {
-	pub threshold: u32,
-	pub members: Vec<(Address, u32)>,
-}
-
 #[derive(Encode, Decode, Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct Authorities {
 	pub members: Vec<Address>,
@@ -395,18 +332,11 @@ pub struct Meta {
 	pub election_timeout_max: u64,
 }
 
-#[derive(Encode, Decode, Debug, PartialEq, Clone, Serialize, Deserialize)]
-pub struct UpdateAdminProposal {
-	pub proposal_id: u32,
-	pub admin: Admin,
-	pub vote: Vec<Address>,
-}
-
-#[derive(Encode, Decode, Debug, PartialEq, Clone, Serialize, Deserialize)]
-pub struct UpdateAuthoritiesProposal {
-	pub proposal_id: u32,
-	pub authorities: Authorities,
-	pub vote: Vec<Address>,
+#[derive(Encode, Decode, Debug, PartialEq)]
+pub struct ProposeParams {
+	pub call: Call,
+	/// the last block the proposal can be voted in, none for the default period
+	pub expire_block: Option<BlockNumber>,
 }
 
 #[derive(Encode, Decode, Debug, PartialEq)]
@@ -414,47 +344,31 @@ pub struct UpdateAdminParams {
 	pub admin: Admin,
 }
 
-#[derive(Encode, Decode, Debug, PartialEq)]
-pub struct UpdateAdminVoteParams {
-	pub proposal_id: u32,
-}
-
 #[derive(Encode, Decode, Debug, PartialEq)]
 pub struct UpdateAuthoritiesParams {
 	pub authorities: Authorities,
 }
 
 #[derive(Encode, Decode, Debug, PartialEq)]
-pub struct UpdateAuthoritiesVoteParams {
-	pub proposal_id: u32,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-pub struct UpdateAdminProposalCreated {
-	pub proposal: UpdateAdminProposal,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-pub struct UpdateAdminProposalVoted {
-	pub proposal: UpdateAdminProposal,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-pub struct UpdateAdminProposalPassed {
-	pub proposal: UpdateAdminProposal,
+pub struct UpdateAdminVoteParams {
+	pub proposal_id: ProposalId,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct UpdateAuthoritiesProposalCreated {
-	pub proposal: UpdateAuthoritiesProposal,
+#[derive(Encode, Decode, Debug, PartialEq)]
+pub struct UpdateAuthoritiesVoteParams {
+	pub proposal_id: ProposalId,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct UpdateAuthoritiesProposalVoted {
-	pub proposal: UpdateAuthoritiesProposal,
+#[derive(Encode, Decode, Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct UpdateAdminProposal {
+	pub proposal_id: ProposalId,
+	pub admin: Admin,
+	pub vote: Vec<Address>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct UpdateAuthoritiesProposalPassed {
-	pub proposal: UpdateAuthoritiesProposal,
+#[derive(Encode, Decode, Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct UpdateAuthoritiesProposal {
+	pub proposal_id: ProposalId,
+	pub authorities: Authorities,
+	pub vote: Vec<Address>,
 }
@@ -16,13 +16,18 @@ use std::sync::Arc;
 
 use executor_macro::{call, module};
 use executor_primitives::{
-	errors, Context, ContextEnv, EmptyParams, Module as ModuleT, ModuleResult, OpaqueModuleResult,
-	StorageValue, Util,
+	errors, errors::ApplicationError, Context, ContextEnv, EmptyParams, Module as ModuleT,
+	ModuleResult, OpaqueModuleResult, StorageMap, StorageValue, Util,
+};
+use module_governance::{
+	aggregate_admin, CancelParams, GetProposalParams, Governance, Proposal, ProposalId, VoteParams,
 };
 use node_consensus_primitives::CONSENSUS_LIST;
 use primitives::codec::{Decode, Encode};
 use primitives::types::ExecutionGap;
-use primitives::{codec, Address, BlockNumber, Call};
+use primitives::{codec, Address, Balance, BlockNumber, Call, Nonce, Params};
+
+pub use module_governance::Admin;
 
 pub struct Module<C, U>
 where
@@ -30,13 +35,19 @@ where
 	U: Util,
 {
 	env: Arc<ContextEnv>,
-	#[allow(dead_code)]
 	util: U,
 	chain_id: StorageValue<String, Self>,
 	timestamp: StorageValue<u64, Self>,
 	max_until_gap: StorageValue<BlockNumber, Self>,
 	max_execution_gap: StorageValue<ExecutionGap, Self>,
+	max_block_txs: StorageValue<u32, Self>,
+	max_block_bytes: StorageValue<u64, Self>,
 	consensus: StorageValue<String, Self>,
+	fee: StorageValue<Option<Fee>, Self>,
+	nonce: StorageMap<Address, Nonce, Self>,
+	/// admin to update the meta, none if the meta is immutable
+	admin: StorageValue<Admin, Self>,
+	governance: Governance<(), Self>,
 }
 
 #[module]
@@ -44,15 +55,21 @@ impl<C: Context, U: Util> Module<C, U> {
 	const META_MODULE: bool = true;
 	const STORAGE_KEY: &'static [u8] = b"system";
 
-	fn new(context: C, util: U) -> Self {
+	pub fn new(context: C, util: U) -> Self {
 		Self {
 			env: context.env(),
-			util,
+			util: util.clone(),
 			chain_id: StorageValue::new(context.clone(), b"chain_id"),
 			timestamp: StorageValue::new(context.clone(), b"timestamp"),
 			max_until_gap: StorageValue::new(context.clone(), b"max_until_gap"),
 			max_execution_gap: StorageValue::new(context.clone(), b"max_execution_gap"),
-			consensus: StorageValue::new(context, b"consensus"),
+			max_block_txs: StorageValue::new(context.clone(), b"max_block_txs"),
+			max_block_bytes: StorageValue::new(context.clone(), b"max_block_bytes"),
+			consensus: StorageValue::new(context.clone(), b"consensus"),
+			fee: StorageValue::new(context.clone(), b"fee"),
+			nonce: StorageMap::new(context.clone(), b"nonce"),
+			admin: StorageValue::new(context.clone(), b"admin"),
+			governance: Governance::new(context, util),
 		}
 	}
 
@@ -65,7 +82,13 @@ impl<C: Context, U: Util> Module<C, U> {
 		self.timestamp.set(&params.timestamp)?;
 		self.max_until_gap.set(&params.max_until_gap)?;
 		self.max_execution_gap.set(&params.max_execution_gap)?;
+		self.max_block_txs.set(&params.max_block_txs)?;
+		self.max_block_bytes.set(&params.max_block_bytes)?;
 		self.consensus.set(&params.consensus)?;
+		self.fee.set(&params.fee)?;
+		if let Some(admin) = &params.admin {
+			self.admin.set(admin)?;
+		}
 		Ok(())
 	}
 
@@ -73,28 +96,260 @@ impl<C: Context, U: Util> Module<C, U> {
 		if !CONSENSUS_LIST.iter().any(|&x| x == params.consensus) {
 			return Err("Unknown consensus".into());
 		}
+		validate_gaps(params.max_until_gap, params.max_execution_gap)?;
+		validate_block_limits(params.max_block_txs, params.max_block_bytes)?;
+		if let Some(admin) = &params.admin {
+			for (address, _) in &admin.members {
+				self.util.validate_address(address)?;
+			}
+		}
 		Ok(())
 	}
 
+	fn on_finalize(&self) -> ModuleResult<()> {
+		self.governance.remove_expired()
+	}
+
 	#[call]
 	fn get_meta(&self, _sender: Option<&Address>, _params: EmptyParams) -> ModuleResult<Meta> {
 		let chain_id = self.chain_id.get()?.ok_or("Unexpected none")?;
 		let timestamp = self.timestamp.get()?.ok_or("Unexpected none")?;
 		let max_until_gap = self.max_until_gap.get()?.ok_or("Unexpected none")?;
 		let max_execution_gap = self.max_execution_gap.get()?.ok_or("Unexpected none")?;
+		let max_block_txs = self.max_block_txs.get()?.ok_or("Unexpected none")?;
+		let max_block_bytes = self.max_block_bytes.get()?.ok_or("Unexpected none")?;
 		let consensus = self.consensus.get()?.ok_or("Unexpected none")?;
+		let fee = self.fee.get()?.ok_or("Unexpected none")?;
 		let meta = Meta {
 			chain_id,
 			timestamp,
 			max_until_gap,
 			max_execution_gap,
+			max_block_txs,
+			max_block_bytes,
 			consensus,
+			fee,
 		};
 		Ok(meta)
 	}
+
+	#[call]
+	fn get_admin(
+		&self,
+		_sender: Option<&Address>,
+		_params: EmptyParams,
+	) -> ModuleResult<Option<Admin>> {
+		let admin = self.admin.get()?;
+		Ok(admin)
+	}
+
+	#[call]
+	fn get_proposal(
+		&self,
+		_sender: Option<&Address>,
+		params: GetProposalParams,
+	) -> ModuleResult<Option<Proposal<()>>> {
+		self.governance.get_proposal(params.proposal_id)
+	}
+
+	#[call]
+	fn get_proposals(
+		&self,
+		_sender: Option<&Address>,
+		_params: EmptyParams,
+	) -> ModuleResult<Vec<Proposal<()>>> {
+		self.governance.get_proposals()
+	}
+
+	/// Propose a call of the module, executed once passed
+	#[call(write = true)]
+	fn propose(&self, sender: Option<&Address>, params: ProposeParams) -> ModuleResult<ProposalId> {
+		let sender = sender.ok_or(ApplicationError::Unsigned)?;
+		let admin = self.admin.get()?.ok_or("Admin not found")?;
+		self.governance
+			.propose(self, sender, (), params.call, params.expire_block, &admin)
+	}
+
+	#[call(write = true)]
+	fn vote(&self, sender: Option<&Address>, params: VoteParams) -> ModuleResult<()> {
+		let sender = sender.ok_or(ApplicationError::Unsigned)?;
+		self.governance.vote(self, sender, params.proposal_id, |_| {
+			let admin = self.admin.get()?;
+			Ok(admin.ok_or("Admin not found")?)
+		})
+	}
+
+	#[call(write = true)]
+	fn cancel(&self, sender: Option<&Address>, params: CancelParams) -> ModuleResult<()> {
+		let sender = sender.ok_or(ApplicationError::Unsigned)?;
+		self.governance.cancel(self, sender, params.proposal_id)
+	}
+
+	/// Propose to update the admin
+	#[call(write = true)]
+	fn update_admin(
+		&self,
+		sender: Option<&Address>,
+		params: UpdateAdminParams,
+	) -> ModuleResult<ProposalId> {
+		let call = Call {
+			module: "system".to_string(),
+			method: "set_admin".to_string(),
+			params: Params(codec::encode(&params)?),
+		};
+		self.propose(
+			sender,
+			ProposeParams {
+				call,
+				expire_block: None,
+			},
+		)
+	}
+
+	/// Propose to update the meta
+	/// the new meta takes effect from the block after the proposal passes
+	#[call(write = true)]
+	fn update_meta(
+		&self,
+		sender: Option<&Address>,
+		params: UpdateMetaParams,
+	) -> ModuleResult<ProposalId> {
+		let call = Call {
+			module: "system".to_string(),
+			method: "set_meta".to_string(),
+			params: Params(codec::encode(&params)?),
+		};
+		self.propose(
+			sender,
+			ProposeParams {
+				call,
+				expire_block: None,
+			},
+		)
+	}
+
+	fn validate_set_admin(
+		&self,
+		_sender: Option<&Address>,
+		params: UpdateAdminParams,
+	) -> ModuleResult<()> {
+		for (address, _) in &params.admin.members {
+			self.util.validate_address(address)?;
+		}
+		Ok(())
+	}
+
+	/// Set the admin, only allowed by a passed proposal
+	#[call(write = true)]
+	fn set_admin(&self, _sender: Option<&Address>, params: UpdateAdminParams) -> ModuleResult<()> {
+		self.governance.verify_executing(&())?;
+		self.admin.set(&aggregate_admin(params.admin))?;
+		Ok(())
+	}
+
+	fn validate_set_meta(
+		&self,
+		_sender: Option<&Address>,
+		params: UpdateMetaParams,
+	) -> ModuleResult<()> {
+		let max_until_gap = match params.max_until_gap {
+			Some(max_until_gap) => max_until_gap,
+			None => self.max_until_gap.get()?.ok_or("Unexpected none")?,
+		};
+		let max_execution_gap = match params.max_execution_gap {
+			Some(max_execution_gap) => max_execution_gap,
+			None => self.max_execution_gap.get()?.ok_or("Unexpected none")?,
+		};
+		validate_gaps(max_until_gap, max_execution_gap)?;
+		let max_block_txs = match params.max_block_txs {
+			Some(max_block_txs) => max_block_txs,
+			None => self.max_block_txs.get()?.ok_or("Unexpected none")?,
+		};
+		let max_block_bytes = match params.max_block_bytes {
+			Some(max_block_bytes) => max_block_bytes,
+			None => self.max_block_bytes.get()?.ok_or("Unexpected none")?,
+		};
+		validate_block_limits(max_block_txs, max_block_bytes)
+	}
+
+	/// Set the meta, only allowed by a passed proposal
+	#[call(write = true)]
+	fn set_meta(&self, _sender: Option<&Address>, params: UpdateMetaParams) -> ModuleResult<()> {
+		self.governance.verify_executing(&())?;
+		if let Some(max_until_gap) = &params.max_until_gap {
+			self.max_until_gap.set(max_until_gap)?;
+		}
+		if let Some(max_execution_gap) = &params.max_execution_gap {
+			self.max_execution_gap.set(max_execution_gap)?;
+		}
+		if let Some(max_block_txs) = &params.max_block_txs {
+			self.max_block_txs.set(max_block_txs)?;
+		}
+		if let Some(max_block_bytes) = &params.max_block_bytes {
+			self.max_block_bytes.set(max_block_bytes)?;
+		}
+		Ok(())
+	}
+
+	/// Get the fee config, none if transactions are free
+	pub fn get_fee(&self) -> ModuleResult<Option<Fee>> {
+		let fee = self.fee.get()?;
+		Ok(fee.flatten())
+	}
+
+	/// Get the nonce expected by the next transaction of the account
+	#[call]
+	pub fn get_nonce(
+		&self,
+		_sender: Option<&Address>,
+		params: GetNonceParams,
+	) -> ModuleResult<Nonce> {
+		let nonce = self.nonce.get(&params.address)?.unwrap_or(0);
+		Ok(nonce)
+	}
+
+	/// Set the nonce expected by the next transaction of the account
+	pub fn set_nonce(&self, address: &Address, nonce: Nonce) -> ModuleResult<()> {
+		self.nonce.set(address, &nonce)
+	}
+}
+
+fn validate_gaps(max_until_gap: BlockNumber, max_execution_gap: ExecutionGap) -> ModuleResult<()> {
+	if max_until_gap == 0 {
+		return Err("Invalid max until gap".into());
+	}
+	if max_execution_gap <= 0 {
+		return Err("Invalid max execution gap".into());
+	}
+	Ok(())
+}
+
+fn validate_block_limits(max_block_txs: u32, max_block_bytes: u64) -> ModuleResult<()> {
+	if max_block_txs == 0 {
+		return Err("Invalid max block txs".into());
+	}
+	if max_block_bytes == 0 {
+		return Err("Invalid max block bytes".into());
+	}
+	Ok(())
 }
 
-pub type InitParams = Meta;
+#[derive(Encode, Decode, Debug, PartialEq)]
+pub struct InitParams {
+	pub chain_id: String,
+	pub timestamp: u64,
+	pub max_until_gap: BlockNumber,
+	pub max_execution_gap: ExecutionGap,
+	/// max count of the txs (meta and payload) in a block
+	pub max_block_txs: u32,
+	/// max sum of the encoded length of the txs in a block
+	pub max_block_bytes: u64,
+	pub consensus: String,
+	/// none means transactions are free
+	pub fee: Option<Fee>,
+	/// none means the meta can not be updated
+	pub admin: Option<Admin>,
+}
 
 #[derive(Encode, Decode, Debug, PartialEq)]
 pub struct Meta {
@@ -102,5 +357,46 @@ pub struct Meta {
 	pub timestamp: u64,
 	pub max_until_gap: BlockNumber,
 	pub max_execution_gap: ExecutionGap,
+	/// max count of the txs (meta and payload) in a block
+	pub max_block_txs: u32,
+	/// max sum of the encoded length of the txs in a block
+	pub max_block_bytes: u64,
 	pub consensus: String,
+	/// none means transactions are free
+	pub fee: Option<Fee>,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+pub struct ProposeParams {
+	pub call: Call,
+	/// the last block the proposal can be voted in, none for the default period
+	pub expire_block: Option<BlockNumber>,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq)]
+pub struct UpdateAdminParams {
+	pub admin: Admin,
+}
+
+/// The meta fields to update, none means unchanged
+#[derive(Encode, Decode, Debug, PartialEq)]
+pub struct UpdateMetaParams {
+	pub max_until_gap: Option<BlockNumber>,
+	pub max_execution_gap: Option<ExecutionGap>,
+	pub max_block_txs: Option<u32>,
+	pub max_block_bytes: Option<u64>,
+}
+
+#[derive(Encode, Decode)]
+pub struct GetNonceParams {
+	pub address: Address,
+}
+
+/// Fee charged for each signed payload transaction
+/// fee = base + per_byte * encoded tx length + per_gas * gas used
+#[derive(Encode, Decode, Debug, PartialEq, Clone)]
+pub struct Fee {
+	pub base: Balance,
+	pub per_byte: Balance,
+	pub per_gas: Balance,
 }
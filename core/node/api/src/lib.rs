@@ -15,6 +15,7 @@
 //! API
 //! including rpc, websocket etc.
 
+use std::path::PathBuf;
 use std::sync::Arc;
 
 use crate::support::ApiSupport;
@@ -32,6 +33,13 @@ pub struct ApiConfig {
 	pub rpc_workers: usize,
 	/// connection count per worker
 	pub rpc_maxconn: usize,
+	/// enable the admin methods, e.g. txpool_remove, chain_createCheckpoint
+	pub rpc_admin: bool,
+	/// token of the admin methods, sent as `Authorization: Bearer <token>`
+	/// the admin methods are only allowed from localhost if not set
+	pub rpc_admin_token: Option<String>,
+	/// base directory of the checkpoints, the path of chain_createCheckpoint is relative to it
+	pub rpc_checkpoint_dir: PathBuf,
 }
 
 pub struct Api<S>
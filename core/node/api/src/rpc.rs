@@ -16,7 +16,9 @@ use std::cmp;
 use std::sync::Arc;
 use std::thread;
 
-use jsonrpc_v2::{Data, Server};
+use actix_web::dev::RequestHead;
+use actix_web::http::header;
+use jsonrpc_v2::{Data, MapRouter, Server, ServerBuilder};
 
 use primitives::errors::CommonResult;
 
@@ -53,8 +55,84 @@ async fn start_rpc_app<S>(config: &ApiConfig, support: Arc<S>) -> CommonResult<(
 where
 	S: ApiSupport,
 {
-	let rpc = Server::new()
-		.with_data(Data::new(support))
+	let rpc =
+		with_public_methods::<S>(Server::new().with_data(Data::new(support.clone()))).finish();
+
+	// admin methods are not found unless enabled and authorized
+	let admin_rpc = match config.rpc_admin {
+		true => Some(
+			with_public_methods::<S>(Server::new().with_data(Data::new(support)))
+				.with_data(Data::new(config.clone()))
+				.with_method(
+					"chain_createCheckpoint",
+					method::chain_create_checkpoint::<S>,
+				)
+				.with_method("txpool_remove", method::txpool_remove::<S>)
+				.finish(),
+		),
+		false => None,
+	};
+	let admin_token = config.rpc_admin_token.clone();
+
+	let workers = match config.rpc_workers {
+		0 => num_cpus::get(),
+		other => other,
+	};
+
+	log::info!("Initializing rpc: addr: {}", config.rpc_addr);
+
+	actix_web::HttpServer::new(move || {
+		let mut app = actix_web::App::new();
+		if let Some(admin_rpc) = &admin_rpc {
+			let admin_token = admin_token.clone();
+			app = app.service(
+				actix_web::web::service("/")
+					.guard(actix_web::guard::Post())
+					.guard(actix_web::guard::fn_guard(move |head| {
+						is_admin_authorized(head, admin_token.as_deref())
+					}))
+					.finish(admin_rpc.clone().into_web_service()),
+			);
+		}
+		app.service(
+			actix_web::web::service("/")
+				.guard(actix_web::guard::Post())
+				.finish(rpc.clone().into_web_service()),
+		)
+	})
+	.workers(workers)
+	.maxconn(cmp::max(config.rpc_maxconn / workers, 1))
+	.bind(&config.rpc_addr)
+	.map_err(errors::ErrorKind::IO)?
+	.run()
+	.await
+	.map_err(errors::ErrorKind::IO)?;
+
+	Ok(())
+}
+
+/// Admin requests carry `Authorization: Bearer <token>` if the token is configured,
+/// otherwise they are only accepted from localhost
+fn is_admin_authorized(head: &RequestHead, token: Option<&str>) -> bool {
+	match token {
+		Some(token) => head
+			.headers
+			.get(header::AUTHORIZATION)
+			.and_then(|x| x.to_str().ok())
+			.map(|x| x == format!("Bearer {}", token))
+			.unwrap_or(false),
+		None => head
+			.peer_addr
+			.map(|x| x.ip().is_loopback())
+			.unwrap_or(false),
+	}
+}
+
+fn with_public_methods<S>(builder: ServerBuilder<MapRouter>) -> ServerBuilder<MapRouter>
+where
+	S: ApiSupport,
+{
+	builder
 		.with_method(
 			"chain_getHeaderByNumber",
 			method::chain_get_header_by_number::<S>,
@@ -89,38 +167,33 @@ where
 			"chain_sendRawTransaction",
 			method::chain_send_raw_transaction::<S>,
 		)
+		.with_method("chain_getNonce", method::chain_get_nonce::<S>)
 		.with_method("chain_executeCall", method::chain_execute_call::<S>)
 		.with_method(
 			"chain_buildTransaction",
 			method::chain_build_transaction::<S>,
 		)
+		.with_method("chain_getStateProof", method::chain_get_state_proof::<S>)
+		.with_method(
+			"chain_getTransactionProof",
+			method::chain_get_transaction_proof::<S>,
+		)
+		.with_method(
+			"chain_getReceiptProof",
+			method::chain_get_receipt_proof::<S>,
+		)
+		.with_method(
+			"chain_getTransactionsByAddress",
+			method::chain_get_transactions_by_address::<S>,
+		)
+		.with_method("chain_getEvents", method::chain_get_events::<S>)
 		.with_method("txpool_getTransaction", method::txpool_get_transaction::<S>)
+		.with_method("txpool_status", method::txpool_status::<S>)
+		.with_method("txpool_content", method::txpool_content::<S>)
+		.with_method(
+			"txpool_getRemoveReason",
+			method::txpool_get_remove_reason::<S>,
+		)
 		.with_method("network_getState", method::network_get_state::<S>)
 		.with_method("consensus_getState", method::consensus_get_state::<S>)
-		.finish();
-
-	let workers = match config.rpc_workers {
-		0 => num_cpus::get(),
-		other => other,
-	};
-
-	log::info!("Initializing rpc: addr: {}", config.rpc_addr);
-
-	actix_web::HttpServer::new(move || {
-		let rpc = rpc.clone();
-		actix_web::App::new().service(
-			actix_web::web::service("/")
-				.guard(actix_web::guard::Post())
-				.finish(rpc.into_web_service()),
-		)
-	})
-	.workers(workers)
-	.maxconn(cmp::max(config.rpc_maxconn / workers, 1))
-	.bind(&config.rpc_addr)
-	.map_err(errors::ErrorKind::IO)?
-	.run()
-	.await
-	.map_err(errors::ErrorKind::IO)?;
-
-	Ok(())
 }
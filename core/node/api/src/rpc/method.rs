@@ -12,7 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::cmp;
 use std::convert::{TryFrom, TryInto};
+use std::path::{Component, PathBuf};
 use std::sync::Arc;
 
 use futures::channel::oneshot;
@@ -26,10 +28,17 @@ use primitives::{codec, SecretKey};
 use crate::errors;
 use crate::errors::ErrorKind;
 use crate::support::ApiSupport;
+use crate::ApiConfig;
 use node_consensus_base::ConsensusInMessage;
 use node_coordinator::{CoordinatorInMessage, NetworkInMessage};
 use std::collections::HashSet;
 
+/// Max transaction count returned by txpool_content
+const TXPOOL_CONTENT_MAX_LIMIT: u64 = 1024;
+
+/// Max transaction or receipt count returned by the index methods
+const INDEX_MAX_LIMIT: u64 = 1024;
+
 pub async fn chain_get_header_by_number<S: ApiSupport>(
 	data: Data<Arc<S>>,
 	Params((block_number,)): Params<(BlockNumber,)>,
@@ -177,6 +186,42 @@ pub async fn chain_get_proof_by_hash<S: ApiSupport>(
 	Ok(proof)
 }
 
+pub async fn chain_get_state_proof<S: ApiSupport>(
+	data: Data<Arc<S>>,
+	Params((block_number, module, key)): Params<(BlockNumber, String, Hex)>,
+) -> CustomResult<Option<StateProof>> {
+	let number_enum: BlockNumberEnum = block_number.try_into()?;
+	let key: Vec<u8> = key.try_into()?;
+
+	let support = data.0;
+	let number = match number_enum {
+		BlockNumberEnum::Confirmed => support.get_confirmed_number()?,
+		BlockNumberEnum::ConfirmedExecuted => support.get_confirmed_executed_number()?,
+		BlockNumberEnum::Number(number) => Some(number),
+	};
+
+	let number = match number {
+		Some(number) => number,
+		None => return Ok(None),
+	};
+
+	let block_hash = match support.get_block_hash(&number)? {
+		Some(block_hash) => block_hash,
+		None => return Ok(None),
+	};
+
+	let state_proof: Option<StateProof> = support
+		.get_state_proof(&block_hash, &module, &key)?
+		.map(Into::into);
+
+	let state_proof = state_proof.map(|mut x| {
+		x.hash = Some(block_hash.into());
+		x
+	});
+
+	Ok(state_proof)
+}
+
 pub async fn chain_get_transaction_by_hash<S: ApiSupport>(
 	data: Data<Arc<S>>,
 	Params((hash,)): Params<(Hash,)>,
@@ -219,6 +264,106 @@ pub async fn chain_get_receipt_by_hash<S: ApiSupport>(
 	Ok(tx)
 }
 
+pub async fn chain_get_transaction_proof<S: ApiSupport>(
+	data: Data<Arc<S>>,
+	Params((hash,)): Params<(Hash,)>,
+) -> CustomResult<Option<InclusionProof>> {
+	let hash = hash.try_into()?;
+	let support = data.0;
+	let proof: Option<InclusionProof> = support.get_transaction_proof(&hash)?.map(Into::into);
+
+	Ok(proof)
+}
+
+pub async fn chain_get_receipt_proof<S: ApiSupport>(
+	data: Data<Arc<S>>,
+	Params((hash,)): Params<(Hash,)>,
+) -> CustomResult<Option<InclusionProof>> {
+	let hash = hash.try_into()?;
+	let support = data.0;
+	let proof: Option<InclusionProof> = support.get_receipt_proof(&hash)?.map(Into::into);
+
+	Ok(proof)
+}
+
+pub async fn chain_get_transactions_by_address<S: ApiSupport>(
+	data: Data<Arc<S>>,
+	Params(request): Params<AddressTransactionsRequest>,
+) -> CustomResult<AddressTransactions> {
+	let address: primitives::Address = request.address.try_into()?;
+	let (offset, limit) = get_page_params(request.offset, request.limit)?;
+
+	let support = data.0;
+	let page = support.get_address_transactions(&address, offset, limit)?;
+	let transactions = page
+		.entries
+		.into_iter()
+		.filter_map(|entry| match support.get_transaction(&entry.tx_hash) {
+			Ok(tx) => tx.map(|tx| {
+				let mut tx: Transaction = tx.into();
+				tx.hash = Some(entry.tx_hash.into());
+				Ok(tx)
+			}),
+			Err(e) => Some(Err(e)),
+		})
+		.collect::<CommonResult<Vec<_>>>()?;
+
+	Ok(AddressTransactions {
+		total: page.total.into(),
+		transactions,
+	})
+}
+
+pub async fn chain_get_events<S: ApiSupport>(
+	data: Data<Arc<S>>,
+	Params((filter, from, to)): Params<(EventFilter, BlockNumber, BlockNumber)>,
+) -> CustomResult<Events> {
+	if filter.module.is_none() && filter.name.is_none() {
+		return Err(CommonError::from(errors::ErrorKind::InvalidParams(
+			"Either module or name should be specified".to_string(),
+		))
+		.into());
+	}
+	let (offset, limit) = get_page_params(filter.offset, filter.limit)?;
+
+	let support = data.0;
+	let (from, to) = match (
+		get_number(support.as_ref().as_ref(), from)?,
+		get_number(support.as_ref().as_ref(), to)?,
+	) {
+		(Some(from), Some(to)) => (from, to),
+		_ => {
+			return Ok(Events {
+				total: 0u64.into(),
+				receipts: vec![],
+			})
+		}
+	};
+
+	let event_filter = node_chain::EventFilter {
+		module: filter.module,
+		name: filter.name,
+	};
+	let page = support.get_events(&event_filter, from, to, offset, limit)?;
+	let receipts = page
+		.entries
+		.into_iter()
+		.filter_map(|entry| match support.get_receipt(&entry.tx_hash) {
+			Ok(receipt) => receipt.map(|receipt| {
+				let mut receipt: Receipt = receipt.into();
+				receipt.hash = Some(entry.tx_hash.into());
+				Ok(receipt)
+			}),
+			Err(e) => Some(Err(e)),
+		})
+		.collect::<CommonResult<Vec<_>>>()?;
+
+	Ok(Events {
+		total: page.total.into(),
+		receipts,
+	})
+}
+
 pub async fn chain_send_raw_transaction<S: ApiSupport>(
 	data: Data<Arc<S>>,
 	Params((raw_transaction,)): Params<(Hex,)>,
@@ -239,6 +384,16 @@ pub async fn chain_send_raw_transaction<S: ApiSupport>(
 	Ok(tx_hash)
 }
 
+pub async fn chain_get_nonce<S: ApiSupport>(
+	data: Data<Arc<S>>,
+	Params((address,)): Params<(Address,)>,
+) -> CustomResult<Hex> {
+	let address = address.try_into()?;
+	let support = data.0;
+	let nonce = support.get_nonce(&address)?.into();
+	Ok(nonce)
+}
+
 pub async fn chain_execute_call<S: ApiSupport>(
 	data: Data<Arc<S>>,
 	Params(request): Params<ExecuteTransactionRequest>,
@@ -296,6 +451,93 @@ pub async fn txpool_get_transaction<S: ApiSupport>(
 	Ok(tx)
 }
 
+pub async fn txpool_status<S: ApiSupport>(
+	data: Data<Arc<S>>,
+	Params(_request): Params<EmptyRequest>,
+) -> CustomResult<TxPoolStatus> {
+	let status = data.0.txpool_get_status()?.into();
+	Ok(status)
+}
+
+pub async fn txpool_content<S: ApiSupport>(
+	data: Data<Arc<S>>,
+	Params(request): Params<TxPoolContentRequest>,
+) -> CustomResult<TxPoolContent> {
+	let sender: Option<primitives::Address> = match request.sender {
+		Some(sender) => Some(sender.try_into()?),
+		None => None,
+	};
+	let offset: u64 = match request.offset {
+		Some(offset) => offset.try_into()?,
+		None => 0,
+	};
+	let limit: u64 = match request.limit {
+		Some(limit) => limit.try_into()?,
+		None => TXPOOL_CONTENT_MAX_LIMIT,
+	};
+	let limit = cmp::min(limit, TXPOOL_CONTENT_MAX_LIMIT);
+
+	let txs = data.0.txpool_get_transactions(sender.as_ref())?;
+	let total = txs.len() as u64;
+	let transactions = txs
+		.into_iter()
+		.skip(offset as usize)
+		.take(limit as usize)
+		.map(|x| {
+			let mut tx: Transaction = x.tx.clone().into();
+			tx.hash = Some(x.tx_hash.clone().into());
+			tx
+		})
+		.collect();
+
+	Ok(TxPoolContent {
+		total: total.into(),
+		transactions,
+	})
+}
+
+/// Reason of a recently removed transaction, e.g. Included, Expired, Evicted
+pub async fn txpool_get_remove_reason<S: ApiSupport>(
+	data: Data<Arc<S>>,
+	Params((hash,)): Params<(Hash,)>,
+) -> CustomResult<Option<String>> {
+	let hash = hash.try_into()?;
+	let reason = data
+		.0
+		.txpool_get_remove_reason(&hash)?
+		.map(|x| format!("{:?}", x));
+	Ok(reason)
+}
+
+pub async fn chain_create_checkpoint<S: ApiSupport>(
+	data: Data<Arc<S>>,
+	config: Data<ApiConfig>,
+	Params((path,)): Params<(String,)>,
+) -> CustomResult<Checkpoint> {
+	// the path can not escape from the checkpoint dir
+	let path = PathBuf::from(path);
+	if path.as_os_str().is_empty() || !path.components().all(|x| matches!(x, Component::Normal(_)))
+	{
+		return Err(CommonError::from(errors::ErrorKind::InvalidParams(format!(
+			"Path should be relative to the checkpoint dir: {:?}",
+			path
+		)))
+		.into());
+	}
+	let path = config.rpc_checkpoint_dir.join(path);
+	let checkpoint = data.0.create_checkpoint(&path)?;
+	Ok(checkpoint.into())
+}
+
+pub async fn txpool_remove<S: ApiSupport>(
+	data: Data<Arc<S>>,
+	Params((hash,)): Params<(Hash,)>,
+) -> CustomResult<bool> {
+	let hash = hash.try_into()?;
+	let removed = data.0.txpool_remove_transaction(&hash)?;
+	Ok(removed)
+}
+
 pub async fn network_get_state<S: ApiSupport>(
 	data: Data<Arc<S>>,
 	Params(_request): Params<EmptyRequest>,
@@ -329,6 +571,36 @@ pub async fn consensus_get_state<S: ApiSupport>(
 	Ok(consensus_state)
 }
 
+/// Resolve the number input to the block number, None if the tag refers to no block
+fn get_number<S: ApiSupport>(
+	support: &S,
+	number: BlockNumber,
+) -> CommonResult<Option<primitives::BlockNumber>> {
+	let number_enum: BlockNumberEnum = number.try_into()?;
+	let number = match number_enum {
+		BlockNumberEnum::Confirmed => support.get_confirmed_number()?,
+		BlockNumberEnum::ConfirmedExecuted => support.get_confirmed_executed_number()?,
+		BlockNumberEnum::Number(number) => Some(number),
+	};
+	Ok(number)
+}
+
+/// Offset and limit of the index methods, with the limit capped by INDEX_MAX_LIMIT
+fn get_page_params(
+	offset: Option<NumberOrHex>,
+	limit: Option<NumberOrHex>,
+) -> CommonResult<(u64, u64)> {
+	let offset: u64 = match offset {
+		Some(offset) => offset.try_into()?,
+		None => 0,
+	};
+	let limit: u64 = match limit {
+		Some(limit) => limit.try_into()?,
+		None => INDEX_MAX_LIMIT,
+	};
+	Ok((offset, cmp::min(limit, INDEX_MAX_LIMIT)))
+}
+
 /// Number input: number, hex or tag (confirmed, confirmed_executed)
 #[derive(Deserialize)]
 #[serde(untagged)]
@@ -369,6 +641,7 @@ pub struct Header {
 	pub hash: Option<Hash>,
 	pub number: Hex,
 	pub timestamp: Hex,
+	pub author: Option<Address>,
 	pub parent_hash: Hash,
 	pub meta_txs_root: Hash,
 	pub meta_state_root: Hash,
@@ -401,6 +674,30 @@ pub struct Proof {
 	pub data: Hex,
 }
 
+#[derive(Serialize)]
+pub struct StateProof {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub hash: Option<Hash>,
+	/// meta_state_root or payload_execution_state_root of the header
+	pub root: Hash,
+	pub meta: bool,
+	pub key: Hex,
+	pub value: Option<Hex>,
+	pub proof: Vec<Hex>,
+}
+
+#[derive(Serialize)]
+pub struct InclusionProof {
+	/// Hash of the block whose header contains the root,
+	/// for a payload receipt, the first block whose header commits the execution of its block
+	pub block_hash: Hash,
+	pub root: Hash,
+	pub meta: bool,
+	pub key: Hex,
+	pub value: Hex,
+	pub proof: Vec<Hex>,
+}
+
 #[derive(Serialize)]
 pub struct Transaction {
 	#[serde(skip_serializing_if = "Option::is_none")]
@@ -431,6 +728,7 @@ pub struct Receipt {
 	pub block_number: Hex,
 	pub events: Vec<serde_json::Value>,
 	pub result: Result<Hex, String>,
+	pub gas_used: Hex,
 }
 
 #[derive(Deserialize)]
@@ -449,6 +747,66 @@ pub struct BuildTransactionRequest {
 #[derive(Deserialize)]
 pub struct EmptyRequest {}
 
+#[derive(Deserialize)]
+pub struct TxPoolContentRequest {
+	pub sender: Option<Address>,
+	pub offset: Option<NumberOrHex>,
+	pub limit: Option<NumberOrHex>,
+}
+
+#[derive(Deserialize)]
+pub struct AddressTransactionsRequest {
+	pub address: Address,
+	pub offset: Option<NumberOrHex>,
+	pub limit: Option<NumberOrHex>,
+}
+
+#[derive(Serialize)]
+pub struct AddressTransactions {
+	/// Transaction count of the address, regardless of offset and limit
+	pub total: Hex,
+	pub transactions: Vec<Transaction>,
+}
+
+/// Filter of chain_getEvents, at least one of module and name should be specified
+#[derive(Deserialize)]
+pub struct EventFilter {
+	pub module: Option<String>,
+	pub name: Option<String>,
+	pub offset: Option<NumberOrHex>,
+	pub limit: Option<NumberOrHex>,
+}
+
+#[derive(Serialize)]
+pub struct Events {
+	/// Receipt count matching the filter, regardless of offset and limit
+	pub total: Hex,
+	pub receipts: Vec<Receipt>,
+}
+
+#[derive(Serialize)]
+pub struct Checkpoint {
+	pub genesis_hash: Hash,
+	/// Confirmed number of the db in the checkpoint
+	pub confirmed_number: Hex,
+	/// Executed number of the db in the checkpoint
+	pub execution_number: Hex,
+}
+
+#[derive(Serialize)]
+pub struct TxPoolStatus {
+	pub count: Hex,
+	pub capacity: Hex,
+	pub bytes: Hex,
+}
+
+#[derive(Serialize)]
+pub struct TxPoolContent {
+	/// Transaction count matching the request, regardless of offset and limit
+	pub total: Hex,
+	pub transactions: Vec<Transaction>,
+}
+
 #[derive(Serialize)]
 pub struct NetworkState {
 	pub peer_id: String,
@@ -528,6 +886,7 @@ impl From<primitives::Header> for Header {
 			hash: None,
 			number: header.number.into(),
 			timestamp: header.timestamp.into(),
+			author: header.author.map(Into::into),
 			parent_hash: header.parent_hash.into(),
 			meta_txs_root: header.meta_txs_root.into(),
 			meta_state_root: header.meta_state_root.into(),
@@ -601,6 +960,17 @@ impl From<primitives::Receipt> for Receipt {
 				.map(|x| serde_json::from_slice(&x.0).unwrap_or(serde_json::Value::Null))
 				.collect(),
 			result: receipt.result.map(Into::into),
+			gas_used: receipt.gas_used.into(),
+		}
+	}
+}
+
+impl From<node_txpool::TxPoolStatus> for TxPoolStatus {
+	fn from(status: node_txpool::TxPoolStatus) -> Self {
+		Self {
+			count: (status.count as u64).into(),
+			capacity: (status.capacity as u64).into(),
+			bytes: (status.bytes as u64).into(),
 		}
 	}
 }
@@ -615,6 +985,42 @@ impl From<primitives::Proof> for Proof {
 	}
 }
 
+impl From<node_chain::StateProof> for StateProof {
+	fn from(state_proof: node_chain::StateProof) -> Self {
+		Self {
+			hash: None,
+			root: state_proof.root.into(),
+			meta: state_proof.meta,
+			key: state_proof.key.into(),
+			value: state_proof.value.map(Into::into),
+			proof: state_proof.proof.into_iter().map(Into::into).collect(),
+		}
+	}
+}
+
+impl From<node_chain::InclusionProof> for InclusionProof {
+	fn from(proof: node_chain::InclusionProof) -> Self {
+		Self {
+			block_hash: proof.block_hash.into(),
+			root: proof.root.into(),
+			meta: proof.meta,
+			key: proof.key.into(),
+			value: proof.value.into(),
+			proof: proof.proof.into_iter().map(Into::into).collect(),
+		}
+	}
+}
+
+impl From<node_chain::Checkpoint> for Checkpoint {
+	fn from(checkpoint: node_chain::Checkpoint) -> Self {
+		Self {
+			genesis_hash: checkpoint.genesis_hash.into(),
+			confirmed_number: checkpoint.confirmed_number.into(),
+			execution_number: checkpoint.execution_number.into(),
+		}
+	}
+}
+
 impl From<u32> for Hex {
 	fn from(number: u32) -> Self {
 		Hex(format!("0x{}", hex::encode(number.to_be_bytes())))
@@ -669,6 +1075,12 @@ impl From<primitives::Hash> for Hash {
 	}
 }
 
+impl From<primitives::Address> for Address {
+	fn from(address: primitives::Address) -> Self {
+		Address(format!("0x{}", hex::encode(address.0)))
+	}
+}
+
 impl TryInto<primitives::Hash> for Hash {
 	type Error = CommonError;
 
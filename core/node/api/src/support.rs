@@ -12,25 +12,32 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::path::Path;
 use std::sync::Arc;
 
 use async_trait::async_trait;
 use futures::channel::mpsc::UnboundedSender;
+use futures::StreamExt;
+use lru::LruCache;
+use parking_lot::Mutex;
 
-use node_chain::Chain;
+use node_chain::{Chain, Checkpoint, EventFilter, InclusionProof, IndexPage, StateProof};
 use node_consensus::Consensus;
 use node_consensus_base::support::DefaultConsensusSupport;
 use node_consensus_base::ConsensusInMessage;
 use node_coordinator::support::DefaultCoordinatorSupport;
 use node_coordinator::{Coordinator, CoordinatorInMessage};
 use node_txpool::support::DefaultTxPoolSupport;
-use node_txpool::TxPool;
+use node_txpool::{RemoveReason, TxPool, TxPoolOutMessage, TxPoolStatus};
 use primitives::errors::CommonResult;
 use primitives::{
-	Address, Block, BlockNumber, Call, Hash, Header, Nonce, OpaqueCallResult, Proof, Receipt,
-	SecretKey, Transaction,
+	Address, Block, BlockNumber, Call, FullTransaction, Hash, Header, Nonce, OpaqueCallResult,
+	Proof, Receipt, SecretKey, Transaction,
 };
 
+/// Count of the recently removed transactions kept for txpool_getRemoveReason
+const REMOVED_TXS_CAPACITY: usize = 1024;
+
 #[async_trait]
 pub trait ApiSupport: Send + Sync + 'static {
 	fn hash_transaction(&self, tx: &Transaction) -> CommonResult<Hash>;
@@ -40,10 +47,34 @@ pub trait ApiSupport: Send + Sync + 'static {
 	fn get_block(&self, block_hash: &Hash) -> CommonResult<Option<Block>>;
 	fn get_header(&self, block_hash: &Hash) -> CommonResult<Option<Header>>;
 	fn get_proof(&self, block_hash: &Hash) -> CommonResult<Option<Proof>>;
+	fn get_state_proof(
+		&self,
+		block_hash: &Hash,
+		module: &str,
+		key: &[u8],
+	) -> CommonResult<Option<StateProof>>;
 	fn get_transaction(&self, tx_hash: &Hash) -> CommonResult<Option<Transaction>>;
 	fn get_raw_transaction(&self, tx_hash: &Hash) -> CommonResult<Option<Vec<u8>>>;
 	fn get_receipt(&self, tx_hash: &Hash) -> CommonResult<Option<Receipt>>;
+	fn get_transaction_proof(&self, tx_hash: &Hash) -> CommonResult<Option<InclusionProof>>;
+	fn get_receipt_proof(&self, tx_hash: &Hash) -> CommonResult<Option<InclusionProof>>;
+	fn get_address_transactions(
+		&self,
+		address: &Address,
+		offset: u64,
+		limit: u64,
+	) -> CommonResult<IndexPage>;
+	fn get_events(
+		&self,
+		filter: &EventFilter,
+		from: BlockNumber,
+		to: BlockNumber,
+		offset: u64,
+		limit: u64,
+	) -> CommonResult<IndexPage>;
+	fn create_checkpoint(&self, path: &Path) -> CommonResult<Checkpoint>;
 	fn insert_transaction(&self, transaction: Transaction) -> CommonResult<()>;
+	fn get_nonce(&self, address: &Address) -> CommonResult<Nonce>;
 	fn execute_call(
 		&self,
 		block_hash: &Hash,
@@ -56,6 +87,13 @@ pub trait ApiSupport: Send + Sync + 'static {
 		call: Call,
 	) -> CommonResult<Transaction>;
 	fn txpool_get_transaction(&self, tx_hash: &Hash) -> CommonResult<Option<Transaction>>;
+	fn txpool_get_status(&self) -> CommonResult<TxPoolStatus>;
+	fn txpool_get_transactions(
+		&self,
+		sender: Option<&Address>,
+	) -> CommonResult<Vec<Arc<FullTransaction>>>;
+	fn txpool_remove_transaction(&self, tx_hash: &Hash) -> CommonResult<bool>;
+	fn txpool_get_remove_reason(&self, tx_hash: &Hash) -> CommonResult<Option<RemoveReason>>;
 	fn coordinator_tx(&self) -> CommonResult<UnboundedSender<CoordinatorInMessage>>;
 	fn consensus_tx(&self) -> CommonResult<UnboundedSender<ConsensusInMessage>>;
 }
@@ -65,6 +103,7 @@ pub struct DefaultApiSupport {
 	txpool: Arc<TxPool<DefaultTxPoolSupport>>,
 	consensus: Arc<Consensus<DefaultConsensusSupport>>,
 	coordinator: Arc<Coordinator<DefaultCoordinatorSupport>>,
+	removed_txs: Arc<Mutex<LruCache<Hash, RemoveReason>>>,
 }
 
 impl DefaultApiSupport {
@@ -74,11 +113,26 @@ impl DefaultApiSupport {
 		consensus: Arc<Consensus<DefaultConsensusSupport>>,
 		coordinator: Arc<Coordinator<DefaultCoordinatorSupport>>,
 	) -> Self {
+		// keep the reasons of the recently removed txs
+		let removed_txs = Arc::new(Mutex::new(LruCache::new(REMOVED_TXS_CAPACITY)));
+		let mut txpool_rx = txpool.subscribe_message();
+		{
+			let removed_txs = removed_txs.clone();
+			tokio::spawn(async move {
+				while let Some(message) = txpool_rx.next().await {
+					if let TxPoolOutMessage::TxRemoved { tx_hash, reason } = message {
+						removed_txs.lock().put(tx_hash, reason);
+					}
+				}
+			});
+		}
+
 		Self {
 			chain,
 			txpool,
 			consensus,
 			coordinator,
+			removed_txs,
 		}
 	}
 }
@@ -106,6 +160,14 @@ impl ApiSupport for DefaultApiSupport {
 	fn get_proof(&self, block_hash: &Hash) -> CommonResult<Option<Proof>> {
 		self.chain.get_proof(block_hash)
 	}
+	fn get_state_proof(
+		&self,
+		block_hash: &Hash,
+		module: &str,
+		key: &[u8],
+	) -> CommonResult<Option<StateProof>> {
+		self.chain.get_state_proof(block_hash, module, key)
+	}
 	fn get_transaction(&self, tx_hash: &Hash) -> CommonResult<Option<Transaction>> {
 		self.chain.get_transaction(tx_hash)
 	}
@@ -115,9 +177,39 @@ impl ApiSupport for DefaultApiSupport {
 	fn get_receipt(&self, tx_hash: &Hash) -> CommonResult<Option<Receipt>> {
 		self.chain.get_receipt(tx_hash)
 	}
+	fn get_transaction_proof(&self, tx_hash: &Hash) -> CommonResult<Option<InclusionProof>> {
+		self.chain.get_transaction_proof(tx_hash)
+	}
+	fn get_receipt_proof(&self, tx_hash: &Hash) -> CommonResult<Option<InclusionProof>> {
+		self.chain.get_receipt_proof(tx_hash)
+	}
+	fn get_address_transactions(
+		&self,
+		address: &Address,
+		offset: u64,
+		limit: u64,
+	) -> CommonResult<IndexPage> {
+		self.chain.get_address_transactions(address, offset, limit)
+	}
+	fn get_events(
+		&self,
+		filter: &EventFilter,
+		from: BlockNumber,
+		to: BlockNumber,
+		offset: u64,
+		limit: u64,
+	) -> CommonResult<IndexPage> {
+		self.chain.get_events(filter, from, to, offset, limit)
+	}
+	fn create_checkpoint(&self, path: &Path) -> CommonResult<Checkpoint> {
+		self.chain.create_checkpoint(path)
+	}
 	fn insert_transaction(&self, tx: Transaction) -> CommonResult<()> {
 		self.txpool.insert(tx)
 	}
+	fn get_nonce(&self, address: &Address) -> CommonResult<Nonce> {
+		self.chain.get_nonce(address)
+	}
 	fn execute_call(
 		&self,
 		block_hash: &Hash,
@@ -143,6 +235,47 @@ impl ApiSupport for DefaultApiSupport {
 		Ok(Some(tx))
 	}
 
+	fn txpool_get_status(&self) -> CommonResult<TxPoolStatus> {
+		self.txpool.get_status()
+	}
+
+	fn txpool_get_transactions(
+		&self,
+		sender: Option<&Address>,
+	) -> CommonResult<Vec<Arc<FullTransaction>>> {
+		let queue = self.txpool.get_queue().read();
+		let txs = match sender {
+			Some(sender) => queue
+				.iter()
+				.filter(|x| match &x.tx.witness {
+					Some(witness) => &self.chain.get_sender(witness) == sender,
+					None => false,
+				})
+				.cloned()
+				.collect(),
+			None => queue.clone(),
+		};
+		Ok(txs)
+	}
+
+	fn txpool_remove_transaction(&self, tx_hash: &Hash) -> CommonResult<bool> {
+		if !self.txpool.get_map().contains_key(tx_hash) {
+			return Ok(false);
+		}
+		let tx_hash_set = std::iter::once(tx_hash.clone()).collect();
+		self.txpool.remove(&tx_hash_set, RemoveReason::Removed)?;
+		Ok(true)
+	}
+
+	fn txpool_get_remove_reason(&self, tx_hash: &Hash) -> CommonResult<Option<RemoveReason>> {
+		// the tx may be inserted again after removed
+		if self.txpool.get_map().contains_key(tx_hash) {
+			return Ok(None);
+		}
+		let reason = self.removed_txs.lock().get(tx_hash).cloned();
+		Ok(reason)
+	}
+
 	fn coordinator_tx(&self) -> CommonResult<UnboundedSender<CoordinatorInMessage>> {
 		Ok(self.coordinator.coordinator_tx())
 	}
@@ -19,7 +19,7 @@ use std::sync::Arc;
 use std::time::Duration;
 use tempfile::tempdir;
 
-use node_chain::{Chain, ChainConfig, DBConfig};
+use node_chain::{Chain, ChainConfig, DBBackend, DBConfig, StatePruning};
 use node_consensus::{Consensus, ConsensusConfig};
 use node_consensus_base::support::DefaultConsensusSupport;
 use node_consensus_poa::PoaConfig;
@@ -47,7 +47,11 @@ pub fn get_service(
 ) {
 	let chain = get_chain(authority_accounts);
 
-	let txpool_config = TxPoolConfig { pool_capacity: 32 };
+	let txpool_config = TxPoolConfig {
+		pool_capacity: 32,
+		sender_capacity: None,
+		journal: false,
+	};
 
 	let txpool_support = Arc::new(DefaultTxPoolSupport::new(chain.clone()));
 	let txpool = Arc::new(TxPool::new(txpool_config, txpool_support).unwrap());
@@ -59,6 +63,7 @@ pub fn get_service(
 			secret_key: Some(account.secret_key.clone()),
 		}),
 		raft: None,
+		instant: None,
 	};
 
 	let consensus = Arc::new(Consensus::new(consensus_config, support).unwrap());
@@ -138,9 +143,15 @@ fn get_chain(authority_accounts: &[&TestAccount]) -> Arc<Chain> {
 		memory_budget: 1 * 1024 * 1024,
 		path: home.join("data").join("db"),
 		partitions: vec![],
+		backend: DBBackend::RocksDB,
 	};
 
-	let chain_config = ChainConfig { home, db };
+	let chain_config = ChainConfig {
+		home,
+		db,
+		pruning: StatePruning::Archive,
+		index: true,
+	};
 
 	let chain = Arc::new(Chain::new(chain_config).unwrap());
 
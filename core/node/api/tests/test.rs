@@ -25,6 +25,7 @@ use node_api_rt::tokio::time::Duration;
 use node_chain::module;
 use node_consensus_base::ConsensusInMessage;
 use node_coordinator::{Keypair, LinkedHashMap, Multiaddr, PeerId, Protocol};
+use node_statedb::ProofVerifier;
 use primitives::codec::Encode;
 use primitives::Proof;
 use utils_test::test_accounts;
@@ -84,10 +85,16 @@ async fn test_api() {
 	let txpool0 = &services[0].1;
 	let consensus0 = &services[0].2;
 	let coordinator0 = &services[0].3;
+	let checkpoint_dir = tempfile::tempdir()
+		.expect("Could not create a temp dir")
+		.into_path();
 	let config = ApiConfig {
 		rpc_addr: "0.0.0.0:3109".to_string(),
 		rpc_workers: 1,
 		rpc_maxconn: 100,
+		rpc_admin: true,
+		rpc_admin_token: None,
+		rpc_checkpoint_dir: checkpoint_dir.clone(),
 	};
 
 	let support = Arc::new(DefaultApiSupport::new(
@@ -102,10 +109,41 @@ async fn test_api() {
 	// chain_getBlockByNumber
 	let request = r#"{"jsonrpc": "2.0", "method": "chain_getBlockByNumber", "params": ["confirmed"], "id": 1}"#;
 	let response = call_rpc(request).await;
-	let expected = r#"{"jsonrpc":"2.0","result":{"hash":"0xf4c086c905313e502beeaaa8fb5049372ed43dfd3f38f36a51fe7aff24751b1c","header":{"number":"0x0000000000000000","timestamp":"0x00000171c4eb7136","parent_hash":"0x0000000000000000000000000000000000000000000000000000000000000000","meta_txs_root":"0xf0ffb42b8a0c9fa4e9718be498b01abb0c218f199bbb9a774b7ead48e893c11a","meta_state_root":"0xb559a1f10dce8e2791e43143df1e86d3a792e73c6d97ace18735526a2dee430e","meta_receipts_root":"0xe6c79028e5a20c619a5faa0dde88df82f378ca796a717570ef329de275ca1282","payload_txs_root":"0xcbe666e1dff8590ccfad41047bb4a6b8a682b52d1899e3f6a1c40c9eae65e363","payload_execution_gap":"0x00","payload_execution_state_root":"0x0000000000000000000000000000000000000000000000000000000000000000","payload_execution_receipts_root":"0x0000000000000000000000000000000000000000000000000000000000000000"},"body":{"meta_txs":["0x709ab477fc45b28aab319323399bee607bac3af49518e33a78f099c6916ef75e","0xb79f3e47af70ef2900ca680736fccb89841fcc64390bbbb2455a4d8dbff72ad1"],"payload_txs":["0x6745417d545c3e0f7d610cadfd1ee8d450a92e89fa74bb75777950a779f2aa94","0xa0faf0ea2a0c3bf69ae5c1124199c76336b36a159826e823a9fc1cd2d7b5ff55"]}},"id":1}"#;
+	let expected = r#"{"jsonrpc":"2.0","result":{"hash":"0xf4c086c905313e502beeaaa8fb5049372ed43dfd3f38f36a51fe7aff24751b1c","header":{"number":"0x0000000000000000","timestamp":"0x00000171c4eb7136","author":null,"parent_hash":"0x0000000000000000000000000000000000000000000000000000000000000000","meta_txs_root":"0xf0ffb42b8a0c9fa4e9718be498b01abb0c218f199bbb9a774b7ead48e893c11a","meta_state_root":"0xb559a1f10dce8e2791e43143df1e86d3a792e73c6d97ace18735526a2dee430e","meta_receipts_root":"0xe6c79028e5a20c619a5faa0dde88df82f378ca796a717570ef329de275ca1282","payload_txs_root":"0xcbe666e1dff8590ccfad41047bb4a6b8a682b52d1899e3f6a1c40c9eae65e363","payload_execution_gap":"0x00","payload_execution_state_root":"0x0000000000000000000000000000000000000000000000000000000000000000","payload_execution_receipts_root":"0x0000000000000000000000000000000000000000000000000000000000000000"},"body":{"meta_txs":["0x709ab477fc45b28aab319323399bee607bac3af49518e33a78f099c6916ef75e","0xb79f3e47af70ef2900ca680736fccb89841fcc64390bbbb2455a4d8dbff72ad1"],"payload_txs":["0x6745417d545c3e0f7d610cadfd1ee8d450a92e89fa74bb75777950a779f2aa94","0xa0faf0ea2a0c3bf69ae5c1124199c76336b36a159826e823a9fc1cd2d7b5ff55"]}},"id":1}"#;
 	info!("chain_getBlockByNumber response: {}", response);
 	assert_eq!(response, expected);
 
+	// chain_getStateProof
+	let request = format!(
+		r#"{{"jsonrpc": "2.0", "method": "chain_getStateProof", "params": ["confirmed", "system", "0x{}"], "id": 1}}"#,
+		hex::encode(b"chain_id"),
+	);
+	let response = call_rpc(&request).await;
+	info!("chain_getStateProof response: {}", response);
+	let response: serde_json::Value = serde_json::from_str(&response).unwrap();
+	let result = &response["result"];
+	assert_eq!(
+		result["root"],
+		"0xb559a1f10dce8e2791e43143df1e86d3a792e73c6d97ace18735526a2dee430e"
+	);
+	assert_eq!(result["meta"], true);
+	assert_eq!(
+		result["key"],
+		format!("0x{}", hex::encode(b"system_chain_id"))
+	);
+	let decode_hex = |x: &serde_json::Value| hex::decode(&x.as_str().unwrap()[2..]).unwrap();
+	let root = decode_hex(&result["root"]);
+	let key = decode_hex(&result["key"]);
+	let value = decode_hex(&result["value"]);
+	let proof = result["proof"]
+		.as_array()
+		.unwrap()
+		.iter()
+		.map(decode_hex)
+		.collect::<Vec<_>>();
+	let verifier = ProofVerifier::new(chain0.get_basic().hash.clone()).unwrap();
+	verifier.verify(&root, &proof, &key, Some(&value)).unwrap();
+
 	// chain_sendRawTransaction
 	let tx0 = chain0
 		.build_transaction(
@@ -149,6 +187,89 @@ async fn test_api() {
 	info!("chain_getTransactionInTxPool response: {}", response);
 	assert_eq!(response, expected);
 
+	// txpool_status
+	let request = r#"{"jsonrpc": "2.0", "method": "txpool_status", "params": [], "id": 1}"#;
+	let response = call_rpc(request).await;
+	let expected = format!(
+		r#"{{"jsonrpc":"2.0","result":{{"count":"0x{}","capacity":"0x{}","bytes":"0x{}"}},"id":1}}"#,
+		hex::encode(1u64.to_be_bytes()),
+		hex::encode(32u64.to_be_bytes()),
+		hex::encode((tx0.encode().len() as u64).to_be_bytes()),
+	);
+	info!("txpool_status response: {}", response);
+	assert_eq!(response, expected);
+
+	// txpool_content
+	let request = format!(
+		r#"{{"jsonrpc": "2.0", "method": "txpool_content", "params": {{ "sender": "0x{}", "offset": 0, "limit": 10 }}, "id": 1}}"#,
+		hex::encode(&account1.address.0),
+	);
+	let response = call_rpc(&request).await;
+	info!("txpool_content response: {}", response);
+	let response: serde_json::Value = serde_json::from_str(&response).unwrap();
+	assert_eq!(response["result"]["total"], "0x0000000000000001");
+	assert_eq!(
+		response["result"]["transactions"][0]["hash"],
+		format!("0x{}", hex::encode(&tx0_hash.0))
+	);
+
+	let request = format!(
+		r#"{{"jsonrpc": "2.0", "method": "txpool_content", "params": {{ "sender": "0x{}" }}, "id": 1}}"#,
+		hex::encode(&account2.address.0),
+	);
+	let response = call_rpc(&request).await;
+	info!("txpool_content response: {}", response);
+	let response: serde_json::Value = serde_json::from_str(&response).unwrap();
+	assert_eq!(response["result"]["total"], "0x0000000000000000");
+	assert_eq!(response["result"]["transactions"], serde_json::json!([]));
+
+	// txpool_remove
+	let tx1 = chain0
+		.build_transaction(
+			Some((account1.secret_key.clone(), 1, 10)),
+			chain0
+				.build_call(
+					"balance".to_string(),
+					"transfer".to_string(),
+					module::balance::TransferParams {
+						recipient: account2.address.clone(),
+						value: 1,
+					},
+				)
+				.unwrap(),
+		)
+		.unwrap();
+	let tx1_hash = chain0.hash_transaction(&tx1).unwrap();
+	txpool0.insert(tx1).unwrap();
+	base::wait_txpool(&txpool0, 2).await;
+
+	let request = format!(
+		r#"{{"jsonrpc": "2.0", "method": "txpool_remove", "params": ["0x{}"], "id": 1}}"#,
+		hex::encode(&tx1_hash.0)
+	);
+	let response = call_rpc(&request).await;
+	info!("txpool_remove response: {}", response);
+	assert_eq!(response, r#"{"jsonrpc":"2.0","result":true,"id":1}"#);
+	base::wait_txpool(&txpool0, 1).await;
+
+	let response = call_rpc(&request).await;
+	assert_eq!(response, r#"{"jsonrpc":"2.0","result":false,"id":1}"#);
+
+	// txpool_getRemoveReason
+	let request = format!(
+		r#"{{"jsonrpc": "2.0", "method": "txpool_getRemoveReason", "params": ["0x{}"], "id": 1}}"#,
+		hex::encode(&tx1_hash.0)
+	);
+	loop {
+		let response = call_rpc(&request).await;
+		if response != r#"{"jsonrpc":"2.0","result":null,"id":1}"# {
+			info!("txpool_getRemoveReason response: {}", response);
+			assert_eq!(response, r#"{"jsonrpc":"2.0","result":"Removed","id":1}"#);
+			break;
+		}
+		futures_timer::Delay::new(Duration::from_millis(10)).await;
+	}
+
 	// generate block 1
 	consensus0
 		.in_message_tx()
@@ -182,10 +303,107 @@ async fn test_api() {
 		hex::encode(&tx0_hash.0)
 	);
 	let response = call_rpc(&request).await;
-	let expected = r#"{"jsonrpc":"2.0","result":{"hash":"0x8ece9a3e63a339d854f762ff45e2b19ce110a43efe57d2499fc2c13749c1018f","block_number":"0x0000000000000001","events":[{"data":{"recipient":"43346e326b6721be4a070bfb2eb49127322fa5e4","sender":"b4decd5a5f8f2ba708f8ced72eec89f44f3be96a","value":1},"name":"Transferred"}],"result":{"Ok":"0x"}},"id":1}"#;
+	let expected = r#"{"jsonrpc":"2.0","result":{"hash":"0x8ece9a3e63a339d854f762ff45e2b19ce110a43efe57d2499fc2c13749c1018f","block_number":"0x0000000000000001","events":[{"data":{"recipient":"43346e326b6721be4a070bfb2eb49127322fa5e4","sender":"b4decd5a5f8f2ba708f8ced72eec89f44f3be96a","value":1},"name":"Transferred"}],"result":{"Ok":"0x"},"gas_used":"0x0000000000000000"},"id":1}"#;
 	info!("chain_getReceiptByHash response: {}", response);
 	assert_eq!(response, expected);
 
+	// chain_getTransactionsByAddress
+	let request = format!(
+		r#"{{"jsonrpc": "2.0", "method": "chain_getTransactionsByAddress", "params": {{ "address": "0x{}", "offset": 0, "limit": 10 }}, "id": 1}}"#,
+		hex::encode(&account2.address.0),
+	);
+	let response = call_rpc(&request).await;
+	info!("chain_getTransactionsByAddress response: {}", response);
+	let response: serde_json::Value = serde_json::from_str(&response).unwrap();
+	assert_eq!(response["result"]["total"], "0x0000000000000001");
+	assert_eq!(
+		response["result"]["transactions"][0]["hash"],
+		format!("0x{}", hex::encode(&tx0_hash.0))
+	);
+
+	// chain_getEvents
+	let request = r#"{"jsonrpc": "2.0", "method": "chain_getEvents", "params": [{ "module": "balance", "name": "Transferred" }, 0, "confirmed"], "id": 1}"#;
+	let response = call_rpc(request).await;
+	info!("chain_getEvents response: {}", response);
+	let response: serde_json::Value = serde_json::from_str(&response).unwrap();
+	assert_eq!(response["result"]["total"], "0x0000000000000001");
+	assert_eq!(
+		response["result"]["receipts"][0]["hash"],
+		format!("0x{}", hex::encode(&tx0_hash.0))
+	);
+	assert_eq!(
+		response["result"]["receipts"][0]["events"][0]["name"],
+		"Transferred"
+	);
+
+	let request = r#"{"jsonrpc": "2.0", "method": "chain_getEvents", "params": [{ "name": "Transferred", "offset": 1 }, 0, 1], "id": 1}"#;
+	let response = call_rpc(request).await;
+	info!("chain_getEvents response: {}", response);
+	let response: serde_json::Value = serde_json::from_str(&response).unwrap();
+	assert_eq!(response["result"]["total"], "0x0000000000000001");
+	assert_eq!(response["result"]["receipts"], serde_json::json!([]));
+
+	// chain_getTransactionProof
+	let block1_header = chain0
+		.get_header(&chain0.get_block_hash(&1).unwrap().unwrap())
+		.unwrap()
+		.unwrap();
+	let request = format!(
+		r#"{{"jsonrpc": "2.0", "method": "chain_getTransactionProof", "params": ["0x{}"], "id": 1}}"#,
+		hex::encode(&tx0_hash.0)
+	);
+	let response = call_rpc(&request).await;
+	info!("chain_getTransactionProof response: {}", response);
+	let response: serde_json::Value = serde_json::from_str(&response).unwrap();
+	let result = &response["result"];
+	assert_eq!(
+		result["root"],
+		format!("0x{}", hex::encode(&block1_header.payload_txs_root.0))
+	);
+	assert_eq!(result["meta"], false);
+	let root = decode_hex(&result["root"]);
+	let key = decode_hex(&result["key"]);
+	let value = decode_hex(&result["value"]);
+	let proof = result["proof"]
+		.as_array()
+		.unwrap()
+		.iter()
+		.map(decode_hex)
+		.collect::<Vec<_>>();
+	verifier.verify(&root, &proof, &key, Some(&value)).unwrap();
+
+	// chain_getReceiptProof
+	let block1_execution = chain0
+		.get_execution(&chain0.get_block_hash(&1).unwrap().unwrap())
+		.unwrap()
+		.unwrap();
+	let request = format!(
+		r#"{{"jsonrpc": "2.0", "method": "chain_getReceiptProof", "params": ["0x{}"], "id": 1}}"#,
+		hex::encode(&tx0_hash.0)
+	);
+	let response = call_rpc(&request).await;
+	info!("chain_getReceiptProof response: {}", response);
+	let response: serde_json::Value = serde_json::from_str(&response).unwrap();
+	let result = &response["result"];
+	assert_eq!(
+		result["root"],
+		format!(
+			"0x{}",
+			hex::encode(&block1_execution.payload_execution_receipts_root.0)
+		)
+	);
+	assert_eq!(result["meta"], false);
+	let root = decode_hex(&result["root"]);
+	let key = decode_hex(&result["key"]);
+	let value = decode_hex(&result["value"]);
+	let proof = result["proof"]
+		.as_array()
+		.unwrap()
+		.iter()
+		.map(decode_hex)
+		.collect::<Vec<_>>();
+	verifier.verify(&root, &proof, &key, Some(&value)).unwrap();
+
 	// chain_executeCall
 	let block1_hash = chain0.get_block_hash(&1).unwrap().unwrap();
 	let request = format!(
@@ -198,6 +416,16 @@ async fn test_api() {
 	info!("chain_executeCall response: {}", response);
 	assert_eq!(response, expected);
 
+	// chain_getNonce
+	let request = format!(
+		r#"{{"jsonrpc": "2.0", "method": "chain_getNonce", "params": ["0x{}"], "id": 1}}"#,
+		hex::encode(&account1.address.0),
+	);
+	let response = call_rpc(&request).await;
+	let expected = r#"{"jsonrpc":"2.0","result":"0x00000001","id":1}"#;
+	info!("chain_getNonce response: {}", response);
+	assert_eq!(response, expected);
+
 	// chain_getProofByHash
 	let request = format!(
 		r#"{{"jsonrpc": "2.0", "method": "chain_getProofByHash", "params": ["0x{}"], "id": 1}}"#,
@@ -219,6 +447,30 @@ async fn test_api() {
 	info!("chain_getProofByHash response: {}", response);
 	assert_eq!(response, expected);
 
+	// chain_createCheckpoint
+	let path = checkpoint_dir.join("checkpoint");
+	let request = r#"{"jsonrpc": "2.0", "method": "chain_createCheckpoint", "params": ["checkpoint"], "id": 1}"#;
+	let response = call_rpc(request).await;
+	info!("chain_createCheckpoint response: {}", response);
+	let response: serde_json::Value = serde_json::from_str(&response).unwrap();
+	assert_eq!(response["result"]["confirmed_number"], "0x0000000000000001");
+	assert_eq!(response["result"]["execution_number"], "0x0000000000000001");
+	assert!(path.join(node_chain::CHECKPOINT_DB).exists());
+	assert!(path.join(node_chain::CHECKPOINT_FILE).exists());
+
+	// the path out of the checkpoint dir is rejected
+	let request = format!(
+		r#"{{"jsonrpc": "2.0", "method": "chain_createCheckpoint", "params": ["{}"], "id": 1}}"#,
+		checkpoint_dir.join("other").to_string_lossy()
+	);
+	let response = call_rpc(&request).await;
+	let response: serde_json::Value = serde_json::from_str(&response).unwrap();
+	assert!(response["error"].is_object());
+	let request = r#"{"jsonrpc": "2.0", "method": "chain_createCheckpoint", "params": ["../other"], "id": 1}"#;
+	let response = call_rpc(request).await;
+	let response: serde_json::Value = serde_json::from_str(&response).unwrap();
+	assert!(response["error"].is_object());
+
 	// wait chain1 to sync
 	let chain1 = &services[1].0;
 	loop {
@@ -248,7 +500,7 @@ async fn test_api() {
 	info!("consensus_getState response: {}", response);
 	assert_eq!(
 		response,
-		r#"{"jsonrpc":"2.0","result":{"address":"b4decd5a5f8f2ba708f8ced72eec89f44f3be96a","authority":"b4decd5a5f8f2ba708f8ced72eec89f44f3be96a","consensus_name":"poa","meta":{"block_interval":null}},"id":1}"#
+		r#"{"jsonrpc":"2.0","result":{"address":"b4decd5a5f8f2ba708f8ced72eec89f44f3be96a","authorities":{"members":["b4decd5a5f8f2ba708f8ced72eec89f44f3be96a"]},"consensus_name":"poa","meta":{"block_interval":null}},"id":1}"#
 	)
 }
 
@@ -14,7 +14,10 @@
 
 //! Backend to provide chain api by handling the db, statedb and executor
 
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs;
+use std::path::Path;
 use std::str::FromStr;
 use std::sync::Arc;
 use std::time::SystemTime;
@@ -30,20 +33,24 @@ use main_base::spec::Spec;
 use node_db::{DBTransaction, DB};
 use node_executor::module::system::Meta;
 use node_executor::{Context, ContextEssence, Executor};
-use node_executor_primitives::{ContextEnv, EmptyParams};
+use node_executor_primitives::{ContextEnv, EmptyParams, SEPARATOR};
 use node_statedb::{StateDB, TrieRoot};
 use primitives::codec::{self, Decode, Encode};
-use primitives::errors::{Catchable, CommonResult};
-use primitives::types::{CallResult, ExecutionGap};
+use primitives::errors::{Catchable, CommonError, CommonResult};
+use primitives::types::{CallResult, ExecutionGap, FullReceipt};
 use primitives::{
 	Address, Block, BlockNumber, Body, BuildBlockParams, BuildExecutionParams, Call, DBKey,
-	Execution, Hash, Header, Nonce, OpaqueCallResult, Proof, Receipt, SecretKey, Transaction,
+	DBValue, Execution, FullTransaction, Hash, Header, Nonce, OpaqueCallResult, Proof, Receipt,
+	SecretKey, Transaction, TransactionForHash, Witness,
 };
 
 use crate::errors::{CommitBlockError, ErrorKind, ValidateTxError};
 use crate::genesis::build_genesis;
+use crate::index::{self, BlockIndex};
 use crate::{
-	errors, Basic, ChainCommitBlockParams, ChainCommitExecutionParams, ChainConfig, ChainOutMessage,
+	errors, Basic, ChainCommitBlockParams, ChainCommitExecutionParams, ChainConfig,
+	ChainOutMessage, Checkpoint, EventFilter, InclusionProof, IndexPage, RevertSummary, Snapshot,
+	SnapshotBlock, StateProof, StatePruning, CHECKPOINT_DB, CHECKPOINT_FILE,
 };
 
 pub struct Backend {
@@ -57,7 +64,19 @@ pub struct Backend {
 	current_state: RwLock<Option<Arc<CurrentState>>>,
 	message_tx: UnboundedSender<ChainOutMessage>,
 	message_rx: RwLock<Option<UnboundedReceiver<ChainOutMessage>>>,
+	message_subscribers: RwLock<Vec<UnboundedSender<ChainOutMessage>>>,
 	commit_block_lock: RwLock<()>,
+	commit_execution_lock: RwLock<()>,
+}
+
+/// Location of a transaction in the block body
+struct TxLocation {
+	block_hash: Hash,
+	header: Header,
+	meta: bool,
+	index: u32,
+	/// Hashes of the meta txs or the payload txs of the block
+	tx_hashes: Vec<Hash>,
 }
 
 pub struct CurrentState {
@@ -81,15 +100,23 @@ impl Backend {
 		let dsa = Arc::new(DsaImpl::from_str(&spec.basic.dsa)?);
 		let address = Arc::new(AddressImpl::from_str(&spec.basic.address)?);
 
-		let meta_statedb = Arc::new(StateDB::new(
+		if genesis_inited {
+			Self::check_state_pruning(&db, &config.pruning)?;
+		}
+
+		let meta_statedb = Arc::new(new_statedb(
 			db.clone(),
 			node_db::columns::META_STATE,
+			node_db::columns::META_STATE_JOURNAL,
 			hash.clone(),
+			&config.pruning,
 		)?);
-		let payload_statedb = Arc::new(StateDB::new(
+		let payload_statedb = Arc::new(new_statedb(
 			db.clone(),
 			node_db::columns::PAYLOAD_STATE,
+			node_db::columns::PAYLOAD_STATE_JOURNAL,
 			hash.clone(),
+			&config.pruning,
 		)?);
 		let trie_root = Arc::new(TrieRoot::new(hash.clone())?);
 
@@ -110,7 +137,9 @@ impl Backend {
 			current_state: RwLock::new(None),
 			message_tx,
 			message_rx: RwLock::new(Some(message_rx)),
+			message_subscribers: RwLock::new(vec![]),
 			commit_block_lock: RwLock::new(()),
+			commit_execution_lock: RwLock::new(()),
 		};
 
 		info!("Initializing backend: genesis_inited: {}", genesis_inited);
@@ -139,6 +168,11 @@ impl Backend {
 		self.executor.hash_transaction(tx)
 	}
 
+	/// Get the sender address of a transaction by its witness
+	pub fn get_sender(&self, witness: &Witness) -> Address {
+		self.executor.get_sender(witness)
+	}
+
 	/// Get the hash of the given encodable data
 	pub fn hash<D: Encode>(&self, data: &D) -> CommonResult<Hash> {
 		self.executor.hash(data)
@@ -182,6 +216,18 @@ impl Backend {
 			}
 		}
 
+		// validate size, a tx exceeding the max block bytes can never be packed
+		let tx_bytes = codec::encode(tx)?.len() as u64;
+		if tx_bytes > system_meta.max_block_bytes {
+			return Err(
+				errors::ErrorKind::ValidateTxError(ValidateTxError::InvalidTxSize(format!(
+					"Exceed max block bytes: {}, max: {}",
+					tx_bytes, system_meta.max_block_bytes
+				)))
+				.into(),
+			);
+		}
+
 		// validate duplication
 		if self.get_transaction(tx_hash)?.is_some() {
 			return Err(
@@ -210,6 +256,12 @@ impl Backend {
 				node_executor_primitives::errors::ErrorKind::InvalidTxParams(e) => {
 					Some(ValidateTxError::InvalidTxParams(e.clone()))
 				}
+				node_executor_primitives::errors::ErrorKind::InvalidTxFee(e) => {
+					Some(ValidateTxError::InvalidTxFee(e.clone()))
+				}
+				node_executor_primitives::errors::ErrorKind::InvalidTxNonce(e) => {
+					Some(ValidateTxError::InvalidTxNonce(e.clone()))
+				}
 				node_executor_primitives::errors::ErrorKind::Application(e) => {
 					Some(ValidateTxError::Application(e.clone()))
 				}
@@ -223,6 +275,15 @@ impl Backend {
 		Ok(())
 	}
 
+	/// Get the nonce expected by the next transaction of the given account
+	/// based on the confirmed meta state
+	pub fn get_nonce(&self, address: &Address) -> CommonResult<Nonce> {
+		let context_state = self.current_state.read();
+		let context_state = context_state.as_ref().expect("qed");
+		let context = Context::new(&context_state.context_essence)?;
+		self.executor.get_nonce(&context, address)
+	}
+
 	/// Build a call
 	pub fn build_call<P: Encode>(
 		&self,
@@ -371,6 +432,38 @@ impl Backend {
 		)
 	}
 
+	/// Get the proof of the storage key of the module in the state of the given block
+	/// the state root is the meta_state_root of the header for meta modules,
+	/// or the payload_execution_state_root of the header for payload modules
+	pub fn get_state_proof(
+		&self,
+		block_hash: &Hash,
+		module: &str,
+		key: &[u8],
+	) -> CommonResult<Option<StateProof>> {
+		let header = match self.get_header(block_hash)? {
+			Some(header) => header,
+			None => return Ok(None),
+		};
+		let meta = self.executor.is_meta_module(module)?;
+		let (statedb, root) = match meta {
+			true => (&self.meta_statedb, header.meta_state_root),
+			false => (&self.payload_statedb, header.payload_execution_state_root),
+		};
+
+		let key = [module.as_bytes(), SEPARATOR, key].concat();
+		let value = statedb.get(&root.0, &key)?;
+		let proof = statedb.get_proof(&root.0, &key)?;
+
+		Ok(Some(StateProof {
+			root,
+			meta,
+			key,
+			value,
+			proof,
+		}))
+	}
+
 	/// Get the transaction by transaction hash
 	pub fn get_transaction(&self, tx_hash: &Hash) -> CommonResult<Option<Transaction>> {
 		self.db
@@ -394,6 +487,73 @@ impl Backend {
 		)
 	}
 
+	/// Get the proof of the transaction in the meta_txs_root or payload_txs_root of the block header
+	/// the transaction is located by its receipt, so None is returned before it is executed
+	pub fn get_transaction_proof(&self, tx_hash: &Hash) -> CommonResult<Option<InclusionProof>> {
+		let location = match self.locate_transaction(tx_hash)? {
+			Some(location) => location,
+			None => return Ok(None),
+		};
+
+		let input = location
+			.tx_hashes
+			.iter()
+			.map(|x| {
+				let tx = self.get_transaction(x)?.ok_or_else(|| {
+					errors::ErrorKind::Data(format!("Missing transaction: tx_hash: {}", x))
+				})?;
+				codec::encode(&TransactionForHash::new(&tx))
+			})
+			.collect::<CommonResult<Vec<_>>>()?;
+
+		let root = match location.meta {
+			true => location.header.meta_txs_root,
+			false => location.header.payload_txs_root,
+		};
+
+		let proof = self.build_inclusion_proof(
+			location.block_hash,
+			root,
+			location.meta,
+			location.index,
+			input,
+		)?;
+		Ok(Some(proof))
+	}
+
+	/// Get the proof of the receipt in the meta_receipts_root of the block header for meta txs,
+	/// or in the payload_execution_receipts_root of the committing block header for payload txs
+	/// None is returned if the execution of the block is not committed by a header yet
+	pub fn get_receipt_proof(&self, tx_hash: &Hash) -> CommonResult<Option<InclusionProof>> {
+		let location = match self.locate_transaction(tx_hash)? {
+			Some(location) => location,
+			None => return Ok(None),
+		};
+
+		let input = location
+			.tx_hashes
+			.iter()
+			.map(|x| {
+				let receipt = self.get_receipt(x)?.ok_or_else(|| {
+					errors::ErrorKind::Data(format!("Missing receipt: tx_hash: {}", x))
+				})?;
+				codec::encode(&receipt)
+			})
+			.collect::<CommonResult<Vec<_>>>()?;
+
+		let (block_hash, root) = match location.meta {
+			true => (location.block_hash, location.header.meta_receipts_root),
+			false => match self.get_committing_block(location.header.number)? {
+				Some((block_hash, header)) => (block_hash, header.payload_execution_receipts_root),
+				None => return Ok(None),
+			},
+		};
+
+		let proof =
+			self.build_inclusion_proof(block_hash, root, location.meta, location.index, input)?;
+		Ok(Some(proof))
+	}
+
 	/// Get consensus data
 	pub fn get_consensus_data<T: Decode>(&self, key: &[u8]) -> CommonResult<Option<T>> {
 		self.db
@@ -423,6 +583,36 @@ impl Backend {
 		Ok(())
 	}
 
+	/// Get the transactions in the txpool journal
+	pub fn get_txpool_journal(&self) -> CommonResult<Vec<Transaction>> {
+		self.db
+			.iter(node_db::columns::TXPOOL)?
+			.into_iter()
+			.map(|(_, v)| codec::decode(&mut &v[..]))
+			.collect()
+	}
+
+	/// Update the txpool journal
+	pub fn update_txpool_journal(
+		&self,
+		inserted: &[Arc<FullTransaction>],
+		removed: &HashSet<Hash>,
+	) -> CommonResult<()> {
+		let mut transaction = self.db.transaction();
+		for tx in inserted {
+			transaction.put_owned(
+				node_db::columns::TXPOOL,
+				DBKey::from_slice(&tx.tx_hash.0),
+				codec::encode(&tx.tx)?,
+			);
+		}
+		for tx_hash in removed {
+			transaction.delete(node_db::columns::TXPOOL, &tx_hash.0);
+		}
+		self.db.write(transaction)?;
+		Ok(())
+	}
+
 	/// Execute a call on a certain block specified by block hash
 	/// this will not commit to the chain
 	pub fn execute_call(
@@ -456,10 +646,15 @@ impl Backend {
 		let number = header.number;
 		let timestamp = header.timestamp;
 
+		let author = header.author;
 		let meta_state_root = header.meta_state_root;
 		let payload_state_root = execution.payload_execution_state_root;
 
-		let env = ContextEnv { number, timestamp };
+		let env = ContextEnv {
+			number,
+			timestamp,
+			author,
+		};
 
 		let context_essence = ContextEssence::new(
 			env,
@@ -526,7 +721,12 @@ impl Backend {
 		let parent_hash = self.get_block_hash(&parent_number)?.ok_or_else(|| {
 			errors::ErrorKind::Data(format!("Invalid block number: {}", parent_number))
 		})?;
-		let env = ContextEnv { number, timestamp };
+		let author = build_block_params.author;
+		let env = ContextEnv {
+			number,
+			timestamp,
+			author: author.clone(),
+		};
 
 		let parent_header = self.get_header(&parent_hash)?.ok_or_else(|| {
 			errors::ErrorKind::Data(format!("Invalid block hash: {}", parent_hash))
@@ -561,9 +761,20 @@ impl Backend {
 
 		let context = Context::new(&context_essence)?;
 
+		self.executor.on_initialize(&context, true)?;
+
+		// nonces are updated for both meta txs and payload txs when building a block,
+		// as payload txs will not be executed until the execution phase
+		self.executor
+			.update_nonces(&context, &build_block_params.meta_txs)?;
+		self.executor
+			.update_nonces(&context, &build_block_params.payload_txs)?;
+
 		self.executor
 			.execute_txs(&context, build_block_params.meta_txs)?;
 
+		self.executor.on_finalize(&context, true)?;
+
 		let (meta_state_root, meta_transaction) = context.get_meta_update()?;
 		let (meta_txs_root, meta_txs) = context.get_meta_txs()?;
 		let (meta_receipts_root, meta_receipts) = context.get_meta_receipts()?;
@@ -583,6 +794,7 @@ impl Backend {
 		let header = Header {
 			number,
 			timestamp,
+			author,
 			parent_hash,
 			meta_txs_root,
 			meta_state_root,
@@ -669,11 +881,16 @@ impl Backend {
 	) -> CommonResult<ChainCommitExecutionParams> {
 		let number = build_execution_params.number;
 		let timestamp = build_execution_params.timestamp;
+		let author = build_execution_params.author;
 		let block_hash = build_execution_params.block_hash;
 		let meta_state_root = build_execution_params.meta_state_root;
 		let payload_state_root = build_execution_params.payload_state_root;
 		let payload_txs = build_execution_params.payload_txs;
-		let env = ContextEnv { number, timestamp };
+		let env = ContextEnv {
+			number,
+			timestamp,
+			author,
+		};
 
 		let context_essence = ContextEssence::new(
 			env,
@@ -686,8 +903,12 @@ impl Backend {
 
 		let context = Context::new(&context_essence)?;
 
+		self.executor.on_initialize(&context, false)?;
+
 		self.executor.execute_txs(&context, payload_txs)?;
 
+		self.executor.on_finalize(&context, false)?;
+
 		let (payload_state_root, payload_transaction) = context.get_payload_update()?;
 		let (payload_receipts_root, payload_receipts) = context.get_payload_receipts()?;
 
@@ -711,6 +932,8 @@ impl Backend {
 		&self,
 		commit_execution_params: ChainCommitExecutionParams,
 	) -> CommonResult<()> {
+		let _guard = self.commit_execution_lock.write();
+
 		debug!("Commit execution params: {:?}", commit_execution_params);
 
 		let mut transaction = DBTransaction::new();
@@ -719,6 +942,16 @@ impl Backend {
 		let block_hash = commit_execution_params.block_hash.clone();
 		let payload_tx_count = commit_execution_params.payload_receipts.len();
 
+		if self.config.index {
+			let mut block_index = BlockIndex::default();
+			self.build_block_index(
+				&mut block_index,
+				&block_hash,
+				&commit_execution_params.payload_receipts,
+			)?;
+			transaction.extend(block_index.prepare_commit(&self.db, number)?);
+		}
+
 		commit_execution(&mut transaction, commit_execution_params)?;
 
 		self.db.write(transaction)?;
@@ -733,28 +966,895 @@ impl Backend {
 		Ok(())
 	}
 
+	/// Prune the state of the blocks until the given number (inclusive)
+	/// the trie nodes only used by the pruned state are removed
+	pub fn prune_state(&self, number: BlockNumber) -> CommonResult<()> {
+		let mut pruned = 0;
+		{
+			let from = self.meta_statedb.get_pruned_number()?.map_or(0, |x| x + 1);
+			for current in from..=number {
+				// avoid pruning the trie nodes inserted again by a committing block
+				let _guard = self.commit_block_lock.write();
+				self.meta_statedb.prune(current)?;
+				pruned += 1;
+			}
+		}
+		{
+			let from = self
+				.payload_statedb
+				.get_pruned_number()?
+				.map_or(0, |x| x + 1);
+			for current in from..=number {
+				let _guard = self.commit_execution_lock.write();
+				self.payload_statedb.prune(current)?;
+				pruned += 1;
+			}
+		}
+		if pruned > 0 {
+			debug!("State pruned: block number: {}", number);
+		}
+		Ok(())
+	}
+
+	/// Get the state snapshot at the given confirmed block number
+	/// the state since the execution number of the block should not have been pruned
+	pub fn get_snapshot(&self, number: BlockNumber) -> CommonResult<Snapshot> {
+		let confirmed_number = self
+			.get_confirmed_number()?
+			.ok_or_else(|| errors::ErrorKind::Data("Confirmed number not found".to_string()))?;
+		if number == 0 || number > confirmed_number {
+			return Err(errors::ErrorKind::Snapshot(format!(
+				"Invalid number: {}, confirmed number: {}",
+				number, confirmed_number
+			))
+			.into());
+		}
+
+		let blocks = {
+			let block = self.get_snapshot_block(number)?;
+			let execution_number = number - block.header.payload_execution_gap as BlockNumber;
+			let mut blocks = (execution_number..number)
+				.map(|x| self.get_snapshot_block(x))
+				.collect::<CommonResult<Vec<_>>>()?;
+			blocks.push(block);
+			blocks
+		};
+
+		let mut meta_states = Vec::with_capacity(blocks.len() - 1);
+		let mut last_meta_state: Option<Vec<(DBKey, DBValue)>> = None;
+		for block in &blocks[1..] {
+			let meta_state = self
+				.meta_statedb
+				.iter_prefix(&block.header.meta_state_root.0, &[])?;
+			let changes = match &last_meta_state {
+				Some(last_meta_state) => diff_state(last_meta_state, &meta_state),
+				None => meta_state
+					.iter()
+					.map(|(k, v)| (k.to_vec(), Some(v.clone())))
+					.collect(),
+			};
+			meta_states.push(changes);
+			last_meta_state = Some(meta_state);
+		}
+
+		Ok(Snapshot {
+			blocks,
+			meta_states,
+		})
+	}
+
+	/// Get a chunk of the payload state at the execution number of the snapshot block
+	/// from the given start key, until the size of the chunk reaches max bytes,
+	/// an empty chunk is returned after the last key
+	pub fn get_snapshot_payload_state(
+		&self,
+		snapshot: &Snapshot,
+		start: &[u8],
+		max_bytes: usize,
+	) -> CommonResult<Vec<(Vec<u8>, DBValue)>> {
+		let payload_state_root = &snapshot
+			.blocks
+			.last()
+			.ok_or_else(|| errors::ErrorKind::Snapshot("Missing blocks".to_string()))?
+			.header
+			.payload_execution_state_root;
+		let chunk = self
+			.payload_statedb
+			.iter_from(&payload_state_root.0, start, max_bytes)?
+			.into_iter()
+			.map(|(k, v)| (k.to_vec(), v))
+			.collect();
+		Ok(chunk)
+	}
+
+	/// Restore the chain from a state snapshot
+	/// the blocks are linked by the parent hash to the snapshot block,
+	/// and the state is rebuilt and verified against the roots in the block headers
+	/// the chain should have only the genesis block
+	pub fn restore_snapshot<I>(&self, snapshot: Snapshot, payload_state: I) -> CommonResult<()>
+	where
+		I: Iterator<Item = CommonResult<Vec<(Vec<u8>, DBValue)>>>,
+	{
+		let _guard = self.commit_block_lock.write();
+		let _execution_guard = self.commit_execution_lock.write();
+
+		let confirmed_number = self
+			.get_confirmed_number()?
+			.ok_or_else(|| errors::ErrorKind::Data("Confirmed number not found".to_string()))?;
+		if confirmed_number != 0 {
+			return Err(errors::ErrorKind::Snapshot(format!(
+				"Chain is not empty: confirmed number: {}",
+				confirmed_number
+			))
+			.into());
+		}
+
+		let Snapshot {
+			blocks,
+			meta_states,
+		} = snapshot;
+
+		let header = &blocks
+			.last()
+			.ok_or_else(|| errors::ErrorKind::Snapshot("Missing blocks".to_string()))?
+			.header;
+		let number = header.number;
+		let execution_number = number
+			.checked_sub(header.payload_execution_gap as BlockNumber)
+			.filter(|x| *x < number)
+			.ok_or_else(|| {
+				errors::ErrorKind::Snapshot(format!("Invalid snapshot block: number: {}", number))
+			})?;
+		let execution = Execution {
+			payload_execution_state_root: header.payload_execution_state_root.clone(),
+			payload_execution_receipts_root: header.payload_execution_receipts_root.clone(),
+		};
+		if blocks.len() as u64 != number - execution_number + 1
+			|| meta_states.len() as u64 != number - execution_number
+		{
+			return Err(errors::ErrorKind::Snapshot(format!(
+				"Mismatched length: blocks: {}, meta states: {}",
+				blocks.len(),
+				meta_states.len()
+			))
+			.into());
+		}
+
+		// 1. blocks
+		let mut parent_hash = None;
+		for (block, expected_number) in blocks.iter().zip(execution_number..) {
+			if block.header.number != expected_number
+				|| self.hash(&block.header)? != block.block_hash
+				|| parent_hash
+					.map(|x| x != &block.header.parent_hash)
+					.unwrap_or(false)
+			{
+				return Err(errors::ErrorKind::Snapshot(format!(
+					"Invalid block: number: {}",
+					expected_number
+				))
+				.into());
+			}
+			parent_hash = Some(&block.block_hash);
+		}
+		let genesis_hash = self.get_current_state().genesis_hash.clone();
+		if execution_number == 0 && blocks[0].block_hash != genesis_hash {
+			return Err(errors::ErrorKind::Snapshot("Mismatched genesis block".to_string()).into());
+		}
+
+		// 2. meta state
+		// the trie nodes are written before the whole snapshot is verified,
+		// as updating the trie reads the nodes of the former root from the db,
+		// they are addressed by hash so the nodes of an invalid snapshot do no harm
+		let mut meta_state_root = self.meta_statedb.default_root();
+		for (changes, block) in meta_states.into_iter().zip(&blocks[1..]) {
+			let data = changes
+				.into_iter()
+				.map(|(k, v)| (DBKey::from_vec(k), v))
+				.collect::<Vec<_>>();
+			let (root, transaction) = self.meta_statedb.prepare_update(
+				&meta_state_root,
+				data.iter().map(|(k, v)| (k, v)),
+				block.header.number,
+			)?;
+			if root != block.header.meta_state_root.0 {
+				return Err(errors::ErrorKind::Snapshot(format!(
+					"Mismatched meta state root: number: {}",
+					block.header.number
+				))
+				.into());
+			}
+			self.db.write(transaction)?;
+			meta_state_root = root;
+		}
+
+		// 3. payload state, updated chunk by chunk
+		let mut payload_state_root = self.payload_statedb.default_root();
+		for chunk in payload_state {
+			let data = chunk?
+				.into_iter()
+				.map(|(k, v)| (DBKey::from_vec(k), Some(v)))
+				.collect::<Vec<_>>();
+			let (root, transaction) = self.payload_statedb.prepare_update(
+				&payload_state_root,
+				data.iter().map(|(k, v)| (k, v)),
+				execution_number,
+			)?;
+			self.db.write(transaction)?;
+			payload_state_root = root;
+		}
+		if payload_state_root != execution.payload_execution_state_root.0 {
+			return Err(errors::ErrorKind::Snapshot(format!(
+				"Mismatched payload state root: number: {}",
+				execution_number
+			))
+			.into());
+		}
+
+		// 4. blocks and the execution
+		let mut transaction = DBTransaction::new();
+		for block in blocks {
+			let number = block.header.number;
+			let meta_txs = self.get_snapshot_txs(block.meta_txs)?;
+			let payload_txs = self.get_snapshot_txs(block.payload_txs)?;
+			let meta_txs_root = self.calc_txs_root(&meta_txs)?;
+			let payload_txs_root = self.calc_txs_root(&payload_txs)?;
+			if meta_txs_root != block.header.meta_txs_root
+				|| payload_txs_root != block.header.payload_txs_root
+			{
+				return Err(errors::ErrorKind::Snapshot(format!(
+					"Mismatched txs root: number: {}",
+					number
+				))
+				.into());
+			}
+
+			if number == execution_number {
+				match number {
+					// the genesis execution is kept
+					0 => {
+						if self.get_execution(&genesis_hash)?.as_ref() != Some(&execution) {
+							return Err(errors::ErrorKind::Snapshot(
+								"Mismatched genesis execution".to_string(),
+							)
+							.into());
+						}
+					}
+					_ => commit_execution(
+						&mut transaction,
+						ChainCommitExecutionParams {
+							block_hash: block.block_hash.clone(),
+							number,
+							execution: execution.clone(),
+							payload_receipts: vec![],
+							payload_transaction: DBTransaction::new(),
+						},
+					)?,
+				}
+			}
+			if number == 0 {
+				continue;
+			}
+
+			let commit_block_params = ChainCommitBlockParams {
+				block_hash: block.block_hash,
+				body: Body {
+					meta_txs: meta_txs.iter().map(|x| x.tx_hash.clone()).collect(),
+					payload_txs: payload_txs.iter().map(|x| x.tx_hash.clone()).collect(),
+				},
+				header: block.header,
+				meta_txs,
+				meta_receipts: vec![],
+				payload_txs,
+				meta_transaction: DBTransaction::new(),
+				proof: block.proof,
+			};
+			commit_block(&mut transaction, commit_block_params)?;
+		}
+		self.db.write(transaction)?;
+
+		info!(
+			"Snapshot restored: block number: {}, execution number: {}",
+			number, execution_number
+		);
+
+		self.update_current_state()?;
+
+		Ok(())
+	}
+
+	/// Revert the chain to the given block number
+	/// the blocks above the number are removed with their transactions, receipts,
+	/// executions and proofs, while the trie nodes and the consensus data are kept
+	pub fn revert(&self, number: BlockNumber) -> CommonResult<RevertSummary> {
+		let _guard = self.commit_block_lock.write();
+		let _execution_guard = self.commit_execution_lock.write();
+
+		let confirmed_number = self
+			.get_confirmed_number()?
+			.ok_or_else(|| errors::ErrorKind::Data("Confirmed number not found".to_string()))?;
+		let execution_number = self
+			.get_execution_number()?
+			.ok_or_else(|| errors::ErrorKind::Data("Executed number not found".to_string()))?;
+		if number >= confirmed_number {
+			return Err(errors::ErrorKind::Revert(format!(
+				"Invalid number: {}, confirmed number: {}",
+				number, confirmed_number
+			))
+			.into());
+		}
+		let reverted_execution_number = execution_number.min(number);
+
+		// the state of a pruned block is incomplete
+		let pruned = |statedb: &StateDB, number: BlockNumber| -> CommonResult<bool> {
+			Ok(statedb.get_pruned_number()?.map_or(false, |x| number <= x))
+		};
+		if pruned(&self.meta_statedb, number)?
+			|| pruned(&self.payload_statedb, reverted_execution_number)?
+		{
+			return Err(
+				errors::ErrorKind::Revert(format!("State pruned: number: {}", number)).into(),
+			);
+		}
+
+		let mut summary = RevertSummary {
+			from: confirmed_number,
+			to: number,
+			..Default::default()
+		};
+		let mut transaction = DBTransaction::new();
+		let mut block_index = BlockIndex::default();
+		for current in (number + 1)..=confirmed_number {
+			let block_hash = self.get_block_hash(&current)?.ok_or_else(|| {
+				errors::ErrorKind::Data(format!("Invalid block number: {}", current))
+			})?;
+			let body = self.get_body(&block_hash)?.ok_or_else(|| {
+				errors::ErrorKind::Data(format!("Invalid block hash: {}", block_hash))
+			})?;
+
+			for tx_hash in body.meta_txs.iter().chain(body.payload_txs.iter()) {
+				transaction.delete(node_db::columns::TX, &tx_hash.0);
+				summary.txs += 1;
+				if self.get_receipt(tx_hash)?.is_some() {
+					transaction.delete(node_db::columns::RECEIPT, &tx_hash.0);
+					summary.receipts += 1;
+				}
+			}
+
+			if self.get_execution(&block_hash)?.is_some() {
+				// the index may be kept before, even if disabled now
+				self.build_block_index(&mut block_index, &block_hash, &[])?;
+				transaction.delete(node_db::columns::EXECUTION, &block_hash.0);
+				summary.executions += 1;
+			}
+
+			transaction.delete(node_db::columns::HEADER, &block_hash.0);
+			transaction.delete(node_db::columns::META_TXS, &block_hash.0);
+			transaction.delete(node_db::columns::PAYLOAD_TXS, &block_hash.0);
+			transaction.delete(node_db::columns::PROOF, &block_hash.0);
+			transaction.delete(node_db::columns::BLOCK_HASH, &codec::encode(&current)?);
+			summary.blocks += 1;
+
+			transaction.extend(self.meta_statedb.prepare_revert(current)?);
+			transaction.extend(self.payload_statedb.prepare_revert(current)?);
+		}
+
+		transaction.extend(block_index.prepare_revert(&self.db, reverted_execution_number)?);
+
+		transaction.put_owned(
+			node_db::columns::GLOBAL,
+			DBKey::from_slice(node_db::global_key::CONFIRMED_NUMBER),
+			codec::encode(&number)?,
+		);
+		transaction.put_owned(
+			node_db::columns::GLOBAL,
+			DBKey::from_slice(node_db::global_key::EXECUTION_NUMBER),
+			codec::encode(&reverted_execution_number)?,
+		);
+
+		self.db.write(transaction)?;
+
+		info!(
+			"Chain reverted: from: {}, to: {}, blocks: {}, txs: {}, receipts: {}, executions: {}",
+			summary.from,
+			summary.to,
+			summary.blocks,
+			summary.txs,
+			summary.receipts,
+			summary.executions
+		);
+
+		self.update_current_state()?;
+
+		Ok(summary)
+	}
+
+	/// Create a checkpoint of the db with the chain status,
+	/// no block or execution is committed meanwhile, so that the status matches the db
+	pub fn create_checkpoint(&self, path: &Path) -> CommonResult<Checkpoint> {
+		let _guard = self.commit_block_lock.write();
+		let _execution_guard = self.commit_execution_lock.write();
+
+		if path.exists() {
+			return Err(
+				errors::ErrorKind::Checkpoint(format!("Path already exists: {:?}", path)).into(),
+			);
+		}
+
+		let confirmed_number = self
+			.get_confirmed_number()?
+			.ok_or_else(|| errors::ErrorKind::Data("Confirmed number not found".to_string()))?;
+		let execution_number = self
+			.get_execution_number()?
+			.ok_or_else(|| errors::ErrorKind::Data("Executed number not found".to_string()))?;
+		let genesis_hash = self
+			.get_block_hash(&0)?
+			.ok_or_else(|| errors::ErrorKind::Data("Genesis block not found".to_string()))?;
+		let checkpoint = Checkpoint {
+			genesis_hash,
+			confirmed_number,
+			execution_number,
+		};
+
+		let write = || -> CommonResult<()> {
+			fs::create_dir_all(path).map_err(|e| {
+				errors::ErrorKind::Checkpoint(format!("Failed to create dir: {:?}: {}", path, e))
+			})?;
+			self.db.checkpoint(&path.join(CHECKPOINT_DB))?;
+
+			let file = path.join(CHECKPOINT_FILE);
+			let content = toml::to_string(&checkpoint).map_err(|e| {
+				errors::ErrorKind::Checkpoint(format!("Failed to encode checkpoint: {}", e))
+			})?;
+			fs::write(&file, content).map_err(|e| {
+				errors::ErrorKind::Checkpoint(format!("Failed to write file: {:?}: {}", file, e))
+			})?;
+			Ok(())
+		};
+
+		// an incomplete checkpoint is removed
+		if let Err(e) = write() {
+			let _ = fs::remove_dir_all(path);
+			return Err(e);
+		}
+
+		info!(
+			"Checkpoint created: path: {:?}, confirmed number: {}, execution number: {}",
+			path, confirmed_number, execution_number
+		);
+
+		Ok(checkpoint)
+	}
+
+	/// Verify the block of the number against the data it commits to:
+	/// the block hash, the parent hash, the transactions and the receipts,
+	/// the execution of the block and the execution committed by the header,
+	/// and the state roots by executing the block again if `execute`
+	pub fn verify_block(&self, number: BlockNumber, execute: bool) -> CommonResult<()> {
+		let verify_error = |msg: String| -> CommonError { errors::ErrorKind::Verify(msg).into() };
+
+		let block_hash = self
+			.get_block_hash(&number)?
+			.ok_or_else(|| verify_error("Missing block hash".to_string()))?;
+		let block = self
+			.get_block(&block_hash)?
+			.ok_or_else(|| verify_error(format!("Missing block: block hash: {}", block_hash)))?;
+		let header = &block.header;
+
+		let header_hash = self.hash(header)?;
+		if header_hash != block_hash || header.number != number {
+			return Err(verify_error(format!(
+				"Mismatched header: block hash: {}, header hash: {}, header number: {}",
+				block_hash, header_hash, header.number
+			)));
+		}
+
+		if number > 0 {
+			let parent_hash = self
+				.get_block_hash(&(number - 1))?
+				.ok_or_else(|| verify_error("Missing parent block hash".to_string()))?;
+			if header.parent_hash != parent_hash {
+				return Err(verify_error(format!(
+					"Mismatched parent hash: {}, expected: {}",
+					header.parent_hash, parent_hash
+				)));
+			}
+		}
+
+		let get_txs = |tx_hashes: &[Hash]| -> CommonResult<Vec<Arc<FullTransaction>>> {
+			tx_hashes
+				.iter()
+				.map(|tx_hash| {
+					let tx = self.get_transaction(tx_hash)?.ok_or_else(|| {
+						verify_error(format!("Missing transaction: tx_hash: {}", tx_hash))
+					})?;
+					if &self.hash_transaction(&tx)? != tx_hash {
+						return Err(verify_error(format!(
+							"Mismatched transaction: tx_hash: {}",
+							tx_hash
+						)));
+					}
+					Ok(Arc::new(FullTransaction {
+						tx,
+						tx_hash: tx_hash.clone(),
+					}))
+				})
+				.collect()
+		};
+		let calc_receipts_root = |tx_hashes: &[Hash]| -> CommonResult<Hash> {
+			let input = tx_hashes
+				.iter()
+				.map(|tx_hash| {
+					let receipt = self.get_receipt(tx_hash)?.ok_or_else(|| {
+						verify_error(format!("Missing receipt: tx_hash: {}", tx_hash))
+					})?;
+					codec::encode(&receipt)
+				})
+				.collect::<CommonResult<Vec<_>>>()?;
+			Ok(Hash(self.trie_root.calc_ordered_trie_root(input)))
+		};
+		let check_root = |name: &str, root: Hash, expected: &Hash| -> CommonResult<()> {
+			if &root != expected {
+				return Err(verify_error(format!(
+					"Mismatched {}: {}, expected: {}",
+					name, root, expected
+				)));
+			}
+			Ok(())
+		};
+
+		let meta_txs = get_txs(&block.body.meta_txs)?;
+		let payload_txs = get_txs(&block.body.payload_txs)?;
+		check_root(
+			"meta_txs_root",
+			self.calc_txs_root(&meta_txs)?,
+			&header.meta_txs_root,
+		)?;
+		check_root(
+			"payload_txs_root",
+			self.calc_txs_root(&payload_txs)?,
+			&header.payload_txs_root,
+		)?;
+		check_root(
+			"meta_receipts_root",
+			calc_receipts_root(&block.body.meta_txs)?,
+			&header.meta_receipts_root,
+		)?;
+
+		// the payload receipts are only available after the block is executed
+		let execution = self.get_execution(&block_hash)?;
+		if let Some(execution) = &execution {
+			check_root(
+				"payload_execution_receipts_root",
+				calc_receipts_root(&block.body.payload_txs)?,
+				&execution.payload_execution_receipts_root,
+			)?;
+		}
+
+		// the header of the genesis block commits no execution
+		if number == 0 {
+			if execute {
+				self.verify_genesis_block(&block_hash, execution.as_ref())?;
+			}
+			return Ok(());
+		}
+
+		let execution_number = number
+			.checked_sub(header.payload_execution_gap as BlockNumber)
+			.filter(|_| header.payload_execution_gap > 0)
+			.ok_or_else(|| {
+				verify_error(format!(
+					"Invalid execution gap: {}",
+					header.payload_execution_gap
+				))
+			})?;
+		let committed_execution = self
+			.get_block_hash(&execution_number)?
+			.map(|x| self.get_execution(&x))
+			.transpose()?
+			.flatten()
+			.ok_or_else(|| {
+				verify_error(format!(
+					"Missing execution: block number: {}",
+					execution_number
+				))
+			})?;
+		check_root(
+			"payload_execution_state_root",
+			committed_execution.payload_execution_state_root,
+			&header.payload_execution_state_root,
+		)?;
+		check_root(
+			"payload_execution_receipts_root",
+			committed_execution.payload_execution_receipts_root,
+			&header.payload_execution_receipts_root,
+		)?;
+
+		if !execute {
+			return Ok(());
+		}
+
+		// executing the block needs the meta state of the parent block,
+		// and the payload state of the execution committed by the header and the parent block
+		let pruned = |statedb: &StateDB, number: BlockNumber| -> CommonResult<bool> {
+			Ok(statedb.get_pruned_number()?.map_or(false, |x| number <= x))
+		};
+		if pruned(&self.meta_statedb, number - 1)?
+			|| pruned(&self.payload_statedb, execution_number)?
+		{
+			return Err(errors::ErrorKind::StatePruning(format!(
+				"State pruned: number: {}",
+				number
+			))
+			.into());
+		}
+
+		let commit_block_params = self.build_block(BuildBlockParams {
+			number,
+			timestamp: header.timestamp,
+			author: header.author.clone(),
+			meta_txs,
+			payload_txs: payload_txs.clone(),
+			execution_number,
+		})?;
+		if &commit_block_params.header != header {
+			return Err(verify_error(format!(
+				"Mismatched header by executing: {:?}, expected: {:?}",
+				commit_block_params.header, header
+			)));
+		}
+
+		let execution = match execution {
+			Some(execution) => execution,
+			None => return Ok(()),
+		};
+		let parent_execution = self.get_execution(&header.parent_hash)?.ok_or_else(|| {
+			verify_error(format!("Missing execution: block number: {}", number - 1))
+		})?;
+		let commit_execution_params = self.build_execution(BuildExecutionParams {
+			number,
+			timestamp: header.timestamp,
+			author: header.author.clone(),
+			block_hash,
+			meta_state_root: header.meta_state_root.clone(),
+			payload_state_root: parent_execution.payload_execution_state_root,
+			payload_txs,
+		})?;
+		if commit_execution_params.execution != execution {
+			return Err(verify_error(format!(
+				"Mismatched execution by executing: {:?}, expected: {:?}",
+				commit_execution_params.execution, execution
+			)));
+		}
+
+		Ok(())
+	}
+
+	/// Get the transactions sent or received by the address from the address index
+	pub fn get_address_transactions(
+		&self,
+		address: &Address,
+		offset: u64,
+		limit: u64,
+	) -> CommonResult<IndexPage> {
+		index::get_address_transactions(&self.db, address, offset, limit)
+	}
+
+	/// Get the receipts with the events matching the filter from the event index
+	pub fn get_events(
+		&self,
+		filter: &EventFilter,
+		from: BlockNumber,
+		to: BlockNumber,
+		offset: u64,
+		limit: u64,
+	) -> CommonResult<IndexPage> {
+		index::get_events(&self.db, filter, from, to, offset, limit)
+	}
+
 	pub fn message_rx(&self) -> Option<UnboundedReceiver<ChainOutMessage>> {
 		self.message_rx.write().take()
 	}
 
+	/// Subscribe the out messages besides message_rx
+	/// the subscriber is dropped once its receiver is dropped
+	pub fn subscribe_message(&self) -> UnboundedReceiver<ChainOutMessage> {
+		let (tx, rx) = unbounded();
+		self.message_subscribers.write().push(tx);
+		rx
+	}
+
+	/// Locate the transaction in the body of the block in its receipt
+	fn locate_transaction(&self, tx_hash: &Hash) -> CommonResult<Option<TxLocation>> {
+		let receipt = match self.get_receipt(tx_hash)? {
+			Some(receipt) => receipt,
+			None => return Ok(None),
+		};
+		let number = receipt.block_number;
+		let block_hash = self
+			.get_block_hash(&number)?
+			.ok_or_else(|| errors::ErrorKind::Data(format!("Invalid block number: {}", number)))?;
+		let block = self.get_block(&block_hash)?.ok_or_else(|| {
+			errors::ErrorKind::Data(format!("Invalid block hash: {}", block_hash))
+		})?;
+
+		let (meta, tx_hashes) = match block.body.meta_txs.contains(tx_hash) {
+			true => (true, block.body.meta_txs),
+			false => (false, block.body.payload_txs),
+		};
+		let index = tx_hashes.iter().position(|x| x == tx_hash).ok_or_else(|| {
+			errors::ErrorKind::Data(format!(
+				"Transaction not in block: tx_hash: {}, block_hash: {}",
+				tx_hash, block_hash
+			))
+		})?;
+
+		Ok(Some(TxLocation {
+			block_hash,
+			header: block.header,
+			meta,
+			index: index as u32,
+			tx_hashes,
+		}))
+	}
+
+	/// Get the first block whose header commits the execution of the given block number,
+	/// i.e. number - payload_execution_gap == execution_number
+	fn get_committing_block(
+		&self,
+		execution_number: BlockNumber,
+	) -> CommonResult<Option<(Hash, Header)>> {
+		// the execution numbers of the headers never decrease
+		let mut number = execution_number + 1;
+		while let Some(block_hash) = self.get_block_hash(&number)? {
+			let header = self.get_header(&block_hash)?.ok_or_else(|| {
+				errors::ErrorKind::Data(format!("Invalid block hash: {}", block_hash))
+			})?;
+			match (number - header.payload_execution_gap as BlockNumber).cmp(&execution_number) {
+				Ordering::Less => number += 1,
+				Ordering::Equal => return Ok(Some((block_hash, header))),
+				Ordering::Greater => return Ok(None),
+			}
+		}
+		Ok(None)
+	}
+
+	/// Build the proof of the item at the given index of the ordered trie
+	fn build_inclusion_proof(
+		&self,
+		block_hash: Hash,
+		root: Hash,
+		meta: bool,
+		index: u32,
+		input: Vec<Vec<u8>>,
+	) -> CommonResult<InclusionProof> {
+		// the proof is useless if the items do not match the root in the header
+		if self.trie_root.calc_ordered_trie_root(&input) != root.0 {
+			return Err(errors::ErrorKind::Data(format!(
+				"Mismatched root: block_hash: {}",
+				block_hash
+			))
+			.into());
+		}
+		let proof = self.trie_root.calc_ordered_trie_proof(&input, index)?;
+		let key = codec::encode(&index)?;
+		let value = input[index as usize].clone();
+
+		Ok(InclusionProof {
+			block_hash,
+			root,
+			meta,
+			key,
+			value,
+			proof,
+		})
+	}
+
+	/// Add the transactions of an executed block to the index
+	/// the receipts not in `payload_receipts` are read from the db
+	fn build_block_index(
+		&self,
+		block_index: &mut BlockIndex,
+		block_hash: &Hash,
+		payload_receipts: &[Arc<FullReceipt>],
+	) -> CommonResult<()> {
+		let body = self.get_body(block_hash)?.ok_or_else(|| {
+			errors::ErrorKind::Data(format!("Invalid block hash: {}", block_hash))
+		})?;
+		let payload_receipts = payload_receipts
+			.iter()
+			.map(|x| (&x.tx_hash, &x.receipt))
+			.collect::<HashMap<_, _>>();
+
+		for tx_hash in body.meta_txs.iter().chain(body.payload_txs.iter()) {
+			let tx = self
+				.get_transaction(tx_hash)?
+				.ok_or_else(|| errors::ErrorKind::Data(format!("Invalid tx hash: {}", tx_hash)))?;
+			let sender = tx.witness.as_ref().map(|x| self.get_sender(x));
+			let events = match payload_receipts.get(tx_hash) {
+				Some(receipt) => receipt.events.clone(),
+				None => self
+					.get_receipt(tx_hash)?
+					.map(|x| x.events)
+					.unwrap_or_default(),
+			};
+			block_index.add(tx_hash, sender.as_ref(), &tx.call.module, &events)?;
+		}
+		Ok(())
+	}
+
+	/// Get the block with the full transactions by block number for the snapshot
+	fn get_snapshot_block(&self, number: BlockNumber) -> CommonResult<SnapshotBlock> {
+		let block_hash = self
+			.get_block_hash(&number)?
+			.ok_or_else(|| errors::ErrorKind::Data(format!("Invalid block number: {}", number)))?;
+		let block = self.get_block(&block_hash)?.ok_or_else(|| {
+			errors::ErrorKind::Data(format!("Invalid block hash: {}", block_hash))
+		})?;
+		let proof = self.get_proof(&block_hash)?.unwrap_or_default();
+
+		let get_txs = |tx_hashes: Vec<Hash>| -> CommonResult<Vec<Transaction>> {
+			tx_hashes
+				.into_iter()
+				.map(|x| {
+					self.get_transaction(&x)?.ok_or_else(|| {
+						errors::ErrorKind::Data(format!("Missing transaction: tx_hash: {}", x))
+							.into()
+					})
+				})
+				.collect()
+		};
+
+		Ok(SnapshotBlock {
+			block_hash,
+			header: block.header,
+			meta_txs: get_txs(block.body.meta_txs)?,
+			payload_txs: get_txs(block.body.payload_txs)?,
+			proof,
+		})
+	}
+
+	fn get_snapshot_txs(&self, txs: Vec<Transaction>) -> CommonResult<Vec<Arc<FullTransaction>>> {
+		txs.into_iter()
+			.map(|tx| {
+				let tx_hash = self.hash_transaction(&tx)?;
+				Ok(Arc::new(FullTransaction { tx, tx_hash }))
+			})
+			.collect()
+	}
+
+	fn calc_txs_root(&self, txs: &[Arc<FullTransaction>]) -> CommonResult<Hash> {
+		let input = txs
+			.iter()
+			.map(|x| codec::encode(&TransactionForHash::new(&x.tx)))
+			.collect::<CommonResult<Vec<_>>>()?;
+		Ok(Hash(self.trie_root.calc_ordered_trie_root(input)))
+	}
+
 	fn on_block_committed(&self, number: u64, block_hash: Hash) -> CommonResult<()> {
 		self.update_current_state()?;
-		self.message_tx
-			.unbounded_send(ChainOutMessage::BlockCommitted {
-				number,
-				hash: block_hash,
-			})
-			.map_err(|e| ErrorKind::Channel(Box::new(e)))?;
+		self.send_message(ChainOutMessage::BlockCommitted {
+			number,
+			hash: block_hash,
+		})?;
 		Ok(())
 	}
 
 	fn on_execution_committed(&self, number: u64, block_hash: Hash) -> CommonResult<()> {
 		self.update_current_state()?;
+		self.send_message(ChainOutMessage::ExecutionCommitted {
+			number,
+			hash: block_hash,
+		})?;
+		Ok(())
+	}
+
+	fn send_message(&self, message: ChainOutMessage) -> CommonResult<()> {
+		self.message_subscribers
+			.write()
+			.retain(|x| x.unbounded_send(message.clone()).is_ok());
 		self.message_tx
-			.unbounded_send(ChainOutMessage::ExecutionCommitted {
-				number,
-				hash: block_hash,
-			})
+			.unbounded_send(message)
 			.map_err(|e| ErrorKind::Channel(Box::new(e)))?;
 		Ok(())
 	}
@@ -789,7 +1889,11 @@ impl Backend {
 		let meta_state_root = header.meta_state_root;
 		let payload_state_root = execution.payload_execution_state_root;
 
-		let env = ContextEnv { number, timestamp };
+		let env = ContextEnv {
+			number,
+			timestamp,
+			author: None,
+		};
 
 		let context_essence = ContextEssence::new(
 			env,
@@ -800,6 +1904,8 @@ impl Backend {
 			payload_state_root,
 		)?;
 
+		// the meta may be updated by governance,
+		// so it is reloaded from the confirmed block and takes effect from the next block
 		let system_meta = self
 			.execute_call_with_block_hash(
 				&confirmed_block_hash,
@@ -829,6 +1935,26 @@ impl Backend {
 		Ok(())
 	}
 
+	/// Check the state pruning mode is the same as the one the db is created with
+	/// the db created before the state pruning was introduced is treated as archive
+	fn check_state_pruning(db: &DB, pruning: &StatePruning) -> CommonResult<()> {
+		let name = db.get_with(
+			node_db::columns::GLOBAL,
+			node_db::global_key::STATE_PRUNING,
+			|x| codec::decode::<String>(&mut &x[..]),
+		)?;
+		let name = name.unwrap_or_else(|| StatePruning::Archive.name().to_string());
+		if name != pruning.name() {
+			return Err(errors::ErrorKind::StatePruning(format!(
+				"Mismatched mode: db: {}, config: {}",
+				name,
+				pruning.name()
+			))
+			.into());
+		}
+		Ok(())
+	}
+
 	/// Get the spec
 	/// from the db if the chain is inited
 	/// from the spec file if the chain is not inited
@@ -876,13 +2002,68 @@ impl Backend {
 		let spec_str = fs::read_to_string(&spec_path).map_err(|_| {
 			errors::ErrorKind::Spec(format!("Failed to read spec file: {:?}", spec_path))
 		})?;
-		let spec: Spec = toml::from_str(&spec_str)
+
+		let (commit_block_params, commit_execution_params) = self.build_genesis_block(&spec_str)?;
+		let block_hash = commit_block_params.block_hash.clone();
+		let number = commit_block_params.header.number;
+
+		let mut transaction = DBTransaction::new();
+
+		commit_block(&mut transaction, commit_block_params)?;
+
+		commit_execution(&mut transaction, commit_execution_params)?;
+
+		commit_spec(&mut transaction, &spec_str)?;
+
+		commit_state_pruning(&mut transaction, &self.config.pruning)?;
+
+		self.db.write(transaction)?;
+
+		info!("Genesis block inited: block hash: {:?}", block_hash);
+
+		self.on_execution_committed(number, block_hash)?;
+
+		Ok(())
+	}
+
+	/// Verify the genesis block and its execution by building them again with the spec in the db
+	fn verify_genesis_block(
+		&self,
+		block_hash: &Hash,
+		execution: Option<&Execution>,
+	) -> CommonResult<()> {
+		let spec_str = self
+			.db
+			.get_with(node_db::columns::GLOBAL, node_db::global_key::SPEC, |x| {
+				codec::decode::<String>(&mut &x[..])
+			})?
+			.ok_or_else(|| errors::ErrorKind::Spec("Missing spec in db".to_string()))?;
+		let (commit_block_params, commit_execution_params) = self.build_genesis_block(&spec_str)?;
+		if &commit_block_params.block_hash != block_hash
+			|| Some(&commit_execution_params.execution) != execution
+		{
+			return Err(errors::ErrorKind::Verify(format!(
+				"Mismatched genesis block by the spec: block hash: {}, expected: {}",
+				block_hash, commit_block_params.block_hash
+			))
+			.into());
+		}
+		Ok(())
+	}
+
+	/// Build the genesis block and its execution by the spec
+	fn build_genesis_block(
+		&self,
+		spec_str: &str,
+	) -> CommonResult<(ChainCommitBlockParams, ChainCommitExecutionParams)> {
+		let spec: Spec = toml::from_str(spec_str)
 			.map_err(|e| errors::ErrorKind::Spec(format!("Failed to parse spec file: {:?}", e)))?;
 
 		// build genesis
 		let env = ContextEnv {
 			number: 0,
 			timestamp: 0,
+			author: None,
 		};
 		let build_genesis_context_essence = ContextEssence::new(
 			env,
@@ -897,6 +2078,7 @@ impl Backend {
 		let BuildBlockParams {
 			number,
 			timestamp,
+			author,
 			meta_txs,
 			payload_txs,
 			execution_number,
@@ -905,7 +2087,11 @@ impl Backend {
 		// execute genesis
 		let zero_hash = self.executor.default_hash();
 
-		let env = ContextEnv { number, timestamp };
+		let env = ContextEnv {
+			number,
+			timestamp,
+			author: author.clone(),
+		};
 		let context_essence = ContextEssence::new(
 			env,
 			self.trie_root.clone(),
@@ -948,6 +2134,7 @@ impl Backend {
 		let header = Header {
 			number,
 			timestamp,
+			author,
 			parent_hash: zero_hash.clone(),
 			meta_txs_root,
 			meta_state_root,
@@ -980,29 +2167,15 @@ impl Backend {
 			payload_execution_receipts_root: payload_receipts_root,
 		};
 
-		let mut transaction = DBTransaction::new();
-
-		commit_block(&mut transaction, commit_block_params)?;
-
 		let commit_execution_params = ChainCommitExecutionParams {
-			block_hash: block_hash.clone(),
+			block_hash,
 			number,
 			execution,
 			payload_receipts,
 			payload_transaction,
 		};
 
-		commit_execution(&mut transaction, commit_execution_params)?;
-
-		commit_spec(&mut transaction, &spec_str)?;
-
-		self.db.write(transaction)?;
-
-		info!("Genesis block inited: block hash: {:?}", block_hash);
-
-		self.on_execution_committed(number, block_hash)?;
-
-		Ok(())
+		Ok((commit_block_params, commit_execution_params))
 	}
 }
 
@@ -1122,6 +2295,30 @@ fn commit_execution(
 	Ok(())
 }
 
+/// Changed key-values from one state to another,
+/// the value is None if the key is removed
+fn diff_state(
+	from: &[(DBKey, DBValue)],
+	to: &[(DBKey, DBValue)],
+) -> Vec<(Vec<u8>, Option<DBValue>)> {
+	let mut changes = to
+		.iter()
+		.map(|(k, v)| (k.to_vec(), Some(v.clone())))
+		.collect::<BTreeMap<_, _>>();
+	for (k, v) in from {
+		match changes.get(&k[..]) {
+			Some(Some(value)) if value == v => {
+				changes.remove(&k[..]);
+			}
+			Some(_) => (),
+			None => {
+				changes.insert(k.to_vec(), None);
+			}
+		}
+	}
+	changes.into_iter().collect()
+}
+
 fn commit_spec(transaction: &mut DBTransaction, spec_str: &str) -> CommonResult<()> {
 	transaction.put_owned(
 		node_db::columns::GLOBAL,
@@ -1130,3 +2327,33 @@ fn commit_spec(transaction: &mut DBTransaction, spec_str: &str) -> CommonResult<
 	);
 	Ok(())
 }
+
+fn commit_state_pruning(
+	transaction: &mut DBTransaction,
+	pruning: &StatePruning,
+) -> CommonResult<()> {
+	transaction.put_owned(
+		node_db::columns::GLOBAL,
+		DBKey::from_slice(node_db::global_key::STATE_PRUNING),
+		codec::encode(&pruning.name())?,
+	);
+	Ok(())
+}
+
+/// Create a statedb, with the journal enabled if the state is pruned
+fn new_statedb(
+	db: Arc<DB>,
+	db_column: u32,
+	journal_column: u32,
+	hash: Arc<HashImpl>,
+	pruning: &StatePruning,
+) -> CommonResult<StateDB> {
+	match pruning {
+		StatePruning::Archive => StateDB::new(db, db_column, hash),
+		StatePruning::Pruned(0) => Err(errors::ErrorKind::StatePruning(
+			"Pruned mode should keep at least 1 block".to_string(),
+		)
+		.into()),
+		StatePruning::Pruned(_) => StateDB::new_with_journal(db, db_column, journal_column, hash),
+	}
+}
@@ -30,6 +30,24 @@ pub enum ErrorKind {
 	#[display(fmt = "Execute queue error: {}", _0)]
 	ExecuteQueue(String),
 
+	#[display(fmt = "State pruning error: {}", _0)]
+	StatePruning(String),
+
+	#[display(fmt = "Snapshot error: {}", _0)]
+	Snapshot(String),
+
+	#[display(fmt = "Revert error: {}", _0)]
+	Revert(String),
+
+	#[display(fmt = "Index error: {}", _0)]
+	Index(String),
+
+	#[display(fmt = "Checkpoint error: {}", _0)]
+	Checkpoint(String),
+
+	#[display(fmt = "Verify error: {}", _0)]
+	Verify(String),
+
 	#[display(fmt = "Channel error: {:?}", _0)]
 	Channel(Box<dyn Error + Send + Sync>),
 
@@ -58,6 +76,9 @@ pub enum ValidateTxError {
 	#[display(fmt = "Invalid tx until: {}", _0)]
 	InvalidTxUntil(String),
 
+	#[display(fmt = "Invalid tx size: {}", _0)]
+	InvalidTxSize(String),
+
 	#[display(fmt = "Invalid tx witness: {}", _0)]
 	InvalidTxWitness(String),
 
@@ -70,6 +91,12 @@ pub enum ValidateTxError {
 	#[display(fmt = "Invalid tx params: {}", _0)]
 	InvalidTxParams(String),
 
+	#[display(fmt = "Invalid tx fee: {}", _0)]
+	InvalidTxFee(String),
+
+	#[display(fmt = "Invalid tx nonce: {}", _0)]
+	InvalidTxNonce(String),
+
 	#[display(fmt = "{}", _0)]
 	Application(String),
 }
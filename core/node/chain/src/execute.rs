@@ -21,7 +21,7 @@ use futures::channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender};
 use log::{debug, warn};
 
 use primitives::errors::CommonResult;
-use primitives::{BlockNumber, BuildExecutionParams, FullTransaction, Hash};
+use primitives::{Address, BlockNumber, BuildExecutionParams, FullTransaction, Hash};
 
 use crate::backend::Backend;
 use crate::errors;
@@ -31,6 +31,7 @@ use futures::StreamExt;
 pub struct ExecuteTask {
 	pub number: BlockNumber,
 	pub timestamp: u64,
+	pub author: Option<Address>,
 	pub block_hash: Hash,
 	pub parent_hash: Hash,
 	pub meta_state_root: Hash,
@@ -156,6 +157,7 @@ impl TaskStream {
 				ExecuteTask {
 					number: current_number,
 					timestamp: block.header.timestamp,
+					author: block.header.author,
 					block_hash,
 					parent_hash: block.header.parent_hash,
 					meta_state_root: block.header.meta_state_root,
@@ -191,6 +193,7 @@ impl TaskStream {
 		let build_execution_params = BuildExecutionParams {
 			number: current_task.number,
 			timestamp: current_task.timestamp,
+			author: current_task.author,
 			block_hash: current_task.block_hash,
 			meta_state_root: current_task.meta_state_root,
 			payload_state_root: execution.payload_execution_state_root,
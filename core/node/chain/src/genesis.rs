@@ -26,10 +26,16 @@ use node_executor::{module, Context, Executor};
 use primitives::codec::Encode;
 use primitives::errors::{CommonError, CommonResult};
 use primitives::types::ExecutionGap;
-use primitives::{BlockNumber, BuildBlockParams, FullTransaction, Transaction};
+use primitives::{Address, Balance, BlockNumber, BuildBlockParams, FullTransaction, Transaction};
 
 use crate::errors;
 
+/// Max count of the txs in a block if not specified in the spec
+pub const DEFAULT_MAX_BLOCK_TXS: u32 = 10_000;
+
+/// Max sum of the encoded length of the txs in a block if not specified in the spec
+pub const DEFAULT_MAX_BLOCK_BYTES: u64 = 4 * 1024 * 1024;
+
 pub fn build_genesis(
 	spec: &Spec,
 	executor: &Executor,
@@ -60,6 +66,7 @@ pub fn build_genesis(
 	Ok(BuildBlockParams {
 		number,
 		timestamp,
+		author: None,
 		meta_txs,
 		payload_txs,
 		execution_number,
@@ -88,7 +95,8 @@ fn build_tx(
 			build_validate_tx(executor, context, module, method, module_params, params)
 		}
 		("poa", "init") => {
-			let module_params: module::poa::InitParams = get_module_params(params)?;
+			let module_params: module::poa::InitParams =
+				get_module_params::<PoaInitParams>(params)?.try_into()?;
 			build_validate_tx(executor, context, module, method, module_params, params)
 		}
 		("raft", "init") => {
@@ -138,7 +146,31 @@ pub struct SystemInitParams {
 	pub timestamp: String,
 	pub max_until_gap: BlockNumber,
 	pub max_execution_gap: ExecutionGap,
+	#[serde(default = "default_max_block_txs")]
+	pub max_block_txs: u32,
+	#[serde(default = "default_max_block_bytes")]
+	pub max_block_bytes: u64,
 	pub consensus: String,
+	pub fee: Option<SystemFeeParams>,
+	pub admin: Option<module::system::Admin>,
+}
+
+fn default_max_block_txs() -> u32 {
+	DEFAULT_MAX_BLOCK_TXS
+}
+
+fn default_max_block_bytes() -> u64 {
+	DEFAULT_MAX_BLOCK_BYTES
+}
+
+#[derive(Deserialize)]
+pub struct SystemFeeParams {
+	#[serde(default)]
+	pub base: Balance,
+	#[serde(default)]
+	pub per_byte: Balance,
+	#[serde(default)]
+	pub per_gas: Balance,
 }
 
 impl TryFrom<SystemInitParams> for module::system::InitParams {
@@ -153,7 +185,49 @@ impl TryFrom<SystemInitParams> for module::system::InitParams {
 			timestamp,
 			max_until_gap: value.max_until_gap,
 			max_execution_gap: value.max_execution_gap,
+			max_block_txs: value.max_block_txs,
+			max_block_bytes: value.max_block_bytes,
 			consensus: value.consensus,
+			fee: value.fee.map(|fee| module::system::Fee {
+				base: fee.base,
+				per_byte: fee.per_byte,
+				per_gas: fee.per_gas,
+			}),
+			admin: value.admin,
+		})
+	}
+}
+
+/// Poa init params in the spec,
+/// a single `authority` is accepted as the shorthand of `authorities`
+#[derive(Deserialize)]
+pub struct PoaInitParams {
+	pub block_interval: Option<u64>,
+	pub admin: module::poa::Admin,
+	pub authority: Option<Address>,
+	pub authorities: Option<module::poa::Authorities>,
+}
+
+impl TryFrom<PoaInitParams> for module::poa::InitParams {
+	type Error = CommonError;
+
+	fn try_from(value: PoaInitParams) -> Result<Self, Self::Error> {
+		let authorities = match (value.authority, value.authorities) {
+			(Some(authority), None) => module::poa::Authorities {
+				members: vec![authority],
+			},
+			(None, Some(authorities)) => authorities,
+			_ => {
+				return Err(errors::ErrorKind::Spec(
+					"Either authority or authorities should be specified".to_string(),
+				)
+				.into())
+			}
+		};
+		Ok(module::poa::InitParams {
+			block_interval: value.block_interval,
+			admin: value.admin,
+			authorities,
 		})
 	}
 }
@@ -169,8 +243,6 @@ where
 
 #[cfg(test)]
 mod tests {
-	use primitives::Address;
-
 	use super::*;
 
 	#[test]
@@ -195,9 +267,67 @@ mod tests {
 				timestamp: 1587051962189,
 				max_until_gap: 20,
 				max_execution_gap: 8,
+				max_block_txs: DEFAULT_MAX_BLOCK_TXS,
+				max_block_bytes: DEFAULT_MAX_BLOCK_BYTES,
 				consensus: "poa".to_string(),
+				fee: None,
+				admin: None,
 			}
-		)
+		);
+
+		let str = r#"
+		{
+			"chain_id": "chain-test",
+			"timestamp": "2020-04-16T23:46:02.189+08:00",
+			"max_until_gap": 20,
+			"max_execution_gap": 8,
+			"consensus": "poa",
+			"fee": {
+				"base": 10,
+				"per_byte": 1
+			}
+		}
+		"#;
+
+		let param = get_module_params::<SystemInitParams>(str).unwrap();
+		let param: module::system::InitParams = param.try_into().unwrap();
+
+		assert_eq!(
+			param.fee,
+			Some(module::system::Fee {
+				base: 10,
+				per_byte: 1,
+				per_gas: 0,
+			})
+		);
+
+		let str = r#"
+		{
+			"chain_id": "chain-test",
+			"timestamp": "2020-04-16T23:46:02.189+08:00",
+			"max_until_gap": 20,
+			"max_execution_gap": 8,
+			"consensus": "poa",
+			"admin": {
+				"threshold": 1,
+				"members": [["0001020304050607080900010203040506070809", 1]]
+			}
+		}
+		"#;
+
+		let param = get_module_params::<SystemInitParams>(str).unwrap();
+		let param: module::system::InitParams = param.try_into().unwrap();
+
+		assert_eq!(
+			param.admin,
+			Some(module::system::Admin {
+				threshold: 1,
+				members: vec![(
+					Address::from_hex("0001020304050607080900010203040506070809").unwrap(),
+					1
+				)],
+			})
+		);
 	}
 
 	#[test]
@@ -247,7 +377,10 @@ mod tests {
 		}
 		"#;
 
-		let param = get_module_params::<module::poa::InitParams>(str).unwrap();
+		let param: module::poa::InitParams = get_module_params::<PoaInitParams>(str)
+			.unwrap()
+			.try_into()
+			.unwrap();
 
 		assert_eq!(
 			param,
@@ -257,9 +390,39 @@ mod tests {
 					threshold: 1,
 					members: vec![(Address::from_hex("01020304").unwrap(), 1)],
 				},
-				authority: Address::from_hex("01020304").unwrap(),
+				authorities: module::poa::Authorities {
+					members: vec![Address::from_hex("01020304").unwrap()],
+				},
 			}
-		)
+		);
+
+		let str = r#"
+		{
+			"block_interval": 1000,
+			"admin": {
+				"threshold": 1,
+				"members": [["01020304", 1]]
+			},
+			"authorities": {
+				"members": ["01020304", "01020305"]
+			}
+		}
+		"#;
+
+		let param: module::poa::InitParams = get_module_params::<PoaInitParams>(str)
+			.unwrap()
+			.try_into()
+			.unwrap();
+
+		assert_eq!(
+			param.authorities,
+			module::poa::Authorities {
+				members: vec![
+					Address::from_hex("01020304").unwrap(),
+					Address::from_hex("01020305").unwrap()
+				],
+			}
+		);
 	}
 
 	#[test]
@@ -326,7 +489,8 @@ mod tests {
 			"max_memory_pages": 2048,
 			"max_share_value_len": 104857600,
 			"max_share_size": 1024,
-			"max_nest_depth": 8
+			"max_nest_depth": 8,
+			"max_gas": 268435456
 		}
 		"#;
 
@@ -341,6 +505,7 @@ mod tests {
 				max_share_value_len: Some(104857600),
 				max_share_size: Some(1024),
 				max_nest_depth: Some(8),
+				max_gas: Some(268435456),
 			}
 		)
 	}
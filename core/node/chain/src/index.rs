@@ -0,0 +1,289 @@
+// Copyright 2019, 2020 Wingchain
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Secondary indexes from the addresses and the events to the transactions
+//!
+//! Each key of an index has the count of its entries,
+//! and the entries keyed by the key and the sequence, in the order of execution.
+//! The transactions of a block are indexed once the block is executed,
+//! when all the receipts of the block are available,
+//! so the entries of a key are also in the order of block number.
+
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+
+use node_db::{DBTransaction, DB};
+use primitives::codec::{self, Decode, Encode};
+use primitives::errors::CommonResult;
+use primitives::{Address, BlockNumber, DBKey, Event, Hash};
+
+use crate::{errors, EventFilter, IndexEntry, IndexPage};
+
+/// Key of the event index
+#[derive(Encode, Decode)]
+enum EventKey {
+	Module(String),
+	Name(String),
+	ModuleName(String, String),
+}
+
+/// Event emitted by Event::from_data
+#[derive(Deserialize)]
+struct EventType {
+	name: String,
+	#[serde(default)]
+	data: serde_json::Value,
+}
+
+/// Transaction hashes of each index key
+type IndexMap = BTreeMap<Vec<u8>, Vec<Hash>>;
+
+/// Index keys of the transactions of some blocks,
+/// with the transaction hashes of each key in the order of execution
+#[derive(Default)]
+pub struct BlockIndex {
+	address: IndexMap,
+	event: IndexMap,
+}
+
+impl BlockIndex {
+	/// Index a transaction by the sender and the recipients of its events,
+	/// and its receipt by the names of the events (alone and with the module)
+	/// and the module if there is any event
+	pub fn add(
+		&mut self,
+		tx_hash: &Hash,
+		sender: Option<&Address>,
+		module: &str,
+		events: &[Event],
+	) -> CommonResult<()> {
+		if let Some(sender) = sender {
+			push(&mut self.address, codec::encode(sender)?, tx_hash);
+		}
+		for event in events {
+			// the raw events are only indexed by the module
+			let event: EventType = match serde_json::from_slice(&event.0) {
+				Ok(event) => event,
+				Err(_) => continue,
+			};
+			let recipient = event
+				.data
+				.get("recipient")
+				.and_then(|x| serde_json::from_value::<Address>(x.clone()).ok());
+			if let Some(recipient) = recipient {
+				push(&mut self.address, codec::encode(&recipient)?, tx_hash);
+			}
+			push(
+				&mut self.event,
+				codec::encode(&EventKey::ModuleName(
+					module.to_string(),
+					event.name.clone(),
+				))?,
+				tx_hash,
+			);
+			push(
+				&mut self.event,
+				codec::encode(&EventKey::Name(event.name))?,
+				tx_hash,
+			);
+		}
+		if !events.is_empty() {
+			push(
+				&mut self.event,
+				codec::encode(&EventKey::Module(module.to_string()))?,
+				tx_hash,
+			);
+		}
+		Ok(())
+	}
+
+	/// Append the entries to the existing ones of each key
+	pub fn prepare_commit(
+		&self,
+		db: &DB,
+		block_number: BlockNumber,
+	) -> CommonResult<DBTransaction> {
+		let mut transaction = DBTransaction::new();
+		for &(col, index) in self.cols().iter() {
+			for (key, tx_hashes) in index {
+				let mut count = get_count(db, col, key)?;
+				for tx_hash in tx_hashes {
+					let entry = IndexEntry {
+						block_number,
+						tx_hash: tx_hash.clone(),
+					};
+					transaction.put_owned(col, entry_key(key, count)?, codec::encode(&entry)?);
+					count += 1;
+				}
+				transaction.put_owned(col, DBKey::from_slice(key), codec::encode(&count)?);
+			}
+		}
+		Ok(transaction)
+	}
+
+	/// Remove the entries of each key in the blocks above the block number
+	pub fn prepare_revert(
+		&self,
+		db: &DB,
+		block_number: BlockNumber,
+	) -> CommonResult<DBTransaction> {
+		let mut transaction = DBTransaction::new();
+		for &(col, index) in self.cols().iter() {
+			for key in index.keys() {
+				let count = get_count(db, col, key)?;
+				let mut reverted_count = count;
+				while reverted_count > 0 {
+					let seq = reverted_count - 1;
+					if get_entry(db, col, key, seq)?.block_number <= block_number {
+						break;
+					}
+					transaction.delete(col, &entry_key(key, seq)?);
+					reverted_count = seq;
+				}
+				if reverted_count == count {
+					continue;
+				}
+				if reverted_count == 0 {
+					transaction.delete(col, key);
+				} else {
+					transaction.put_owned(
+						col,
+						DBKey::from_slice(key),
+						codec::encode(&reverted_count)?,
+					);
+				}
+			}
+		}
+		Ok(transaction)
+	}
+
+	fn cols(&self) -> [(u32, &IndexMap); 2] {
+		[
+			(node_db::columns::ADDRESS_INDEX, &self.address),
+			(node_db::columns::EVENT_INDEX, &self.event),
+		]
+	}
+}
+
+/// Get the transactions sent or received by the address
+pub fn get_address_transactions(
+	db: &DB,
+	address: &Address,
+	offset: u64,
+	limit: u64,
+) -> CommonResult<IndexPage> {
+	let col = node_db::columns::ADDRESS_INDEX;
+	let key = codec::encode(address)?;
+	let count = get_count(db, col, &key)?;
+	get_page(db, col, &key, 0, count, offset, limit)
+}
+
+/// Get the receipts matching the filter in the blocks from `from` to `to` (inclusive)
+pub fn get_events(
+	db: &DB,
+	filter: &EventFilter,
+	from: BlockNumber,
+	to: BlockNumber,
+	offset: u64,
+	limit: u64,
+) -> CommonResult<IndexPage> {
+	let col = node_db::columns::EVENT_INDEX;
+	let key = match (&filter.name, &filter.module) {
+		(Some(name), Some(module)) => EventKey::ModuleName(module.clone(), name.clone()),
+		(Some(name), None) => EventKey::Name(name.clone()),
+		(None, Some(module)) => EventKey::Module(module.clone()),
+		(None, None) => {
+			return Err(errors::ErrorKind::Index("Empty event filter".to_string()).into());
+		}
+	};
+	let key = codec::encode(&key)?;
+	let count = get_count(db, col, &key)?;
+
+	let end = match to.checked_add(1) {
+		Some(to) => lower_bound(db, col, &key, count, to)?,
+		None => count,
+	};
+	let start = lower_bound(db, col, &key, count, from)?.min(end);
+
+	get_page(db, col, &key, start, end, offset, limit)
+}
+
+/// Get the entries of the sequence from `start` to `end` (exclusive) in the page
+fn get_page(
+	db: &DB,
+	col: u32,
+	key: &[u8],
+	start: u64,
+	end: u64,
+	offset: u64,
+	limit: u64,
+) -> CommonResult<IndexPage> {
+	let total = end - start;
+	let from = start + offset.min(total);
+	let to = start + offset.saturating_add(limit).min(total);
+	let entries = (from..to)
+		.map(|seq| get_entry(db, col, key, seq))
+		.collect::<CommonResult<Vec<_>>>()?;
+	Ok(IndexPage { total, entries })
+}
+
+/// The first sequence of the entries in the block number or after
+fn lower_bound(
+	db: &DB,
+	col: u32,
+	key: &[u8],
+	count: u64,
+	block_number: BlockNumber,
+) -> CommonResult<u64> {
+	let (mut low, mut high) = (0, count);
+	while low < high {
+		let mid = low + (high - low) / 2;
+		if get_entry(db, col, key, mid)?.block_number < block_number {
+			low = mid + 1;
+		} else {
+			high = mid;
+		}
+	}
+	Ok(low)
+}
+
+fn get_count(db: &DB, col: u32, key: &[u8]) -> CommonResult<u64> {
+	let count = db.get_with(col, key, |x| codec::decode(&mut &x[..]))?;
+	Ok(count.unwrap_or(0))
+}
+
+fn get_entry(db: &DB, col: u32, key: &[u8], seq: u64) -> CommonResult<IndexEntry> {
+	db.get_with(col, &entry_key(key, seq)?, |x| codec::decode(&mut &x[..]))?
+		.ok_or_else(|| {
+			errors::ErrorKind::Data(format!("Missing index entry: col: {}, seq: {}", col, seq))
+				.into()
+		})
+}
+
+/// The encoded key is self-delimiting, so the entry keys never collide with the count keys
+fn entry_key(key: &[u8], seq: u64) -> CommonResult<DBKey> {
+	let mut entry_key = DBKey::from_slice(key);
+	entry_key.extend_from_slice(&codec::encode(&seq)?);
+	Ok(entry_key)
+}
+
+/// Push the transaction hash unless it is the last one of the key,
+/// as a transaction may be indexed by the same key more than once
+fn push(index: &mut IndexMap, key: Vec<u8>, tx_hash: &Hash) {
+	let tx_hashes = index.entry(key).or_insert_with(Vec::new);
+	if tx_hashes.last() != Some(tx_hash) {
+		tx_hashes.push(tx_hash.clone());
+	}
+}
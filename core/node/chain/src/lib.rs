@@ -14,14 +14,17 @@
 
 //! Chain to handle the db, statedb and executor
 
-use std::path::PathBuf;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use futures::channel::mpsc::UnboundedReceiver;
+use serde::{Deserialize, Serialize};
 
 use crypto::address::AddressImpl;
 use crypto::dsa::DsaImpl;
 use crypto::hash::HashImpl;
+pub use node_db::DBBackend;
 pub use node_db::DBConfig;
 pub use node_db::DBTransaction;
 pub use node_executor::module;
@@ -30,18 +33,24 @@ use primitives::errors::CommonResult;
 use primitives::types::CallResult;
 use primitives::{
 	Address, Block, BlockNumber, Body, BuildBlockParams, Call, CommitBlockParams,
-	CommitExecutionParams, Execution, Hash, Header, Nonce, OpaqueCallResult, Proof, Receipt,
-	SecretKey, Transaction,
+	CommitExecutionParams, DBValue, Execution, FullTransaction, Hash, Header, Nonce,
+	OpaqueCallResult, Proof, Receipt, SecretKey, Transaction, Witness,
 };
 
 use crate::backend::Backend;
 pub use crate::backend::CurrentState;
 use crate::execute::{ExecuteQueue, ExecuteTask};
+use crate::prune::PruneStream;
 
 mod backend;
 pub mod errors;
 mod execute;
 mod genesis;
+mod index;
+mod prune;
+
+/// Count of the latest blocks whose state is kept in pruned mode if not specified
+pub const DEFAULT_PRUNING_KEEP_BLOCKS: u64 = 256;
 
 pub type ChainCommitBlockParams = CommitBlockParams<DBTransaction>;
 pub type ChainCommitExecutionParams = CommitExecutionParams<DBTransaction>;
@@ -51,6 +60,137 @@ pub struct ChainConfig {
 	pub home: PathBuf,
 	/// DB config
 	pub db: DBConfig,
+	/// State pruning mode
+	pub pruning: StatePruning,
+	/// If the address index and the event index are kept
+	pub index: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum StatePruning {
+	/// Keep all the historical state
+	Archive,
+	/// Keep only the state of the given count of latest blocks
+	Pruned(u64),
+}
+
+impl StatePruning {
+	pub fn name(&self) -> &'static str {
+		match self {
+			StatePruning::Archive => "archive",
+			StatePruning::Pruned(_) => "pruned",
+		}
+	}
+}
+
+/// Proof of a storage key in the meta state or the payload state of a block
+#[derive(Clone, Debug, PartialEq)]
+pub struct StateProof {
+	/// State root in the block header the proof is generated under
+	pub root: Hash,
+	/// If the key is in the meta state
+	pub meta: bool,
+	/// Full key in the state trie
+	pub key: Vec<u8>,
+	/// Value of the key, None if the proof proves the absence of the key
+	pub value: Option<DBValue>,
+	/// Trie nodes from the root to the key
+	pub proof: Vec<Vec<u8>>,
+}
+
+/// Proof of a transaction or a receipt in the list committed by a block header
+#[derive(Clone, Debug, PartialEq)]
+pub struct InclusionProof {
+	/// Hash of the block whose header contains the root,
+	/// for a payload receipt, the first block whose header commits the execution of its block
+	pub block_hash: Hash,
+	/// meta_txs_root, payload_txs_root, meta_receipts_root or payload_execution_receipts_root
+	pub root: Hash,
+	/// If the transaction is a meta transaction
+	pub meta: bool,
+	/// Key in the ordered trie, i.e. the encoded index in the list
+	pub key: Vec<u8>,
+	/// Encoded transaction (excluding signature) or receipt
+	pub value: Vec<u8>,
+	/// Trie nodes from the root to the key
+	pub proof: Vec<Vec<u8>>,
+}
+
+/// State snapshot at a confirmed block
+/// by which a new node can be initialized without replaying the blocks from genesis
+/// the payload state is read in chunks separately, as it may be too large to keep in memory
+#[derive(Clone, Debug, PartialEq, Encode, Decode)]
+pub struct Snapshot {
+	/// Blocks from the execution number of the snapshot block to the snapshot block
+	pub blocks: Vec<SnapshotBlock>,
+	/// Meta state of the blocks after the execution number:
+	/// all the key-values of the first block, then the changed key-values of each following block
+	pub meta_states: Vec<Vec<(Vec<u8>, Option<DBValue>)>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Encode, Decode)]
+pub struct SnapshotBlock {
+	pub block_hash: Hash,
+	pub header: Header,
+	pub meta_txs: Vec<Transaction>,
+	pub payload_txs: Vec<Transaction>,
+	pub proof: Proof,
+}
+
+/// Summary of the entries removed by reverting the chain
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RevertSummary {
+	/// Confirmed number before reverting
+	pub from: BlockNumber,
+	/// Confirmed number after reverting
+	pub to: BlockNumber,
+	/// Count of the removed blocks, including the headers, bodies and proofs
+	pub blocks: u64,
+	/// Count of the removed transactions
+	pub txs: u64,
+	/// Count of the removed receipts
+	pub receipts: u64,
+	/// Count of the removed executions
+	pub executions: u64,
+}
+
+/// Transaction located by the address index or the event index
+#[derive(Clone, Debug, PartialEq, Encode, Decode)]
+pub struct IndexEntry {
+	/// Number of the block the transaction is executed in
+	pub block_number: BlockNumber,
+	pub tx_hash: Hash,
+}
+
+/// Entries of the index matching a query, in the order of execution
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct IndexPage {
+	/// Count of the entries matching the query, regardless of offset and limit
+	pub total: u64,
+	pub entries: Vec<IndexEntry>,
+}
+
+/// Filter of the receipts in the event index, at least one of the fields should be specified
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct EventFilter {
+	/// Module of the call emitting the events
+	pub module: Option<String>,
+	/// Name of the event
+	pub name: Option<String>,
+}
+
+/// Directory of the db in a checkpoint
+pub const CHECKPOINT_DB: &str = "db";
+
+/// File of the chain status in a checkpoint
+pub const CHECKPOINT_FILE: &str = "checkpoint.toml";
+
+/// Chain status recorded when a db checkpoint is created
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Checkpoint {
+	pub genesis_hash: Hash,
+	pub confirmed_number: BlockNumber,
+	pub execution_number: BlockNumber,
 }
 
 pub struct Chain {
@@ -66,10 +206,16 @@ pub struct Basic {
 
 impl Chain {
 	pub fn new(config: ChainConfig) -> CommonResult<Self> {
+		let pruning = config.pruning.clone();
+
 		let backend = Arc::new(Backend::new(config)?);
 
 		let execute_queue = Arc::new(ExecuteQueue::new(backend.clone()));
 
+		if let StatePruning::Pruned(keep) = pruning {
+			PruneStream::spawn(backend.clone(), keep);
+		}
+
 		let chain = Self {
 			backend,
 			execute_queue,
@@ -108,6 +254,87 @@ impl Chain {
 		self.backend.get_proof(block_hash)
 	}
 
+	/// Get the proof of the storage key of the module in the state of the given block
+	pub fn get_state_proof(
+		&self,
+		block_hash: &Hash,
+		module: &str,
+		key: &[u8],
+	) -> CommonResult<Option<StateProof>> {
+		self.backend.get_state_proof(block_hash, module, key)
+	}
+
+	/// Get the state snapshot at the given confirmed block number
+	pub fn get_snapshot(&self, number: BlockNumber) -> CommonResult<Snapshot> {
+		self.backend.get_snapshot(number)
+	}
+
+	/// Get a chunk of the payload state of the snapshot from the given start key,
+	/// an empty chunk is returned after the last key
+	pub fn get_snapshot_payload_state(
+		&self,
+		snapshot: &Snapshot,
+		start: &[u8],
+		max_bytes: usize,
+	) -> CommonResult<Vec<(Vec<u8>, DBValue)>> {
+		self.backend
+			.get_snapshot_payload_state(snapshot, start, max_bytes)
+	}
+
+	/// Restore the chain from a state snapshot and the chunks of its payload state
+	/// the chain should have only the genesis block,
+	/// the blocks after the execution number of the snapshot block are executed again
+	pub fn restore_snapshot<I>(&self, snapshot: Snapshot, payload_state: I) -> CommonResult<()>
+	where
+		I: Iterator<Item = CommonResult<Vec<(Vec<u8>, DBValue)>>>,
+	{
+		let block = snapshot.blocks.last().cloned();
+
+		self.backend.restore_snapshot(snapshot, payload_state)?;
+
+		if let Some(block) = block {
+			let payload_txs = block
+				.payload_txs
+				.into_iter()
+				.map(|tx| {
+					let tx_hash = self.backend.hash_transaction(&tx)?;
+					Ok(Arc::new(FullTransaction { tx, tx_hash }))
+				})
+				.collect::<CommonResult<Vec<_>>>()?;
+			let execute_task = ExecuteTask {
+				number: block.header.number,
+				timestamp: block.header.timestamp,
+				author: block.header.author,
+				block_hash: block.block_hash,
+				parent_hash: block.header.parent_hash,
+				meta_state_root: block.header.meta_state_root,
+				payload_txs,
+			};
+			self.execute_queue.insert_task(execute_task)?;
+		}
+
+		Ok(())
+	}
+
+	/// Revert the chain to the given block number
+	/// by removing the blocks above the number and their executions
+	pub fn revert(&self, number: BlockNumber) -> CommonResult<RevertSummary> {
+		self.backend.revert(number)
+	}
+
+	/// Create a checkpoint of the db in the directory while the chain is running,
+	/// the directory should not exist
+	pub fn create_checkpoint(&self, path: &Path) -> CommonResult<Checkpoint> {
+		self.backend.create_checkpoint(path)
+	}
+
+	/// Verify the block of the number against the data it commits to,
+	/// and against the state roots by executing it again if `execute`,
+	/// an inconsistency is reported by errors::ErrorKind::Verify
+	pub fn verify_block(&self, number: BlockNumber, execute: bool) -> CommonResult<()> {
+		self.backend.verify_block(number, execute)
+	}
+
 	/// Get the transaction by transaction hash
 	pub fn get_transaction(&self, tx_hash: &Hash) -> CommonResult<Option<Transaction>> {
 		self.backend.get_transaction(tx_hash)
@@ -123,6 +350,40 @@ impl Chain {
 		self.backend.get_receipt(tx_hash)
 	}
 
+	/// Get the proof of the transaction in the txs root of the block header
+	pub fn get_transaction_proof(&self, tx_hash: &Hash) -> CommonResult<Option<InclusionProof>> {
+		self.backend.get_transaction_proof(tx_hash)
+	}
+
+	/// Get the proof of the receipt in the receipts root of the block header or execution
+	pub fn get_receipt_proof(&self, tx_hash: &Hash) -> CommonResult<Option<InclusionProof>> {
+		self.backend.get_receipt_proof(tx_hash)
+	}
+
+	/// Get the transactions sent or received by the address from the address index
+	pub fn get_address_transactions(
+		&self,
+		address: &Address,
+		offset: u64,
+		limit: u64,
+	) -> CommonResult<IndexPage> {
+		self.backend
+			.get_address_transactions(address, offset, limit)
+	}
+
+	/// Get the receipts with the events matching the filter
+	/// in the blocks from `from` to `to` (inclusive) from the event index
+	pub fn get_events(
+		&self,
+		filter: &EventFilter,
+		from: BlockNumber,
+		to: BlockNumber,
+		offset: u64,
+		limit: u64,
+	) -> CommonResult<IndexPage> {
+		self.backend.get_events(filter, from, to, offset, limit)
+	}
+
 	/// Get consensus data
 	pub fn get_consensus_data<T: Decode>(&self, key: &[u8]) -> CommonResult<Option<T>> {
 		self.backend.get_consensus_data(key)
@@ -143,6 +404,20 @@ impl Chain {
 		self.backend.commit_consensus_data(transaction)
 	}
 
+	/// Get the transactions in the txpool journal
+	pub fn get_txpool_journal(&self) -> CommonResult<Vec<Transaction>> {
+		self.backend.get_txpool_journal()
+	}
+
+	/// Update the txpool journal
+	pub fn update_txpool_journal(
+		&self,
+		inserted: &[Arc<FullTransaction>],
+		removed: &HashSet<Hash>,
+	) -> CommonResult<()> {
+		self.backend.update_txpool_journal(inserted, removed)
+	}
+
 	/// Determine if the given call is meta call
 	pub fn is_meta_call(&self, call: &Call) -> CommonResult<bool> {
 		self.backend.is_meta_call(call)
@@ -153,6 +428,11 @@ impl Chain {
 		self.backend.hash_transaction(tx)
 	}
 
+	/// Get the sender address of a transaction by its witness
+	pub fn get_sender(&self, witness: &Witness) -> Address {
+		self.backend.get_sender(witness)
+	}
+
 	/// Validate transaction
 	pub fn validate_transaction(
 		&self,
@@ -164,6 +444,11 @@ impl Chain {
 			.validate_transaction(tx_hash, tx, witness_required)
 	}
 
+	/// Get the nonce expected by the next transaction of the given account
+	pub fn get_nonce(&self, address: &Address) -> CommonResult<Nonce> {
+		self.backend.get_nonce(address)
+	}
+
 	/// Build a call by module, method and params
 	pub fn build_call<P: Encode>(
 		&self,
@@ -197,6 +482,7 @@ impl Chain {
 	pub fn commit_block(&self, commit_block_params: ChainCommitBlockParams) -> CommonResult<()> {
 		let number = commit_block_params.header.number;
 		let timestamp = commit_block_params.header.timestamp;
+		let author = commit_block_params.header.author.clone();
 		let block_hash = commit_block_params.block_hash.clone();
 		let parent_hash = commit_block_params.header.parent_hash.clone();
 		let meta_state_root = commit_block_params.header.meta_state_root.clone();
@@ -207,6 +493,7 @@ impl Chain {
 		let execute_task = ExecuteTask {
 			number,
 			timestamp,
+			author,
 			block_hash,
 			parent_hash,
 			meta_state_root,
@@ -272,8 +559,13 @@ impl Chain {
 	pub fn message_rx(&self) -> Option<UnboundedReceiver<ChainOutMessage>> {
 		self.backend.message_rx()
 	}
+
+	pub fn subscribe_message(&self) -> UnboundedReceiver<ChainOutMessage> {
+		self.backend.subscribe_message()
+	}
 }
 
+#[derive(Clone)]
 pub enum ChainOutMessage {
 	BlockCommitted { number: u64, hash: Hash },
 	ExecutionCommitted { number: u64, hash: Hash },
@@ -0,0 +1,81 @@
+// Copyright 2019, 2020 Wingchain
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Prune the historical state in the background
+//! after the executions are committed
+
+use std::sync::Arc;
+
+use futures::channel::mpsc::UnboundedReceiver;
+use futures::StreamExt;
+use log::warn;
+
+use primitives::errors::CommonResult;
+use primitives::BlockNumber;
+
+use crate::backend::Backend;
+use crate::ChainOutMessage;
+
+pub struct PruneStream {
+	chain_rx: UnboundedReceiver<ChainOutMessage>,
+	backend: Arc<Backend>,
+	/// count of the latest blocks whose state is kept
+	keep: u64,
+}
+
+impl PruneStream {
+	pub fn spawn(backend: Arc<Backend>, keep: u64) {
+		let chain_rx = backend.subscribe_message();
+		let this = Self {
+			chain_rx,
+			backend,
+			keep,
+		};
+		tokio::spawn(this.start());
+	}
+
+	async fn start(mut self) {
+		// catch up with the executions committed before started
+		if let Err(e) = self.on_start() {
+			warn!("Prune state error: {}", e);
+		}
+		loop {
+			let message = self.chain_rx.next().await;
+			match message {
+				Some(ChainOutMessage::ExecutionCommitted { number, .. }) => {
+					if let Err(e) = self.prune(number) {
+						warn!("Prune state error: {}", e);
+					}
+				}
+				Some(ChainOutMessage::BlockCommitted { .. }) => (),
+				None => break,
+			}
+		}
+	}
+
+	fn on_start(&self) -> CommonResult<()> {
+		match self.backend.get_execution_number()? {
+			Some(number) => self.prune(number),
+			None => Ok(()),
+		}
+	}
+
+	/// Prune the state older than the kept blocks
+	fn prune(&self, execution_number: BlockNumber) -> CommonResult<()> {
+		if execution_number < self.keep {
+			return Ok(());
+		}
+		self.backend.prune_state(execution_number - self.keep)
+	}
+}
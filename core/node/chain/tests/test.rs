@@ -17,19 +17,23 @@ use std::fs;
 use std::path::PathBuf;
 use std::sync::Arc;
 
+use futures::StreamExt;
 use log::info;
 
 use crypto::address::AddressImpl;
 use crypto::dsa::DsaImpl;
 use crypto::hash::{Hash as HashT, HashImpl};
-use node_chain::{module, Chain, ChainConfig};
-use node_db::{DBConfig, DB};
+use node_chain::{
+	module, Chain, ChainConfig, Checkpoint, DBTransaction, EventFilter, IndexEntry, IndexPage,
+	RevertSummary, StatePruning, CHECKPOINT_DB, CHECKPOINT_FILE,
+};
+use node_db::{DBBackend, DBConfig, DB};
 use node_statedb::{StateDB, TrieRoot};
 use primitives::codec::Encode;
 use primitives::types::FullReceipt;
 use primitives::{
-	codec, Address, Balance, Block, Body, DBKey, Execution, FullTransaction, Hash, Header, Receipt,
-	TransactionForHash,
+	codec, Address, Balance, Block, BlockNumber, Body, BuildBlockParams, Call, DBKey, Execution,
+	FullTransaction, Hash, Header, Params, Receipt, Transaction, TransactionForHash,
 };
 use utils_test::test_accounts;
 
@@ -54,8 +58,14 @@ async fn test_chain_normal() {
 		memory_budget: 1 * 1024 * 1024,
 		path: home.join("data").join("db"),
 		partitions: vec![],
+		backend: DBBackend::Memory,
+	};
+	let config = ChainConfig {
+		home,
+		db,
+		pruning: StatePruning::Archive,
+		index: false,
 	};
-	let config = ChainConfig { home, db };
 
 	let chain = Chain::new(config).unwrap();
 
@@ -164,8 +174,14 @@ async fn test_chain_execute_call() {
 		memory_budget: 1 * 1024 * 1024,
 		path: home.join("data").join("db"),
 		partitions: vec![],
+		backend: DBBackend::Memory,
+	};
+	let config = ChainConfig {
+		home,
+		db,
+		pruning: StatePruning::Archive,
+		index: false,
 	};
-	let config = ChainConfig { home, db };
 
 	let chain = Chain::new(config).unwrap();
 
@@ -190,6 +206,50 @@ async fn test_chain_execute_call() {
 	assert_eq!(10, result);
 }
 
+#[tokio::test]
+async fn test_chain_validate_tx_size() {
+	use tempfile::tempdir;
+
+	let path = tempdir().expect("Could not create a temp dir");
+	let home = path.into_path();
+
+	let dsa = Arc::new(DsaImpl::Ed25519);
+	let address = Arc::new(AddressImpl::Blake2b160);
+
+	let test_accounts = test_accounts(dsa, address);
+	let account1 = &test_accounts[0];
+
+	init(&home, &account1.address);
+
+	let db = DBConfig {
+		memory_budget: 1 * 1024 * 1024,
+		path: home.join("data").join("db"),
+		partitions: vec![],
+		backend: DBBackend::Memory,
+	};
+	let config = ChainConfig {
+		home,
+		db,
+		pruning: StatePruning::Archive,
+		index: false,
+	};
+
+	let chain = Chain::new(config).unwrap();
+
+	// the test spec takes the default max block bytes: 4 * 1024 * 1024
+	let tx = Transaction {
+		witness: None,
+		call: Call {
+			module: "balance".to_string(),
+			method: "transfer".to_string(),
+			params: Params(vec![0u8; 4 * 1024 * 1024]),
+		},
+	};
+	let tx_hash = chain.hash_transaction(&tx).unwrap();
+	let result = chain.validate_transaction(&tx_hash, &tx, false);
+	assert!(format!("{}", result.unwrap_err()).contains("Invalid tx size"));
+}
+
 #[tokio::test]
 async fn test_chain_invalid_spec() {
 	use tempfile::tempdir;
@@ -203,9 +263,15 @@ async fn test_chain_invalid_spec() {
 		memory_budget: 1 * 1024 * 1024,
 		path: home.join("data").join("db"),
 		partitions: vec![],
+		backend: DBBackend::Memory,
 	};
 
-	let config = ChainConfig { home, db };
+	let config = ChainConfig {
+		home,
+		db,
+		pruning: StatePruning::Archive,
+		index: false,
+	};
 
 	let chain = Chain::new(config);
 
@@ -217,6 +283,520 @@ async fn test_chain_invalid_spec() {
 	}
 }
 
+#[tokio::test]
+async fn test_chain_snapshot() {
+	let _ = env_logger::try_init();
+
+	use tempfile::tempdir;
+
+	let dsa = Arc::new(DsaImpl::Ed25519);
+	let address = Arc::new(AddressImpl::Blake2b160);
+
+	let test_accounts = test_accounts(dsa, address);
+	let (account1, account2) = (&test_accounts[0], &test_accounts[1]);
+
+	let home = tempdir().expect("Could not create a temp dir").into_path();
+	init(&home, &account1.address);
+	let chain = new_chain(home);
+
+	// block 1 is empty, block 2 transfers
+	commit_test_block(&chain, 1, vec![]).await;
+	let tx = chain
+		.build_transaction(
+			Some((account1.secret_key.clone(), 0, 10)),
+			chain
+				.build_call(
+					"balance".to_string(),
+					"transfer".to_string(),
+					module::balance::TransferParams {
+						recipient: account2.address.clone(),
+						value: 1,
+					},
+				)
+				.unwrap(),
+		)
+		.unwrap();
+	let tx_hash = chain.hash_transaction(&tx).unwrap();
+	commit_test_block(&chain, 2, vec![Arc::new(FullTransaction { tx, tx_hash })]).await;
+
+	// snapshot of block 2, whose execution number is 1
+	let snapshot = chain.get_snapshot(2).unwrap();
+	assert_eq!(snapshot.blocks.len(), 2);
+	assert_eq!(snapshot.meta_states.len(), 1);
+
+	let restored_home = tempdir().expect("Could not create a temp dir").into_path();
+	init(&restored_home, &account1.address);
+	let restored_chain = new_chain(restored_home);
+
+	// the payload state is read one key-value per chunk
+	let mut payload_state = vec![];
+	let mut start = vec![];
+	loop {
+		let chunk = chain
+			.get_snapshot_payload_state(&snapshot, &start, 1)
+			.unwrap();
+		let last_key = match chunk.last() {
+			Some((key, _)) => key.clone(),
+			None => break,
+		};
+		payload_state.push(chunk);
+		start = [&last_key[..], &[0u8][..]].concat();
+	}
+	let all = chain
+		.get_snapshot_payload_state(&snapshot, &[], usize::MAX)
+		.unwrap();
+	assert!(!all.is_empty());
+	assert_eq!(payload_state.len(), all.len());
+	assert_eq!(payload_state.concat(), all);
+
+	// the tampered state is rejected
+	let mut tampered = payload_state.clone();
+	tampered.last_mut().unwrap().last_mut().unwrap().1 = codec::encode(&100u64).unwrap();
+	let result = restored_chain.restore_snapshot(snapshot.clone(), tampered.into_iter().map(Ok));
+	assert!(format!("{}", result.unwrap_err()).contains("Mismatched payload state root"));
+
+	let mut chain_rx = restored_chain.subscribe_message();
+	restored_chain
+		.restore_snapshot(snapshot, payload_state.into_iter().map(Ok))
+		.unwrap();
+	while restored_chain.get_current_state().executed_number < 2 {
+		chain_rx.next().await;
+	}
+
+	let block_hash = chain.get_block_hash(&2).unwrap().unwrap();
+	assert_eq!(restored_chain.get_confirmed_number().unwrap(), Some(2));
+	assert_eq!(
+		restored_chain.get_block_hash(&2).unwrap(),
+		Some(block_hash.clone())
+	);
+	assert_eq!(
+		restored_chain.get_execution(&block_hash).unwrap(),
+		chain.get_execution(&block_hash).unwrap()
+	);
+
+	// the restored chain builds the same block as the original one
+	let build_block_params = || BuildBlockParams {
+		number: 3,
+		timestamp: 3,
+		author: None,
+		meta_txs: vec![],
+		payload_txs: vec![],
+		execution_number: 2,
+	};
+	let header = chain.build_block(build_block_params()).unwrap().header;
+	let restored_header = restored_chain
+		.build_block(build_block_params())
+		.unwrap()
+		.header;
+	assert_eq!(restored_header, header);
+}
+
+#[tokio::test]
+async fn test_chain_revert() {
+	let _ = env_logger::try_init();
+
+	use tempfile::tempdir;
+
+	let dsa = Arc::new(DsaImpl::Ed25519);
+	let address = Arc::new(AddressImpl::Blake2b160);
+
+	let test_accounts = test_accounts(dsa, address);
+	let (account1, account2) = (&test_accounts[0], &test_accounts[1]);
+
+	let home = tempdir().expect("Could not create a temp dir").into_path();
+	init(&home, &account1.address);
+	let chain = new_chain(home);
+
+	commit_test_block(&chain, 1, vec![]).await;
+	let tx = chain
+		.build_transaction(
+			Some((account1.secret_key.clone(), 0, 10)),
+			chain
+				.build_call(
+					"balance".to_string(),
+					"transfer".to_string(),
+					module::balance::TransferParams {
+						recipient: account2.address.clone(),
+						value: 1,
+					},
+				)
+				.unwrap(),
+		)
+		.unwrap();
+	let tx_hash = chain.hash_transaction(&tx).unwrap();
+	commit_test_block(
+		&chain,
+		2,
+		vec![Arc::new(FullTransaction {
+			tx,
+			tx_hash: tx_hash.clone(),
+		})],
+	)
+	.await;
+	let block_hash = chain.get_block_hash(&2).unwrap().unwrap();
+
+	// only the blocks above the confirmed number can be reverted
+	assert!(chain.revert(2).is_err());
+
+	let summary = chain.revert(1).unwrap();
+	assert_eq!(
+		summary,
+		RevertSummary {
+			from: 2,
+			to: 1,
+			blocks: 1,
+			txs: 1,
+			receipts: 1,
+			executions: 1,
+		}
+	);
+
+	assert_eq!(chain.get_confirmed_number().unwrap(), Some(1));
+	assert_eq!(chain.get_execution_number().unwrap(), Some(1));
+	assert_eq!(chain.get_block_hash(&2).unwrap(), None);
+	assert_eq!(chain.get_block(&block_hash).unwrap(), None);
+	assert_eq!(chain.get_execution(&block_hash).unwrap(), None);
+	assert_eq!(chain.get_proof(&block_hash).unwrap(), None);
+	assert_eq!(chain.get_transaction(&tx_hash).unwrap(), None);
+	assert_eq!(chain.get_receipt(&tx_hash).unwrap(), None);
+
+	// the chain continues from the reverted block
+	commit_test_block(&chain, 2, vec![]).await;
+	assert_ne!(chain.get_block_hash(&2).unwrap(), Some(block_hash));
+}
+
+#[tokio::test]
+async fn test_chain_index() {
+	let _ = env_logger::try_init();
+
+	use tempfile::tempdir;
+
+	let dsa = Arc::new(DsaImpl::Ed25519);
+	let address = Arc::new(AddressImpl::Blake2b160);
+
+	let test_accounts = test_accounts(dsa, address);
+	let (account1, account2) = (&test_accounts[0], &test_accounts[1]);
+
+	let home = tempdir().expect("Could not create a temp dir").into_path();
+	init(&home, &account1.address);
+	let chain = new_chain(home);
+
+	let transfer = |nonce| {
+		let tx = chain
+			.build_transaction(
+				Some((account1.secret_key.clone(), nonce, 10)),
+				chain
+					.build_call(
+						"balance".to_string(),
+						"transfer".to_string(),
+						module::balance::TransferParams {
+							recipient: account2.address.clone(),
+							value: 1,
+						},
+					)
+					.unwrap(),
+			)
+			.unwrap();
+		let tx_hash = chain.hash_transaction(&tx).unwrap();
+		Arc::new(FullTransaction { tx, tx_hash })
+	};
+	let tx0 = transfer(0);
+	let tx1 = transfer(1);
+	let tx2 = transfer(2);
+
+	commit_test_block(&chain, 1, vec![tx0.clone()]).await;
+	commit_test_block(&chain, 2, vec![]).await;
+	commit_test_block(&chain, 3, vec![tx1.clone(), tx2.clone()]).await;
+
+	let entry = |number, tx: &Arc<FullTransaction>| IndexEntry {
+		block_number: number,
+		tx_hash: tx.tx_hash.clone(),
+	};
+
+	// address index of both the sender and the recipient
+	let expected = IndexPage {
+		total: 3,
+		entries: vec![entry(1, &tx0), entry(3, &tx1), entry(3, &tx2)],
+	};
+	assert_eq!(
+		chain
+			.get_address_transactions(&account1.address, 0, 10)
+			.unwrap(),
+		expected
+	);
+	assert_eq!(
+		chain
+			.get_address_transactions(&account2.address, 0, 10)
+			.unwrap(),
+		expected
+	);
+	assert_eq!(
+		chain
+			.get_address_transactions(&account2.address, 1, 1)
+			.unwrap(),
+		IndexPage {
+			total: 3,
+			entries: vec![entry(3, &tx1)],
+		}
+	);
+
+	// event index
+	let filter = |module: Option<&str>, name: Option<&str>| EventFilter {
+		module: module.map(ToString::to_string),
+		name: name.map(ToString::to_string),
+	};
+	assert_eq!(
+		chain
+			.get_events(&filter(None, Some("Transferred")), 0, 3, 0, 10)
+			.unwrap(),
+		expected
+	);
+	assert_eq!(
+		chain
+			.get_events(&filter(Some("balance"), None), 2, 3, 1, 10)
+			.unwrap(),
+		IndexPage {
+			total: 2,
+			entries: vec![entry(3, &tx2)],
+		}
+	);
+	assert_eq!(
+		chain
+			.get_events(&filter(Some("balance"), Some("Transferred")), 1, 2, 0, 10)
+			.unwrap(),
+		IndexPage {
+			total: 1,
+			entries: vec![entry(1, &tx0)],
+		}
+	);
+	assert_eq!(
+		chain
+			.get_events(&filter(Some("system"), Some("Transferred")), 0, 3, 0, 10)
+			.unwrap(),
+		IndexPage::default()
+	);
+	assert!(chain.get_events(&filter(None, None), 0, 3, 0, 10).is_err());
+
+	// the entries of the reverted blocks are removed
+	chain.revert(2).unwrap();
+	assert_eq!(
+		chain
+			.get_address_transactions(&account2.address, 0, 10)
+			.unwrap(),
+		IndexPage {
+			total: 1,
+			entries: vec![entry(1, &tx0)],
+		}
+	);
+	commit_test_block(&chain, 3, vec![tx1.clone()]).await;
+	assert_eq!(
+		chain
+			.get_events(&filter(None, Some("Transferred")), 0, 3, 0, 10)
+			.unwrap(),
+		IndexPage {
+			total: 2,
+			entries: vec![entry(1, &tx0), entry(3, &tx1)],
+		}
+	);
+}
+
+#[tokio::test]
+async fn test_chain_receipt_proof() {
+	let _ = env_logger::try_init();
+
+	use tempfile::tempdir;
+
+	let dsa = Arc::new(DsaImpl::Ed25519);
+	let address = Arc::new(AddressImpl::Blake2b160);
+
+	let test_accounts = test_accounts(dsa, address);
+	let (account1, account2) = (&test_accounts[0], &test_accounts[1]);
+
+	let home = tempdir().expect("Could not create a temp dir").into_path();
+	init(&home, &account1.address);
+	let chain = new_chain(home);
+
+	let tx = chain
+		.build_transaction(
+			Some((account1.secret_key.clone(), 0, 10)),
+			chain
+				.build_call(
+					"balance".to_string(),
+					"transfer".to_string(),
+					module::balance::TransferParams {
+						recipient: account2.address.clone(),
+						value: 1,
+					},
+				)
+				.unwrap(),
+		)
+		.unwrap();
+	let tx_hash = chain.hash_transaction(&tx).unwrap();
+
+	commit_test_block(
+		&chain,
+		1,
+		vec![Arc::new(FullTransaction {
+			tx,
+			tx_hash: tx_hash.clone(),
+		})],
+	)
+	.await;
+
+	// block 1 is executed, but its execution is not committed by a header yet
+	assert!(chain.get_receipt(&tx_hash).unwrap().is_some());
+	assert_eq!(chain.get_receipt_proof(&tx_hash).unwrap(), None);
+
+	commit_test_block(&chain, 2, vec![]).await;
+
+	// the header of block 2 commits the execution of block 1
+	let block_hash = chain.get_block_hash(&2).unwrap().unwrap();
+	let header = chain.get_header(&block_hash).unwrap().unwrap();
+	let receipt = chain.get_receipt(&tx_hash).unwrap().unwrap();
+	let proof = chain.get_receipt_proof(&tx_hash).unwrap().unwrap();
+	assert_eq!(proof.block_hash, block_hash);
+	assert_eq!(proof.root, header.payload_execution_receipts_root);
+	assert!(!proof.meta);
+	assert_eq!(proof.value, receipt.encode());
+}
+
+#[tokio::test]
+async fn test_chain_verify() {
+	let _ = env_logger::try_init();
+
+	use tempfile::tempdir;
+
+	let dsa = Arc::new(DsaImpl::Ed25519);
+	let address = Arc::new(AddressImpl::Blake2b160);
+
+	let test_accounts = test_accounts(dsa, address);
+	let (account1, account2) = (&test_accounts[0], &test_accounts[1]);
+
+	let home = tempdir().expect("Could not create a temp dir").into_path();
+	init(&home, &account1.address);
+	let chain = new_chain(home);
+
+	commit_test_block(&chain, 1, vec![]).await;
+	let tx = chain
+		.build_transaction(
+			Some((account1.secret_key.clone(), 0, 10)),
+			chain
+				.build_call(
+					"balance".to_string(),
+					"transfer".to_string(),
+					module::balance::TransferParams {
+						recipient: account2.address.clone(),
+						value: 1,
+					},
+				)
+				.unwrap(),
+		)
+		.unwrap();
+	let tx_hash = chain.hash_transaction(&tx).unwrap();
+	commit_test_block(
+		&chain,
+		2,
+		vec![Arc::new(FullTransaction {
+			tx,
+			tx_hash: tx_hash.clone(),
+		})],
+	)
+	.await;
+	commit_test_block(&chain, 3, vec![]).await;
+
+	for number in 0..=3 {
+		chain.verify_block(number, false).unwrap();
+		chain.verify_block(number, true).unwrap();
+	}
+	assert!(chain.verify_block(4, false).is_err());
+
+	// tamper the receipt of the transaction
+	let mut receipt = chain.get_receipt(&tx_hash).unwrap().unwrap();
+	receipt.events = vec![];
+	let mut transaction = DBTransaction::new();
+	transaction.put(
+		node_db::columns::RECEIPT,
+		&tx_hash.0,
+		&codec::encode(&receipt).unwrap(),
+	);
+	chain.commit_consensus_data(transaction).unwrap();
+
+	chain.verify_block(1, true).unwrap();
+	let result = chain.verify_block(2, false);
+	assert!(format!("{}", result.unwrap_err()).contains("payload_execution_receipts_root"));
+}
+
+#[tokio::test]
+async fn test_chain_checkpoint() {
+	let _ = env_logger::try_init();
+
+	use tempfile::tempdir;
+
+	let dsa = Arc::new(DsaImpl::Ed25519);
+	let address = Arc::new(AddressImpl::Blake2b160);
+
+	let test_accounts = test_accounts(dsa, address);
+	let account1 = &test_accounts[0];
+
+	let home = tempdir().expect("Could not create a temp dir").into_path();
+	init(&home, &account1.address);
+	let db = DBConfig {
+		memory_budget: 1 * 1024 * 1024,
+		path: home.join("data").join("db"),
+		partitions: vec![],
+		backend: DBBackend::RocksDB,
+	};
+	let chain = Chain::new(ChainConfig {
+		home: home.clone(),
+		db: db.clone(),
+		pruning: StatePruning::Archive,
+		index: false,
+	})
+	.unwrap();
+
+	commit_test_block(&chain, 1, vec![]).await;
+	commit_test_block(&chain, 2, vec![]).await;
+
+	let path = home.join("checkpoint");
+	let checkpoint = chain.create_checkpoint(&path).unwrap();
+	let genesis_hash = chain.get_block_hash(&0).unwrap().unwrap();
+	assert_eq!(
+		checkpoint,
+		Checkpoint {
+			genesis_hash,
+			confirmed_number: 2,
+			execution_number: 2,
+		}
+	);
+
+	let content = fs::read_to_string(path.join(CHECKPOINT_FILE)).unwrap();
+	assert_eq!(toml::from_str::<Checkpoint>(&content).unwrap(), checkpoint);
+
+	// the existing checkpoint is never overwritten
+	assert!(chain.create_checkpoint(&path).is_err());
+
+	// the chain keeps going after the checkpoint
+	commit_test_block(&chain, 3, vec![]).await;
+
+	let checkpoint_chain = Chain::new(ChainConfig {
+		home,
+		db: DBConfig {
+			path: path.join(CHECKPOINT_DB),
+			..db
+		},
+		pruning: StatePruning::Archive,
+		index: false,
+	})
+	.unwrap();
+	assert_eq!(checkpoint_chain.get_confirmed_number().unwrap(), Some(2));
+	assert_eq!(checkpoint_chain.get_execution_number().unwrap(), Some(2));
+	assert_eq!(
+		checkpoint_chain.get_block_hash(&2).unwrap(),
+		chain.get_block_hash(&2).unwrap()
+	);
+	assert_eq!(checkpoint_chain.get_block_hash(&3).unwrap(), None);
+}
+
 fn expected_data(
 	chain: &Chain,
 	account: &Address,
@@ -243,7 +823,11 @@ fn expected_data(
 						timestamp,
 						max_until_gap: 20,
 						max_execution_gap: 8,
+						max_block_txs: 10_000,
+						max_block_bytes: 4 * 1024 * 1024,
 						consensus: "poa".to_string(),
+						fee: None,
+						admin: None,
 					},
 				)
 				.unwrap(),
@@ -296,6 +880,7 @@ fn expected_data(
 	let header = Header {
 		number: 0,
 		timestamp,
+		author: None,
 		parent_hash: Hash(zero_hash.clone()),
 		meta_txs_root,
 		meta_state_root,
@@ -361,6 +946,7 @@ fn expected_block_0_receipts_root(
 					block_number: 0,
 					events: vec![],
 					result: Ok(codec::encode(&()).unwrap()),
+					gas_used: 0,
 				},
 			})
 		})
@@ -393,10 +979,22 @@ fn expected_block_0_meta_state_root(txs: &Vec<Arc<FullTransaction>>) -> Hash {
 			DBKey::from_slice(b"system_max_execution_gap"),
 			Some(codec::encode(&params.max_execution_gap).unwrap()),
 		),
+		(
+			DBKey::from_slice(b"system_max_block_txs"),
+			Some(codec::encode(&params.max_block_txs).unwrap()),
+		),
+		(
+			DBKey::from_slice(b"system_max_block_bytes"),
+			Some(codec::encode(&params.max_block_bytes).unwrap()),
+		),
 		(
 			DBKey::from_slice(b"system_consensus"),
 			Some(codec::encode(&params.consensus).unwrap()),
 		),
+		(
+			DBKey::from_slice(b"system_fee"),
+			Some(codec::encode(&params.fee).unwrap()),
+		),
 	]
 	.into_iter()
 	.collect::<HashMap<_, _>>();
@@ -410,6 +1008,7 @@ fn expected_block_0_meta_state_root(txs: &Vec<Arc<FullTransaction>>) -> Hash {
 		memory_budget: 1 * 1024 * 1024,
 		path,
 		partitions: vec![],
+		backend: DBBackend::Memory,
 	};
 
 	let db = Arc::new(DB::open(db_config).unwrap());
@@ -419,7 +1018,7 @@ fn expected_block_0_meta_state_root(txs: &Vec<Arc<FullTransaction>>) -> Hash {
 		Arc::new(StateDB::new(db.clone(), node_db::columns::META_STATE, hasher.clone()).unwrap());
 
 	let (state_root, _) = statedb
-		.prepare_update(&statedb.default_root(), data.iter())
+		.prepare_update(&statedb.default_root(), data.iter(), 0)
 		.unwrap();
 	Hash(state_root)
 }
@@ -433,7 +1032,7 @@ fn expected_block_0_payload_state_root(txs: &Vec<Arc<FullTransaction>>) -> Hash
 	let data = vec![(
 		DBKey::from_slice(
 			&[
-				&b"balance_balance_"[..],
+				&b"balance_balance\0"[..],
 				&codec::encode(&account.0).unwrap(),
 			]
 			.concat(),
@@ -452,6 +1051,7 @@ fn expected_block_0_payload_state_root(txs: &Vec<Arc<FullTransaction>>) -> Hash
 		memory_budget: 1 * 1024 * 1024,
 		path,
 		partitions: vec![],
+		backend: DBBackend::Memory,
 	};
 
 	let db = Arc::new(DB::open(db_config).unwrap());
@@ -462,7 +1062,7 @@ fn expected_block_0_payload_state_root(txs: &Vec<Arc<FullTransaction>>) -> Hash
 	);
 
 	let (state_root, _) = statedb
-		.prepare_update(&statedb.default_root(), data.iter())
+		.prepare_update(&statedb.default_root(), data.iter(), 0)
 		.unwrap();
 	Hash(state_root)
 }
@@ -551,3 +1151,42 @@ params = '''
 
 	fs::write(config_path.join("spec.toml"), &spec).unwrap();
 }
+
+fn new_chain(home: PathBuf) -> Chain {
+	let db = DBConfig {
+		memory_budget: 1 * 1024 * 1024,
+		path: home.join("data").join("db"),
+		partitions: vec![],
+		backend: DBBackend::Memory,
+	};
+	let config = ChainConfig {
+		home,
+		db,
+		pruning: StatePruning::Archive,
+		index: true,
+	};
+	Chain::new(config).unwrap()
+}
+
+/// Build and commit a block executing the parent block, then wait for its execution
+async fn commit_test_block(
+	chain: &Chain,
+	number: BlockNumber,
+	payload_txs: Vec<Arc<FullTransaction>>,
+) {
+	let mut chain_rx = chain.subscribe_message();
+	let commit_block_params = chain
+		.build_block(BuildBlockParams {
+			number,
+			timestamp: number,
+			author: None,
+			meta_txs: vec![],
+			payload_txs,
+			execution_number: number - 1,
+		})
+		.unwrap();
+	chain.commit_block(commit_block_params).unwrap();
+	while chain.get_current_state().executed_number < number {
+		chain_rx.next().await;
+	}
+}
@@ -26,6 +26,9 @@ pub enum ErrorKind {
 	#[display(fmt = "Verify proof error: {}", _0)]
 	VerifyProofError(String),
 
+	#[display(fmt = "Exceed block limit: {}", _0)]
+	ExceedBlockLimit(String),
+
 	#[display(fmt = "Data error: {}", _0)]
 	Data(String),
 
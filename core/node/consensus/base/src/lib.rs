@@ -51,6 +51,9 @@ pub enum ConsensusInMessage {
 		block_hash: Hash,
 	},
 	Generate,
+	TxInserted {
+		tx_hash: Hash,
+	},
 	GetConsensusState {
 		tx: oneshot::Sender<Value>,
 	},
@@ -12,22 +12,52 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 use crate::errors::ErrorKind;
 use crate::scheduler::ScheduleInfo;
+use crypto::address::Address as AddressT;
 use log::debug;
 use node_chain::{Basic, Chain, ChainCommitBlockParams, CurrentState, DBTransaction};
 use node_txpool::support::DefaultTxPoolSupport;
-use node_txpool::TxPool;
-use primitives::codec::{Decode, Encode};
+use node_txpool::{RemoveReason, TxPool};
+use primitives::codec::{self, Decode, Encode};
 use primitives::errors::{Catchable, CommonResult};
 use primitives::types::{CallResult, ExecutionGap};
 use primitives::{
-	Address, BlockNumber, BuildBlockParams, Call, FullTransaction, Hash, Header, Proof, Transaction,
+	Address, BlockNumber, BuildBlockParams, Call, FullTransaction, Hash, Header, Nonce, Proof,
+	Transaction,
 };
 
+/// The txs of a block (meta_txs, payload_txs)
+type BlockTxs = (Vec<Arc<FullTransaction>>, Vec<Arc<FullTransaction>>);
+
+/// Verify the txs of a block received from other nodes against max_block_txs and max_block_bytes
+pub fn verify_block_limits(
+	meta_txs: &[Transaction],
+	payload_txs: &[Transaction],
+	max_block_txs: u32,
+	max_block_bytes: u64,
+) -> CommonResult<()> {
+	let txs_count = meta_txs.len() + payload_txs.len();
+	if txs_count > max_block_txs as usize {
+		let msg = format!("txs count: {}, max: {}", txs_count, max_block_txs);
+		return Err(ErrorKind::ExceedBlockLimit(msg).into());
+	}
+
+	let mut txs_bytes = 0u64;
+	for tx in meta_txs.iter().chain(payload_txs.iter()) {
+		txs_bytes += codec::encode(tx)?.len() as u64;
+	}
+	if txs_bytes > max_block_bytes {
+		let msg = format!("txs bytes: {}, max: {}", txs_bytes, max_block_bytes);
+		return Err(ErrorKind::ExceedBlockLimit(msg).into());
+	}
+
+	Ok(())
+}
+
 pub trait ConsensusSupport: Send + Sync + 'static {
 	fn get_confirmed_number(&self) -> CommonResult<Option<BlockNumber>>;
 	fn get_execution_number(&self) -> CommonResult<Option<BlockNumber>>;
@@ -50,7 +80,12 @@ pub trait ConsensusSupport: Send + Sync + 'static {
 		params: P,
 	) -> CommonResult<CallResult<R>>;
 	fn is_meta_call(&self, call: &Call) -> CommonResult<bool>;
-	fn prepare_block(&self, schedule_info: ScheduleInfo) -> CommonResult<BuildBlockParams>;
+	fn get_nonce(&self, address: &Address) -> CommonResult<Nonce>;
+	fn prepare_block(
+		&self,
+		schedule_info: ScheduleInfo,
+		author: Option<Address>,
+	) -> CommonResult<BuildBlockParams>;
 	fn build_block(
 		&self,
 		build_block_params: BuildBlockParams,
@@ -68,7 +103,11 @@ pub trait ConsensusSupport: Send + Sync + 'static {
 	) -> CommonResult<()>;
 	fn commit_consensus_data(&self, transaction: DBTransaction) -> CommonResult<()>;
 	fn txpool_get_transactions(&self) -> CommonResult<Vec<Arc<FullTransaction>>>;
-	fn txpool_remove_transactions(&self, tx_hash_set: &HashSet<Hash>) -> CommonResult<()>;
+	fn txpool_remove_transactions(
+		&self,
+		tx_hash_set: &HashSet<Hash>,
+		reason: RemoveReason,
+	) -> CommonResult<()>;
 }
 
 pub struct DefaultConsensusSupport {
@@ -80,6 +119,88 @@ impl DefaultConsensusSupport {
 	pub fn new(chain: Arc<Chain>, txpool: Arc<TxPool<DefaultTxPoolSupport>>) -> Self {
 		Self { chain, txpool }
 	}
+
+	/// Select the txs whose nonces are contiguous with the nonces expected by their senders
+	fn sequence_transactions(
+		&self,
+		txs: Vec<Arc<FullTransaction>>,
+		next_nonces: &mut HashMap<Address, Nonce>,
+	) -> CommonResult<Vec<Arc<FullTransaction>>> {
+		let addresser = self.get_basic()?.address.clone();
+		let address_len = addresser.length().into();
+
+		let mut result = Vec::with_capacity(txs.len());
+		for tx in txs {
+			let witness = match &tx.tx.witness {
+				Some(witness) => witness,
+				None => {
+					result.push(tx);
+					continue;
+				}
+			};
+			let sender = {
+				let mut address = vec![0u8; address_len];
+				addresser.address(&mut address, &witness.public_key.0);
+				Address(address)
+			};
+			let next_nonce = match next_nonces.get(&sender) {
+				Some(next_nonce) => *next_nonce,
+				None => self.get_nonce(&sender)?,
+			};
+			if witness.nonce == next_nonce {
+				next_nonces.insert(sender, next_nonce.saturating_add(1));
+				result.push(tx);
+			}
+		}
+		Ok(result)
+	}
+
+	/// Take the txs in order within max_block_txs and max_block_bytes
+	/// (meta txs before payload txs)
+	/// a tx exceeding the limits is skipped so that the smaller txs after it can still be taken,
+	/// and so are the later txs of its sender, so that the nonces of each sender keep contiguous
+	fn limit_transactions(
+		&self,
+		meta_txs: Vec<Arc<FullTransaction>>,
+		payload_txs: Vec<Arc<FullTransaction>>,
+		max_block_txs: u32,
+		max_block_bytes: u64,
+	) -> CommonResult<BlockTxs> {
+		let mut txs_count = 0u32;
+		let mut txs_bytes = 0u64;
+		let mut skipped_senders = HashSet::new();
+
+		let mut take = |txs: Vec<Arc<FullTransaction>>| -> CommonResult<Vec<Arc<FullTransaction>>> {
+			let mut result = Vec::with_capacity(txs.len());
+			for tx in txs {
+				if txs_count >= max_block_txs {
+					break;
+				}
+				let sender = tx.tx.witness.as_ref().map(|x| &x.public_key);
+				if let Some(sender) = sender {
+					if skipped_senders.contains(sender) {
+						continue;
+					}
+				}
+				let tx_bytes = codec::encode(&tx.tx)?.len() as u64;
+				if txs_bytes + tx_bytes > max_block_bytes {
+					if let Some(sender) = sender {
+						skipped_senders.insert(sender.clone());
+					}
+					continue;
+				}
+				txs_count += 1;
+				txs_bytes += tx_bytes;
+				result.push(tx);
+			}
+			Ok(result)
+		};
+
+		let meta_txs = take(meta_txs)?;
+		let payload_txs = take(payload_txs)?;
+
+		Ok((meta_txs, payload_txs))
+	}
 }
 
 impl ConsensusSupport for DefaultConsensusSupport {
@@ -124,7 +245,14 @@ impl ConsensusSupport for DefaultConsensusSupport {
 	fn is_meta_call(&self, call: &Call) -> CommonResult<bool> {
 		self.chain.is_meta_call(call)
 	}
-	fn prepare_block(&self, schedule_info: ScheduleInfo) -> CommonResult<BuildBlockParams> {
+	fn get_nonce(&self, address: &Address) -> CommonResult<Nonce> {
+		self.chain.get_nonce(address)
+	}
+	fn prepare_block(
+		&self,
+		schedule_info: ScheduleInfo,
+		author: Option<Address>,
+	) -> CommonResult<BuildBlockParams> {
 		let current_state = &self.get_current_state();
 
 		let system_meta = &current_state.system_meta;
@@ -166,11 +294,33 @@ impl ConsensusSupport for DefaultConsensusSupport {
 			}
 		}
 		debug!("Invalid txs count: {}", invalid_txs.len());
+
+		// txs of each sender should be packed in the order of nonce without gap,
+		// the others (e.g. arrived before their predecessors) are kept in the txpool
+		// for the later blocks
+		// (meta txs are checked before payload txs when building a block)
+		let mut next_nonces = HashMap::new();
+		let meta_txs = self.sequence_transactions(meta_txs, &mut next_nonces)?;
+		let payload_txs = self.sequence_transactions(payload_txs, &mut next_nonces)?;
+
+		// the txs exceeding the block limits are kept in the txpool for the later blocks
+		let (meta_txs, payload_txs) = self.limit_transactions(
+			meta_txs,
+			payload_txs,
+			system_meta.max_block_txs,
+			system_meta.max_block_bytes,
+		)?;
+		debug!(
+			"Packed txs count: meta: {}, payload: {}",
+			meta_txs.len(),
+			payload_txs.len()
+		);
+
 		let invalid_tx_hash_set = invalid_txs
 			.into_iter()
 			.map(|x| x.tx_hash.clone())
 			.collect::<HashSet<_>>();
-		self.txpool_remove_transactions(&invalid_tx_hash_set)?;
+		self.txpool_remove_transactions(&invalid_tx_hash_set, RemoveReason::Invalid)?;
 
 		let block_execution_gap = (number - execution_number) as ExecutionGap;
 		if block_execution_gap > system_meta.max_execution_gap {
@@ -184,6 +334,7 @@ impl ConsensusSupport for DefaultConsensusSupport {
 		let build_block_params = BuildBlockParams {
 			number,
 			timestamp,
+			author,
 			meta_txs,
 			payload_txs,
 			execution_number,
@@ -227,7 +378,11 @@ impl ConsensusSupport for DefaultConsensusSupport {
 		let txs = (*self.txpool.get_queue().read()).clone();
 		Ok(txs)
 	}
-	fn txpool_remove_transactions(&self, tx_hash_set: &HashSet<Hash>) -> CommonResult<()> {
-		self.txpool.remove(tx_hash_set)
+	fn txpool_remove_transactions(
+		&self,
+		tx_hash_set: &HashSet<Hash>,
+		reason: RemoveReason,
+	) -> CommonResult<()> {
+		self.txpool.remove(tx_hash_set, reason)
 	}
 }
@@ -0,0 +1,309 @@
+// Copyright 2019, 2020 Wingchain
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Instant seal consensus, for development only
+//! one node seals a new block as soon as a transaction is inserted into the txpool,
+//! or on demand when asked to generate one
+//!
+//! The proof proves that the block is sealed by the configured authority as its author,
+//! so there should be only one node with the secret key of the authority in the network
+
+use std::collections::HashSet;
+use std::convert::TryInto;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use futures::channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender};
+use futures::prelude::*;
+use log::{error, info};
+use parking_lot::RwLock;
+use serde::Serialize;
+use serde_json::Value;
+
+use crypto::address::Address as AddressT;
+use crypto::dsa::{Dsa, KeyPair, Verifier as VerifierT};
+use crypto::hash::Hash as HashT;
+use node_consensus_base::{
+	scheduler::ScheduleInfo, support::ConsensusSupport, Consensus as ConsensusT,
+	ConsensusInMessage, ConsensusOutMessage,
+};
+use node_consensus_primitives::CONSENSUS_INSTANT;
+use node_txpool::RemoveReason;
+use primitives::errors::CommonResult;
+use primitives::{codec, Address, Hash, Header, SecretKey};
+
+use crate::proof::Proof;
+
+pub mod proof;
+
+pub struct InstantConfig {
+	pub secret_key: Option<SecretKey>,
+	/// Address of the node sealing the blocks, the address of the secret key by default
+	pub authority: Option<Address>,
+}
+
+pub struct Instant<S>
+where
+	S: ConsensusSupport,
+{
+	support: Arc<S>,
+	authority: Option<Address>,
+	in_tx: UnboundedSender<ConsensusInMessage>,
+	out_rx: RwLock<Option<UnboundedReceiver<ConsensusOutMessage>>>,
+}
+
+impl<S> ConsensusT for Instant<S>
+where
+	S: ConsensusSupport,
+{
+	type Config = InstantConfig;
+	type Support = S;
+
+	fn new(config: InstantConfig, support: Arc<S>) -> CommonResult<Self> {
+		let authority = match (&config.authority, &config.secret_key) {
+			(Some(authority), _) => Some(authority.clone()),
+			(None, Some(secret_key)) => Some(get_address(secret_key, &support)?),
+			(None, None) => None,
+		};
+
+		let (in_tx, in_rx) = unbounded();
+		let (out_tx, out_rx) = unbounded();
+
+		InstantStream::spawn(support.clone(), config, out_tx, in_rx)?;
+
+		info!("Initializing consensus instant");
+
+		let instant = Instant {
+			support,
+			authority,
+			in_tx,
+			out_rx: RwLock::new(Some(out_rx)),
+		};
+
+		Ok(instant)
+	}
+	fn verify_proof(&self, header: &Header, proof: &primitives::Proof) -> CommonResult<()> {
+		let name = &proof.name;
+		if name != CONSENSUS_INSTANT {
+			return Err(
+				node_consensus_base::errors::ErrorKind::VerifyProofError(format!(
+					"Unexpected consensus: {}",
+					name
+				))
+				.into(),
+			);
+		}
+		let data = &proof.data;
+		let proof: Proof = codec::decode(&mut &data[..]).map_err(|_| {
+			node_consensus_base::errors::ErrorKind::VerifyProofError("Decode error".to_string())
+		})?;
+
+		let basic = self.support.get_basic()?;
+
+		let block_hash = {
+			let hasher = basic.hash.clone();
+			let mut out = vec![0u8; hasher.length().into()];
+			hasher.hash(&mut out, &codec::encode(header)?);
+			Hash(out)
+		};
+		let verifier = basic
+			.dsa
+			.verifier_from_public_key(&proof.public_key.0)
+			.map_err(|_| {
+				node_consensus_base::errors::ErrorKind::VerifyProofError(
+					"Invalid public key".to_string(),
+				)
+			})?;
+		verifier
+			.verify(&codec::encode(&block_hash)?, &proof.signature.0)
+			.map_err(|_| {
+				node_consensus_base::errors::ErrorKind::VerifyProofError(
+					"Invalid signature".to_string(),
+				)
+			})?;
+
+		let address = {
+			let addresser = basic.address.clone();
+			let address_len = addresser.length().into();
+			let mut address = vec![0u8; address_len];
+			addresser.address(&mut address, &proof.public_key.0);
+			Address(address)
+		};
+		if self.authority.as_ref() != Some(&address) {
+			return Err(node_consensus_base::errors::ErrorKind::VerifyProofError(
+				"Not authority".to_string(),
+			)
+			.into());
+		}
+		if header.author.as_ref() != Some(&address) {
+			return Err(node_consensus_base::errors::ErrorKind::VerifyProofError(
+				"Not author".to_string(),
+			)
+			.into());
+		}
+		Ok(())
+	}
+
+	fn in_message_tx(&self) -> UnboundedSender<ConsensusInMessage> {
+		self.in_tx.clone()
+	}
+
+	fn out_message_rx(&self) -> Option<UnboundedReceiver<ConsensusOutMessage>> {
+		self.out_rx.write().take()
+	}
+}
+
+struct InstantStream<S>
+where
+	S: ConsensusSupport,
+{
+	support: Arc<S>,
+	secret_key: SecretKey,
+	address: Address,
+	#[allow(dead_code)]
+	out_tx: UnboundedSender<ConsensusOutMessage>,
+	in_rx: UnboundedReceiver<ConsensusInMessage>,
+}
+
+impl<S> InstantStream<S>
+where
+	S: ConsensusSupport,
+{
+	fn spawn(
+		support: Arc<S>,
+		instant_config: InstantConfig,
+		out_tx: UnboundedSender<ConsensusOutMessage>,
+		in_rx: UnboundedReceiver<ConsensusInMessage>,
+	) -> CommonResult<()> {
+		let secret_key = match instant_config.secret_key {
+			Some(v) => v,
+			None => return Ok(()),
+		};
+		let address = get_address(&secret_key, &support)?;
+
+		let this = Self {
+			support,
+			secret_key,
+			address,
+			out_tx,
+			in_rx,
+		};
+		tokio::spawn(this.start());
+		Ok(())
+	}
+
+	async fn start(mut self) {
+		info!("Start instant work");
+		while let Some(in_message) = self.in_rx.next().await {
+			self.on_in_message(in_message)
+				.unwrap_or_else(|e| error!("Instant stream handle in message error: {}", e));
+		}
+	}
+
+	/// Seal a new block with the transactions in the txpool,
+	/// the empty block is only sealed if `allow_empty` is true
+	fn work(&self, allow_empty: bool) -> CommonResult<()> {
+		let timestamp = SystemTime::now();
+		let timestamp = timestamp
+			.duration_since(SystemTime::UNIX_EPOCH)
+			.map_err(|_| node_consensus_base::errors::ErrorKind::Time)?;
+		let timestamp = timestamp.as_millis() as u64;
+		let schedule_info = ScheduleInfo { timestamp };
+
+		let build_block_params = self
+			.support
+			.prepare_block(schedule_info, Some(self.address.clone()))?;
+		let tx_hash_set = build_block_params
+			.meta_txs
+			.iter()
+			.map(|x| x.tx_hash.clone())
+			.chain(
+				build_block_params
+					.payload_txs
+					.iter()
+					.map(|x| x.tx_hash.clone()),
+			)
+			.collect::<HashSet<_>>();
+
+		if tx_hash_set.is_empty() && !allow_empty {
+			return Ok(());
+		}
+
+		let mut commit_block_params = self.support.build_block(build_block_params)?;
+
+		let proof = Proof::new(
+			&commit_block_params.block_hash,
+			&self.secret_key,
+			self.support.get_basic()?.dsa.clone(),
+		)?;
+		commit_block_params.proof = proof.try_into()?;
+
+		self.support.commit_block(commit_block_params)?;
+
+		self.support
+			.txpool_remove_transactions(&tx_hash_set, RemoveReason::Included)?;
+
+		Ok(())
+	}
+
+	fn on_in_message(&self, in_message: ConsensusInMessage) -> CommonResult<()> {
+		match in_message {
+			ConsensusInMessage::Generate => {
+				self.work(true)?;
+			}
+			// the transactions left by the previous block are sealed once it is committed
+			ConsensusInMessage::TxInserted { .. } | ConsensusInMessage::BlockCommitted { .. } => {
+				self.work(false)?;
+			}
+			ConsensusInMessage::GetConsensusState { tx } => {
+				let value = serde_json::to_value(self.consensus_state()).unwrap_or(Value::Null);
+				let _ = tx.send(value);
+			}
+			_ => {}
+		}
+		Ok(())
+	}
+
+	fn consensus_state(&self) -> ConsensusState {
+		ConsensusState {
+			consensus_name: CONSENSUS_INSTANT.to_string(),
+			address: self.address.clone(),
+		}
+	}
+}
+
+fn get_address<S: ConsensusSupport>(
+	secret_key: &SecretKey,
+	support: &Arc<S>,
+) -> CommonResult<Address> {
+	let dsa = support.get_basic()?.dsa.clone();
+	let (_, public_key_len, _) = dsa.length().into();
+	let mut public_key = vec![0u8; public_key_len];
+	dsa.key_pair_from_secret_key(&secret_key.0)?
+		.public_key(&mut public_key);
+
+	let addresser = support.get_basic()?.address.clone();
+	let address_len = addresser.length().into();
+	let mut address = vec![0u8; address_len];
+	addresser.address(&mut address, &public_key);
+
+	let address = Address(address);
+	Ok(address)
+}
+
+#[derive(Serialize)]
+struct ConsensusState {
+	consensus_name: String,
+	address: Address,
+}
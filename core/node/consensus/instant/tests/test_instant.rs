@@ -0,0 +1,116 @@
+// Copyright 2019, 2020 Wingchain
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::convert::TryInto;
+use std::sync::Arc;
+
+use crypto::address::AddressImpl;
+use crypto::dsa::DsaImpl;
+use crypto::hash::{Hash as HashT, HashImpl};
+use node_consensus_base::ConsensusInMessage;
+use node_executor::module;
+use primitives::{codec, Hash, Proof};
+use utils_test::test_accounts;
+
+mod base;
+
+#[tokio::test]
+async fn test_instant() {
+	let _ = env_logger::try_init();
+
+	let dsa = Arc::new(DsaImpl::Ed25519);
+	let address = Arc::new(AddressImpl::Blake2b160);
+	let hasher = Arc::new(HashImpl::Blake2b256);
+
+	let test_accounts = test_accounts(dsa.clone(), address);
+	let (account1, account2) = (&test_accounts[0], &test_accounts[1]);
+
+	let (chain, txpool, consensus) = base::get_standalone_service(&[account1], account1);
+
+	// generate the empty block 1 on demand
+	consensus
+		.in_message_tx()
+		.unbounded_send(ConsensusInMessage::Generate)
+		.unwrap();
+	base::wait_block_execution(&chain, 1).await;
+
+	let block_hash = chain.get_block_hash(&1).unwrap().unwrap();
+	let block = chain.get_block(&block_hash).unwrap().unwrap();
+	assert!(block.body.payload_txs.is_empty());
+
+	// seal block 2 once the transaction is inserted
+	let tx_hash = base::insert_tx(
+		&chain,
+		&txpool,
+		chain
+			.build_transaction(
+				Some((account1.secret_key.clone(), 0, 10)),
+				chain
+					.build_call(
+						"balance".to_string(),
+						"transfer".to_string(),
+						module::balance::TransferParams {
+							recipient: account2.address.clone(),
+							value: 1,
+						},
+					)
+					.unwrap(),
+			)
+			.unwrap(),
+	)
+	.await;
+	consensus
+		.in_message_tx()
+		.unbounded_send(ConsensusInMessage::TxInserted {
+			tx_hash: tx_hash.clone(),
+		})
+		.unwrap();
+	base::wait_block_execution(&chain, 2).await;
+	base::wait_txpool(&txpool, 0).await;
+
+	let block_hash = chain.get_block_hash(&2).unwrap().unwrap();
+	let block = chain.get_block(&block_hash).unwrap().unwrap();
+	assert_eq!(block.body.payload_txs, vec![tx_hash]);
+	assert_eq!(block.header.author, Some(account1.address.clone()));
+
+	// verify the proof
+	let proof = chain.get_proof(&block_hash).unwrap().unwrap();
+	consensus.verify_proof(&block.header, &proof).unwrap();
+
+	let mut header = block.header.clone();
+	header.author = Some(account2.address.clone());
+	let result = consensus.verify_proof(&header, &proof);
+	assert!(format!("{}", result.unwrap_err()).contains("Invalid signature"));
+
+	let mut header = block.header.clone();
+	header.timestamp += 1;
+	let result = consensus.verify_proof(&header, &proof);
+	assert!(format!("{}", result.unwrap_err()).contains("Invalid signature"));
+
+	// sealed by another account as the author
+	let mut header = block.header;
+	header.author = Some(account2.address.clone());
+	let block_hash = {
+		let mut out = vec![0; hasher.length().into()];
+		hasher.hash(&mut out, &codec::encode(&header).unwrap());
+		Hash(out)
+	};
+	let proof: Proof =
+		node_consensus_instant::proof::Proof::new(&block_hash, &account2.secret_key, dsa)
+			.unwrap()
+			.try_into()
+			.unwrap();
+	let result = consensus.verify_proof(&header, &proof);
+	assert!(format!("{}", result.unwrap_err()).contains("Not authority"));
+}
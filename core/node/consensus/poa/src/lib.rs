@@ -13,7 +13,14 @@
 // limitations under the License.
 
 //! POA consensus
-//! one node append new blocks at a certain frequency specified by block interval
+//! the authorities take turns to append new blocks slot by slot,
+//! the slot is derived from the timestamp and the block interval,
+//! or is the block number if the blocks are generated on demand
+//!
+//! The authority of a slot is the one at `slot % authorities`,
+//! if no block of the slot is received by the middle of the slot,
+//! the next authority seals the block as the backup,
+//! and the block of the backup is rejected once the block of the authority is seen
 
 use std::collections::HashSet;
 use std::convert::TryInto;
@@ -26,17 +33,19 @@ use log::{error, info, trace};
 use parking_lot::RwLock;
 
 use crypto::address::Address as AddressT;
-use crypto::dsa::{Dsa, KeyPair};
+use crypto::dsa::{Dsa, KeyPair, Verifier as VerifierT};
+use crypto::hash::Hash as HashT;
 use node_consensus_base::{
 	scheduler::ScheduleInfo, scheduler::Scheduler, support::ConsensusSupport,
 	Consensus as ConsensusT, ConsensusInMessage, ConsensusOutMessage,
 };
 use node_consensus_primitives::CONSENSUS_POA;
 use node_executor::module;
-use node_executor::module::poa::Meta;
+use node_executor::module::poa::{Authorities, Meta};
 use node_executor_primitives::EmptyParams;
+use node_txpool::RemoveReason;
 use primitives::errors::CommonResult;
-use primitives::{codec, Address, BlockNumber, Header, SecretKey};
+use primitives::{codec, Address, BlockNumber, Hash, Header, SecretKey};
 use serde::Serialize;
 
 use crate::proof::Proof;
@@ -53,6 +62,7 @@ where
 	S: ConsensusSupport,
 {
 	support: Arc<S>,
+	poa_meta: Arc<Meta>,
 	in_tx: UnboundedSender<ConsensusInMessage>,
 	out_rx: RwLock<Option<UnboundedReceiver<ConsensusOutMessage>>>,
 }
@@ -65,17 +75,18 @@ where
 	type Support = S;
 
 	fn new(config: PoaConfig, support: Arc<S>) -> CommonResult<Self> {
-		let poa_meta = get_poa_meta(&support, &0)?;
+		let poa_meta = Arc::new(get_poa_meta(&support, &0)?);
 
 		let (in_tx, in_rx) = unbounded();
 		let (out_tx, out_rx) = unbounded();
 
-		PoaStream::spawn(support.clone(), poa_meta, config, out_tx, in_rx)?;
+		PoaStream::spawn(support.clone(), poa_meta.clone(), config, out_tx, in_rx)?;
 
 		info!("Initializing consensus poa");
 
 		let poa = Poa {
 			support,
+			poa_meta,
 			in_tx,
 			out_rx: RwLock::new(Some(out_rx)),
 		};
@@ -98,22 +109,99 @@ where
 			node_consensus_base::errors::ErrorKind::VerifyProofError("Decode error".to_string())
 		})?;
 
+		let basic = self.support.get_basic()?;
+
+		let block_hash = {
+			let hasher = basic.hash.clone();
+			let mut out = vec![0u8; hasher.length().into()];
+			hasher.hash(&mut out, &codec::encode(header)?);
+			Hash(out)
+		};
+		let verifier = basic
+			.dsa
+			.verifier_from_public_key(&proof.public_key.0)
+			.map_err(|_| {
+				node_consensus_base::errors::ErrorKind::VerifyProofError(
+					"Invalid public key".to_string(),
+				)
+			})?;
+		verifier
+			.verify(&codec::encode(&block_hash)?, &proof.signature.0)
+			.map_err(|_| {
+				node_consensus_base::errors::ErrorKind::VerifyProofError(
+					"Invalid signature".to_string(),
+				)
+			})?;
+
+		// the slot can not be sealed ahead of time
+		if let Some(block_interval) = self.poa_meta.block_interval {
+			let now = SystemTime::now()
+				.duration_since(SystemTime::UNIX_EPOCH)
+				.map_err(|_| node_consensus_base::errors::ErrorKind::Time)?;
+			let now = now.as_millis() as u64;
+			if header.timestamp > now + block_interval {
+				return Err(
+					node_consensus_base::errors::ErrorKind::VerifyProofError(format!(
+						"Future timestamp: {}",
+						header.timestamp
+					))
+					.into(),
+				);
+			}
+		}
+
 		let address = {
-			let addresser = self.support.get_basic()?.address.clone();
+			let addresser = basic.address.clone();
 			let address_len = addresser.length().into();
 			let mut address = vec![0u8; address_len];
 			addresser.address(&mut address, &proof.public_key.0);
 			Address(address)
 		};
 
-		let authority_address = get_poa_authority(&self.support, &(header.number - 1))?;
-		let is_authority = address == authority_address;
-		if !is_authority {
+		let parent_header = self
+			.support
+			.get_header(&header.parent_hash)?
+			.ok_or_else(|| {
+				node_consensus_base::errors::ErrorKind::VerifyProofError(format!(
+					"Missing parent header: block_hash: {}",
+					header.parent_hash
+				))
+			})?;
+		let slot = get_slot(&self.poa_meta, header.number, header.timestamp);
+		let parent_slot = get_slot(
+			&self.poa_meta,
+			parent_header.number,
+			parent_header.timestamp,
+		);
+		if slot <= parent_slot {
+			return Err(node_consensus_base::errors::ErrorKind::VerifyProofError(
+				"Slot already sealed".to_string(),
+			)
+			.into());
+		}
+
+		let authorities = get_poa_authorities(&self.support, &(header.number - 1))?;
+		let is_author = is_slot_author(
+			&self.support,
+			&self.poa_meta,
+			&authorities,
+			header.number,
+			slot,
+			header.timestamp,
+			&address,
+		)?;
+		if !is_author {
 			return Err(node_consensus_base::errors::ErrorKind::VerifyProofError(
 				"Not authority".to_string(),
 			)
 			.into());
 		}
+		if header.author.as_ref() != Some(&address) {
+			return Err(node_consensus_base::errors::ErrorKind::VerifyProofError(
+				"Not author".to_string(),
+			)
+			.into());
+		}
 		Ok(())
 	}
 
@@ -147,7 +235,7 @@ where
 {
 	fn spawn(
 		support: Arc<S>,
-		poa_meta: Meta,
+		poa_meta: Arc<Meta>,
 		poa_config: PoaConfig,
 		out_tx: UnboundedSender<ConsensusOutMessage>,
 		in_rx: UnboundedReceiver<ConsensusInMessage>,
@@ -157,7 +245,6 @@ where
 			None => return Ok(()),
 		};
 		let address = get_address(&secret_key, &support)?;
-		let poa_meta = Arc::new(poa_meta);
 		let poa_config = Arc::new(poa_config);
 
 		let this = Self {
@@ -175,7 +262,9 @@ where
 
 	async fn start(mut self) {
 		info!("Start poa work");
-		let mut scheduler = Scheduler::new(self.poa_meta.block_interval);
+		// check the slot every quarter of the block interval,
+		// so that the backup authority can take over the missed slot in time
+		let mut scheduler = Scheduler::new(self.poa_meta.block_interval.map(|x| (x / 4).max(1)));
 		loop {
 			tokio::select! {
 				Some(schedule_info) = scheduler.next() => {
@@ -193,21 +282,53 @@ where
 	fn work(&self, schedule_info: ScheduleInfo) -> CommonResult<()> {
 		let current_state = &self.support.get_current_state();
 
-		let authority_address = get_poa_authority(&self.support, &current_state.confirmed_number)?;
-		let is_authority = self.address == authority_address;
+		let number = current_state.confirmed_number + 1;
+		let slot = get_slot(&self.poa_meta, number, schedule_info.timestamp);
+
+		let confirmed_header = self
+			.support
+			.get_header(&current_state.confirmed_block_hash)?
+			.ok_or_else(|| {
+				node_consensus_base::errors::ErrorKind::Data(format!(
+					"Missing header: block_hash: {}",
+					current_state.confirmed_block_hash
+				))
+			})?;
+		let confirmed_slot = get_slot(
+			&self.poa_meta,
+			confirmed_header.number,
+			confirmed_header.timestamp,
+		);
+		if slot <= confirmed_slot {
+			return Ok(());
+		}
+
+		let authorities = get_poa_authorities(&self.support, &current_state.confirmed_number)?;
+		let is_author = is_slot_author(
+			&self.support,
+			&self.poa_meta,
+			&authorities,
+			number,
+			slot,
+			schedule_info.timestamp,
+			&self.address,
+		)?;
 
 		trace!(
-			"Current node is authority: {}, authority address: {}, current address: {:?}",
-			is_authority,
-			authority_address,
+			"Current node is author: {}, slot: {}, authorities: {:?}, current address: {:?}",
+			is_author,
+			slot,
+			authorities.members,
 			self.address
 		);
 
-		if !is_authority {
+		if !is_author {
 			return Ok(());
 		}
 
-		let build_block_params = self.support.prepare_block(schedule_info)?;
+		let build_block_params = self
+			.support
+			.prepare_block(schedule_info, Some(self.address.clone()))?;
 		let tx_hash_set = build_block_params
 			.meta_txs
 			.iter()
@@ -232,7 +353,8 @@ where
 
 		self.support.commit_block(commit_block_params)?;
 
-		self.support.txpool_remove_transactions(&tx_hash_set)?;
+		self.support
+			.txpool_remove_transactions(&tx_hash_set, RemoveReason::Included)?;
 
 		Ok(())
 	}
@@ -269,13 +391,13 @@ where
 {
 	fn consensus_state(&self) -> CommonResult<ConsensusState> {
 		let current_state = self.support.get_current_state();
-		let authority = get_poa_authority(&self.support, &current_state.confirmed_number)?;
+		let authorities = get_poa_authorities(&self.support, &current_state.confirmed_number)?;
 
 		Ok(ConsensusState {
 			consensus_name: CONSENSUS_POA.to_string(),
 			address: self.address.clone(),
 			meta: (*self.poa_meta).clone(),
-			authority,
+			authorities,
 		})
 	}
 }
@@ -295,21 +417,78 @@ fn get_poa_meta<S: ConsensusSupport>(
 		.map(|x| x.expect("qed"))
 }
 
-fn get_poa_authority<S: ConsensusSupport>(
+fn get_poa_authorities<S: ConsensusSupport>(
 	support: &Arc<S>,
 	number: &BlockNumber,
-) -> CommonResult<Address> {
+) -> CommonResult<Authorities> {
 	support
 		.execute_call_with_block_number(
 			number,
 			None,
 			"poa".to_string(),
-			"get_authority".to_string(),
+			"get_authorities".to_string(),
 			EmptyParams,
 		)
 		.map(|x| x.expect("qed"))
 }
 
+/// Get the slot of the block
+fn get_slot(poa_meta: &Meta, number: BlockNumber, timestamp: u64) -> u64 {
+	match poa_meta.block_interval {
+		Some(block_interval) => timestamp / block_interval,
+		None => number,
+	}
+}
+
+/// Check if the address is allowed to seal the block of the number in the slot at the timestamp,
+/// the backup authority is only allowed in the second half of the slot,
+/// and only if the block of the number in the slot sealed by the authority is not seen
+fn is_slot_author<S: ConsensusSupport>(
+	support: &Arc<S>,
+	poa_meta: &Meta,
+	authorities: &Authorities,
+	number: BlockNumber,
+	slot: u64,
+	timestamp: u64,
+	address: &Address,
+) -> CommonResult<bool> {
+	let members = &authorities.members;
+	if members.is_empty() {
+		return Err(
+			node_consensus_base::errors::ErrorKind::Data("Empty authorities".to_string()).into(),
+		);
+	}
+	let len = members.len() as u64;
+	let authority = &members[(slot % len) as usize];
+	let backup = &members[((slot + 1) % len) as usize];
+
+	if address == authority {
+		return Ok(true);
+	}
+
+	let is_backup_time = match poa_meta.block_interval {
+		Some(block_interval) => timestamp % block_interval >= block_interval / 2,
+		None => false,
+	};
+	if !is_backup_time || address != backup {
+		return Ok(false);
+	}
+
+	let authority_header = match support.get_block_hash(&number)? {
+		Some(block_hash) => support.get_header(&block_hash)?,
+		None => None,
+	};
+	let sealed_by_authority = match authority_header {
+		Some(header) => {
+			get_slot(poa_meta, header.number, header.timestamp) == slot
+				&& header.author.as_ref() == Some(authority)
+		}
+		None => false,
+	};
+
+	Ok(!sealed_by_authority)
+}
+
 fn get_address<S: ConsensusSupport>(
 	secret_key: &SecretKey,
 	support: &Arc<S>,
@@ -334,5 +513,5 @@ struct ConsensusState {
 	consensus_name: String,
 	address: Address,
 	meta: Meta,
-	authority: Address,
+	authorities: Authorities,
 }
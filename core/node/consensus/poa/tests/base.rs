@@ -19,7 +19,7 @@ use std::sync::Arc;
 use std::time::Duration;
 use tempfile::tempdir;
 
-use node_chain::{Chain, ChainConfig, DBConfig};
+use node_chain::{Chain, ChainConfig, DBBackend, DBConfig, StatePruning};
 use node_consensus::{Consensus, ConsensusConfig};
 use node_consensus_base::support::DefaultConsensusSupport;
 use node_consensus_poa::PoaConfig;
@@ -46,9 +46,13 @@ pub fn get_service(
 	Arc<Consensus<DefaultConsensusSupport>>,
 	Arc<Coordinator<DefaultCoordinatorSupport>>,
 ) {
-	let chain = get_chain(authority_accounts);
+	let chain = get_chain(authority_accounts, None);
 
-	let txpool_config = TxPoolConfig { pool_capacity: 32 };
+	let txpool_config = TxPoolConfig {
+		pool_capacity: 32,
+		sender_capacity: None,
+		journal: false,
+	};
 
 	let txpool_support = Arc::new(DefaultTxPoolSupport::new(chain.clone()));
 	let txpool = Arc::new(TxPool::new(txpool_config, txpool_support).unwrap());
@@ -60,6 +64,7 @@ pub fn get_service(
 			secret_key: Some(account.secret_key.clone()),
 		}),
 		raft: None,
+		instant: None,
 	};
 
 	let consensus = Arc::new(Consensus::new(consensus_config, support).unwrap());
@@ -82,9 +87,27 @@ pub fn get_standalone_service(
 	Arc<TxPool<DefaultTxPoolSupport>>,
 	Arc<Consensus<DefaultConsensusSupport>>,
 ) {
-	let chain = get_chain(authority_accounts);
+	get_standalone_service_with_block_interval(authority_accounts, account, None)
+}
 
-	let txpool_config = TxPoolConfig { pool_capacity: 32 };
+/// Get the standalone service generating blocks every block interval
+#[allow(dead_code)]
+pub fn get_standalone_service_with_block_interval(
+	authority_accounts: &[&TestAccount],
+	account: &TestAccount,
+	block_interval: Option<u64>,
+) -> (
+	Arc<Chain>,
+	Arc<TxPool<DefaultTxPoolSupport>>,
+	Arc<Consensus<DefaultConsensusSupport>>,
+) {
+	let chain = get_chain(authority_accounts, block_interval);
+
+	let txpool_config = TxPoolConfig {
+		pool_capacity: 32,
+		sender_capacity: None,
+		journal: false,
+	};
 
 	let txpool_support = Arc::new(DefaultTxPoolSupport::new(chain.clone()));
 	let txpool = Arc::new(TxPool::new(txpool_config, txpool_support).unwrap());
@@ -96,6 +119,7 @@ pub fn get_standalone_service(
 			secret_key: Some(account.secret_key.clone()),
 		}),
 		raft: None,
+		instant: None,
 	};
 
 	let consensus = Arc::new(Consensus::new(consensus_config, support).unwrap());
@@ -103,6 +127,7 @@ pub fn get_standalone_service(
 	(chain, txpool, consensus)
 }
 
+#[allow(dead_code)]
 pub async fn insert_tx(
 	chain: &Arc<Chain>,
 	txpool: &Arc<TxPool<DefaultTxPoolSupport>>,
@@ -113,6 +138,7 @@ pub async fn insert_tx(
 	tx_hash
 }
 
+#[allow(dead_code)]
 pub async fn wait_txpool(txpool: &Arc<TxPool<DefaultTxPoolSupport>>, count: usize) {
 	loop {
 		{
@@ -168,30 +194,47 @@ fn get_coordinator(
 	Arc::new(coordinator)
 }
 
-fn get_chain(authority_accounts: &[&TestAccount]) -> Arc<Chain> {
+fn get_chain(authority_accounts: &[&TestAccount], block_interval: Option<u64>) -> Arc<Chain> {
 	let path = tempdir().expect("Could not create a temp dir");
 	let home = path.into_path();
 
-	init(&home, authority_accounts);
+	init(&home, authority_accounts, block_interval);
 
 	let db = DBConfig {
 		memory_budget: 1 * 1024 * 1024,
 		path: home.join("data").join("db"),
 		partitions: vec![],
+		backend: DBBackend::RocksDB,
 	};
 
-	let chain_config = ChainConfig { home, db };
+	let chain_config = ChainConfig {
+		home,
+		db,
+		pruning: StatePruning::Archive,
+		index: false,
+	};
 
 	let chain = Arc::new(Chain::new(chain_config).unwrap());
 
 	chain
 }
 
-fn init(home: &PathBuf, authority_accounts: &[&TestAccount]) {
+fn init(home: &PathBuf, authority_accounts: &[&TestAccount], block_interval: Option<u64>) {
 	let config_path = home.join("config");
 
 	fs::create_dir_all(&config_path).unwrap();
 
+	let authorities = authority_accounts
+		.iter()
+		.map(|x| format!("\"{}\"", x.address))
+		.collect::<Vec<_>>()
+		.join(", ");
+
+	let block_interval = match block_interval {
+		Some(block_interval) => block_interval.to_string(),
+		None => "null".to_string(),
+	};
+
 	let spec = format!(
 		r#"
 [basic]
@@ -210,7 +253,11 @@ params = '''
     "timestamp": "2020-04-29T15:51:36.502+08:00",
     "max_until_gap": 20,
     "max_execution_gap": 8,
-    "consensus": "poa"
+    "consensus": "poa",
+    "admin": {{
+    	"threshold": 1,
+    	"members": [["{}", 1]]
+    }}
 }}
 '''
 
@@ -230,12 +277,14 @@ module = "poa"
 method = "init"
 params = '''
 {{
-    "block_interval": null,
+    "block_interval": {},
     "admin": {{
     	"threshold": 1,
     	"members": [["{}", 1]]
     }},
-    "authority": "{}"
+    "authorities": {{
+    	"members": [{}]
+    }}
 }}
 '''
 
@@ -247,7 +296,11 @@ params = '''
 }}
 '''
 	"#,
-		authority_accounts[0].address, authority_accounts[0].address, authority_accounts[0].address
+		authority_accounts[0].address,
+		authority_accounts[0].address,
+		block_interval,
+		authority_accounts[0].address,
+		authorities
 	);
 
 	fs::write(config_path.join("spec.toml"), &spec).unwrap();
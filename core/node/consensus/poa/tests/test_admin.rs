@@ -21,7 +21,6 @@ use node_consensus_base::{ConsensusInMessage, PeerId};
 use node_coordinator::{Keypair, LinkedHashMap, Multiaddr, Protocol};
 use node_executor::module;
 use node_executor_primitives::EmptyParams;
-use primitives::Address;
 use tokio::time::Duration;
 use utils_test::test_accounts;
 
@@ -164,12 +163,12 @@ async fn test_poa_update_admin() {
 		&txpool,
 		chain
 			.build_transaction(
-				Some((account1.secret_key.clone(), 0, 10)),
+				Some((account1.secret_key.clone(), 1, 10)),
 				chain
 					.build_call(
 						"poa".to_string(),
-						"update_admin_vote".to_string(),
-						module::poa::UpdateAdminVoteParams { proposal_id: 2 },
+						"vote".to_string(),
+						module::governance::VoteParams { proposal_id: 2 },
 					)
 					.unwrap(),
 			)
@@ -230,7 +229,7 @@ async fn test_poa_update_admin() {
 }
 
 #[tokio::test]
-async fn test_poa_update_authority() {
+async fn test_poa_update_authorities() {
 	let _ = env_logger::try_init();
 
 	let dsa = Arc::new(DsaImpl::Ed25519);
@@ -292,17 +291,17 @@ async fn test_poa_update_authority() {
 	let block_number = chain0.get_confirmed_number().unwrap().unwrap();
 	log::info!("block_number: {}", block_number);
 
-	let authority: Address = chain0
+	let authorities: module::poa::Authorities = chain0
 		.execute_call_with_block_number(
 			&block_number,
 			None,
 			"poa".to_string(),
-			"get_authority".to_string(),
+			"get_authorities".to_string(),
 			EmptyParams,
 		)
 		.unwrap()
 		.unwrap();
-	assert_eq!(authority, account1.address);
+	assert_eq!(authorities.members, vec![account1.address.clone()]);
 
 	let admin: module::poa::Admin = chain0
 		.execute_call_with_block_number(
@@ -389,7 +388,7 @@ async fn test_poa_update_authority() {
 		}
 	);
 
-	// update authority
+	// update authorities
 	let tx1_hash = base::insert_tx(
 		&chain0,
 		&txpool0,
@@ -399,9 +398,11 @@ async fn test_poa_update_authority() {
 				chain0
 					.build_call(
 						"poa".to_string(),
-						"update_authority".to_string(),
-						module::poa::UpdateAuthorityParams {
-							authority: account2.address.clone(),
+						"update_authorities".to_string(),
+						module::poa::UpdateAuthoritiesParams {
+							authorities: module::poa::Authorities {
+								members: vec![account2.address.clone()],
+							},
 						},
 					)
 					.unwrap(),
@@ -415,12 +416,12 @@ async fn test_poa_update_authority() {
 		&txpool0,
 		chain0
 			.build_transaction(
-				Some((account1.secret_key.clone(), 0, 10)),
+				Some((account1.secret_key.clone(), 1, 10)),
 				chain0
 					.build_call(
 						"poa".to_string(),
-						"update_authority_vote".to_string(),
-						module::poa::UpdateAuthorityVoteParams { proposal_id: 1 },
+						"vote".to_string(),
+						module::governance::VoteParams { proposal_id: 2 },
 					)
 					.unwrap(),
 			)
@@ -460,17 +461,17 @@ async fn test_poa_update_authority() {
 	log::info!("tx2_events: {:x?}", tx2_events);
 
 	let block_number = chain0.get_confirmed_number().unwrap().unwrap();
-	let authority: Address = chain0
+	let authorities: module::poa::Authorities = chain0
 		.execute_call_with_block_number(
 			&block_number,
 			None,
 			"poa".to_string(),
-			"get_authority".to_string(),
+			"get_authorities".to_string(),
 			EmptyParams,
 		)
 		.unwrap()
 		.unwrap();
-	assert_eq!(authority, account2.address);
+	assert_eq!(authorities.members, vec![account2.address.clone()]);
 
 	// generate block 4
 	// account2 performs generation
@@ -497,3 +498,186 @@ async fn test_poa_update_authority() {
 	let block_number = chain0.get_confirmed_number().unwrap().unwrap();
 	log::info!("block_number: {}", block_number);
 }
+
+#[tokio::test]
+async fn test_poa_update_system_meta() {
+	let _ = env_logger::try_init();
+
+	let dsa = Arc::new(DsaImpl::Ed25519);
+	let address = Arc::new(AddressImpl::Blake2b160);
+
+	let test_accounts = test_accounts(dsa, address);
+	let account1 = &test_accounts[0];
+
+	let authority_accounts = [account1];
+	let (chain, txpool, consensus) = base::get_standalone_service(&authority_accounts, account1);
+
+	// generate block 1
+	consensus
+		.in_message_tx()
+		.unbounded_send(ConsensusInMessage::Generate)
+		.unwrap();
+	base::wait_block_execution(&chain, 1).await;
+
+	let system_meta = &chain.get_current_state().system_meta;
+	assert_eq!(system_meta.max_until_gap, 20);
+	assert_eq!(system_meta.max_execution_gap, 8);
+
+	// update meta
+	let tx1_hash = base::insert_tx(
+		&chain,
+		&txpool,
+		chain
+			.build_transaction(
+				Some((account1.secret_key.clone(), 0, 10)),
+				chain
+					.build_call(
+						"system".to_string(),
+						"update_meta".to_string(),
+						module::system::UpdateMetaParams {
+							max_until_gap: Some(30),
+							max_execution_gap: None,
+							max_block_txs: None,
+							max_block_bytes: None,
+						},
+					)
+					.unwrap(),
+			)
+			.unwrap(),
+	)
+	.await;
+	base::wait_txpool(&txpool, 1).await;
+
+	// generate block 2
+	consensus
+		.in_message_tx()
+		.unbounded_send(ConsensusInMessage::Generate)
+		.unwrap();
+	base::wait_block_execution(&chain, 2).await;
+
+	let tx1_receipt = chain.get_receipt(&tx1_hash).unwrap().unwrap();
+	assert!(tx1_receipt.result.is_ok());
+
+	// the new meta takes effect from block 3
+	// so that a tx with until = 25 is acceptable
+	let system_meta = &chain.get_current_state().system_meta;
+	assert_eq!(system_meta.max_until_gap, 30);
+	assert_eq!(system_meta.max_execution_gap, 8);
+
+	let tx = chain
+		.build_transaction(
+			Some((account1.secret_key.clone(), 1, 25)),
+			chain
+				.build_call(
+					"system".to_string(),
+					"update_meta".to_string(),
+					module::system::UpdateMetaParams {
+						max_until_gap: Some(40),
+						max_execution_gap: None,
+						max_block_txs: None,
+						max_block_bytes: None,
+					},
+				)
+				.unwrap(),
+		)
+		.unwrap();
+	let tx_hash = chain.hash_transaction(&tx).unwrap();
+	chain.validate_transaction(&tx_hash, &tx, true).unwrap();
+}
+
+#[tokio::test]
+async fn test_poa_max_block_txs() {
+	let _ = env_logger::try_init();
+
+	let dsa = Arc::new(DsaImpl::Ed25519);
+	let address = Arc::new(AddressImpl::Blake2b160);
+
+	let test_accounts = test_accounts(dsa, address);
+	let (account1, account2) = (&test_accounts[0], &test_accounts[1]);
+
+	let authority_accounts = [account1];
+	let (chain, txpool, consensus) = base::get_standalone_service(&authority_accounts, account1);
+
+	// update meta
+	let tx1_hash = base::insert_tx(
+		&chain,
+		&txpool,
+		chain
+			.build_transaction(
+				Some((account1.secret_key.clone(), 0, 10)),
+				chain
+					.build_call(
+						"system".to_string(),
+						"update_meta".to_string(),
+						module::system::UpdateMetaParams {
+							max_until_gap: None,
+							max_execution_gap: None,
+							max_block_txs: Some(1),
+							max_block_bytes: None,
+						},
+					)
+					.unwrap(),
+			)
+			.unwrap(),
+	)
+	.await;
+	base::wait_txpool(&txpool, 1).await;
+
+	// generate block 1
+	consensus
+		.in_message_tx()
+		.unbounded_send(ConsensusInMessage::Generate)
+		.unwrap();
+	base::wait_block_execution(&chain, 1).await;
+
+	let tx1_receipt = chain.get_receipt(&tx1_hash).unwrap().unwrap();
+	assert!(tx1_receipt.result.is_ok());
+	assert_eq!(chain.get_current_state().system_meta.max_block_txs, 1);
+
+	let mut tx_hashes = vec![];
+	for nonce in 1..3 {
+		let tx_hash = base::insert_tx(
+			&chain,
+			&txpool,
+			chain
+				.build_transaction(
+					Some((account1.secret_key.clone(), nonce, 10)),
+					chain
+						.build_call(
+							"balance".to_string(),
+							"transfer".to_string(),
+							module::balance::TransferParams {
+								recipient: account2.address.clone(),
+								value: 1,
+							},
+						)
+						.unwrap(),
+				)
+				.unwrap(),
+		)
+		.await;
+		tx_hashes.push(tx_hash);
+	}
+	base::wait_txpool(&txpool, 2).await;
+
+	// generate block 2, the tx exceeding the limit is kept in the txpool
+	consensus
+		.in_message_tx()
+		.unbounded_send(ConsensusInMessage::Generate)
+		.unwrap();
+	base::wait_block_execution(&chain, 2).await;
+	base::wait_txpool(&txpool, 1).await;
+
+	assert!(chain.get_receipt(&tx_hashes[0]).unwrap().is_some());
+	assert!(chain.get_receipt(&tx_hashes[1]).unwrap().is_none());
+
+	// generate block 3
+	consensus
+		.in_message_tx()
+		.unbounded_send(ConsensusInMessage::Generate)
+		.unwrap();
+	base::wait_block_execution(&chain, 3).await;
+	base::wait_txpool(&txpool, 0).await;
+
+	assert!(chain.get_receipt(&tx_hashes[1]).unwrap().is_some());
+}
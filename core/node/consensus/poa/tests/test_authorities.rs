@@ -0,0 +1,180 @@
+// Copyright 2019, 2020 Wingchain
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::convert::TryInto;
+use std::sync::Arc;
+
+use crypto::address::AddressImpl;
+use crypto::dsa::DsaImpl;
+use crypto::hash::{Hash as HashT, HashImpl};
+use node_consensus_base::ConsensusInMessage;
+use node_executor::module;
+use primitives::{codec, Hash, Header, Proof, SecretKey};
+use tokio::time::Duration;
+use utils_test::test_accounts;
+
+mod base;
+
+#[tokio::test]
+async fn test_poa_authorities() {
+	let _ = env_logger::try_init();
+
+	let dsa = Arc::new(DsaImpl::Ed25519);
+	let address = Arc::new(AddressImpl::Blake2b160);
+	let hasher = Arc::new(HashImpl::Blake2b256);
+
+	let test_accounts = test_accounts(dsa.clone(), address);
+	let (account1, account2) = (&test_accounts[0], &test_accounts[1]);
+
+	// the slot of block 1 is 1, which belongs to the second authority
+	let authority_accounts = [account2, account1];
+	let (chain, _txpool, consensus) = base::get_standalone_service(&authority_accounts, account1);
+
+	// generate block 1
+	consensus
+		.in_message_tx()
+		.unbounded_send(ConsensusInMessage::Generate)
+		.unwrap();
+	base::wait_block_execution(&chain, 1).await;
+
+	let block_hash = chain.get_block_hash(&1).unwrap().unwrap();
+	let header = chain.get_header(&block_hash).unwrap().unwrap();
+	assert_eq!(header.author, Some(account1.address.clone()));
+
+	let proof = chain.get_proof(&block_hash).unwrap().unwrap();
+	consensus.verify_proof(&header, &proof).unwrap();
+
+	// sealed by the authority of another slot
+	let mut header = header;
+	header.author = Some(account2.address.clone());
+	let proof = get_proof(&header, &account2.secret_key, &hasher, &dsa);
+	let result = consensus.verify_proof(&header, &proof);
+	assert!(format!("{}", result.unwrap_err()).contains("Not authority"));
+
+	// tampered header
+	header.timestamp += 1;
+	let result = consensus.verify_proof(&header, &proof);
+	assert!(format!("{}", result.unwrap_err()).contains("Invalid signature"));
+}
+
+#[tokio::test]
+async fn test_poa_authorities_timestamp_slot() {
+	let _ = env_logger::try_init();
+
+	let dsa = Arc::new(DsaImpl::Ed25519);
+	let address = Arc::new(AddressImpl::Blake2b160);
+	let hasher = Arc::new(HashImpl::Blake2b256);
+
+	let test_accounts = test_accounts(dsa.clone(), address);
+	let (account1, account2) = (&test_accounts[0], &test_accounts[1]);
+
+	// account2 is offline, so that account1 seals the odd slots as the backup
+	let block_interval = 200;
+	let authority_accounts = [account1, account2];
+	let (chain, txpool, consensus) = base::get_standalone_service_with_block_interval(
+		&authority_accounts,
+		account1,
+		Some(block_interval),
+	);
+
+	base::insert_tx(
+		&chain,
+		&txpool,
+		chain
+			.build_transaction(
+				Some((account1.secret_key.clone(), 0, 20)),
+				chain
+					.build_call(
+						"balance".to_string(),
+						"transfer".to_string(),
+						module::balance::TransferParams {
+							recipient: account2.address.clone(),
+							value: 1,
+						},
+					)
+					.unwrap(),
+			)
+			.unwrap(),
+	)
+	.await;
+	base::wait_txpool(&txpool, 0).await;
+
+	while chain.get_confirmed_number().unwrap().unwrap() < 4 {
+		tokio::time::sleep(Duration::from_millis(10)).await;
+	}
+
+	let mut parent_slot = 0;
+	let mut authority_header = None;
+	let mut backup_header = None;
+	for number in 1..=4 {
+		let block_hash = chain.get_block_hash(&number).unwrap().unwrap();
+		let header = chain.get_header(&block_hash).unwrap().unwrap();
+		let proof = chain.get_proof(&block_hash).unwrap().unwrap();
+		consensus.verify_proof(&header, &proof).unwrap();
+
+		let slot = header.timestamp / block_interval;
+		assert!(slot > parent_slot);
+		parent_slot = slot;
+
+		assert_eq!(header.author, Some(account1.address.clone()));
+		if slot % 2 == 1 {
+			// the backup only seals in the second half of the slot
+			assert!(header.timestamp % block_interval >= block_interval / 2);
+			backup_header = Some(header);
+		} else {
+			authority_header = Some(header);
+		}
+	}
+	let authority_header = authority_header.expect("Missing the block sealed by the authority");
+	let backup_header = backup_header.expect("Missing the block sealed by the backup");
+
+	// sealed by the backup after the block of the authority is seen
+	let mut header = authority_header;
+	header.timestamp = header.timestamp / block_interval * block_interval + block_interval / 2;
+	header.author = Some(account2.address.clone());
+	let proof = get_proof(&header, &account2.secret_key, &hasher, &dsa);
+	let result = consensus.verify_proof(&header, &proof);
+	assert!(format!("{}", result.unwrap_err()).contains("Not authority"));
+
+	// sealed by the backup in the first half of the slot
+	let mut header = backup_header.clone();
+	header.timestamp = header.timestamp / block_interval * block_interval;
+	let proof = get_proof(&header, &account1.secret_key, &hasher, &dsa);
+	let result = consensus.verify_proof(&header, &proof);
+	assert!(format!("{}", result.unwrap_err()).contains("Not authority"));
+
+	// sealed ahead of time
+	let mut header = backup_header;
+	header.timestamp += 10 * block_interval;
+	let proof = get_proof(&header, &account1.secret_key, &hasher, &dsa);
+	let result = consensus.verify_proof(&header, &proof);
+	assert!(format!("{}", result.unwrap_err()).contains("Future timestamp"));
+}
+
+fn get_proof(
+	header: &Header,
+	secret_key: &SecretKey,
+	hasher: &Arc<HashImpl>,
+	dsa: &Arc<DsaImpl>,
+) -> Proof {
+	let block_hash = {
+		let mut out = vec![0; hasher.length().into()];
+		hasher.hash(&mut out, &codec::encode(header).unwrap());
+		Hash(out)
+	};
+	node_consensus_poa::proof::Proof::new(&block_hash, secret_key, dsa.clone())
+		.unwrap()
+		.try_into()
+		.unwrap()
+}
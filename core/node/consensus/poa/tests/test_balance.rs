@@ -87,7 +87,7 @@ async fn test_poa_balance() {
 		&txpool,
 		chain
 			.build_transaction(
-				Some((account1.secret_key.clone(), 0, 11)),
+				Some((account1.secret_key.clone(), 1, 11)),
 				chain
 					.build_call(
 						"balance".to_string(),
@@ -116,7 +116,7 @@ async fn test_poa_balance() {
 		&txpool,
 		chain
 			.build_transaction(
-				Some((account1.secret_key.clone(), 0, 12)),
+				Some((account1.secret_key.clone(), 2, 12)),
 				chain
 					.build_call(
 						"balance".to_string(),
@@ -211,6 +211,7 @@ async fn test_poa_balance() {
 			)
 			.unwrap()],
 			result: Ok(codec::encode(&()).unwrap()),
+			gas_used: 0,
 		}
 	);
 }
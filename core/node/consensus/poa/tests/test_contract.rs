@@ -280,7 +280,7 @@ async fn test_poa_contract_update_admin() {
 		&txpool,
 		chain
 			.build_transaction(
-				Some((account1.secret_key.clone(), 0, 10)),
+				Some((account1.secret_key.clone(), 1, 10)),
 				chain
 					.build_call(
 						"contract".to_string(),
@@ -373,15 +373,12 @@ async fn test_poa_contract_update_admin() {
 		&txpool,
 		chain
 			.build_transaction(
-				Some((account1.secret_key.clone(), 0, 10)),
+				Some((account1.secret_key.clone(), 2, 10)),
 				chain
 					.build_call(
 						"contract".to_string(),
-						"update_admin_vote".to_string(),
-						module::contract::UpdateAdminVoteParams {
-							contract_address: contract_address.clone(),
-							proposal_id: 2,
-						},
+						"vote".to_string(),
+						module::governance::VoteParams { proposal_id: 2 },
 					)
 					.unwrap(),
 			)
@@ -540,7 +537,7 @@ async fn test_poa_contract_update_code() {
 		&txpool,
 		chain
 			.build_transaction(
-				Some((account1.secret_key.clone(), 0, 10)),
+				Some((account1.secret_key.clone(), 1, 10)),
 				chain
 					.build_call(
 						"contract".to_string(),
@@ -631,15 +628,12 @@ async fn test_poa_contract_update_code() {
 		&txpool,
 		chain
 			.build_transaction(
-				Some((account1.secret_key.clone(), 0, 10)),
+				Some((account1.secret_key.clone(), 2, 10)),
 				chain
 					.build_call(
 						"contract".to_string(),
-						"update_code_vote".to_string(),
-						module::contract::UpdateCodeVoteParams {
-							contract_address: contract_address.clone(),
-							proposal_id: 1,
-						},
+						"vote".to_string(),
+						module::governance::VoteParams { proposal_id: 2 },
 					)
 					.unwrap(),
 			)
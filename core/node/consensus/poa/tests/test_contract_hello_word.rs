@@ -372,7 +372,7 @@ async fn test_poa_contract_hw_write() {
 		&txpool,
 		chain
 			.build_transaction(
-				Some((account1.secret_key.clone(), 0, 10)),
+				Some((account1.secret_key.clone(), 1, 10)),
 				chain
 					.build_call(
 						"contract".to_string(),
@@ -394,7 +394,7 @@ async fn test_poa_contract_hw_write() {
 		&txpool,
 		chain
 			.build_transaction(
-				Some((account1.secret_key.clone(), 0, 10)),
+				Some((account1.secret_key.clone(), 2, 10)),
 				chain
 					.build_call(
 						"contract".to_string(),
@@ -416,7 +416,7 @@ async fn test_poa_contract_hw_write() {
 		&txpool,
 		chain
 			.build_transaction(
-				Some((account1.secret_key.clone(), 0, 10)),
+				Some((account1.secret_key.clone(), 3, 10)),
 				chain
 					.build_call(
 						"contract".to_string(),
@@ -551,7 +551,7 @@ async fn test_poa_contract_hw_transfer_success() {
 		&txpool,
 		chain
 			.build_transaction(
-				Some((account1.secret_key.clone(), 0, 10)),
+				Some((account1.secret_key.clone(), 1, 10)),
 				chain
 					.build_call(
 						"contract".to_string(),
@@ -702,7 +702,7 @@ async fn test_poa_contract_hw_transfer_failed() {
 		&txpool,
 		chain
 			.build_transaction(
-				Some((account1.secret_key.clone(), 0, 10)),
+				Some((account1.secret_key.clone(), 1, 10)),
 				chain
 					.build_call(
 						"contract".to_string(),
@@ -839,7 +839,7 @@ async fn test_poa_contract_hw_transfer_partial_failed() {
 		&txpool,
 		chain
 			.build_transaction(
-				Some((account1.secret_key.clone(), 0, 10)),
+				Some((account1.secret_key.clone(), 1, 10)),
 				chain
 					.build_call(
 						"contract".to_string(),
@@ -983,7 +983,7 @@ async fn test_poa_contract_hw_nested_contract() {
 		&txpool,
 		chain
 			.build_transaction(
-				Some((account1.secret_key.clone(), 0, 10)),
+				Some((account1.secret_key.clone(), 1, 10)),
 				chain
 					.build_call(
 						"contract".to_string(),
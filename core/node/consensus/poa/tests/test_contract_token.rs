@@ -229,7 +229,7 @@ async fn test_poa_contract_token_transfer() {
 		&txpool,
 		chain
 			.build_transaction(
-				Some((account1.secret_key.clone(), 0, 10)),
+				Some((account1.secret_key.clone(), 1, 10)),
 				chain
 					.build_call(
 						"contract".to_string(),
@@ -363,7 +363,7 @@ async fn test_poa_contract_token_transfer_from() {
 		&txpool,
 		chain
 			.build_transaction(
-				Some((account1.secret_key.clone(), 0, 10)),
+				Some((account1.secret_key.clone(), 1, 10)),
 				chain
 					.build_call(
 						"contract".to_string(),
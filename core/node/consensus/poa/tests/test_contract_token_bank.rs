@@ -80,7 +80,7 @@ async fn test_poa_contract_tb_success() {
 		&txpool,
 		chain
 			.build_transaction(
-				Some((account1.secret_key.clone(), 0, 10)),
+				Some((account1.secret_key.clone(), 1, 10)),
 				chain
 					.build_call(
 						"contract".to_string(),
@@ -125,7 +125,7 @@ async fn test_poa_contract_tb_success() {
 		&txpool,
 		chain
 			.build_transaction(
-				Some((account1.secret_key.clone(), 0, 10)),
+				Some((account1.secret_key.clone(), 2, 10)),
 				chain
 					.build_call(
 						"contract".to_string(),
@@ -162,7 +162,7 @@ async fn test_poa_contract_tb_success() {
 		&txpool,
 		chain
 			.build_transaction(
-				Some((account1.secret_key.clone(), 0, 10)),
+				Some((account1.secret_key.clone(), 3, 10)),
 				chain
 					.build_call(
 						"contract".to_string(),
@@ -278,7 +278,7 @@ async fn test_poa_contract_tb_success() {
 		&txpool,
 		chain
 			.build_transaction(
-				Some((account1.secret_key.clone(), 0, 10)),
+				Some((account1.secret_key.clone(), 4, 10)),
 				chain
 					.build_call(
 						"contract".to_string(),
@@ -439,7 +439,7 @@ async fn test_poa_contract_tb_failed() {
 		&txpool,
 		chain
 			.build_transaction(
-				Some((account1.secret_key.clone(), 0, 10)),
+				Some((account1.secret_key.clone(), 1, 10)),
 				chain
 					.build_call(
 						"contract".to_string(),
@@ -484,7 +484,7 @@ async fn test_poa_contract_tb_failed() {
 		&txpool,
 		chain
 			.build_transaction(
-				Some((account1.secret_key.clone(), 0, 10)),
+				Some((account1.secret_key.clone(), 2, 10)),
 				chain
 					.build_call(
 						"contract".to_string(),
@@ -521,7 +521,7 @@ async fn test_poa_contract_tb_failed() {
 		&txpool,
 		chain
 			.build_transaction(
-				Some((account1.secret_key.clone(), 0, 10)),
+				Some((account1.secret_key.clone(), 3, 10)),
 				chain
 					.build_call(
 						"contract".to_string(),
@@ -628,7 +628,7 @@ async fn test_poa_contract_tb_ea() {
 		&txpool,
 		chain
 			.build_transaction(
-				Some((account1.secret_key.clone(), 0, 10)),
+				Some((account1.secret_key.clone(), 1, 10)),
 				chain
 					.build_call(
 						"contract".to_string(),
@@ -673,7 +673,7 @@ async fn test_poa_contract_tb_ea() {
 		&txpool,
 		chain
 			.build_transaction(
-				Some((account1.secret_key.clone(), 0, 10)),
+				Some((account1.secret_key.clone(), 2, 10)),
 				chain
 					.build_call(
 						"contract".to_string(),
@@ -710,7 +710,7 @@ async fn test_poa_contract_tb_ea() {
 		&txpool,
 		chain
 			.build_transaction(
-				Some((account1.secret_key.clone(), 0, 10)),
+				Some((account1.secret_key.clone(), 3, 10)),
 				chain
 					.build_call(
 						"contract".to_string(),
@@ -14,4 +14,5 @@
 
 pub const CONSENSUS_POA: &str = "poa";
 pub const CONSENSUS_RAFT: &str = "raft";
-pub const CONSENSUS_LIST: [&str; 2] = [CONSENSUS_POA, CONSENSUS_RAFT];
+pub const CONSENSUS_INSTANT: &str = "instant";
+pub const CONSENSUS_LIST: [&str; 3] = [CONSENSUS_POA, CONSENSUS_RAFT, CONSENSUS_INSTANT];
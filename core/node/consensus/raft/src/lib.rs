@@ -29,11 +29,13 @@ use node_executor::module;
 use node_executor_primitives::EmptyParams;
 use primitives::codec;
 use primitives::errors::CommonResult;
-use primitives::{Address, BlockNumber, Header};
+use primitives::{Address, BlockNumber, Hash, Header};
 
 use crate::proof::Proof;
 use crate::stream::RaftStream;
 use crypto::address::Address as AddressT;
+use crypto::dsa::{Dsa, Verifier as VerifierT};
+use crypto::hash::Hash as HashT;
 use node_executor::module::raft::Authorities;
 
 pub use crate::config::RaftConfig;
@@ -98,23 +100,20 @@ where
 			node_consensus_base::errors::ErrorKind::VerifyProofError("Decode error".to_string())
 		})?;
 
-		let address = {
-			let addresser = self.support.get_basic()?.address.clone();
-			let address_len = addresser.length().into();
-			let mut address = vec![0u8; address_len];
-			addresser.address(&mut address, &proof.public_key.0);
-			Address(address)
+		let block_hash = {
+			let hasher = self.support.get_basic()?.hash.clone();
+			let mut out = vec![0u8; hasher.length().into()];
+			hasher.hash(&mut out, &codec::encode(header)?);
+			Hash(out)
 		};
 
-		let authorities = get_raft_authorities(&self.support, &(header.number - 1))?;
-		let is_authority = authorities.members.contains(&address);
-		if !is_authority {
-			return Err(node_consensus_base::errors::ErrorKind::VerifyProofError(
-				"Not authority".to_string(),
-			)
-			.into());
-		}
-		Ok(())
+		verify_author(
+			&self.support,
+			&block_hash,
+			header.number,
+			&header.author,
+			&proof,
+		)
 	}
 
 	fn in_message_tx(&self) -> UnboundedSender<ConsensusInMessage> {
@@ -141,6 +140,55 @@ fn get_raft_meta<S: ConsensusSupport>(
 		.map(|x| x.expect("qed"))
 }
 
+/// Verify that the proof is signed by the author of the block,
+/// who should be one of the authorities
+pub(crate) fn verify_author<S: ConsensusSupport>(
+	support: &Arc<S>,
+	block_hash: &Hash,
+	number: BlockNumber,
+	author: &Option<Address>,
+	proof: &Proof,
+) -> CommonResult<()> {
+	let basic = support.get_basic()?;
+
+	let verifier = basic
+		.dsa
+		.verifier_from_public_key(&proof.public_key.0)
+		.map_err(|_| {
+			node_consensus_base::errors::ErrorKind::VerifyProofError(
+				"Invalid public key".to_string(),
+			)
+		})?;
+	let message = codec::encode(&(block_hash, proof.log_index, proof.log_term))?;
+	verifier.verify(&message, &proof.signature.0).map_err(|_| {
+		node_consensus_base::errors::ErrorKind::VerifyProofError("Invalid signature".to_string())
+	})?;
+
+	let address = {
+		let addresser = basic.address.clone();
+		let address_len = addresser.length().into();
+		let mut address = vec![0u8; address_len];
+		addresser.address(&mut address, &proof.public_key.0);
+		Address(address)
+	};
+
+	let authorities = get_raft_authorities(support, &(number - 1))?;
+	let is_authority = authorities.members.contains(&address);
+	if !is_authority {
+		return Err(node_consensus_base::errors::ErrorKind::VerifyProofError(
+			"Not authority".to_string(),
+		)
+		.into());
+	}
+	if author.as_ref() != Some(&address) {
+		return Err(node_consensus_base::errors::ErrorKind::VerifyProofError(
+			"Not author".to_string(),
+		)
+		.into());
+	}
+	Ok(())
+}
+
 fn get_raft_authorities<S: ConsensusSupport>(
 	support: &Arc<S>,
 	number: &BlockNumber,
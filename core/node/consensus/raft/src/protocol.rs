@@ -14,9 +14,11 @@
 
 use derive_more::{Display, From, TryInto};
 use primitives::codec::{Decode, Encode};
-use primitives::{BlockNumber, Hash, PublicKey, Signature, Transaction};
+use primitives::{Address, BlockNumber, Hash, PublicKey, Signature, Transaction};
 use utils_enum_codec::enum_codec;
 
+use crate::proof::Proof;
+
 #[enum_codec]
 #[derive(From, TryInto)]
 pub enum RaftMessage {
@@ -107,9 +109,12 @@ pub struct Proposal {
 	pub block_hash: Hash,
 	pub number: BlockNumber,
 	pub timestamp: u64,
+	pub author: Option<Address>,
 	pub meta_txs: Vec<Transaction>,
 	pub payload_txs: Vec<Transaction>,
 	pub execution_number: BlockNumber,
+	/// signed by the author on the proposal entry, the proof of the committed block
+	pub proof: Proof,
 }
 
 impl RequestIdAware for RegisterValidatorReq {
@@ -27,6 +27,7 @@ use node_chain::ChainCommitBlockParams;
 use node_consensus_base::support::ConsensusSupport;
 use node_consensus_base::{ConsensusInMessage, ConsensusOutMessage, PeerId};
 use node_executor::module::raft::{Authorities, Meta};
+use node_txpool::RemoveReason;
 use primitives::codec;
 use primitives::codec::Encode;
 use primitives::errors::{Catchable, CommonResult};
@@ -40,7 +41,6 @@ use crate::config::{
 	DEFAULT_REQUEST_PROPOSAL_MIN_INTERVAL,
 };
 use crate::errors::ErrorKind;
-use crate::proof::Proof;
 use crate::protocol::{
 	AppendEntriesReq, AppendEntriesRes, EntryData, Proposal, RaftMessage, RegisterValidatorReq,
 	RegisterValidatorRes, RequestId, RequestIdAware, RequestProposalReq, RequestProposalRes,
@@ -844,6 +844,8 @@ where
 			VerifyError::InvalidHeader(_) => VerifyAction::Discard,
 			VerifyError::DuplicatedTx(_) => VerifyAction::Discard,
 			VerifyError::InvalidTx(_) => VerifyAction::Discard,
+			VerifyError::ExceedBlockLimit(_) => VerifyAction::Discard,
+			VerifyError::InvalidProof(_) => VerifyAction::Discard,
 		};
 		Ok(action)
 	}
@@ -884,6 +886,7 @@ where
 			ConsensusInMessage::SyncLatencyUpdated { latency } => {
 				self.update_sync_latency(latency);
 			}
+			ConsensusInMessage::TxInserted { .. } => {}
 		}
 		Ok(())
 	}
@@ -1026,6 +1029,7 @@ where
 					let build_block_params = BuildBlockParams {
 						number: proposal.number,
 						timestamp: proposal.timestamp,
+						author: proposal.author,
 						meta_txs: convert_txs(proposal.meta_txs)?,
 						payload_txs: convert_txs(proposal.payload_txs)?,
 						execution_number: proposal.execution_number,
@@ -1034,15 +1038,8 @@ where
 				}
 			};
 
-			let (log_index, log_term) = self.storage.get_last_log_index_term();
-			let proof = Proof::new(
-				&commit_block_params.block_hash,
-				log_index,
-				log_term,
-				&self.secret_key,
-				self.support.get_basic()?.dsa.clone(),
-			)?;
-			commit_block_params.proof = proof.try_into()?;
+			// the proof signed by the author on the proposal entry
+			commit_block_params.proof = proposal.proof.try_into()?;
 
 			let number = commit_block_params.header.number;
 			let block_hash = commit_block_params.block_hash.clone();
@@ -1057,7 +1054,8 @@ where
 
 			self.support.commit_block(commit_block_params)?;
 
-			self.support.txpool_remove_transactions(&tx_hash_set)?;
+			self.support
+				.txpool_remove_transactions(&tx_hash_set, RemoveReason::Included)?;
 
 			info!(
 				"Block committed: number: {}, block_hash: {}",
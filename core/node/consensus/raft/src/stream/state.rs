@@ -23,8 +23,9 @@ use tokio::time::{interval, sleep_until, Duration, Interval};
 use node_consensus_base::support::ConsensusSupport;
 use node_executor::module::raft::{Authorities, Meta};
 use primitives::errors::CommonResult;
-use primitives::{Address, Hash};
+use primitives::Address;
 
+use crate::proof::Proof;
 use crate::protocol::{
 	AppendEntriesReq, AppendEntriesRes, Entry, EntryData, Proposal, RequestId, RequestVoteReq,
 	RequestVoteRes,
@@ -159,37 +160,57 @@ where
 			.storage
 			.get_log_entries(..)
 			.iter()
-			.any(|x| matches!(x.data, EntryData::Proposal {..}));
+			.any(|x| matches!(x.data, EntryData::Proposal { .. }));
 		if contains_proposal {
 			return Ok(());
 		}
 
-		let build_block_params = self.stream.support.prepare_block(schedule_info)?;
-
-		let mut proposal = Proposal {
-			block_hash: Hash(vec![]),
-			number: build_block_params.number,
-			timestamp: build_block_params.timestamp,
-			meta_txs: build_block_params
-				.meta_txs
-				.iter()
-				.map(|x| x.tx.clone())
-				.collect(),
-			payload_txs: build_block_params
-				.payload_txs
-				.iter()
-				.map(|x| x.tx.clone())
-				.collect(),
-			execution_number: build_block_params.execution_number,
-		};
+		let build_block_params = self
+			.stream
+			.support
+			.prepare_block(schedule_info, Some(self.stream.address.clone()))?;
+
+		let number = build_block_params.number;
+		let timestamp = build_block_params.timestamp;
+		let author = build_block_params.author.clone();
+		let meta_txs = build_block_params
+			.meta_txs
+			.iter()
+			.map(|x| x.tx.clone())
+			.collect();
+		let payload_txs = build_block_params
+			.payload_txs
+			.iter()
+			.map(|x| x.tx.clone())
+			.collect();
+		let execution_number = build_block_params.execution_number;
 
 		let commit_block_params = self.stream.support.build_block(build_block_params)?;
-		proposal.block_hash = commit_block_params.block_hash.clone();
-
-		let number = proposal.number;
-		let execution_number = proposal.execution_number;
 		let block_hash = commit_block_params.block_hash.clone();
 
+		// sign on the proposal entry
+		let (last_log_index, _) = self.stream.storage.get_last_log_index_term();
+		let log_index = last_log_index + 1;
+		let log_term = self.stream.storage.get_current_term();
+		let proof = Proof::new(
+			&block_hash,
+			log_index,
+			log_term,
+			&self.stream.secret_key,
+			self.stream.support.get_basic()?.dsa.clone(),
+		)?;
+
+		let proposal = Proposal {
+			block_hash: block_hash.clone(),
+			number,
+			timestamp,
+			author,
+			meta_txs,
+			payload_txs,
+			execution_number,
+			proof,
+		};
+
 		self.stream.commit_block_params = Some(commit_block_params);
 
 		trace!(
@@ -202,10 +223,9 @@ where
 		self.stream.storage.update_proposal(Some(proposal))?;
 
 		// append proposal entry
-		let (last_log_index, _) = self.stream.storage.get_last_log_index_term();
 		let entry = Entry {
-			term: self.stream.storage.get_current_term(),
-			index: last_log_index + 1,
+			term: log_term,
+			index: log_index,
 			data: EntryData::Proposal { block_hash },
 		};
 		self.append_entries(vec![entry])?;
@@ -15,7 +15,7 @@
 use crate::errors::ErrorKind;
 use crate::protocol::Proposal;
 use node_chain::ChainCommitBlockParams;
-use node_consensus_base::support::ConsensusSupport;
+use node_consensus_base::support::{verify_block_limits, ConsensusSupport};
 use primitives::errors::{Catchable, CommonResult, Display};
 use primitives::types::ExecutionGap;
 use primitives::{BlockNumber, BuildBlockParams, FullTransaction, Hash, Header, Transaction};
@@ -51,6 +51,12 @@ pub enum VerifyError {
 	/// Transaction invalid
 	#[display(fmt = "Invalid tx: {}", _0)]
 	InvalidTx(node_chain::errors::ValidateTxError),
+	/// Block exceeds max_block_txs or max_block_bytes
+	#[display(fmt = "Exceed block limit: {}", _0)]
+	ExceedBlockLimit(String),
+	/// Proof is not signed by the author
+	#[display(fmt = "Invalid proof: {}", _0)]
+	InvalidProof(String),
 }
 
 impl<S> Verifier<S>
@@ -83,6 +89,10 @@ where
 				self.verify_best(number)?;
 
 			self.verify_execution(number, execution_number, &confirmed_header)?;
+
+			self.verify_block_limits(&proposal_ref.meta_txs, &proposal_ref.payload_txs)?;
+
+			self.verify_author(proposal_ref)?;
 		}
 
 		// the following verification need take ownership of proposal
@@ -91,14 +101,16 @@ where
 
 		let (meta_txs, payload_txs) = self.verify_body(proposal.meta_txs, proposal.payload_txs)?;
 
-		let commit_block_params = self.verify_header(
-			&proposal.block_hash,
-			proposal.number,
-			proposal.timestamp,
-			proposal.execution_number,
+		let build_block_params = BuildBlockParams {
+			number: proposal.number,
+			timestamp: proposal.timestamp,
+			author: proposal.author,
 			meta_txs,
 			payload_txs,
-		)?;
+			execution_number: proposal.execution_number,
+		};
+
+		let commit_block_params = self.verify_header(&proposal.block_hash, build_block_params)?;
 
 		Ok((proposal_clone, commit_block_params))
 	}
@@ -166,6 +178,46 @@ where
 		Ok(())
 	}
 
+	fn verify_block_limits(
+		&self,
+		meta_txs: &[Transaction],
+		payload_txs: &[Transaction],
+	) -> CommonResult<()> {
+		let current_state = self.support.get_current_state();
+		let system_meta = &current_state.system_meta;
+
+		verify_block_limits(
+			meta_txs,
+			payload_txs,
+			system_meta.max_block_txs,
+			system_meta.max_block_bytes,
+		)
+		.or_else_catch::<node_consensus_base::errors::ErrorKind, _>(|e| match e {
+			node_consensus_base::errors::ErrorKind::ExceedBlockLimit(e) => Some(Err(
+				ErrorKind::VerifyError(VerifyError::ExceedBlockLimit(e.clone())).into(),
+			)),
+			_ => None,
+		})?;
+		Ok(())
+	}
+
+	fn verify_author(&self, proposal: &Proposal) -> CommonResult<()> {
+		crate::verify_author(
+			&self.support,
+			&proposal.block_hash,
+			proposal.number,
+			&proposal.author,
+			&proposal.proof,
+		)
+		.or_else_catch::<node_consensus_base::errors::ErrorKind, _>(|e| match e {
+			node_consensus_base::errors::ErrorKind::VerifyProofError(e) => Some(Err(
+				ErrorKind::VerifyError(VerifyError::InvalidProof(e.clone())).into(),
+			)),
+			_ => None,
+		})?;
+		Ok(())
+	}
+
 	/// Return verified txs (meta_txs, payload_txs)
 	fn verify_body(
 		&self,
@@ -195,20 +247,8 @@ where
 	fn verify_header(
 		&self,
 		block_hash: &Hash,
-		number: BlockNumber,
-		timestamp: u64,
-		execution_number: BlockNumber,
-		meta_txs: Vec<Arc<FullTransaction>>,
-		payload_txs: Vec<Arc<FullTransaction>>,
+		build_block_params: BuildBlockParams,
 	) -> CommonResult<ChainCommitBlockParams> {
-		let build_block_params = BuildBlockParams {
-			number,
-			timestamp,
-			meta_txs,
-			payload_txs,
-			execution_number,
-		};
-
 		let commit_block_params = self.support.build_block(build_block_params)?;
 
 		if &commit_block_params.block_hash != block_hash {
@@ -222,12 +222,12 @@ async fn test_raft_update_admin() {
 		&txpool,
 		chain
 			.build_transaction(
-				Some((account1.secret_key.clone(), 0, 10)),
+				Some((account1.secret_key.clone(), 1, 10)),
 				chain
 					.build_call(
 						"raft".to_string(),
-						"update_admin_vote".to_string(),
-						module::raft::UpdateAdminVoteParams { proposal_id: 2 },
+						"vote".to_string(),
+						module::governance::VoteParams { proposal_id: 2 },
 					)
 					.unwrap(),
 			)
@@ -527,12 +527,12 @@ async fn test_raft_update_authorities_drop_follower() {
 		&txpool,
 		chain
 			.build_transaction(
-				Some((account1.secret_key.clone(), 0, 10)),
+				Some((account1.secret_key.clone(), 1, 10)),
 				chain
 					.build_call(
 						"raft".to_string(),
-						"update_authorities_vote".to_string(),
-						module::raft::UpdateAuthoritiesVoteParams { proposal_id: 1 },
+						"vote".to_string(),
+						module::governance::VoteParams { proposal_id: 2 },
 					)
 					.unwrap(),
 			)
@@ -867,12 +867,12 @@ async fn test_raft_update_authorities_drop_leader() {
 		&txpool,
 		chain
 			.build_transaction(
-				Some((account1.secret_key.clone(), 0, 10)),
+				Some((account1.secret_key.clone(), 1, 10)),
 				chain
 					.build_call(
 						"raft".to_string(),
-						"update_authorities_vote".to_string(),
-						module::raft::UpdateAuthoritiesVoteParams { proposal_id: 1 },
+						"vote".to_string(),
+						module::governance::VoteParams { proposal_id: 2 },
 					)
 					.unwrap(),
 			)
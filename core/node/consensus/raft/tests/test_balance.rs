@@ -133,12 +133,25 @@ async fn test_raft_balance_3_authorities() {
 	let proof = chain.get_proof(&block_hash).unwrap().unwrap();
 	let _proof: Proof = Decode::decode(&mut &proof.data[..]).unwrap();
 
+	// the proof is signed by the leader as the author
+	let header = chain.get_header(&block_hash).unwrap().unwrap();
+	assert_eq!(header.author, Some(leader_address.clone()));
+	for service in &services {
+		service.2.verify_proof(&header, &proof).unwrap();
+	}
+
+	// tampered author
+	let mut header = header;
+	header.author = None;
+	let result = consensus.verify_proof(&header, &proof);
+	assert!(format!("{}", result.unwrap_err()).contains("Invalid signature"));
+
 	let tx2_hash = base::insert_tx(
 		&chain,
 		&txpool,
 		chain
 			.build_transaction(
-				Some((account1.secret_key.clone(), 0, 11)),
+				Some((account1.secret_key.clone(), 1, 11)),
 				chain
 					.build_call(
 						"balance".to_string(),
@@ -167,7 +180,7 @@ async fn test_raft_balance_3_authorities() {
 		&txpool,
 		chain
 			.build_transaction(
-				Some((account1.secret_key.clone(), 0, 12)),
+				Some((account1.secret_key.clone(), 2, 12)),
 				chain
 					.build_call(
 						"balance".to_string(),
@@ -262,6 +275,7 @@ async fn test_raft_balance_3_authorities() {
 			)
 			.unwrap()],
 			result: Ok(codec::encode(&()).unwrap()),
+			gas_used: 0,
 		}
 	);
 }
@@ -377,7 +391,7 @@ async fn test_raft_balance_2_authorities() {
 		&txpool,
 		chain
 			.build_transaction(
-				Some((account1.secret_key.clone(), 0, 11)),
+				Some((account1.secret_key.clone(), 1, 11)),
 				chain
 					.build_call(
 						"balance".to_string(),
@@ -406,7 +420,7 @@ async fn test_raft_balance_2_authorities() {
 		&txpool,
 		chain
 			.build_transaction(
-				Some((account1.secret_key.clone(), 0, 12)),
+				Some((account1.secret_key.clone(), 2, 12)),
 				chain
 					.build_call(
 						"balance".to_string(),
@@ -501,6 +515,7 @@ async fn test_raft_balance_2_authorities() {
 			)
 			.unwrap()],
 			result: Ok(codec::encode(&()).unwrap()),
+			gas_used: 0,
 		}
 	);
 }
@@ -600,7 +615,7 @@ async fn test_raft_balance_1_authority() {
 		&txpool,
 		chain
 			.build_transaction(
-				Some((account1.secret_key.clone(), 0, 11)),
+				Some((account1.secret_key.clone(), 1, 11)),
 				chain
 					.build_call(
 						"balance".to_string(),
@@ -629,7 +644,7 @@ async fn test_raft_balance_1_authority() {
 		&txpool,
 		chain
 			.build_transaction(
-				Some((account1.secret_key.clone(), 0, 12)),
+				Some((account1.secret_key.clone(), 2, 12)),
 				chain
 					.build_call(
 						"balance".to_string(),
@@ -724,6 +739,7 @@ async fn test_raft_balance_1_authority() {
 			)
 			.unwrap()],
 			result: Ok(codec::encode(&()).unwrap()),
+			gas_used: 0,
 		}
 	);
 }
@@ -20,9 +20,11 @@ use futures::channel::mpsc::{UnboundedReceiver, UnboundedSender};
 
 use node_consensus_base::support::ConsensusSupport;
 use node_consensus_base::{Consensus as ConsensusT, ConsensusInMessage, ConsensusOutMessage};
+use node_consensus_instant::Instant;
+pub use node_consensus_instant::InstantConfig;
 use node_consensus_poa::Poa;
 pub use node_consensus_poa::PoaConfig;
-use node_consensus_primitives::{CONSENSUS_POA, CONSENSUS_RAFT};
+use node_consensus_primitives::{CONSENSUS_INSTANT, CONSENSUS_POA, CONSENSUS_RAFT};
 use node_consensus_raft::Raft;
 pub use node_consensus_raft::RaftConfig;
 use primitives::errors::CommonResult;
@@ -38,6 +40,7 @@ where
 pub struct ConsensusConfig {
 	pub poa: Option<PoaConfig>,
 	pub raft: Option<RaftConfig>,
+	pub instant: Option<InstantConfig>,
 }
 
 enum Dispatcher<S>
@@ -46,6 +49,7 @@ where
 {
 	Poa(Poa<S>),
 	Raft(Raft<S>),
+	Instant(Instant<S>),
 }
 
 impl<S> Consensus<S>
@@ -97,6 +101,14 @@ where
 				})?;
 				Dispatcher::Raft(Raft::new(config, support)?)
 			}
+			CONSENSUS_INSTANT => {
+				let config = config.instant.ok_or_else(|| {
+					node_consensus_base::errors::ErrorKind::Data(
+						"Missing instant config".to_string(),
+					)
+				})?;
+				Dispatcher::Instant(Instant::new(config, support)?)
+			}
 			other => {
 				panic!("Unknown consensus: {}", other);
 			}
@@ -108,6 +120,7 @@ where
 		match self {
 			Dispatcher::Poa(c) => c.verify_proof(header, proof),
 			Dispatcher::Raft(c) => c.verify_proof(header, proof),
+			Dispatcher::Instant(c) => c.verify_proof(header, proof),
 		}
 	}
 
@@ -115,6 +128,7 @@ where
 		match self {
 			Dispatcher::Poa(c) => c.in_message_tx(),
 			Dispatcher::Raft(c) => c.in_message_tx(),
+			Dispatcher::Instant(c) => c.in_message_tx(),
 		}
 	}
 
@@ -122,6 +136,7 @@ where
 		match self {
 			Dispatcher::Poa(c) => c.out_message_rx(),
 			Dispatcher::Raft(c) => c.out_message_rx(),
+			Dispatcher::Instant(c) => c.out_message_rx(),
 		}
 	}
 }
@@ -24,9 +24,11 @@ pub use node_network::{
 use primitives::codec::Encode;
 use primitives::errors::CommonResult;
 
+pub use crate::protocol::{BlockData, BodyData};
 use crate::protocol::{Handshake, ProtocolMessage};
 use crate::stream::CoordinatorStream;
 use crate::support::CoordinatorSupport;
+pub use crate::verifier::{Verifier, VerifyError};
 use futures::channel::mpsc::{unbounded, UnboundedSender};
 use node_network::HandshakeBuilder;
 use parking_lot::RwLock;
@@ -16,12 +16,12 @@ use std::sync::Arc;
 
 use futures::channel::mpsc::{UnboundedReceiver, UnboundedSender};
 use futures::StreamExt;
-use log::{error, info, warn};
+use log::{error, info, trace, warn};
 
 use node_chain::ChainOutMessage;
 use node_consensus_base::{ConsensusInMessage, ConsensusOutMessage};
 use node_network::{BytesMut, NetworkInMessage, NetworkOutMessage, PMInMessage, PeerId};
-use node_txpool::TxPoolOutMessage;
+use node_txpool::{RemoveReason, TxPoolOutMessage};
 use primitives::codec::{Decode, Encode};
 use primitives::errors::CommonResult;
 use primitives::{BlockNumber, Body, Hash, Header, Proof, Transaction};
@@ -141,6 +141,7 @@ where
 	fn on_txpool_message(&mut self, message: TxPoolOutMessage) -> CommonResult<()> {
 		match message {
 			TxPoolOutMessage::TxInserted { tx_hash } => self.on_tx_inserted(tx_hash),
+			TxPoolOutMessage::TxRemoved { tx_hash, reason } => self.on_tx_removed(tx_hash, reason),
 		}
 	}
 
@@ -307,7 +308,15 @@ where
 	S: CoordinatorSupport,
 {
 	fn on_tx_inserted(&mut self, tx_hash: Hash) -> CommonResult<()> {
-		self.sync.on_tx_inserted(tx_hash)
+		self.sync.on_tx_inserted(tx_hash.clone())?;
+		let in_message = ConsensusInMessage::TxInserted { tx_hash };
+		self.support.consensus_send_message(in_message);
+		Ok(())
+	}
+
+	fn on_tx_removed(&mut self, tx_hash: Hash, reason: RemoveReason) -> CommonResult<()> {
+		trace!("Tx removed from txpool: {}, reason: {:?}", tx_hash, reason);
+		Ok(())
 	}
 }
 
@@ -23,7 +23,7 @@ use node_consensus::Consensus;
 use node_consensus_base::support::DefaultConsensusSupport;
 use node_consensus_base::{ConsensusInMessage, ConsensusOutMessage};
 use node_txpool::support::DefaultTxPoolSupport;
-use node_txpool::{TxPool, TxPoolOutMessage};
+use node_txpool::{RemoveReason, TxPool, TxPoolOutMessage};
 use primitives::codec::{Decode, Encode};
 use primitives::errors::CommonResult;
 use primitives::{
@@ -64,7 +64,11 @@ pub trait CoordinatorSupport: Send + Sync + 'static {
 	fn txpool_get_transactions(&self) -> CommonResult<Vec<Arc<FullTransaction>>>;
 	fn txpool_get_transaction(&self, tx_hash: &Hash) -> CommonResult<Option<Arc<FullTransaction>>>;
 	fn txpool_insert_transaction(&self, tx: Transaction) -> CommonResult<()>;
-	fn txpool_remove_transactions(&self, tx_hash_set: &HashSet<Hash>) -> CommonResult<()>;
+	fn txpool_remove_transactions(
+		&self,
+		tx_hash_set: &HashSet<Hash>,
+		reason: RemoveReason,
+	) -> CommonResult<()>;
 	fn consensus_verify_proof(&self, header: &Header, proof: &Proof) -> CommonResult<()>;
 	fn consensus_tx(&self) -> UnboundedSender<ConsensusInMessage>;
 	fn consensus_rx(&self) -> Option<UnboundedReceiver<ConsensusOutMessage>>;
@@ -159,8 +163,12 @@ impl CoordinatorSupport for DefaultCoordinatorSupport {
 	fn txpool_insert_transaction(&self, tx: Transaction) -> CommonResult<()> {
 		self.txpool.insert(tx)
 	}
-	fn txpool_remove_transactions(&self, tx_hash_set: &HashSet<Hash>) -> CommonResult<()> {
-		self.txpool.remove(tx_hash_set)
+	fn txpool_remove_transactions(
+		&self,
+		tx_hash_set: &HashSet<Hash>,
+		reason: RemoveReason,
+	) -> CommonResult<()> {
+		self.txpool.remove(tx_hash_set, reason)
 	}
 	fn consensus_verify_proof(&self, header: &Header, proof: &Proof) -> CommonResult<()> {
 		self.consensus.verify_proof(header, proof)
@@ -21,6 +21,7 @@ use lru::LruCache;
 
 use node_chain::ChainCommitBlockParams;
 use node_network::{NetworkInMessage, PMInMessage, PeerId};
+use node_txpool::RemoveReason;
 use primitives::codec::Encode;
 use primitives::errors::{Catchable, CommonResult};
 use primitives::{BlockNumber, FullTransaction, Hash, Header};
@@ -68,7 +69,7 @@ where
 	S: CoordinatorSupport,
 {
 	pub fn new(support: Arc<StreamSupport<S>>) -> CommonResult<Self> {
-		let verifier = Verifier::new(support.clone())?;
+		let verifier = Verifier::new(support.ori_support())?;
 
 		Ok(ChainSync {
 			peers: HashMap::new(),
@@ -302,7 +303,8 @@ where
 					for block_data in block_response.blocks {
 						let number = block_data.number;
 						if let Some(pending_block) = self.pending_blocks.get_mut(&number) {
-							if matches!(pending_block.state, PendingBlockState::Downloading {..}) {
+							if matches!(pending_block.state, PendingBlockState::Downloading { .. })
+							{
 								pending_block.state = PendingBlockState::Downloaded {
 									from: from.clone(),
 									block_data: Some(block_data),
@@ -631,7 +633,7 @@ where
 			);
 			self.support
 				.ori_support()
-				.txpool_remove_transactions(&tx_hash_set)?;
+				.txpool_remove_transactions(&tx_hash_set, RemoveReason::Included)?;
 		}
 
 		Ok(action)
@@ -648,6 +650,7 @@ where
 			VerifyError::DuplicatedTx(_) => VerifyAction::Reset,
 			VerifyError::InvalidTx(_) => VerifyAction::Reset,
 			VerifyError::InvalidProof(_) => VerifyAction::Reset,
+			VerifyError::ExceedBlockLimit(_) => VerifyAction::Reset,
 		};
 		Ok(action)
 	}
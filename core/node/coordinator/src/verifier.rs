@@ -16,6 +16,7 @@ use std::collections::HashSet;
 use std::sync::Arc;
 
 use node_chain::ChainCommitBlockParams;
+use node_consensus_base::support::verify_block_limits;
 use primitives::errors::{Catchable, CommonResult, Display};
 use primitives::{
 	BlockNumber, BuildBlockParams, FullTransaction, Hash, Header, Proof, Transaction,
@@ -24,21 +25,20 @@ use primitives::{
 use crate::errors;
 use crate::errors::ErrorKind;
 use crate::protocol::{BlockData, BodyData};
-use crate::stream::StreamSupport;
 use crate::support::CoordinatorSupport;
 
 pub struct Verifier<S>
 where
 	S: CoordinatorSupport,
 {
-	support: Arc<StreamSupport<S>>,
+	support: Arc<S>,
 }
 
 impl<S> Verifier<S>
 where
 	S: CoordinatorSupport,
 {
-	pub fn new(support: Arc<StreamSupport<S>>) -> CommonResult<Self> {
+	pub fn new(support: Arc<S>) -> CommonResult<Self> {
 		let verifier = Self { support };
 		Ok(verifier)
 	}
@@ -73,6 +73,9 @@ pub enum VerifyError {
 	/// Proof invalid
 	#[display(fmt = "Invalid proof: {}", _0)]
 	InvalidProof(String),
+	/// Block exceeds max_block_txs or max_block_bytes
+	#[display(fmt = "Exceed block limit: {}", _0)]
+	ExceedBlockLimit(String),
 }
 
 impl<S> Verifier<S>
@@ -95,6 +98,9 @@ where
 				self.verify_best(header)?;
 
 			self.verify_execution(header, &confirmed_header)?;
+
+			let body = block_data_ref.body.as_ref().expect("qed");
+			self.verify_block_limits(&body.meta_txs, &body.payload_txs)?;
 		}
 
 		// the following verification need take ownership of block data
@@ -132,7 +138,7 @@ where
 	}
 
 	fn verify_not_repeat(&self, block_hash: &Hash) -> CommonResult<()> {
-		if self.support.ori_support().get_header(block_hash)?.is_some() {
+		if self.support.get_header(block_hash)?.is_some() {
 			return Err(ErrorKind::VerifyError(VerifyError::Duplicated).into());
 		}
 		Ok(())
@@ -141,16 +147,12 @@ where
 	/// Return confirmed block (number, block hash, header)
 	fn verify_best(&self, header: &Header) -> CommonResult<(BlockNumber, Hash, Header)> {
 		let confirmed = {
-			let current_state = &self.support.ori_support().get_current_state();
+			let current_state = &self.support.get_current_state();
 			let confirmed_number = current_state.confirmed_number;
 			let block_hash = current_state.confirmed_block_hash.clone();
-			let header = self
-				.support
-				.ori_support()
-				.get_header(&block_hash)?
-				.ok_or_else(|| {
-					errors::ErrorKind::Data(format!("Missing header: block_hash: {:?}", block_hash))
-				})?;
+			let header = self.support.get_header(&block_hash)?.ok_or_else(|| {
+				errors::ErrorKind::Data(format!("Missing header: block_hash: {:?}", block_hash))
+			})?;
 			(confirmed_number, block_hash, header)
 		};
 
@@ -162,7 +164,7 @@ where
 	}
 
 	fn verify_execution(&self, header: &Header, confirmed_header: &Header) -> CommonResult<()> {
-		let current_state = self.support.ori_support().get_current_state();
+		let current_state = self.support.get_current_state();
 		let system_meta = &current_state.system_meta;
 
 		if header.payload_execution_gap < 1 {
@@ -192,9 +194,31 @@ where
 		Ok(())
 	}
 
+	fn verify_block_limits(
+		&self,
+		meta_txs: &[Transaction],
+		payload_txs: &[Transaction],
+	) -> CommonResult<()> {
+		let current_state = self.support.get_current_state();
+		let system_meta = &current_state.system_meta;
+
+		verify_block_limits(
+			meta_txs,
+			payload_txs,
+			system_meta.max_block_txs,
+			system_meta.max_block_bytes,
+		)
+		.or_else_catch::<node_consensus_base::errors::ErrorKind, _>(|e| match e {
+			node_consensus_base::errors::ErrorKind::ExceedBlockLimit(e) => Some(Err(
+				ErrorKind::VerifyError(VerifyError::ExceedBlockLimit(e.clone())).into(),
+			)),
+			_ => None,
+		})?;
+		Ok(())
+	}
+
 	fn verify_proof(&self, header: &Header, proof: &Proof) -> CommonResult<()> {
 		self.support
-			.ori_support()
 			.consensus_verify_proof(header, proof)
 			.or_else_catch::<node_consensus_base::errors::ErrorKind, _>(|e| match e {
 				node_consensus_base::errors::ErrorKind::VerifyProofError(e) => Some(Err(
@@ -214,7 +238,7 @@ where
 			let mut set = HashSet::new();
 			let mut result = Vec::with_capacity(txs.len());
 			for tx in txs {
-				let tx_hash = self.support.ori_support().hash_transaction(&tx)?;
+				let tx_hash = self.support.hash_transaction(&tx)?;
 				self.verify_transaction(&tx_hash, &tx, &mut set)?;
 				let tx = Arc::new(FullTransaction { tx_hash, tx });
 				result.push(tx);
@@ -242,12 +266,13 @@ where
 		let build_block_params = BuildBlockParams {
 			number: header.number,
 			timestamp: header.timestamp,
+			author: header.author.clone(),
 			meta_txs,
 			payload_txs,
 			execution_number,
 		};
 
-		let mut commit_block_params = self.support.ori_support().build_block(build_block_params)?;
+		let mut commit_block_params = self.support.build_block(build_block_params)?;
 
 		if &commit_block_params.header != header {
 			let msg = format!(
@@ -277,7 +302,6 @@ where
 		}
 
 		self.support
-			.ori_support()
 			.validate_transaction(tx_hash, &tx, true)
 			.or_else_catch::<node_chain::errors::ErrorKind, _>(|e| match e {
 				node_chain::errors::ErrorKind::ValidateTxError(e) => Some(Err(
@@ -73,6 +73,7 @@ pub fn gen_db_opts(config: &DBConfig) -> CommonResult<Options> {
 	opts.set_report_bg_io_stats(true);
 	opts.set_use_fsync(false);
 	opts.create_if_missing(true);
+	opts.create_missing_column_families(true);
 	opts.set_max_open_files(512);
 	opts.set_bytes_per_sync(1048576);
 	opts.increase_parallelism(cmp::max(1, ::num_cpus::get() as i32 / 2));
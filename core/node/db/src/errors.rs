@@ -20,6 +20,12 @@ use primitives::errors::{CommonError, CommonErrorKind, Display};
 pub enum ErrorKind {
 	#[display(fmt = "RocksDB error: {:?}", _0)]
 	RocksDB(rocksdb::Error),
+
+	#[display(fmt = "Schema version error: {}", _0)]
+	SchemaVersion(String),
+
+	#[display(fmt = "Checkpoint error: {}", _0)]
+	Checkpoint(String),
 }
 
 impl Error for ErrorKind {}
@@ -12,25 +12,21 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-//! Key value db based on rocksdb
+//! Key value db, with the backend based on rocksdb or in memory
 
-use std::path::PathBuf;
-
-use parking_lot::RwLock;
-use rocksdb::{
-	BlockBasedOptions, ColumnFamily, ColumnFamilyDescriptor, Options, ReadOptions, WriteBatch,
-	WriteOptions, DB as RocksDB,
-};
+use std::path::{Path, PathBuf};
 
 use primitives::errors::CommonResult;
 use primitives::{DBKey, DBValue};
 
-use crate::config::{
-	gen_block_opts, gen_cf_opts, gen_col_memory_budgets, gen_db_opts, gen_read_opts, gen_write_opts,
-};
+use crate::memory::MemoryKeyValueDB;
+use crate::rocks::RocksKeyValueDB;
 
 pub mod config;
 pub mod errors;
+pub mod memory;
+pub mod migration;
+pub mod rocks;
 
 #[derive(Clone)]
 pub struct Partition {
@@ -48,76 +44,57 @@ pub struct DBConfig {
 	pub path: PathBuf,
 	/// db partitions
 	pub partitions: Vec<Partition>,
+	/// db backend
+	pub backend: DBBackend,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum DBBackend {
+	/// Persisted by rocksdb in the db path
+	RocksDB,
+	/// Kept in memory, lost once the db is dropped,
+	/// for tests and ephemeral nodes
+	Memory,
+}
+
+/// Key value db backend
+pub trait KeyValueDB: Send + Sync {
+	/// Get value by col and key
+	fn get(&self, col: u32, key: &[u8]) -> CommonResult<Option<DBValue>>;
+
+	/// Get all the key-values of the col, in the order of key
+	fn iter(&self, col: u32) -> CommonResult<Vec<(DBKey, DBValue)>>;
+
+	/// Write with a db transaction atomically
+	fn write(&self, transaction: DBTransaction) -> CommonResult<()>;
+
+	/// Create a consistent copy of the db in the path, which should not exist
+	fn checkpoint(&self, path: &Path) -> CommonResult<()>;
 }
 
 pub struct DB {
-	db: RwLock<RocksDB>,
-	#[allow(dead_code)]
-	db_opts: Options,
-	write_opts: WriteOptions,
-	read_opts: ReadOptions,
-	#[allow(dead_code)]
-	block_opts: BlockBasedOptions,
+	backend: Box<dyn KeyValueDB>,
 }
 
 impl DB {
-	/// Open the db from the given path
+	/// Open the db with the backend of the given config
+	/// the db written with an older schema version is upgraded
 	pub fn open(config: DBConfig) -> CommonResult<DB> {
-		let db_opts = gen_db_opts(&config)?;
-		let block_opts = gen_block_opts(&config)?;
-		let read_opts = gen_read_opts();
-		let write_opts = gen_write_opts();
-		let col_memory_budgets = gen_col_memory_budgets(&config);
-
-		let cfs = columns::COLUMN_NAMES.iter().map(|&name| {
-			ColumnFamilyDescriptor::new(
-				name,
-				gen_cf_opts(*col_memory_budgets.get(name).expect("qed"), &block_opts),
-			)
-		});
-
-		let rocksdb = match RocksDB::open_cf_descriptors(&db_opts, &config.path, cfs) {
-			Err(_) => match RocksDB::open_cf(&db_opts, &config.path, &[] as &[&str]) {
-				Ok(mut db) => {
-					for &name in columns::COLUMN_NAMES.iter() {
-						let _ = db
-							.create_cf(
-								name,
-								&gen_cf_opts(
-									*col_memory_budgets.get(name).expect("qed"),
-									&block_opts,
-								),
-							)
-							.map_err(errors::ErrorKind::RocksDB)?;
-					}
-					db
-				}
-				Err(e) => return Err(errors::ErrorKind::RocksDB(e).into()),
-			},
-			Ok(db) => db,
+		let backend: Box<dyn KeyValueDB> = match config.backend {
+			DBBackend::RocksDB => Box::new(RocksKeyValueDB::open(&config)?),
+			DBBackend::Memory => Box::new(MemoryKeyValueDB::new()),
 		};
 
-		let db = Self {
-			db: RwLock::new(rocksdb),
-			db_opts,
-			write_opts,
-			read_opts,
-			block_opts,
-		};
+		let db = Self { backend };
+
+		migration::migrate(&db)?;
 
 		Ok(db)
 	}
 
 	/// Get value by col and key
 	pub fn get(&self, col: u32, key: &[u8]) -> CommonResult<Option<DBValue>> {
-		let db = &(*self.db.read());
-		let cf = Self::get_cf(&db, col);
-
-		let result = db
-			.get_cf_opt(cf, key, &self.read_opts)
-			.map_err(errors::ErrorKind::RocksDB)?;
-
-		Ok(result)
+		self.backend.get(col, key)
 	}
 
 	/// Get value processed by f
@@ -135,39 +112,26 @@ impl DB {
 		Ok(value)
 	}
 
+	/// Get all the key-values of the col, in the order of key
+	pub fn iter(&self, col: u32) -> CommonResult<Vec<(DBKey, DBValue)>> {
+		self.backend.iter(col)
+	}
+
 	/// Write with a db transaction
 	pub fn write(&self, transaction: DBTransaction) -> CommonResult<()> {
-		let db = &(*self.db.write());
-
-		let ops = transaction.ops;
-		let mut batch = WriteBatch::default();
-		for op in ops {
-			match op {
-				DBOp::Insert { col, key, value } => {
-					let cf = Self::get_cf(&db, col);
-					batch.put_cf(cf, &key, &value);
-				}
-				DBOp::Delete { col, key } => {
-					let cf = Self::get_cf(&db, col);
-					batch.delete_cf(cf, &key);
-				}
-			};
-		}
-		db.write_opt(batch, &self.write_opts)
-			.map_err(errors::ErrorKind::RocksDB)?;
-		Ok(())
+		self.backend.write(transaction)
+	}
+
+	/// Create a consistent copy of the db in the path while the db is being written,
+	/// the copy can be opened as a db of the same backend
+	pub fn checkpoint(&self, path: &Path) -> CommonResult<()> {
+		self.backend.checkpoint(path)
 	}
 
 	/// Init a new empty db transaction
 	pub fn transaction(&self) -> DBTransaction {
 		DBTransaction::new()
 	}
-
-	fn get_cf(db: &RocksDB, col: u32) -> &ColumnFamily {
-		let col_name = columns::COLUMN_NAMES[col as usize];
-		let cf = db.cf_handle(col_name).expect("Col name should exist");
-		cf
-	}
 }
 
 #[derive(Debug)]
@@ -237,7 +201,7 @@ impl Default for DBTransaction {
 
 pub mod columns {
 	/// column names, which should be corresponding to the following const
-	pub const COLUMN_NAMES: [&str; 12] = [
+	pub const COLUMN_NAMES: [&str; 17] = [
 		"global",
 		"block_hash",
 		"header",
@@ -250,6 +214,11 @@ pub mod columns {
 		"execution",
 		"proof",
 		"consensus",
+		"txpool",
+		"meta_state_journal",
+		"payload_state_journal",
+		"address_index",
+		"event_index",
 	];
 
 	/// see global_key
@@ -288,6 +257,26 @@ pub mod columns {
 	/// consensus data
 	/// custom key to custom data
 	pub const CONSENSUS: u32 = 11;
+
+	/// txpool journal
+	/// transaction hash to pending transaction
+	pub const TXPOOL: u32 = 12;
+
+	/// meta state journal for pruning
+	/// block number to inserted and deleted trie nodes
+	pub const META_STATE_JOURNAL: u32 = 13;
+
+	/// payload state journal for pruning
+	/// block number to inserted and deleted trie nodes
+	pub const PAYLOAD_STATE_JOURNAL: u32 = 14;
+
+	/// address index
+	/// address to transaction count, (address, sequence) to transaction
+	pub const ADDRESS_INDEX: u32 = 15;
+
+	/// event index
+	/// event name or module to receipt count, (event name or module, sequence) to receipt
+	pub const EVENT_INDEX: u32 = 16;
 }
 
 pub mod global_key {
@@ -297,4 +286,8 @@ pub mod global_key {
 	pub const EXECUTION_NUMBER: &[u8] = b"execution_number";
 	/// spec
 	pub const SPEC: &[u8] = b"spec";
+	/// The state pruning mode the db is created with
+	pub const STATE_PRUNING: &[u8] = b"state_pruning";
+	/// The schema version the db is written with, see migration
+	pub const SCHEMA_VERSION: &[u8] = b"schema_version";
 }
@@ -0,0 +1,85 @@
+// Copyright 2019, 2020 Wingchain
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Key value db backend in memory
+//! the data is lost once the db is dropped
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use parking_lot::RwLock;
+
+use primitives::errors::CommonResult;
+use primitives::{DBKey, DBValue};
+
+use crate::{columns, errors, DBOp, DBTransaction, KeyValueDB};
+
+pub struct MemoryKeyValueDB {
+	/// key-values of each col, in the order of key
+	cols: RwLock<Vec<BTreeMap<DBKey, DBValue>>>,
+}
+
+impl MemoryKeyValueDB {
+	/// Create an empty db
+	pub fn new() -> Self {
+		let cols = columns::COLUMN_NAMES
+			.iter()
+			.map(|_| BTreeMap::new())
+			.collect();
+		Self {
+			cols: RwLock::new(cols),
+		}
+	}
+}
+
+impl Default for MemoryKeyValueDB {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl KeyValueDB for MemoryKeyValueDB {
+	fn get(&self, col: u32, key: &[u8]) -> CommonResult<Option<DBValue>> {
+		let cols = self.cols.read();
+		Ok(cols[col as usize].get(key).cloned())
+	}
+
+	fn iter(&self, col: u32) -> CommonResult<Vec<(DBKey, DBValue)>> {
+		let cols = self.cols.read();
+		let result = cols[col as usize]
+			.iter()
+			.map(|(k, v)| (k.clone(), v.clone()))
+			.collect();
+		Ok(result)
+	}
+
+	fn write(&self, transaction: DBTransaction) -> CommonResult<()> {
+		let mut cols = self.cols.write();
+		for op in transaction.ops {
+			match op {
+				DBOp::Insert { col, key, value } => {
+					cols[col as usize].insert(key, value);
+				}
+				DBOp::Delete { col, key } => {
+					cols[col as usize].remove(&key);
+				}
+			}
+		}
+		Ok(())
+	}
+
+	fn checkpoint(&self, _path: &Path) -> CommonResult<()> {
+		Err(errors::ErrorKind::Checkpoint("Not supported by the memory backend".to_string()).into())
+	}
+}
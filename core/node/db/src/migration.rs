@@ -0,0 +1,105 @@
+// Copyright 2019, 2020 Wingchain
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Schema versioning of the db
+//! the data directories written with an older schema version are upgraded in place on opening,
+//! while the data directories written before the versioning can not be upgraded and should be re-synced
+
+use log::info;
+
+use primitives::codec;
+use primitives::errors::CommonResult;
+
+use crate::{columns, errors, global_key, DB};
+
+/// Version of the current schema, namely the columns and the content of them,
+/// should be increased with a migration appended when the schema is changed
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Migration upgrading the db from a schema version to the next one
+pub struct Migration {
+	/// Schema version the migration upgrades from
+	pub version: u32,
+	/// What the migration changes
+	pub description: &'static str,
+	/// Upgrade the db in place
+	pub migrate: fn(&DB) -> CommonResult<()>,
+}
+
+/// Registry of the migrations, in the order of version
+pub const MIGRATIONS: &[Migration] = &[];
+
+/// Get the schema version the db is written with
+/// None for a db written before the versioning
+pub fn get_schema_version(db: &DB) -> CommonResult<Option<u32>> {
+	db.get_with(columns::GLOBAL, global_key::SCHEMA_VERSION, |x| {
+		codec::decode(&mut &x[..])
+	})
+}
+
+/// Check the schema version of the db and upgrade it to the current one
+pub(crate) fn migrate(db: &DB) -> CommonResult<()> {
+	let version = match get_schema_version(db)? {
+		Some(version) => version,
+		// a new db is written with the current schema
+		None if db.iter(columns::GLOBAL)?.is_empty() => {
+			return set_schema_version(db, SCHEMA_VERSION);
+		}
+		// the headers lack the author, the system meta lacks the fee and the indexes are missing
+		None => {
+			return Err(errors::ErrorKind::SchemaVersion(
+				"Unversioned db written before the schema versioning, should be re-synced"
+					.to_string(),
+			)
+			.into());
+		}
+	};
+
+	if version > SCHEMA_VERSION {
+		return Err(errors::ErrorKind::SchemaVersion(format!(
+			"Unsupported version: db: {}, current: {}",
+			version, SCHEMA_VERSION
+		))
+		.into());
+	}
+
+	for current in version..SCHEMA_VERSION {
+		let migration = MIGRATIONS
+			.iter()
+			.find(|x| x.version == current)
+			.ok_or_else(|| {
+				errors::ErrorKind::SchemaVersion(format!("Missing migration: version: {}", current))
+			})?;
+		info!(
+			"Migrating db: version: {} -> {}, {}",
+			current,
+			current + 1,
+			migration.description
+		);
+		(migration.migrate)(db)?;
+		set_schema_version(db, current + 1)?;
+	}
+
+	Ok(())
+}
+
+fn set_schema_version(db: &DB, version: u32) -> CommonResult<()> {
+	let mut transaction = db.transaction();
+	transaction.put(
+		columns::GLOBAL,
+		global_key::SCHEMA_VERSION,
+		&codec::encode(&version)?,
+	);
+	db.write(transaction)
+}
@@ -0,0 +1,154 @@
+// Copyright 2019, 2020 Wingchain
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Key value db backend based on rocksdb
+
+use std::path::Path;
+
+use parking_lot::RwLock;
+use rocksdb::checkpoint::Checkpoint;
+use rocksdb::{
+	BlockBasedOptions, ColumnFamily, ColumnFamilyDescriptor, IteratorMode, Options, ReadOptions,
+	WriteBatch, WriteOptions, DB as RocksDB,
+};
+
+use primitives::errors::CommonResult;
+use primitives::{DBKey, DBValue};
+
+use crate::config::{
+	gen_block_opts, gen_cf_opts, gen_col_memory_budgets, gen_db_opts, gen_read_opts, gen_write_opts,
+};
+use crate::{columns, errors, DBConfig, DBOp, DBTransaction, KeyValueDB};
+
+pub struct RocksKeyValueDB {
+	db: RwLock<RocksDB>,
+	#[allow(dead_code)]
+	db_opts: Options,
+	write_opts: WriteOptions,
+	read_opts: ReadOptions,
+	#[allow(dead_code)]
+	block_opts: BlockBasedOptions,
+}
+
+impl RocksKeyValueDB {
+	/// Open the db from the path of the config
+	pub fn open(config: &DBConfig) -> CommonResult<Self> {
+		let db_opts = gen_db_opts(config)?;
+		let block_opts = gen_block_opts(config)?;
+		let read_opts = gen_read_opts();
+		let write_opts = gen_write_opts();
+		let col_memory_budgets = gen_col_memory_budgets(config);
+
+		let cfs = columns::COLUMN_NAMES.iter().map(|&name| {
+			ColumnFamilyDescriptor::new(
+				name,
+				gen_cf_opts(*col_memory_budgets.get(name).expect("qed"), &block_opts),
+			)
+		});
+
+		let rocksdb = match RocksDB::open_cf_descriptors(&db_opts, &config.path, cfs) {
+			Err(_) => match RocksDB::open_cf(&db_opts, &config.path, &[] as &[&str]) {
+				Ok(mut db) => {
+					for &name in columns::COLUMN_NAMES.iter() {
+						let _ = db
+							.create_cf(
+								name,
+								&gen_cf_opts(
+									*col_memory_budgets.get(name).expect("qed"),
+									&block_opts,
+								),
+							)
+							.map_err(errors::ErrorKind::RocksDB)?;
+					}
+					db
+				}
+				Err(e) => return Err(errors::ErrorKind::RocksDB(e).into()),
+			},
+			Ok(db) => db,
+		};
+
+		let db = Self {
+			db: RwLock::new(rocksdb),
+			db_opts,
+			write_opts,
+			read_opts,
+			block_opts,
+		};
+
+		Ok(db)
+	}
+
+	fn get_cf(db: &RocksDB, col: u32) -> &ColumnFamily {
+		let col_name = columns::COLUMN_NAMES[col as usize];
+		let cf = db.cf_handle(col_name).expect("Col name should exist");
+		cf
+	}
+}
+
+impl KeyValueDB for RocksKeyValueDB {
+	fn get(&self, col: u32, key: &[u8]) -> CommonResult<Option<DBValue>> {
+		let db = &(*self.db.read());
+		let cf = Self::get_cf(&db, col);
+
+		let result = db
+			.get_cf_opt(cf, key, &self.read_opts)
+			.map_err(errors::ErrorKind::RocksDB)?;
+
+		Ok(result)
+	}
+
+	fn iter(&self, col: u32) -> CommonResult<Vec<(DBKey, DBValue)>> {
+		let db = &(*self.db.read());
+		let cf = Self::get_cf(&db, col);
+
+		let result = db
+			.iterator_cf(cf, IteratorMode::Start)
+			.map(|(k, v)| (DBKey::from_slice(&k), v.into_vec()))
+			.collect();
+
+		Ok(result)
+	}
+
+	fn write(&self, transaction: DBTransaction) -> CommonResult<()> {
+		let db = &(*self.db.write());
+
+		let ops = transaction.ops;
+		let mut batch = WriteBatch::default();
+		for op in ops {
+			match op {
+				DBOp::Insert { col, key, value } => {
+					let cf = Self::get_cf(&db, col);
+					batch.put_cf(cf, &key, &value);
+				}
+				DBOp::Delete { col, key } => {
+					let cf = Self::get_cf(&db, col);
+					batch.delete_cf(cf, &key);
+				}
+			};
+		}
+		db.write_opt(batch, &self.write_opts)
+			.map_err(errors::ErrorKind::RocksDB)?;
+		Ok(())
+	}
+
+	fn checkpoint(&self, path: &Path) -> CommonResult<()> {
+		let db = &(*self.db.read());
+
+		// the sst files are hard linked if on the same filesystem
+		Checkpoint::new(db)
+			.and_then(|checkpoint| checkpoint.create_checkpoint(path))
+			.map_err(errors::ErrorKind::RocksDB)?;
+		Ok(())
+	}
+}
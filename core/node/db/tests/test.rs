@@ -15,9 +15,10 @@
 use tempfile::tempdir;
 
 use node_db::global_key;
+use node_db::migration::{get_schema_version, MIGRATIONS, SCHEMA_VERSION};
 use node_db::DB;
-use node_db::{columns, DBConfig, Partition};
-use primitives::DBKey;
+use node_db::{columns, DBBackend, DBConfig, DBTransaction, Partition};
+use primitives::{codec, DBKey};
 
 #[test]
 fn test_db() {
@@ -27,6 +28,7 @@ fn test_db() {
 		memory_budget: 1 * 1024 * 1024,
 		path,
 		partitions: vec![],
+		backend: DBBackend::RocksDB,
 	};
 	let db = DB::open(db_config).unwrap();
 
@@ -68,6 +70,75 @@ fn test_db() {
 	);
 }
 
+#[test]
+fn test_db_iter() {
+	let path = tempdir().expect("Could not create a temp dir");
+	let path = path.into_path();
+	let db_config = DBConfig {
+		memory_budget: 1 * 1024 * 1024,
+		path,
+		partitions: vec![],
+		backend: DBBackend::RocksDB,
+	};
+	let db = DB::open(db_config).unwrap();
+
+	let mut transaction = db.transaction();
+	transaction.put(columns::TXPOOL, b"tx2", b"tx2_value");
+	transaction.put(columns::TXPOOL, b"tx1", b"tx1_value");
+	transaction.put(columns::TX, b"tx3", b"tx3_value");
+	db.write(transaction).unwrap();
+
+	let mut transaction = db.transaction();
+	transaction.delete(columns::TXPOOL, b"tx2");
+	transaction.put(columns::TXPOOL, b"tx4", b"tx4_value");
+	db.write(transaction).unwrap();
+
+	assert_eq!(
+		db.iter(columns::TXPOOL).unwrap(),
+		vec![
+			(DBKey::from_slice(b"tx1"), b"tx1_value".to_vec()),
+			(DBKey::from_slice(b"tx4"), b"tx4_value".to_vec()),
+		]
+	);
+}
+
+#[test]
+fn test_db_memory() {
+	let db_config = DBConfig {
+		memory_budget: 1 * 1024 * 1024,
+		path: Default::default(),
+		partitions: vec![],
+		backend: DBBackend::Memory,
+	};
+	let db = DB::open(db_config).unwrap();
+
+	assert_eq!(get_schema_version(&db).unwrap(), Some(SCHEMA_VERSION));
+
+	let mut transaction = db.transaction();
+	transaction.put(columns::TXPOOL, b"tx2", b"tx2_value");
+	transaction.put(columns::TXPOOL, b"tx1", b"tx1_value");
+	transaction.put(columns::TX, b"tx3", b"tx3_value");
+	db.write(transaction).unwrap();
+
+	let mut transaction = db.transaction();
+	transaction.delete(columns::TXPOOL, b"tx2");
+	transaction.put(columns::TXPOOL, b"tx4", b"tx4_value");
+	db.write(transaction).unwrap();
+
+	assert_eq!(db.get(columns::TXPOOL, b"tx2").unwrap(), None);
+	assert_eq!(
+		db.get(columns::TX, b"tx3").unwrap(),
+		Some(b"tx3_value".to_vec())
+	);
+	assert_eq!(
+		db.iter(columns::TXPOOL).unwrap(),
+		vec![
+			(DBKey::from_slice(b"tx1"), b"tx1_value".to_vec()),
+			(DBKey::from_slice(b"tx4"), b"tx4_value".to_vec()),
+		]
+	);
+}
+
 #[test]
 fn test_db_partition() {
 	let path = tempdir().expect("Could not create a temp dir");
@@ -79,6 +150,7 @@ fn test_db_partition() {
 			path: path.join("db0"),
 			target_size: 64 * 1024 * 1024,
 		}],
+		backend: DBBackend::RocksDB,
 	};
 	let db = DB::open(db_config).unwrap();
 
@@ -128,6 +200,7 @@ fn test_existing_db() {
 		memory_budget: 1 * 1024 * 1024,
 		path,
 		partitions: vec![],
+		backend: DBBackend::RocksDB,
 	};
 
 	let db = DB::open(db_config.clone()).unwrap();
@@ -164,6 +237,7 @@ fn test_db_concurrent() {
 		memory_budget: 1 * 1024 * 1024,
 		path,
 		partitions: vec![],
+		backend: DBBackend::RocksDB,
 	};
 
 	let db = Arc::new(DB::open(db_config).unwrap());
@@ -201,3 +275,60 @@ fn test_db_concurrent() {
 
 	assert!(result == vec![1, 0, 0, 0] || result == vec![2, 0, 0, 0])
 }
+
+#[test]
+fn test_db_schema_version() {
+	// every version has a migration to the next one
+	assert_eq!(MIGRATIONS.len() as u32, SCHEMA_VERSION - 1);
+	for (i, migration) in MIGRATIONS.iter().enumerate() {
+		assert_eq!(migration.version, i as u32 + 1);
+	}
+
+	// open a new db, rewrite its global column and reopen it
+	let reopen = |rewrite: &dyn Fn(&mut DBTransaction)| {
+		let path = tempdir().expect("Could not create a temp dir");
+		let path = path.into_path();
+		let db_config = DBConfig {
+			memory_budget: 1 * 1024 * 1024,
+			path,
+			partitions: vec![],
+			backend: DBBackend::RocksDB,
+		};
+
+		// new db
+		let db = DB::open(db_config.clone()).unwrap();
+		assert_eq!(get_schema_version(&db).unwrap(), Some(SCHEMA_VERSION));
+
+		let mut transaction = db.transaction();
+		rewrite(&mut transaction);
+		db.write(transaction).unwrap();
+		drop(db);
+
+		DB::open(db_config)
+	};
+
+	// db written with the current schema
+	let db = reopen(&|_| {}).unwrap();
+	assert_eq!(get_schema_version(&db).unwrap(), Some(SCHEMA_VERSION));
+
+	// db written before the versioning
+	let result = reopen(&|transaction| {
+		transaction.delete(columns::GLOBAL, global_key::SCHEMA_VERSION);
+		transaction.put(
+			columns::GLOBAL,
+			&global_key::CONFIRMED_NUMBER,
+			&vec![1, 0, 0, 0, 0, 0, 0, 0],
+		);
+	});
+	assert!(format!("{}", result.err().unwrap()).contains("should be re-synced"));
+
+	// db written with a newer schema
+	let result = reopen(&|transaction| {
+		transaction.put(
+			columns::GLOBAL,
+			global_key::SCHEMA_VERSION,
+			&codec::encode(&(SCHEMA_VERSION + 1)).unwrap(),
+		);
+	});
+	assert!(format!("{}", result.err().unwrap()).contains("Unsupported version"));
+}
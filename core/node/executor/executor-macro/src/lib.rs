@@ -19,11 +19,14 @@ use syn::{
 	parse_macro_input, FnArg, Ident, ImplItem, ImplItemMethod, ItemImpl, Meta, NestedMeta, Type,
 };
 
-use quote::quote;
+use quote::{format_ident, quote};
 
 /// Module method validate_xxx will be treated as the validator of method xxx
 const VALIDATE_METHOD_PREFIX: &str = "validate_";
 
+/// Module methods with these names will be treated as the block hooks
+const BLOCK_HOOK_METHODS: [&str; 2] = ["on_initialize", "on_finalize"];
+
 #[proc_macro_attribute]
 pub fn dispatcher(_attr: TokenStream, item: TokenStream) -> TokenStream {
 	let ast: syn::DeriveInput = syn::parse(item).unwrap();
@@ -93,10 +96,53 @@ pub fn dispatcher(_attr: TokenStream, item: TokenStream) -> TokenStream {
 		})
 		.collect::<Vec<_>>();
 
+	let module_name_ts_vec = variants
+		.iter()
+		.map(|x| {
+			let ident = &x.ident;
+			quote! { stringify!(#ident), }
+		})
+		.collect::<Vec<_>>();
+
+	let block_hook_ts_vec = BLOCK_HOOK_METHODS
+		.iter()
+		.map(|hook| {
+			let hook_ident = format_ident!("{}", hook);
+			let hook_ts_vec = variants
+				.iter()
+				.map(|x| {
+					let ident = &x.ident;
+					quote! { stringify!(#ident) => {
+						let module = module::#ident::Module::<_, _>::new(context.clone(), util.clone());
+						let result = module.#hook_ident();
+						match result {
+							Ok(result) => Ok(Ok(result)),
+							Err(e) => match e {
+								ModuleError::System(e) => Err(e),
+								ModuleError::Application(e) => Ok(Err(e.to_string())),
+							}
+						}
+					}, }
+				})
+				.collect::<Vec<_>>();
+			quote! {
+				fn #hook_ident<C: ContextT, U: UtilT>(module: &str, context: &C, util: &U) -> CommonResult<CallResult<()>>{
+					match module {
+						#(#hook_ts_vec)*
+						other => Err(errors::ErrorKind::InvalidTxModule(other.to_string()).into()),
+					}
+				}
+			}
+		})
+		.collect::<Vec<_>>();
+
 	let type_name = &ast.ident;
 	let gen = quote! {
 		struct #type_name;
 		impl #type_name {
+			fn modules() -> Vec<&'static str> {
+				vec![#(#module_name_ts_vec)*]
+			}
 			fn is_meta<C: ContextT, U: UtilT>(module: &str) -> CommonResult<bool>{
 				match module {
 					#(#is_meta_ts_vec)*
@@ -127,6 +173,7 @@ pub fn dispatcher(_attr: TokenStream, item: TokenStream) -> TokenStream {
 					other => Err(errors::ErrorKind::InvalidTxModule(other.to_string()).into()),
 				}
 			}
+			#(#block_hook_ts_vec)*
 		}
 	};
 	gen.into()
@@ -145,6 +192,17 @@ pub fn module(_attr: TokenStream, item: TokenStream) -> TokenStream {
 
 	let methods = get_module_call_methods(&impl_item);
 
+	let block_hook_ts_vec = get_module_block_hooks(&impl_item)
+		.iter()
+		.map(|hook_ident| {
+			quote! {
+				fn #hook_ident(&self) -> ModuleResult<()> {
+					Self::#hook_ident(self)
+				}
+			}
+		})
+		.collect::<Vec<_>>();
+
 	let is_write_call_ts_vec = methods
 		.iter()
 		.map(|x| {
@@ -264,6 +322,8 @@ pub fn module(_attr: TokenStream, item: TokenStream) -> TokenStream {
 				}
 			}
 
+			#(#block_hook_ts_vec)*
+
 		}
 
 	};
@@ -370,6 +430,25 @@ fn get_module_call_methods(impl_item: &ItemImpl) -> Vec<ModuleMethod> {
 		.collect::<Vec<_>>()
 }
 
+fn get_module_block_hooks(impl_item: &ItemImpl) -> Vec<Ident> {
+	impl_item
+		.items
+		.iter()
+		.filter_map(|item| {
+			if let ImplItem::Method(method) = item {
+				let method_name = method.sig.ident.to_string();
+				if BLOCK_HOOK_METHODS.contains(&method_name.as_str()) {
+					Some(method.sig.ident.clone())
+				} else {
+					None
+				}
+			} else {
+				None
+			}
+		})
+		.collect::<Vec<_>>()
+}
+
 fn get_method_params_ident(method: &ImplItemMethod) -> Ident {
 	if method.sig.inputs.len() == 3 {
 		let params = &method.sig.inputs[2];
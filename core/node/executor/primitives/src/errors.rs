@@ -34,6 +34,12 @@ pub enum ErrorKind {
 	#[display(fmt = "Invalid tx params: {}", _0)]
 	InvalidTxParams(String),
 
+	#[display(fmt = "Invalid tx fee: {}", _0)]
+	InvalidTxFee(String),
+
+	#[display(fmt = "Invalid tx nonce: {}", _0)]
+	InvalidTxNonce(String),
+
 	#[display(fmt = "{}", _0)]
 	Application(String),
 }
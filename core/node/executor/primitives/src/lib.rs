@@ -13,6 +13,7 @@
 // limitations under the License.
 
 /// Executor base for modules
+use std::collections::{BTreeMap, HashMap};
 use std::marker::PhantomData;
 use std::sync::Arc;
 
@@ -26,6 +27,10 @@ pub mod errors;
 /// Separator to build kv db key
 pub const SEPARATOR: &[u8] = b"_";
 
+/// Separator between the storage key of a map and the key of an entry
+/// storage keys never contain it, so the prefix of a map does not cover other storages
+pub const MAP_SEPARATOR: &[u8] = b"\0";
+
 pub trait Module {
 	type C: Context;
 	type U: Util;
@@ -50,6 +55,17 @@ pub trait Module {
 
 	/// execute the call
 	fn execute_call(&self, sender: Option<&Address>, call: &Call) -> OpaqueModuleResult;
+
+	/// block hook called before the transactions of a block are executed
+	/// meta modules are called when building the block, payload modules are called when executing the block
+	fn on_initialize(&self) -> ModuleResult<()> {
+		Ok(())
+	}
+
+	/// block hook called after the transactions of a block are executed
+	fn on_finalize(&self) -> ModuleResult<()> {
+		Ok(())
+	}
 }
 
 /// Env variables for a block
@@ -57,6 +73,8 @@ pub trait Module {
 pub struct ContextEnv {
 	pub number: BlockNumber,
 	pub timestamp: u64,
+	/// the block producer, none for the genesis block
+	pub author: Option<Address>,
 }
 
 /// Env variables for a call
@@ -84,6 +102,8 @@ pub trait Context: Clone {
 	fn meta_drain_tx_buffer(&self) -> ModuleResult<Vec<(DBKey, Option<DBValue>)>>;
 	/// apply meta
 	fn meta_apply(&self, items: Vec<(DBKey, Option<DBValue>)>) -> ModuleResult<()>;
+	/// get meta state by key prefix (including the changes in buffers) in the order of key
+	fn meta_iter_prefix(&self, prefix: &[u8]) -> ModuleResult<Vec<(DBKey, DBValue)>>;
 	/// get payload state
 	fn payload_get(&self, key: &[u8]) -> ModuleResult<Option<DBValue>>;
 	/// set payload state (only save into tx buffer)
@@ -92,12 +112,18 @@ pub trait Context: Clone {
 	fn payload_drain_tx_buffer(&self) -> ModuleResult<Vec<(DBKey, Option<DBValue>)>>;
 	/// apply payload
 	fn payload_apply(&self, items: Vec<(DBKey, Option<DBValue>)>) -> ModuleResult<()>;
+	/// get payload state by key prefix (including the changes in buffers) in the order of key
+	fn payload_iter_prefix(&self, prefix: &[u8]) -> ModuleResult<Vec<(DBKey, DBValue)>>;
 	/// emit an event
 	fn emit_event(&self, event: Event) -> ModuleResult<()>;
 	/// drain tx events
 	fn drain_tx_events(&self) -> ModuleResult<Vec<Event>>;
 	/// apply events
 	fn apply_events(&self, items: Vec<Event>) -> ModuleResult<()>;
+	/// add gas used by the current tx
+	fn add_gas_used(&self, gas_used: u64) -> ModuleResult<()>;
+	/// drain the gas used by the current tx
+	fn drain_tx_gas_used(&self) -> ModuleResult<u64>;
 }
 
 pub trait Util: Clone {
@@ -107,6 +133,24 @@ pub trait Util: Clone {
 	fn address(&self, data: &[u8]) -> ModuleResult<Address>;
 	/// validate address
 	fn validate_address(&self, address: &Address) -> ModuleResult<()>;
+	/// check if the call is a call of a meta module
+	fn is_meta_call(&self, call: &Call) -> ModuleResult<bool>;
+	/// check the call of any module, and if it is a write call
+	fn is_write_call(&self, call: &Call) -> ModuleResult<bool>;
+	/// validate the call of any module
+	fn validate_call<C: Context>(
+		&self,
+		context: &C,
+		sender: Option<&Address>,
+		call: &Call,
+	) -> ModuleResult<()>;
+	/// execute the call of any module
+	fn execute_call<C: Context>(
+		&self,
+		context: &C,
+		sender: Option<&Address>,
+		call: &Call,
+	) -> OpaqueModuleResult;
 }
 
 /// Storage type for module
@@ -152,7 +196,7 @@ where
 }
 
 /// Storage type for module
-/// module_storage_key + separator + storage_key + separator + key => value
+/// module_storage_key + separator + storage_key + map_separator + key => value
 pub struct StorageMap<K, V, M>
 where
 	K: Encode + Decode,
@@ -172,7 +216,7 @@ where
 	M: Module,
 {
 	pub fn new(context: M::C, storage_key: &'static [u8]) -> Self {
-		let key = [M::STORAGE_KEY, SEPARATOR, storage_key].concat();
+		let key = [M::STORAGE_KEY, SEPARATOR, storage_key, MAP_SEPARATOR].concat();
 		let meta_module = M::META_MODULE;
 		Self {
 			context,
@@ -188,7 +232,7 @@ where
 	}
 
 	pub fn raw_get(&self, key: &[u8]) -> ModuleResult<Option<V>> {
-		let key = &[&self.key, SEPARATOR, &key].concat();
+		let key = &[&self.key, key].concat();
 		context_get(&self.context, self.meta_module, key)
 	}
 
@@ -198,7 +242,7 @@ where
 	}
 
 	pub fn raw_set(&self, key: &[u8], value: &V) -> ModuleResult<()> {
-		let key = &[&self.key, SEPARATOR, &key].concat();
+		let key = &[&self.key, key].concat();
 		context_set(&self.context, self.meta_module, key, value)
 	}
 
@@ -208,9 +252,55 @@ where
 	}
 
 	pub fn raw_delete(&self, key: &[u8]) -> ModuleResult<()> {
-		let key = &[&self.key, SEPARATOR, &key].concat();
+		let key = &[&self.key, key].concat();
 		context_delete(&self.context, self.meta_module, key)
 	}
+
+	/// Get all the entries in the order of encoded key
+	pub fn iter(&self) -> ModuleResult<Vec<(K, V)>> {
+		let prefix = &self.key;
+		let items = context_iter_prefix(&self.context, self.meta_module, prefix)?;
+		let mut result = Vec::with_capacity(items.len());
+		for (key, value) in items {
+			let key = codec::decode(&mut &key[prefix.len()..])?;
+			let value = codec::decode(&mut &value[..])?;
+			result.push((key, value));
+		}
+		Ok(result)
+	}
+
+	/// Get all the keys in the order of encoded key
+	pub fn keys(&self) -> ModuleResult<Vec<K>> {
+		let keys = self.iter()?.into_iter().map(|(k, _)| k).collect();
+		Ok(keys)
+	}
+}
+
+/// Overlay the changes in buffers (from the oldest to the latest)
+/// on the key-values in the state with the given prefix
+pub fn overlay_prefix<'a, I>(
+	items: Vec<(DBKey, DBValue)>,
+	buffers: I,
+	prefix: &[u8],
+) -> Vec<(DBKey, DBValue)>
+where
+	I: IntoIterator<Item = &'a HashMap<DBKey, Option<DBValue>>>,
+{
+	let mut items = items
+		.into_iter()
+		.map(|(k, v)| (k, Some(v)))
+		.collect::<BTreeMap<_, _>>();
+	for buffer in buffers {
+		for (k, v) in buffer {
+			if k.starts_with(prefix) {
+				items.insert(k.clone(), v.clone());
+			}
+		}
+	}
+	items
+		.into_iter()
+		.filter_map(|(k, v)| v.map(|v| (k, v)))
+		.collect()
 }
 
 fn context_get<C: Context, V: Decode>(
@@ -234,6 +324,17 @@ fn context_get<C: Context, V: Decode>(
 	}
 }
 
+fn context_iter_prefix<C: Context>(
+	context: &C,
+	meta_module: bool,
+	prefix: &[u8],
+) -> ModuleResult<Vec<(DBKey, DBValue)>> {
+	match meta_module {
+		true => context.meta_iter_prefix(prefix),
+		false => context.payload_iter_prefix(prefix),
+	}
+}
+
 fn context_set<C: Context, V: Encode>(
 	context: &C,
 	meta_module: bool,
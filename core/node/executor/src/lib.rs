@@ -23,23 +23,26 @@ use crypto::address::{Address as AddressT, AddressImpl};
 use crypto::dsa::{Dsa, DsaImpl, KeyPair, Verifier};
 use crypto::hash::Hash as HashT;
 use crypto::hash::HashImpl;
+use log::warn;
 use node_db::DBTransaction;
 use node_executor_macro::dispatcher;
 use node_executor_primitives::errors::ErrorKind;
 pub use node_executor_primitives::ContextEnv;
 use node_executor_primitives::{
-	errors, CallEnv, Context as ContextT, Module as ModuleT, ModuleError, ModuleResult,
-	Util as UtilT,
+	errors, overlay_prefix, CallEnv, Context as ContextT, EmptyParams, Module as ModuleT,
+	ModuleError, ModuleResult, OpaqueModuleResult, Util as UtilT,
 };
 use node_statedb::{StateDB, StateDBGetter, StateDBStmt, TrieRoot};
 use primitives::codec::Encode;
 use primitives::types::FullReceipt;
 use primitives::{
-	codec, errors::CommonResult, BlockNumber, CallResult, Event, FullTransaction, Nonce,
+	codec, errors::CommonResult, Balance, BlockNumber, CallResult, Event, FullTransaction, Nonce,
 	OpaqueCallResult, PublicKey, Receipt, SecretKey, Signature, TransactionForHash, Witness,
 };
 use primitives::{Address, Call, DBKey, DBValue, Hash, Params, Transaction};
 
+use crate::module::system::Fee;
+
 const META_TXS_SIZE: usize = 16;
 const PAYLOAD_TXS_SIZE: usize = 512;
 const EVENT_SIZE: usize = 4;
@@ -101,6 +104,7 @@ struct ContextInner<'a> {
 	payload_txs: RefCell<Vec<Arc<FullTransaction>>>,
 	payload_receipts: RefCell<Vec<Arc<FullReceipt>>>,
 	events: RefCell<Vec<Event>>,
+	gas_used: Cell<u64>,
 	// to mark the context has already started to execution payload txs
 	payload_phase: Cell<bool>,
 }
@@ -123,6 +127,13 @@ impl<'a> ContextState<'a> {
 			buffer,
 		})
 	}
+
+	fn iter_prefix(&self, prefix: &[u8]) -> ModuleResult<Vec<(DBKey, DBValue)>> {
+		let items = self.statedb_getter.iter_prefix(prefix)?;
+		let buffer = self.buffer.borrow();
+		let tx_buffer = self.tx_buffer.borrow();
+		Ok(overlay_prefix(items, vec![&*buffer, &*tx_buffer], prefix))
+	}
 }
 
 impl<'a> ContextT for Context<'a> {
@@ -164,6 +175,9 @@ impl<'a> ContextT for Context<'a> {
 		buffer.extend(items);
 		Ok(())
 	}
+	fn meta_iter_prefix(&self, prefix: &[u8]) -> ModuleResult<Vec<(DBKey, DBValue)>> {
+		self.inner.meta_state.iter_prefix(prefix)
+	}
 	fn payload_get(&self, key: &[u8]) -> ModuleResult<Option<DBValue>> {
 		let tx_buffer = self.inner.payload_state.tx_buffer.borrow();
 		if let Some(value) = tx_buffer.get(&DBKey::from_slice(key)) {
@@ -196,6 +210,9 @@ impl<'a> ContextT for Context<'a> {
 		buffer.extend(items);
 		Ok(())
 	}
+	fn payload_iter_prefix(&self, prefix: &[u8]) -> ModuleResult<Vec<(DBKey, DBValue)>> {
+		self.inner.payload_state.iter_prefix(prefix)
+	}
 	fn emit_event(&self, event: Event) -> ModuleResult<()> {
 		let mut events = self.inner.events.borrow_mut();
 		events.push(event);
@@ -209,6 +226,14 @@ impl<'a> ContextT for Context<'a> {
 	fn apply_events(&self, _items: Vec<Event>) -> ModuleResult<()> {
 		unreachable!("No need to apply events")
 	}
+	fn add_gas_used(&self, gas_used: u64) -> ModuleResult<()> {
+		let gas = &self.inner.gas_used;
+		gas.set(gas.get().saturating_add(gas_used));
+		Ok(())
+	}
+	fn drain_tx_gas_used(&self) -> ModuleResult<u64> {
+		Ok(self.inner.gas_used.replace(0))
+	}
 }
 
 impl<'a> Context<'a> {
@@ -231,6 +256,7 @@ impl<'a> Context<'a> {
 			payload_txs: RefCell::new(Vec::with_capacity(PAYLOAD_TXS_SIZE)),
 			payload_receipts: RefCell::new(Vec::with_capacity(PAYLOAD_TXS_SIZE)),
 			events: RefCell::new(Vec::with_capacity(EVENT_SIZE)),
+			gas_used: Cell::new(0),
 			payload_phase: Cell::new(false),
 		});
 
@@ -240,10 +266,11 @@ impl<'a> Context<'a> {
 	/// Get the new trie root of the statedb and the db transaction after executing meta transactions
 	pub fn get_meta_update(&self) -> CommonResult<(Hash, DBTransaction)> {
 		let buffer = self.inner.meta_state.buffer.borrow();
-		let (root, transaction) = self
-			.inner
-			.meta_statedb
-			.prepare_update(&self.inner.meta_state_root.0, buffer.iter())?;
+		let (root, transaction) = self.inner.meta_statedb.prepare_update(
+			&self.inner.meta_state_root.0,
+			buffer.iter(),
+			self.inner.env.number,
+		)?;
 		Ok((Hash(root), transaction))
 	}
 
@@ -276,10 +303,11 @@ impl<'a> Context<'a> {
 	/// Get the new trie root of the statedb and the db transaction after executing payload transactions
 	pub fn get_payload_update(&self) -> CommonResult<(Hash, DBTransaction)> {
 		let buffer = self.inner.payload_state.buffer.borrow();
-		let (root, transaction) = self
-			.inner
-			.payload_statedb
-			.prepare_update(&self.inner.payload_state_root.0, buffer.iter())?;
+		let (root, transaction) = self.inner.payload_statedb.prepare_update(
+			&self.inner.payload_state_root.0,
+			buffer.iter(),
+			self.inner.env.number,
+		)?;
 		Ok((Hash(root), transaction))
 	}
 
@@ -346,6 +374,36 @@ impl UtilT for Util {
 		}
 		Ok(())
 	}
+	fn is_meta_call(&self, call: &Call) -> ModuleResult<bool> {
+		Ok(Dispatcher::is_meta::<Context, Util>(&call.module)?)
+	}
+	fn is_write_call(&self, call: &Call) -> ModuleResult<bool> {
+		Dispatcher::check_call::<Context, Util>(&call.module, call)?;
+		Ok(Dispatcher::is_write_call::<Context, Util>(
+			&call.module,
+			call,
+		)?)
+	}
+	fn validate_call<C: ContextT>(
+		&self,
+		context: &C,
+		sender: Option<&Address>,
+		call: &Call,
+	) -> ModuleResult<()> {
+		let result =
+			Dispatcher::validate_call::<C, Util>(&call.module, context, self, sender, call)?;
+		result.map_err(ModuleError::from)
+	}
+	fn execute_call<C: ContextT>(
+		&self,
+		context: &C,
+		sender: Option<&Address>,
+		call: &Call,
+	) -> OpaqueModuleResult {
+		let result =
+			Dispatcher::execute_call::<C, Util>(&call.module, context, self, sender, call)?;
+		result.map_err(ModuleError::from)
+	}
 }
 
 pub struct Executor {
@@ -458,12 +516,7 @@ impl Executor {
 			}
 		};
 
-		let sender = tx.witness.as_ref().map(|witness| {
-			let public_key = &witness.public_key;
-			let mut address = vec![0u8; self.address.length().into()];
-			self.address.address(&mut address, &public_key.0);
-			Address(address)
-		});
+		let sender = tx.witness.as_ref().map(|witness| self.get_sender(witness));
 
 		let module = &call.module;
 		Dispatcher::validate_call::<Context, _>(
@@ -482,12 +535,45 @@ impl Executor {
 			);
 		}
 
+		// the nonce is checked strictly when building a block,
+		// here only stale nonces are rejected as the txs before may be still in the txpool
+		if let (Some(witness), Some(sender)) = (&tx.witness, &sender) {
+			let nonce = self.get_nonce(context, sender)?;
+			if witness.nonce < nonce {
+				return Err(errors::ErrorKind::InvalidTxNonce(format!(
+					"Stale nonce: {}, expected: {}",
+					witness.nonce, nonce
+				))
+				.into());
+			}
+		}
+
+		if let Some(sender) = &sender {
+			if !self.is_meta_call(call)? {
+				if let Some(fee) = self.get_fee(context)? {
+					let base_fee = get_base_fee(&fee, tx)?;
+					let balance = self.get_balance(context, sender)?;
+					if balance < base_fee {
+						return Err(errors::ErrorKind::InvalidTxFee(format!(
+							"Insufficient balance: {}, fee: {}",
+							balance, base_fee
+						))
+						.into());
+					}
+				}
+			}
+		}
+
 		Ok(())
 	}
 
 	/// Determine if a call is meta call
 	pub fn is_meta_call(&self, call: &Call) -> CommonResult<bool> {
-		let module = &call.module;
+		self.is_meta_module(&call.module)
+	}
+
+	/// Determine if a module is meta module
+	pub fn is_meta_module(&self, module: &str) -> CommonResult<bool> {
 		Dispatcher::is_meta::<Context, Util>(module)
 	}
 
@@ -509,6 +595,70 @@ impl Executor {
 		Dispatcher::execute_call::<Context, Util>(module, context, &self.util, sender, call)
 	}
 
+	/// Check and update the nonces of the senders of a batch of transactions
+	/// the nonce of each signed transaction should equal the nonce expected by its sender
+	/// this method will affect the meta state of the context
+	pub fn update_nonces(
+		&self,
+		context: &Context,
+		txs: &[Arc<FullTransaction>],
+	) -> CommonResult<()> {
+		let system = module::system::Module::<Context, Util>::new(context.clone(), self.util());
+		for tx in txs {
+			let witness = match &tx.tx.witness {
+				Some(witness) => witness,
+				None => continue,
+			};
+			let sender = self.get_sender(witness);
+			let nonce = self.get_nonce(context, &sender)?;
+			if witness.nonce != nonce {
+				return Err(errors::ErrorKind::InvalidTxNonce(format!(
+					"{}: nonce: {}, expected: {}",
+					tx.tx_hash, witness.nonce, nonce
+				))
+				.into());
+			}
+			let next_nonce = nonce.checked_add(1).ok_or_else(|| {
+				errors::ErrorKind::InvalidTxNonce(format!("{}: nonce overflow", tx.tx_hash))
+			})?;
+			system.set_nonce(&sender, next_nonce)?;
+		}
+		context.meta_apply(context.meta_drain_tx_buffer()?)?;
+		Ok(())
+	}
+
+	/// Get the nonce expected by the next transaction of the given account
+	pub fn get_nonce(&self, context: &Context, address: &Address) -> CommonResult<Nonce> {
+		let system = module::system::Module::<Context, Util>::new(context.clone(), self.util());
+		let nonce = system.get_nonce(
+			None,
+			module::system::GetNonceParams {
+				address: address.clone(),
+			},
+		)?;
+		Ok(nonce)
+	}
+
+	/// Get the sender address of a transaction by its witness
+	pub fn get_sender(&self, witness: &Witness) -> Address {
+		let public_key = &witness.public_key;
+		let mut address = vec![0u8; self.address.length().into()];
+		self.address.address(&mut address, &public_key.0);
+		Address(address)
+	}
+
+	/// Run the on_initialize hooks of the meta modules or the payload modules
+	/// should be called before executing the txs of the same kind
+	pub fn on_initialize(&self, context: &Context, meta: bool) -> CommonResult<()> {
+		self.execute_block_hooks(context, meta, Dispatcher::on_initialize::<Context, Util>)
+	}
+
+	/// Run the on_finalize hooks of the meta modules or the payload modules
+	/// should be called after executing the txs of the same kind
+	pub fn on_finalize(&self, context: &Context, meta: bool) -> CommonResult<()> {
+		self.execute_block_hooks(context, meta, Dispatcher::on_finalize::<Context, Util>)
+	}
+
 	/// Execute a batch of transactions
 	/// should not mix meta transactions and none-meta transactions in one batch
 	/// should not execute a batch of meta transactions after a batch of none-meta transactions
@@ -522,6 +672,9 @@ impl Executor {
 
 		let mut receipts = Vec::with_capacity(META_TXS_SIZE);
 
+		// lazily loaded when the first signed payload tx comes
+		let mut fee_config: Option<Option<Fee>> = None;
+
 		for tx in &txs {
 			let tx_hash = &tx.tx_hash;
 			let tx = &tx.tx;
@@ -543,12 +696,7 @@ impl Executor {
 				}
 			}
 
-			let sender = tx.witness.as_ref().map(|witness| {
-				let public_key = &witness.public_key;
-				let mut address = vec![0u8; self.address.length().into()];
-				self.address.address(&mut address, &public_key.0);
-				Address(address)
-			});
+			let sender = tx.witness.as_ref().map(|witness| self.get_sender(witness));
 
 			// prepare call env
 			let call_env = Arc::new(CallEnv {
@@ -556,13 +704,36 @@ impl Executor {
 			});
 			*(context.inner.call_env.borrow_mut()) = call_env;
 
-			let result = Dispatcher::execute_call::<Context, Util>(
-				module,
-				context,
-				&self.util,
-				sender.as_ref(),
-				&call,
-			)?;
+			// fee is only charged for signed payload txs,
+			// as the balance belongs to the payload state
+			let fee = match (is_meta, &sender) {
+				(false, Some(sender)) => {
+					if fee_config.is_none() {
+						fee_config = Some(self.get_fee(context)?);
+					}
+					fee_config.clone().flatten().map(|fee| (sender, fee))
+				}
+				_ => None,
+			};
+
+			// the base fee is charged before dispatch and kept even if the call fails
+			let fee_result = match &fee {
+				Some((sender, fee)) => self.charge_fee(context, sender, get_base_fee(fee, tx)?)?,
+				None => Ok(()),
+			};
+
+			let result = match fee_result {
+				Ok(_) => Dispatcher::execute_call::<Context, Util>(
+					module,
+					context,
+					&self.util,
+					sender.as_ref(),
+					&call,
+				)?,
+				Err(e) => Err(e),
+			};
+
+			let gas_used = context.drain_tx_gas_used()?;
 
 			let (result, events) = match result {
 				Ok(result) => {
@@ -583,10 +754,21 @@ impl Executor {
 				}
 			};
 
+			// the gas fee is charged after dispatch, limited by the remaining balance
+			if let Some((sender, fee)) = &fee {
+				let gas_fee = fee.per_gas.saturating_mul(gas_used);
+				if gas_fee > 0 {
+					let gas_fee = gas_fee.min(self.get_balance(context, sender)?);
+					self.charge_fee(context, sender, gas_fee)?
+						.map_err(errors::ErrorKind::Application)?;
+				}
+			}
+
 			let receipt = Receipt {
 				block_number: context.env().number,
 				events,
 				result,
+				gas_used,
 			};
 			let full_receipt = FullReceipt {
 				receipt,
@@ -658,6 +840,88 @@ impl Executor {
 	pub fn util(&self) -> Util {
 		self.util.clone()
 	}
+
+	/// Execute the block hook of each module in the order of the dispatcher
+	/// the changes of a failed hook will be discarded, and the events are ignored
+	fn execute_block_hooks<'a, F>(
+		&self,
+		context: &Context<'a>,
+		meta: bool,
+		hook: F,
+	) -> CommonResult<()>
+	where
+		F: Fn(&str, &Context<'a>, &Util) -> CommonResult<CallResult<()>>,
+	{
+		*(context.inner.call_env.borrow_mut()) = Arc::new(CallEnv::default());
+
+		for module in Dispatcher::modules() {
+			if Dispatcher::is_meta::<Context, Util>(module)? != meta {
+				continue;
+			}
+			let result = hook(module, context, &self.util)?;
+			context.drain_tx_gas_used()?;
+			context.drain_tx_events()?;
+			match result {
+				Ok(_) => {
+					context.meta_apply(context.meta_drain_tx_buffer()?)?;
+					context.payload_apply(context.payload_drain_tx_buffer()?)?;
+				}
+				Err(e) => {
+					warn!(
+						"Block hook failed: number: {}, module: {}, error: {}",
+						context.env().number,
+						module,
+						e
+					);
+					context.meta_drain_tx_buffer()?;
+					context.payload_drain_tx_buffer()?;
+				}
+			}
+		}
+		Ok(())
+	}
+
+	/// Get the fee config from the system module
+	fn get_fee(&self, context: &Context) -> CommonResult<Option<Fee>> {
+		let system = module::system::Module::<Context, Util>::new(context.clone(), self.util());
+		let fee = system.get_fee()?;
+		Ok(fee)
+	}
+
+	fn get_balance(&self, context: &Context, address: &Address) -> CommonResult<Balance> {
+		let balance = module::balance::Module::<Context, Util>::new(context.clone(), self.util());
+		let balance = balance.get_balance(Some(address), EmptyParams)?;
+		Ok(balance)
+	}
+
+	/// Charge the fee from the payer and pay it to the block author
+	/// the fee is applied at once so that it will not be reverted along with a failed call
+	fn charge_fee(
+		&self,
+		context: &Context,
+		payer: &Address,
+		fee: Balance,
+	) -> CommonResult<CallResult<()>> {
+		let balance = module::balance::Module::<Context, Util>::new(context.clone(), self.util());
+		let author = context.env().author.clone();
+		match balance.charge_fee(payer, author.as_ref(), fee) {
+			Ok(_) => {
+				context.payload_apply(context.payload_drain_tx_buffer()?)?;
+				Ok(Ok(()))
+			}
+			Err(ModuleError::System(e)) => Err(e),
+			Err(ModuleError::Application(e)) => {
+				context.payload_drain_tx_buffer()?;
+				Ok(Err(e.to_string()))
+			}
+		}
+	}
+}
+
+/// Get the fee charged before dispatch: base + per_byte * encoded tx length
+fn get_base_fee(fee: &Fee, tx: &Transaction) -> CommonResult<Balance> {
+	let len = codec::encode(tx)?.len() as Balance;
+	Ok(fee.base.saturating_add(fee.per_byte.saturating_mul(len)))
 }
 
 /// Dispatcher for all the modules
@@ -676,6 +940,7 @@ enum Dispatcher {
 pub mod module {
 	pub use module_balance as balance;
 	pub use module_contract as contract;
+	pub use module_governance as governance;
 	pub use module_poa as poa;
 	pub use module_raft as raft;
 	pub use module_system as system;
@@ -18,16 +18,16 @@ use std::sync::Arc;
 use crypto::address::AddressImpl;
 use crypto::dsa::DsaImpl;
 use crypto::hash::HashImpl;
-use node_db::{DBConfig, DB};
-use node_executor::{module, Context, ContextEssence, Executor};
-use node_executor_primitives::ContextEnv;
+use node_db::{DBBackend, DBConfig, DB};
+use node_executor::{module, Context, ContextEssence, Executor, Util};
+use node_executor_primitives::{ContextEnv, EmptyParams, StorageMap};
 use node_statedb::{StateDB, TrieRoot};
 use primitives::types::FullReceipt;
 use primitives::{
-	codec, Address, Balance, DBKey, Event, FullTransaction, Hash, Params, Receipt,
-	TransactionForHash,
+	codec, Address, Balance, BlockNumber, Call, DBKey, Event, FullTransaction, Hash, Params,
+	Receipt, Transaction, TransactionForHash,
 };
-use utils_test::test_accounts;
+use utils_test::{test_accounts, TestAccount};
 
 #[test]
 fn test_executor() {
@@ -40,6 +40,7 @@ fn test_executor() {
 		memory_budget: 1 * 1024 * 1024,
 		path,
 		partitions: vec![],
+		backend: DBBackend::Memory,
 	};
 
 	let db = Arc::new(DB::open(db_config).unwrap());
@@ -75,7 +76,11 @@ fn test_executor() {
 						timestamp,
 						max_until_gap: 20,
 						max_execution_gap: 8,
+						max_block_txs: 512,
+						max_block_bytes: 1024 * 1024,
 						consensus: "poa".to_string(),
+						fee: None,
+						admin: None,
 					},
 				)
 				.unwrap(),
@@ -116,7 +121,11 @@ fn test_executor() {
 	let meta_state_root = meta_statedb.default_root();
 	let payload_state_root = meta_statedb.default_root();
 
-	let env = ContextEnv { number, timestamp };
+	let env = ContextEnv {
+		number,
+		timestamp,
+		author: None,
+	};
 
 	let context_essence = ContextEssence::new(
 		env,
@@ -171,7 +180,11 @@ fn test_executor() {
 	let number = 1;
 	let timestamp = timestamp + 1;
 
-	let env = ContextEnv { number, timestamp };
+	let env = ContextEnv {
+		number,
+		timestamp,
+		author: None,
+	};
 
 	let context_essence = ContextEssence::new(
 		env,
@@ -259,6 +272,7 @@ fn test_executor_validate_tx() {
 		memory_budget: 1 * 1024 * 1024,
 		path,
 		partitions: vec![],
+		backend: DBBackend::Memory,
 	};
 
 	let db = Arc::new(DB::open(db_config).unwrap());
@@ -285,6 +299,7 @@ fn test_executor_validate_tx() {
 	let env = ContextEnv {
 		number: 0,
 		timestamp: 0,
+		author: None,
 	};
 
 	let context_essence = ContextEssence::new(
@@ -388,6 +403,730 @@ fn test_executor_validate_tx() {
 	assert!(format!("{}", result.unwrap_err()).contains("Error: Invalid tx witness"));
 }
 
+#[test]
+fn test_executor_fee() {
+	let chain = TestChain::new(
+		Some(module::system::Fee {
+			base: 1,
+			per_byte: 0,
+			per_gas: 0,
+		}),
+		None,
+		|_, _| vec![],
+	);
+	let executor = &chain.executor;
+	let (account1, account2, account3, account4) = (
+		&chain.accounts[0],
+		&chain.accounts[1],
+		&chain.accounts[2],
+		&chain.accounts[3],
+	);
+
+	// block 1 produced by account3
+	let context_essence = chain.block_1_essence(Some(account3.address.clone()));
+	let context = Context::new(&context_essence).unwrap();
+
+	let transfer_tx = |account: &TestAccount, nonce: u32, value: Balance| {
+		executor
+			.build_tx(
+				Some((account.secret_key.clone(), nonce, 1)),
+				executor
+					.build_call(
+						"balance".to_string(),
+						"transfer".to_string(),
+						module::balance::TransferParams {
+							recipient: account2.address.clone(),
+							value,
+						},
+					)
+					.unwrap(),
+			)
+			.unwrap()
+	};
+
+	// account4 can not afford the fee
+	let result = executor.validate_tx(&context, &transfer_tx(account4, 0, 1), true);
+	assert!(format!("{}", result.unwrap_err()).contains("Error: Invalid tx fee"));
+
+	// the fee is charged even if the call fails
+	let block_1_payload_txs = chain.build_txs(vec![
+		transfer_tx(account1, 0, 2),
+		transfer_tx(account1, 1, 100),
+	]);
+	executor.execute_txs(&context, block_1_payload_txs).unwrap();
+
+	let (_, payload_receipts) = context.get_payload_receipts().unwrap();
+	assert!(payload_receipts[0].receipt.result.is_ok());
+	assert_eq!(
+		payload_receipts[1].receipt.result,
+		Err("Insufficient balance".to_string())
+	);
+
+	let get_balance = |address: &Address| -> Balance {
+		let call = executor
+			.build_call(
+				"balance".to_string(),
+				"get_balance".to_string(),
+				EmptyParams,
+			)
+			.unwrap();
+		let result = executor
+			.execute_call(&context, Some(address), &call)
+			.unwrap()
+			.unwrap();
+		codec::decode(&mut &result[..]).unwrap()
+	};
+
+	assert_eq!(get_balance(&account1.address), 6);
+	assert_eq!(get_balance(&account2.address), 2);
+	assert_eq!(get_balance(&account3.address), 2);
+}
+
+#[test]
+fn test_executor_nonce() {
+	let chain = TestChain::new(None, None, |_, _| vec![]);
+	let executor = &chain.executor;
+	let (account1, account2) = (&chain.accounts[0], &chain.accounts[1]);
+
+	// block 1
+	let context_essence = chain.block_1_essence(None);
+	let context = Context::new(&context_essence).unwrap();
+
+	let transfer_tx = |nonce: u32| {
+		executor
+			.build_tx(
+				Some((account1.secret_key.clone(), nonce, 1)),
+				executor
+					.build_call(
+						"balance".to_string(),
+						"transfer".to_string(),
+						module::balance::TransferParams {
+							recipient: account2.address.clone(),
+							value: 1,
+						},
+					)
+					.unwrap(),
+			)
+			.unwrap()
+	};
+
+	// unsigned txs are not sequenced
+	let unsigned_tx = executor
+		.build_tx(
+			None,
+			executor
+				.build_call(
+					"balance".to_string(),
+					"get_balance".to_string(),
+					EmptyParams,
+				)
+				.unwrap(),
+		)
+		.unwrap();
+	executor
+		.update_nonces(&context, &chain.build_txs(vec![unsigned_tx]))
+		.unwrap();
+	assert_eq!(executor.get_nonce(&context, &account1.address).unwrap(), 0);
+
+	let block_1_payload_txs = chain.build_txs(vec![transfer_tx(0), transfer_tx(1)]);
+	executor
+		.update_nonces(&context, &block_1_payload_txs)
+		.unwrap();
+	assert_eq!(executor.get_nonce(&context, &account1.address).unwrap(), 2);
+	assert_eq!(executor.get_nonce(&context, &account2.address).unwrap(), 0);
+
+	// stale nonce
+	let result = executor.validate_tx(&context, &transfer_tx(1), true);
+	assert!(format!("{}", result.unwrap_err()).contains("Error: Invalid tx nonce"));
+
+	// future nonce is allowed by the txpool
+	executor
+		.validate_tx(&context, &transfer_tx(3), true)
+		.unwrap();
+
+	// but not allowed when building a block
+	let result = executor.update_nonces(&context, &chain.build_txs(vec![transfer_tx(3)]));
+	assert!(format!("{}", result.unwrap_err()).contains("Error: Invalid tx nonce"));
+	assert_eq!(executor.get_nonce(&context, &account1.address).unwrap(), 2);
+}
+
+#[test]
+fn test_executor_block_hooks() {
+	let chain = TestChain::new(None, None, poa_init_calls);
+	let executor = &chain.executor;
+	let account1 = &chain.accounts[0];
+
+	// block 1 with a proposal expiring in the block
+	let context_essence = chain.block_1_essence(None);
+	let context = Context::new(&context_essence).unwrap();
+
+	let propose_tx = executor
+		.build_tx(
+			Some((account1.secret_key.clone(), 0, 10)),
+			executor
+				.build_call(
+					"poa".to_string(),
+					"propose".to_string(),
+					module::poa::ProposeParams {
+						call: executor
+							.build_call(
+								"poa".to_string(),
+								"set_authorities".to_string(),
+								module::poa::UpdateAuthoritiesParams {
+									authorities: module::poa::Authorities {
+										members: vec![account1.address.clone()],
+									},
+								},
+							)
+							.unwrap(),
+						expire_block: Some(1),
+					},
+				)
+				.unwrap(),
+		)
+		.unwrap();
+
+	let get_proposals = || {
+		let call = executor
+			.build_call("poa".to_string(), "get_proposals".to_string(), EmptyParams)
+			.unwrap();
+		let result = executor
+			.execute_call(&context, None, &call)
+			.unwrap()
+			.unwrap();
+		let proposals: Vec<module::governance::Proposal<()>> =
+			codec::decode(&mut &result[..]).unwrap();
+		proposals
+			.into_iter()
+			.map(|x| x.proposal_id)
+			.collect::<Vec<_>>()
+	};
+
+	executor.on_initialize(&context, true).unwrap();
+	executor
+		.execute_txs(&context, chain.build_txs(vec![propose_tx]))
+		.unwrap();
+	assert_eq!(get_proposals(), vec![1]);
+	let meta_state_root = context.get_meta_update().unwrap().0;
+
+	// the payload hooks do not touch the meta state
+	executor.on_initialize(&context, false).unwrap();
+	executor.execute_txs(&context, vec![]).unwrap();
+	executor.on_finalize(&context, false).unwrap();
+	assert_eq!(context.get_meta_update().unwrap().0, meta_state_root);
+
+	// the on_finalize hook of poa removes the expired proposal
+	executor.on_finalize(&context, true).unwrap();
+	assert_eq!(get_proposals(), Vec::<u32>::new());
+	assert_ne!(context.get_meta_update().unwrap().0, meta_state_root);
+
+	// the hooks produce no receipts and leave the payload state alone
+	let (_, meta_receipts) = context.get_meta_receipts().unwrap();
+	assert_eq!(meta_receipts.len(), 1);
+	assert_eq!(
+		context.get_payload_update().unwrap().0,
+		chain.payload_state_root
+	);
+}
+
+#[test]
+fn test_executor_storage_iter() {
+	let chain = TestChain::new(None, None, |_, _| vec![]);
+	let executor = &chain.executor;
+	let (account1, account2, account3) =
+		(&chain.accounts[0], &chain.accounts[1], &chain.accounts[2]);
+
+	// block 1
+	let context_essence = chain.block_1_essence(None);
+	let context = Context::new(&context_essence).unwrap();
+
+	let balance = StorageMap::<Address, Balance, module::balance::Module<Context, Util>>::new(
+		context.clone(),
+		b"balance",
+	);
+
+	// committed state
+	assert_eq!(
+		balance.iter().unwrap(),
+		vec![(account1.address.clone(), 10)]
+	);
+
+	// block buffer
+	let block_1_payload_txs = chain.build_txs(vec![executor
+		.build_tx(
+			Some((account1.secret_key.clone(), 0, 1)),
+			executor
+				.build_call(
+					"balance".to_string(),
+					"transfer".to_string(),
+					module::balance::TransferParams {
+						recipient: account2.address.clone(),
+						value: 2,
+					},
+				)
+				.unwrap(),
+		)
+		.unwrap()]);
+	executor.execute_txs(&context, block_1_payload_txs).unwrap();
+
+	let mut expected = vec![(account1.address.clone(), 8), (account2.address.clone(), 2)];
+	expected.sort_by_key(|(k, _)| codec::encode(k).unwrap());
+	assert_eq!(balance.iter().unwrap(), expected);
+
+	// tx buffer
+	balance.set(&account3.address, &3).unwrap();
+	balance.delete(&account1.address).unwrap();
+
+	let mut expected = vec![(account2.address.clone(), 2), (account3.address.clone(), 3)];
+	expected.sort_by_key(|(k, _)| codec::encode(k).unwrap());
+	assert_eq!(balance.iter().unwrap(), expected);
+	assert_eq!(
+		balance.keys().unwrap(),
+		expected.into_iter().map(|(k, _)| k).collect::<Vec<_>>()
+	);
+}
+
+#[test]
+fn test_executor_governance() {
+	// account1 is the system admin, so that a passed poa proposal of account1 can propose a system call
+	let accounts = test_accounts(
+		Arc::new(DsaImpl::Ed25519),
+		Arc::new(AddressImpl::Blake2b160),
+	);
+	let chain = TestChain::new(
+		None,
+		Some(module::system::Admin {
+			threshold: 1,
+			members: vec![(accounts[0].address.clone(), 1)],
+		}),
+		poa_init_calls,
+	);
+	let executor = &chain.executor;
+	let (account1, account2, account3) =
+		(&chain.accounts[0], &chain.accounts[1], &chain.accounts[2]);
+
+	// block 1
+	let context_essence = chain.block_1_essence(None);
+	let context = Context::new(&context_essence).unwrap();
+
+	let poa_tx = |account: &TestAccount, nonce: u32, method: &str, params: Vec<u8>| {
+		executor
+			.build_tx(
+				Some((account.secret_key.clone(), nonce, 10)),
+				Call {
+					module: "poa".to_string(),
+					method: method.to_string(),
+					params: Params(params),
+				},
+			)
+			.unwrap()
+	};
+	let set_authorities_call = |authority: &Address| {
+		executor
+			.build_call(
+				"poa".to_string(),
+				"set_authorities".to_string(),
+				module::poa::UpdateAuthoritiesParams {
+					authorities: module::poa::Authorities {
+						members: vec![authority.clone()],
+					},
+				},
+			)
+			.unwrap()
+	};
+	let propose = |call: Call, expire_block: Option<BlockNumber>| {
+		codec::encode(&module::poa::ProposeParams { call, expire_block }).unwrap()
+	};
+	let proposal =
+		|proposal_id: u32| codec::encode(&module::governance::VoteParams { proposal_id }).unwrap();
+	let update_meta_call = executor
+		.build_call(
+			"system".to_string(),
+			"update_meta".to_string(),
+			module::system::UpdateMetaParams {
+				max_until_gap: None,
+				max_execution_gap: None,
+				max_block_txs: Some(256),
+				max_block_bytes: None,
+			},
+		)
+		.unwrap();
+	let new_admin = module::poa::Admin {
+		threshold: 1,
+		members: vec![(account1.address.clone(), 1)],
+	};
+
+	let block_1_meta_txs = chain.build_txs(vec![
+		// proposal 1
+		poa_tx(
+			account1,
+			0,
+			"propose",
+			propose(set_authorities_call(&account3.address), None),
+		),
+		// proposal 2
+		poa_tx(
+			account1,
+			1,
+			"update_admin",
+			codec::encode(&module::poa::UpdateAdminParams {
+				admin: module::poa::Admin {
+					threshold: 1,
+					members: vec![(account1.address.clone(), 1)],
+				},
+			})
+			.unwrap(),
+		),
+		// proposal 3
+		poa_tx(
+			account1,
+			2,
+			"propose",
+			propose(set_authorities_call(&account2.address), Some(1)),
+		),
+		poa_tx(account2, 0, "cancel", proposal(2)),
+		poa_tx(account1, 3, "cancel", proposal(2)),
+		poa_tx(account2, 1, "vote", proposal(2)),
+		poa_tx(account2, 2, "vote", proposal(1)),
+		poa_tx(
+			account3,
+			0,
+			"set_authorities",
+			codec::encode(&module::poa::UpdateAuthoritiesParams {
+				authorities: module::poa::Authorities {
+					members: vec![account3.address.clone()],
+				},
+			})
+			.unwrap(),
+		),
+		poa_tx(
+			account1,
+			4,
+			"propose",
+			propose(
+				executor
+					.build_call("balance".to_string(), "init".to_string(), EmptyParams)
+					.unwrap(),
+				None,
+			),
+		),
+		poa_tx(
+			account3,
+			1,
+			"propose",
+			propose(set_authorities_call(&account3.address), None),
+		),
+		poa_tx(account2, 3, "update_admin_vote", proposal(3)),
+		// proposal 4, proposes a system proposal on behalf of the proposer account1 once passed
+		poa_tx(account1, 5, "propose", propose(update_meta_call, None)),
+		poa_tx(account2, 4, "vote", proposal(4)),
+		// proposal 5
+		poa_tx(
+			account1,
+			6,
+			"update_admin",
+			codec::encode(&module::poa::UpdateAdminParams {
+				admin: new_admin.clone(),
+			})
+			.unwrap(),
+		),
+		poa_tx(account2, 5, "update_admin_vote", proposal(5)),
+	]);
+	executor.execute_txs(&context, block_1_meta_txs).unwrap();
+
+	let (_, meta_receipts) = context.get_meta_receipts().unwrap();
+	let results = meta_receipts
+		.iter()
+		.map(|x| x.receipt.result.clone())
+		.collect::<Vec<_>>();
+	assert_eq!(
+		results,
+		vec![
+			Ok(codec::encode(&1u32).unwrap()),
+			Ok(codec::encode(&2u32).unwrap()),
+			Ok(codec::encode(&3u32).unwrap()),
+			Err("Not proposer".to_string()),
+			Ok(vec![]),
+			Err("Proposal not found".to_string()),
+			Ok(vec![]),
+			Err("Not executed by governance".to_string()),
+			Err("Call module not in the same state".to_string()),
+			Err("Not admin".to_string()),
+			Err("Proposal method not match".to_string()),
+			Ok(codec::encode(&4u32).unwrap()),
+			Ok(vec![]),
+			Ok(codec::encode(&5u32).unwrap()),
+			Ok(vec![]),
+		]
+	);
+
+	// the former admin proposal events are emitted along with the proposal events
+	let legacy_event = |name: &str, vote: Vec<Address>| {
+		Event::from_data(
+			name.to_string(),
+			module::governance::LegacyProposalEvent {
+				proposal: module::poa::UpdateAdminProposal {
+					proposal_id: 5,
+					admin: new_admin.clone(),
+					vote,
+				},
+			},
+		)
+		.unwrap()
+	};
+	let events = &meta_receipts[14].receipt.events;
+	assert!(events.contains(&legacy_event(
+		"UpdateAdminProposalVoted",
+		vec![account1.address.clone(), account2.address.clone()]
+	)));
+	assert!(events.contains(&legacy_event(
+		"UpdateAdminProposalPassed",
+		vec![account1.address.clone(), account2.address.clone()]
+	)));
+
+	let poa_call = |method: &str, params: Vec<u8>| {
+		let call = Call {
+			module: "poa".to_string(),
+			method: method.to_string(),
+			params: Params(params),
+		};
+		executor
+			.execute_call(&context, None, &call)
+			.unwrap()
+			.unwrap()
+	};
+
+	let authorities: module::poa::Authorities =
+		codec::decode(&mut &poa_call("get_authorities", codec::encode(&EmptyParams).unwrap())[..])
+			.unwrap();
+	assert_eq!(authorities.members, vec![account3.address.clone()]);
+
+	let admin: module::poa::Admin =
+		codec::decode(&mut &poa_call("get_admin", codec::encode(&EmptyParams).unwrap())[..])
+			.unwrap();
+	assert_eq!(admin, new_admin);
+
+	// proposal 4 is dispatched to the system module
+	let system_call = Call {
+		module: "system".to_string(),
+		method: "get_meta".to_string(),
+		params: Params(codec::encode(&EmptyParams).unwrap()),
+	};
+	let meta: module::system::Meta = codec::decode(
+		&mut &executor
+			.execute_call(&context, None, &system_call)
+			.unwrap()
+			.unwrap()[..],
+	)
+	.unwrap();
+	assert_eq!(meta.max_block_txs, 256);
+
+	let get_proposals = || {
+		let proposals: Vec<module::governance::Proposal<()>> = codec::decode(
+			&mut &poa_call("get_proposals", codec::encode(&EmptyParams).unwrap())[..],
+		)
+		.unwrap();
+		proposals
+			.into_iter()
+			.map(|x| x.proposal_id)
+			.collect::<Vec<_>>()
+	};
+	assert_eq!(get_proposals(), vec![3]);
+
+	// proposal 3 expires at the end of block 1
+	executor.on_finalize(&context, true).unwrap();
+	assert_eq!(get_proposals(), Vec::<u32>::new());
+}
+
+/// Init poa with account1 and account2 as the admin members, and account1 as the authority
+fn poa_init_calls(executor: &Executor, accounts: &[TestAccount]) -> Vec<Call> {
+	vec![executor
+		.build_call(
+			"poa".to_string(),
+			"init".to_string(),
+			module::poa::InitParams {
+				block_interval: None,
+				admin: module::poa::Admin {
+					threshold: 2,
+					members: vec![
+						(accounts[0].address.clone(), 1),
+						(accounts[1].address.clone(), 1),
+					],
+				},
+				authorities: module::poa::Authorities {
+					members: vec![accounts[0].address.clone()],
+				},
+			},
+		)
+		.unwrap()]
+}
+
+/// Executor on top of a committed block 0
+struct TestChain {
+	executor: Executor,
+	accounts: Vec<TestAccount>,
+	trie_root: Arc<TrieRoot>,
+	meta_statedb: Arc<StateDB>,
+	payload_statedb: Arc<StateDB>,
+	meta_state_root: Hash,
+	payload_state_root: Hash,
+	timestamp: u64,
+}
+
+impl TestChain {
+	/// Commit block 0 with the system init, the extra meta calls and
+	/// the balance init endowing account1 with 10
+	fn new<F>(
+		fee: Option<module::system::Fee>,
+		system_admin: Option<module::system::Admin>,
+		extra_meta_calls: F,
+	) -> Self
+	where
+		F: FnOnce(&Executor, &[TestAccount]) -> Vec<Call>,
+	{
+		use tempfile::tempdir;
+
+		let path = tempdir().expect("Could not create a temp dir");
+		let path = path.into_path();
+
+		let db_config = DBConfig {
+			memory_budget: 1 * 1024 * 1024,
+			path,
+			partitions: vec![],
+			backend: DBBackend::Memory,
+		};
+
+		let db = Arc::new(DB::open(db_config).unwrap());
+		let hasher = Arc::new(HashImpl::Blake2b256);
+		let dsa = Arc::new(DsaImpl::Ed25519);
+		let address = Arc::new(AddressImpl::Blake2b160);
+
+		let meta_statedb = Arc::new(
+			StateDB::new(db.clone(), node_db::columns::META_STATE, hasher.clone()).unwrap(),
+		);
+		let payload_statedb = Arc::new(
+			StateDB::new(db.clone(), node_db::columns::PAYLOAD_STATE, hasher.clone()).unwrap(),
+		);
+
+		let trie_root = Arc::new(TrieRoot::new(hasher.clone()).unwrap());
+
+		let timestamp = 1588146696502;
+
+		let accounts = test_accounts(dsa.clone(), address.clone());
+
+		let executor = Executor::new(hasher, dsa, address);
+
+		let mut meta_calls = vec![executor
+			.build_call(
+				"system".to_string(),
+				"init".to_string(),
+				module::system::InitParams {
+					chain_id: "chain-test".to_string(),
+					timestamp,
+					max_until_gap: 20,
+					max_execution_gap: 8,
+					max_block_txs: 512,
+					max_block_bytes: 1024 * 1024,
+					consensus: "poa".to_string(),
+					fee,
+					admin: system_admin,
+				},
+			)
+			.unwrap()];
+		meta_calls.extend(extra_meta_calls(&executor, &accounts));
+		let payload_calls = vec![executor
+			.build_call(
+				"balance".to_string(),
+				"init".to_string(),
+				module::balance::InitParams {
+					endow: vec![(accounts[0].address.clone(), 10)],
+				},
+			)
+			.unwrap()];
+
+		let (meta_state_root, payload_state_root) = {
+			let env = ContextEnv {
+				number: 0,
+				timestamp,
+				author: None,
+			};
+			let context_essence = ContextEssence::new(
+				env,
+				trie_root.clone(),
+				meta_statedb.clone(),
+				Hash(meta_statedb.default_root()),
+				payload_statedb.clone(),
+				Hash(payload_statedb.default_root()),
+			)
+			.unwrap();
+			let context = Context::new(&context_essence).unwrap();
+
+			let build_txs = |calls: Vec<Call>| {
+				build_full_txs(
+					&executor,
+					calls
+						.into_iter()
+						.map(|call| executor.build_tx(None, call).unwrap())
+						.collect(),
+				)
+			};
+			executor
+				.execute_txs(&context, build_txs(meta_calls))
+				.unwrap();
+			executor
+				.execute_txs(&context, build_txs(payload_calls))
+				.unwrap();
+			let (meta_state_root, meta_transaction) = context.get_meta_update().unwrap();
+			let (payload_state_root, payload_transaction) = context.get_payload_update().unwrap();
+			db.write(meta_transaction).unwrap();
+			db.write(payload_transaction).unwrap();
+			(meta_state_root, payload_state_root)
+		};
+
+		Self {
+			executor,
+			accounts,
+			trie_root,
+			meta_statedb,
+			payload_statedb,
+			meta_state_root,
+			payload_state_root,
+			timestamp,
+		}
+	}
+
+	/// Context essence of block 1 on top of block 0
+	fn block_1_essence(&self, author: Option<Address>) -> ContextEssence {
+		let env = ContextEnv {
+			number: 1,
+			timestamp: self.timestamp + 1,
+			author,
+		};
+		ContextEssence::new(
+			env,
+			self.trie_root.clone(),
+			self.meta_statedb.clone(),
+			self.meta_state_root.clone(),
+			self.payload_statedb.clone(),
+			self.payload_state_root.clone(),
+		)
+		.unwrap()
+	}
+
+	fn build_txs(&self, txs: Vec<Transaction>) -> Vec<Arc<FullTransaction>> {
+		build_full_txs(&self.executor, txs)
+	}
+}
+
+fn build_full_txs(executor: &Executor, txs: Vec<Transaction>) -> Vec<Arc<FullTransaction>> {
+	txs.into_iter()
+		.map(|tx| {
+			let tx_hash = executor.hash_transaction(&tx).unwrap();
+			Arc::new(FullTransaction { tx, tx_hash })
+		})
+		.collect()
+}
+
 fn expected_txs_root(txs: &Vec<Arc<FullTransaction>>) -> Hash {
 	let trie_root = TrieRoot::new(Arc::new(HashImpl::Blake2b256)).unwrap();
 	let txs = txs
@@ -410,6 +1149,7 @@ fn expected_block_0_receipts_root(
 					block_number: 0,
 					events: vec![],
 					result: Ok(codec::encode(&()).unwrap()),
+					gas_used: 0,
 				},
 			})
 		})
@@ -442,10 +1182,22 @@ fn expected_block_0_meta_state_root(txs: &Vec<Arc<FullTransaction>>) -> Hash {
 			DBKey::from_slice(b"system_max_execution_gap"),
 			Some(codec::encode(&params.max_execution_gap).unwrap()),
 		),
+		(
+			DBKey::from_slice(b"system_max_block_txs"),
+			Some(codec::encode(&params.max_block_txs).unwrap()),
+		),
+		(
+			DBKey::from_slice(b"system_max_block_bytes"),
+			Some(codec::encode(&params.max_block_bytes).unwrap()),
+		),
 		(
 			DBKey::from_slice(b"system_consensus"),
 			Some(codec::encode(&params.consensus).unwrap()),
 		),
+		(
+			DBKey::from_slice(b"system_fee"),
+			Some(codec::encode(&params.fee).unwrap()),
+		),
 	]
 	.into_iter()
 	.collect::<HashMap<_, _>>();
@@ -459,6 +1211,7 @@ fn expected_block_0_meta_state_root(txs: &Vec<Arc<FullTransaction>>) -> Hash {
 		memory_budget: 1 * 1024 * 1024,
 		path,
 		partitions: vec![],
+		backend: DBBackend::Memory,
 	};
 
 	let db = Arc::new(DB::open(db_config).unwrap());
@@ -468,7 +1221,7 @@ fn expected_block_0_meta_state_root(txs: &Vec<Arc<FullTransaction>>) -> Hash {
 		Arc::new(StateDB::new(db.clone(), node_db::columns::META_STATE, hasher.clone()).unwrap());
 
 	let (state_root, _) = statedb
-		.prepare_update(&statedb.default_root(), data.iter())
+		.prepare_update(&statedb.default_root(), data.iter(), 0)
 		.unwrap();
 	Hash(state_root)
 }
@@ -482,7 +1235,7 @@ fn expected_block_0_payload_state_root(txs: &Vec<Arc<FullTransaction>>) -> Hash
 	let data = vec![(
 		DBKey::from_slice(
 			&[
-				&b"balance_balance_"[..],
+				&b"balance_balance\0"[..],
 				&codec::encode(&account.0).unwrap(),
 			]
 			.concat(),
@@ -501,6 +1254,7 @@ fn expected_block_0_payload_state_root(txs: &Vec<Arc<FullTransaction>>) -> Hash
 		memory_budget: 1 * 1024 * 1024,
 		path,
 		partitions: vec![],
+		backend: DBBackend::Memory,
 	};
 
 	let db = Arc::new(DB::open(db_config).unwrap());
@@ -511,7 +1265,7 @@ fn expected_block_0_payload_state_root(txs: &Vec<Arc<FullTransaction>>) -> Hash
 	);
 
 	let (state_root, _) = statedb
-		.prepare_update(&statedb.default_root(), data.iter())
+		.prepare_update(&statedb.default_root(), data.iter(), 0)
 		.unwrap();
 	Hash(state_root)
 }
@@ -525,7 +1279,13 @@ fn expected_block_1_payload_state_root(
 	let (account1, balance) = &params.endow[0];
 
 	let data = vec![(
-		DBKey::from_slice(&[&b"balance_balance_"[..], &codec::encode(&account1).unwrap()].concat()),
+		DBKey::from_slice(
+			&[
+				&b"balance_balance\0"[..],
+				&codec::encode(&account1).unwrap(),
+			]
+			.concat(),
+		),
 		Some(codec::encode(&balance).unwrap()),
 	)]
 	.into_iter()
@@ -540,6 +1300,7 @@ fn expected_block_1_payload_state_root(
 		memory_budget: 1 * 1024 * 1024,
 		path,
 		partitions: vec![],
+		backend: DBBackend::Memory,
 	};
 
 	let db = Arc::new(DB::open(db_config).unwrap());
@@ -550,7 +1311,7 @@ fn expected_block_1_payload_state_root(
 	);
 
 	let (state_root, transaction) = statedb
-		.prepare_update(&statedb.default_root(), data.iter())
+		.prepare_update(&statedb.default_root(), data.iter(), 0)
 		.unwrap();
 
 	db.write(transaction).unwrap();
@@ -564,13 +1325,21 @@ fn expected_block_1_payload_state_root(
 	let data = vec![
 		(
 			DBKey::from_slice(
-				&[&b"balance_balance_"[..], &codec::encode(&account1).unwrap()].concat(),
+				&[
+					&b"balance_balance\0"[..],
+					&codec::encode(&account1).unwrap(),
+				]
+				.concat(),
 			),
 			Some(codec::encode(&(balance - value)).unwrap()),
 		),
 		(
 			DBKey::from_slice(
-				&[&b"balance_balance_"[..], &codec::encode(&account2).unwrap()].concat(),
+				&[
+					&b"balance_balance\0"[..],
+					&codec::encode(&account2).unwrap(),
+				]
+				.concat(),
 			),
 			Some(codec::encode(&value).unwrap()),
 		),
@@ -578,7 +1347,7 @@ fn expected_block_1_payload_state_root(
 	.into_iter()
 	.collect::<HashMap<_, _>>();
 
-	let (state_root, _) = statedb.prepare_update(&state_root, data.iter()).unwrap();
+	let (state_root, _) = statedb.prepare_update(&state_root, data.iter(), 1).unwrap();
 	Hash(state_root)
 }
 
@@ -609,6 +1378,7 @@ fn expected_block_1_receipts_root(
 					block_number: 1,
 					events: vec![event.clone()],
 					result: Ok(codec::encode(&()).unwrap()),
+					gas_used: 0,
 				},
 			})
 		})
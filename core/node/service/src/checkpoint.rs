@@ -0,0 +1,152 @@
+// Copyright 2019, 2020 Wingchain
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Restore the db from a checkpoint created by a running node
+//!
+//! Checkpoint layout:
+//!   db/              copy of the db
+//!   checkpoint.toml  genesis hash, confirmed number and execution number of the db
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use log::info;
+
+use node_chain::{Chain, ChainConfig, Checkpoint, DBBackend, CHECKPOINT_DB, CHECKPOINT_FILE};
+use node_db::DB;
+use primitives::codec;
+use primitives::errors::CommonResult;
+
+use crate::config::{get_chain_config, get_file_config};
+use crate::errors::ErrorKind;
+use crate::ServiceConfig;
+
+pub struct RestoreCheckpointConfig {
+	/// Path of the checkpoint directory
+	pub path: PathBuf,
+}
+
+pub async fn restore_checkpoint(
+	config: ServiceConfig,
+	restore_config: RestoreCheckpointConfig,
+) -> CommonResult<()> {
+	let file_config = get_file_config(&config.home)?;
+	let chain_config = get_chain_config(&file_config, &config)?;
+
+	if chain_config.db.backend != DBBackend::RocksDB || !chain_config.db.partitions.is_empty() {
+		return Err(ErrorKind::Checkpoint(
+			"Only the rocksdb backend without partitions is supported".to_string(),
+		)
+		.into());
+	}
+
+	let file = restore_config.path.join(CHECKPOINT_FILE);
+	let content = fs::read_to_string(&file)
+		.map_err(|e| ErrorKind::Checkpoint(format!("Failed to read file: {:?}: {}", file, e)))?;
+	let checkpoint: Checkpoint = toml::from_str(&content)
+		.map_err(|e| ErrorKind::Checkpoint(format!("Failed to parse file: {:?}: {}", file, e)))?;
+
+	// the db of the running node is never overwritten
+	let db_path = chain_config.db.path.clone();
+	if db_path.exists()
+		&& fs::read_dir(&db_path)
+			.map_err(ErrorKind::IO)?
+			.next()
+			.is_some()
+	{
+		return Err(ErrorKind::Checkpoint(format!(
+			"DB path is not empty: {:?}, make sure the node is not running and remove the db first",
+			db_path
+		))
+		.into());
+	}
+
+	let result = copy_dir(&restore_config.path.join(CHECKPOINT_DB), &db_path)
+		.and_then(|_| validate(&config, chain_config, &checkpoint));
+	if let Err(e) = result {
+		let _ = fs::remove_dir_all(&db_path);
+		return Err(e);
+	}
+
+	info!(
+		"Checkpoint restored: path: {:?}, confirmed number: {}, execution number: {}",
+		restore_config.path, checkpoint.confirmed_number, checkpoint.execution_number
+	);
+
+	Ok(())
+}
+
+/// Validate the restored db against the spec of the home and the checkpoint file
+fn validate(
+	config: &ServiceConfig,
+	chain_config: ChainConfig,
+	checkpoint: &Checkpoint,
+) -> CommonResult<()> {
+	let spec_file = config
+		.home
+		.join(main_base::CONFIG)
+		.join(main_base::SPEC_FILE);
+	let spec = fs::read_to_string(&spec_file).map_err(|e| {
+		ErrorKind::Checkpoint(format!("Failed to read file: {:?}: {}", spec_file, e))
+	})?;
+
+	// the db is released before the chain opens it
+	{
+		let db = DB::open(chain_config.db.clone())?;
+		let db_spec = db
+			.get_with(node_db::columns::GLOBAL, node_db::global_key::SPEC, |x| {
+				codec::decode::<String>(&mut &x[..])
+			})?
+			.ok_or_else(|| ErrorKind::Checkpoint("Missing spec in the db".to_string()))?;
+		if db_spec != spec {
+			return Err(ErrorKind::Checkpoint(format!(
+				"Mismatched spec: the checkpoint is not of the chain of {:?}",
+				spec_file
+			))
+			.into());
+		}
+	}
+
+	let chain = Chain::new(chain_config)?;
+	let current_state = chain.get_current_state();
+	let confirmed_number = chain.get_confirmed_number()?;
+	let execution_number = chain.get_execution_number()?;
+	if current_state.genesis_hash != checkpoint.genesis_hash
+		|| confirmed_number != Some(checkpoint.confirmed_number)
+		|| execution_number != Some(checkpoint.execution_number)
+	{
+		return Err(ErrorKind::Checkpoint(format!(
+			"Mismatched db: genesis hash: {}, confirmed number: {:?}, execution number: {:?}",
+			current_state.genesis_hash, confirmed_number, execution_number
+		))
+		.into());
+	}
+
+	Ok(())
+}
+
+fn copy_dir(from: &Path, to: &Path) -> CommonResult<()> {
+	fs::create_dir_all(to).map_err(ErrorKind::IO)?;
+	for entry in fs::read_dir(from).map_err(ErrorKind::IO)? {
+		let entry = entry.map_err(ErrorKind::IO)?;
+		let path = entry.path();
+		let target = to.join(entry.file_name());
+		if path.is_dir() {
+			copy_dir(&path, &target)?;
+		} else {
+			fs::copy(&path, &target).map_err(ErrorKind::IO)?;
+		}
+	}
+	Ok(())
+}
@@ -20,18 +20,18 @@ use std::sync::Arc;
 use crypto::dsa::{Dsa, DsaImpl, KeyPair};
 use main_base::config::Config as FileConfig;
 use node_api::ApiConfig;
-use node_chain::{Basic, ChainConfig};
+use node_chain::{Basic, ChainConfig, StatePruning, DEFAULT_PRUNING_KEEP_BLOCKS};
 use node_coordinator::{
 	ed25519, CoordinatorConfig, Keypair, LinkedHashMap, Multiaddr, PeerId, Protocol,
 };
-use node_db::{DBConfig, Partition};
+use node_db::{DBBackend, DBConfig, Partition};
 use node_txpool::TxPoolConfig;
 use primitives::errors::CommonResult;
 use primitives::SecretKey;
 
 use crate::errors::ErrorKind;
 use crate::{errors, ServiceConfig};
-use node_consensus::{ConsensusConfig, PoaConfig, RaftConfig};
+use node_consensus::{ConsensusConfig, InstantConfig, PoaConfig, RaftConfig};
 
 pub struct OtherConfig {
 	pub txpool: TxPoolConfig,
@@ -58,9 +58,12 @@ pub fn get_chain_config(
 ) -> CommonResult<ChainConfig> {
 	let home = &service_config.home;
 	let db = get_db_config(file_config, home)?;
+	let pruning = get_state_pruning(file_config)?;
 	let chain_config = ChainConfig {
 		home: home.to_path_buf(),
 		db,
+		pruning,
+		index: file_config.db.index.unwrap_or(false),
 	};
 	Ok(chain_config)
 }
@@ -74,25 +77,38 @@ pub fn get_other_config(
 	let agent_version = &service_config.agent_version;
 	let config = OtherConfig {
 		txpool: get_txpool_config(&file_config)?,
-		api: get_api_config(&file_config)?,
+		api: get_api_config(&file_config, home)?,
 		consensus: get_consensus_config(&file_config, home, basic)?,
 		coordinator: get_coordinator_config(&file_config, home, agent_version)?,
 	};
 	Ok(config)
 }
 
-fn get_txpool_config(file_config: &FileConfig) -> CommonResult<TxPoolConfig> {
+pub fn get_txpool_config(file_config: &FileConfig) -> CommonResult<TxPoolConfig> {
 	let txpool = TxPoolConfig {
 		pool_capacity: file_config.txpool.pool_capacity,
+		sender_capacity: file_config.txpool.sender_capacity,
+		journal: file_config.txpool.journal.unwrap_or(false),
 	};
 	Ok(txpool)
 }
 
-fn get_api_config(file_config: &FileConfig) -> CommonResult<ApiConfig> {
+fn get_api_config(file_config: &FileConfig, home: &Path) -> CommonResult<ApiConfig> {
+	let rpc_checkpoint_dir = {
+		let path = file_config
+			.api
+			.rpc_checkpoint_dir
+			.clone()
+			.unwrap_or_else(|| PathBuf::from(main_base::DATA).join(main_base::CHECKPOINT));
+		get_abs_path(&path, home)
+	};
 	let api = ApiConfig {
 		rpc_addr: file_config.api.rpc_addr.clone(),
 		rpc_workers: file_config.api.rpc_workers,
 		rpc_maxconn: file_config.api.rpc_maxconn,
+		rpc_admin: file_config.api.rpc_admin.unwrap_or(false),
+		rpc_admin_token: file_config.api.rpc_admin_token.clone(),
+		rpc_checkpoint_dir,
 	};
 	Ok(api)
 }
@@ -119,15 +135,46 @@ fn get_db_config(file_config: &FileConfig, home: &Path) -> CommonResult<DBConfig
 			.collect(),
 		None => vec![],
 	};
+	let backend = match file_config.db.backend.as_deref() {
+		None | Some("rocksdb") => DBBackend::RocksDB,
+		Some("memory") => DBBackend::Memory,
+		Some(other) => {
+			return Err(ErrorKind::Config(format!("Invalid db backend: {}", other)).into());
+		}
+	};
 	let db = DBConfig {
 		memory_budget: file_config.db.memory_budget,
 		path,
 		partitions,
+		backend,
 	};
 	Ok(db)
 }
 
-fn get_consensus_config(
+fn get_state_pruning(file_config: &FileConfig) -> CommonResult<StatePruning> {
+	let pruning = match file_config.db.pruning.as_deref() {
+		None | Some("archive") => StatePruning::Archive,
+		Some("pruned") => {
+			let keep_blocks = file_config
+				.db
+				.pruning_keep_blocks
+				.unwrap_or(DEFAULT_PRUNING_KEEP_BLOCKS);
+			if keep_blocks == 0 {
+				return Err(ErrorKind::Config(
+					"Pruning keep blocks should be positive".to_string(),
+				)
+				.into());
+			}
+			StatePruning::Pruned(keep_blocks)
+		}
+		Some(other) => {
+			return Err(ErrorKind::Config(format!("Invalid pruning: {}", other)).into());
+		}
+	};
+	Ok(pruning)
+}
+
+pub fn get_consensus_config(
 	file_config: &FileConfig,
 	home: &Path,
 	basic: Arc<Basic>,
@@ -159,7 +206,21 @@ fn get_consensus_config(
 		None => None,
 	};
 
-	let consensus = ConsensusConfig { poa, raft };
+	let instant = match &file_config.consensus.instant {
+		Some(instant) => {
+			let secret_key = match &instant.secret_key_file {
+				Some(file) => Some(get_secret_key(file, home, &basic)?),
+				None => None,
+			};
+			Some(InstantConfig {
+				secret_key,
+				authority: instant.authority.clone(),
+			})
+		}
+		None => None,
+	};
+
+	let consensus = ConsensusConfig { poa, raft, instant };
 	Ok(consensus)
 }
 
@@ -24,6 +24,24 @@ pub enum ErrorKind {
 
 	#[display(fmt = "Runtime error: {:?}", _0)]
 	Runtime(io::Error),
+
+	#[display(fmt = "IO error: {:?}", _0)]
+	IO(io::Error),
+
+	#[display(fmt = "Block file error: {}", _0)]
+	BlockFile(String),
+
+	#[display(fmt = "Snapshot file error: {}", _0)]
+	SnapshotFile(String),
+
+	#[display(fmt = "Revert error: {}", _0)]
+	Revert(String),
+
+	#[display(fmt = "Checkpoint error: {}", _0)]
+	Checkpoint(String),
+
+	#[display(fmt = "Verify error: {}", _0)]
+	Verify(String),
 }
 
 impl Error for ErrorKind {}
@@ -61,11 +61,21 @@ use node_txpool::support::DefaultTxPoolSupport;
 use node_txpool::TxPool;
 use primitives::errors::CommonResult;
 
+pub use crate::checkpoint::RestoreCheckpointConfig;
 use crate::config::{get_chain_config, get_file_config, get_other_config};
 use crate::errors::ErrorKind;
+pub use crate::revert::RevertConfig;
+pub use crate::snapshot::{RestoreConfig, SnapshotConfig};
+pub use crate::transfer::{ExportConfig, ImportConfig};
+pub use crate::verify::VerifyConfig;
 
+mod checkpoint;
 mod config;
 pub mod errors;
+mod revert;
+mod snapshot;
+mod transfer;
+mod verify;
 
 pub struct ServiceConfig {
 	/// Home path
@@ -145,6 +155,63 @@ pub fn start(config: ServiceConfig) -> CommonResult<()> {
 	Ok(())
 }
 
+/// Export the blocks to a portable file
+pub fn export(config: ServiceConfig, export_config: ExportConfig) -> CommonResult<()> {
+	let rt = Runtime::new().map_err(ErrorKind::Runtime)?;
+	rt.block_on(transfer::export(config, export_config))?;
+	Ok(())
+}
+
+/// Import the blocks from a portable file,
+/// each block is verified and executed as the blocks synced from the network
+pub fn import(config: ServiceConfig, import_config: ImportConfig) -> CommonResult<()> {
+	let rt = Runtime::new().map_err(ErrorKind::Runtime)?;
+	rt.block_on(transfer::import(config, import_config))?;
+	Ok(())
+}
+
+/// Create a state snapshot of a confirmed block
+pub fn snapshot(config: ServiceConfig, snapshot_config: SnapshotConfig) -> CommonResult<()> {
+	let rt = Runtime::new().map_err(ErrorKind::Runtime)?;
+	rt.block_on(snapshot::snapshot(config, snapshot_config))?;
+	Ok(())
+}
+
+/// Initialize the chain from a state snapshot,
+/// the node then syncs the blocks following the snapshot block
+pub fn restore(config: ServiceConfig, restore_config: RestoreConfig) -> CommonResult<()> {
+	let rt = Runtime::new().map_err(ErrorKind::Runtime)?;
+	rt.block_on(snapshot::restore(config, restore_config))?;
+	Ok(())
+}
+
+/// Revert the chain to an earlier block,
+/// the node should not be running
+pub fn revert(config: ServiceConfig, revert_config: RevertConfig) -> CommonResult<()> {
+	let rt = Runtime::new().map_err(ErrorKind::Runtime)?;
+	rt.block_on(revert::revert(config, revert_config))?;
+	Ok(())
+}
+
+/// Restore the db from a checkpoint created by a running node,
+/// the node should not be running
+pub fn restore_checkpoint(
+	config: ServiceConfig,
+	restore_config: RestoreCheckpointConfig,
+) -> CommonResult<()> {
+	let rt = Runtime::new().map_err(ErrorKind::Runtime)?;
+	rt.block_on(checkpoint::restore_checkpoint(config, restore_config))?;
+	Ok(())
+}
+
+/// Verify the integrity of the chain from the blocks to the data they commit to,
+/// the node should not be running
+pub fn verify(config: ServiceConfig, verify_config: VerifyConfig) -> CommonResult<()> {
+	let rt = Runtime::new().map_err(ErrorKind::Runtime)?;
+	rt.block_on(verify::verify(config, verify_config))?;
+	Ok(())
+}
+
 async fn start_service(config: ServiceConfig) -> CommonResult<()> {
 	let service = Service::new(config)?;
 	wait_shutdown().await;
@@ -0,0 +1,46 @@
+// Copyright 2019, 2020 Wingchain
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Revert the chain to an earlier block
+
+use node_chain::Chain;
+use primitives::errors::CommonResult;
+use primitives::BlockNumber;
+
+use crate::config::{get_chain_config, get_file_config};
+use crate::errors::ErrorKind;
+use crate::ServiceConfig;
+
+pub struct RevertConfig {
+	/// The block number to revert to
+	pub to: BlockNumber,
+}
+
+pub async fn revert(config: ServiceConfig, revert_config: RevertConfig) -> CommonResult<()> {
+	let file_config = get_file_config(&config.home)?;
+	let chain_config = get_chain_config(&file_config, &config)?;
+
+	// the db is locked by the running node
+	let chain = Chain::new(chain_config).map_err(|e| {
+		ErrorKind::Revert(format!(
+			"Failed to open the chain, make sure the node is not running: {}",
+			e
+		))
+	})?;
+
+	// the removed entries are reported by the chain
+	chain.revert(revert_config.to)?;
+
+	Ok(())
+}
@@ -0,0 +1,185 @@
+// Copyright 2019, 2020 Wingchain
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Create a state snapshot of a confirmed block and initialize a new chain from it
+//!
+//! File format:
+//!   magic + record(file header) + record(snapshot) + record(payload state chunk) * n
+//! where a record is the same as the one of the block file
+
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::PathBuf;
+
+use log::info;
+
+use node_chain::{Chain, Snapshot};
+use primitives::codec::{Decode, Encode};
+use primitives::errors::CommonResult;
+use primitives::{BlockNumber, Hash};
+
+use crate::config::{get_chain_config, get_file_config};
+use crate::errors::ErrorKind;
+use crate::transfer::{read_record, wait_execution, write_record};
+use crate::ServiceConfig;
+
+/// Magic bytes at the beginning of the snapshot file
+const SNAPSHOT_FILE_MAGIC: &[u8] = b"WINGCHAIN-SNAPSHOT";
+
+/// Version of the snapshot file format
+const SNAPSHOT_FILE_VERSION: u32 = 2;
+
+/// Max size of the keys and values in a payload state chunk
+const PAYLOAD_STATE_CHUNK_BYTES: usize = 4 * 1024 * 1024;
+
+pub struct SnapshotConfig {
+	/// The block number of the snapshot, the confirmed number if not specified
+	pub number: Option<BlockNumber>,
+	/// Path of the snapshot file
+	pub file: PathBuf,
+}
+
+pub struct RestoreConfig {
+	/// Path of the snapshot file
+	pub file: PathBuf,
+	/// Trusted hash of the snapshot block, e.g. got from another node,
+	/// the proof can not be verified as the chain does not have the authorities of the block yet
+	pub block_hash: Hash,
+}
+
+#[derive(Encode, Decode)]
+struct SnapshotFileHeader {
+	version: u32,
+	genesis_hash: Hash,
+	number: BlockNumber,
+	block_hash: Hash,
+}
+
+pub async fn snapshot(config: ServiceConfig, snapshot_config: SnapshotConfig) -> CommonResult<()> {
+	let file_config = get_file_config(&config.home)?;
+	let chain_config = get_chain_config(&file_config, &config)?;
+	let chain = Chain::new(chain_config)?;
+
+	let current_state = chain.get_current_state();
+	let number = snapshot_config
+		.number
+		.unwrap_or(current_state.confirmed_number);
+
+	let snapshot = chain.get_snapshot(number)?;
+	let block_hash = snapshot.blocks.last().expect("qed").block_hash.clone();
+
+	let file = File::create(&snapshot_config.file).map_err(ErrorKind::IO)?;
+	let mut writer = BufWriter::new(file);
+
+	writer
+		.write_all(SNAPSHOT_FILE_MAGIC)
+		.map_err(ErrorKind::IO)?;
+	let file_header = SnapshotFileHeader {
+		version: SNAPSHOT_FILE_VERSION,
+		genesis_hash: current_state.genesis_hash.clone(),
+		number,
+		block_hash: block_hash.clone(),
+	};
+	write_record(&mut writer, &file_header)?;
+	write_record(&mut writer, &snapshot)?;
+
+	let mut start = vec![];
+	loop {
+		let chunk =
+			chain.get_snapshot_payload_state(&snapshot, &start, PAYLOAD_STATE_CHUNK_BYTES)?;
+		let last_key = match chunk.last() {
+			Some((key, _)) => key.clone(),
+			None => break,
+		};
+		write_record(&mut writer, &chunk)?;
+		// the next chunk starts right after the last key
+		start = [&last_key[..], &[0u8][..]].concat();
+	}
+	writer.flush().map_err(ErrorKind::IO)?;
+
+	info!(
+		"Snapshot created: block number: {}, block hash: {}, file: {:?}",
+		number, block_hash, snapshot_config.file
+	);
+
+	Ok(())
+}
+
+pub async fn restore(config: ServiceConfig, restore_config: RestoreConfig) -> CommonResult<()> {
+	let file_config = get_file_config(&config.home)?;
+	let chain_config = get_chain_config(&file_config, &config)?;
+	let chain = Chain::new(chain_config)?;
+
+	let file = File::open(&restore_config.file).map_err(ErrorKind::IO)?;
+	let mut reader = BufReader::new(file);
+
+	let mut magic = vec![0u8; SNAPSHOT_FILE_MAGIC.len()];
+	reader.read_exact(&mut magic).map_err(ErrorKind::IO)?;
+	if magic != SNAPSHOT_FILE_MAGIC {
+		return Err(ErrorKind::SnapshotFile("Invalid magic".to_string()).into());
+	}
+	let file_header: SnapshotFileHeader = read_record(&mut reader)?
+		.ok_or_else(|| ErrorKind::SnapshotFile("Missing file header".to_string()))?;
+	if file_header.version != SNAPSHOT_FILE_VERSION {
+		return Err(ErrorKind::SnapshotFile(format!(
+			"Unsupported version: {}",
+			file_header.version
+		))
+		.into());
+	}
+	if file_header.block_hash != restore_config.block_hash {
+		return Err(ErrorKind::SnapshotFile(format!(
+			"Mismatched block hash: file: {}, trusted: {}",
+			file_header.block_hash, restore_config.block_hash
+		))
+		.into());
+	}
+	let genesis_hash = chain.get_current_state().genesis_hash.clone();
+	if file_header.genesis_hash != genesis_hash {
+		return Err(ErrorKind::SnapshotFile(format!(
+			"Mismatched genesis hash: file: {}, chain: {}",
+			file_header.genesis_hash, genesis_hash
+		))
+		.into());
+	}
+
+	let snapshot: Snapshot = read_record(&mut reader)?
+		.ok_or_else(|| ErrorKind::SnapshotFile("Missing snapshot".to_string()))?;
+	let block = snapshot
+		.blocks
+		.last()
+		.ok_or_else(|| ErrorKind::SnapshotFile("Missing blocks".to_string()))?;
+	if block.header.number != file_header.number || block.block_hash != file_header.block_hash {
+		return Err(ErrorKind::SnapshotFile(format!(
+			"Mismatched snapshot block: number: {}, block hash: {}",
+			file_header.number, file_header.block_hash
+		))
+		.into());
+	}
+
+	let mut chain_rx = chain.subscribe_message();
+
+	let payload_state = std::iter::from_fn(|| read_record(&mut reader).transpose());
+	chain.restore_snapshot(snapshot, payload_state)?;
+
+	// the blocks after the execution number of the snapshot block are executed again
+	wait_execution(&chain, &mut chain_rx, file_header.number).await?;
+
+	info!(
+		"Snapshot restored: block number: {}, block hash: {}",
+		file_header.number, file_header.block_hash
+	);
+
+	Ok(())
+}
@@ -0,0 +1,308 @@
+// Copyright 2019, 2020 Wingchain
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Export the blocks to a portable file and import them back
+//!
+//! File format:
+//!   magic + record(file header) + record(block data) * n
+//! where a record is the length (u32, little endian) followed by the scale encoded data,
+//! and the block data is the same as the one of the coordinator protocol
+
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use futures::channel::mpsc::UnboundedReceiver;
+use futures::StreamExt;
+use log::info;
+
+use node_chain::{Chain, ChainOutMessage};
+use node_consensus::Consensus;
+use node_consensus_base::support::DefaultConsensusSupport;
+use node_coordinator::support::DefaultCoordinatorSupport;
+use node_coordinator::{BlockData, BodyData, Verifier};
+use node_txpool::support::DefaultTxPoolSupport;
+use node_txpool::TxPool;
+use primitives::codec::{self, Decode, Encode};
+use primitives::errors::CommonResult;
+use primitives::{BlockNumber, Hash, Transaction};
+
+use crate::config::{get_chain_config, get_consensus_config, get_file_config, get_txpool_config};
+use crate::errors::ErrorKind;
+use crate::ServiceConfig;
+
+/// Magic bytes at the beginning of the block file
+const BLOCK_FILE_MAGIC: &[u8] = b"WINGCHAIN-BLOCKS";
+
+/// Version of the block file format
+const BLOCK_FILE_VERSION: u32 = 1;
+
+/// Max length of a record, so that a corrupted length can not exhaust the memory
+const MAX_RECORD_LEN: usize = 64 * 1024 * 1024;
+
+pub struct ExportConfig {
+	/// The first block number to export
+	pub from: BlockNumber,
+	/// The last block number to export, the confirmed number if not specified
+	pub to: Option<BlockNumber>,
+	/// Path of the block file
+	pub file: PathBuf,
+}
+
+pub struct ImportConfig {
+	/// Path of the block file
+	pub file: PathBuf,
+}
+
+#[derive(Encode, Decode)]
+struct BlockFileHeader {
+	version: u32,
+	genesis_hash: Hash,
+	from: BlockNumber,
+	to: BlockNumber,
+}
+
+pub async fn export(config: ServiceConfig, export_config: ExportConfig) -> CommonResult<()> {
+	let file_config = get_file_config(&config.home)?;
+	let chain_config = get_chain_config(&file_config, &config)?;
+	let chain = Chain::new(chain_config)?;
+
+	let current_state = chain.get_current_state();
+	let confirmed_number = current_state.confirmed_number;
+
+	let from = export_config.from;
+	let to = export_config.to.unwrap_or(confirmed_number);
+	if from > to || to > confirmed_number {
+		return Err(ErrorKind::BlockFile(format!(
+			"Invalid range: from: {}, to: {}, confirmed number: {}",
+			from, to, confirmed_number
+		))
+		.into());
+	}
+
+	let file = File::create(&export_config.file).map_err(ErrorKind::IO)?;
+	let mut writer = BufWriter::new(file);
+
+	writer.write_all(BLOCK_FILE_MAGIC).map_err(ErrorKind::IO)?;
+	let file_header = BlockFileHeader {
+		version: BLOCK_FILE_VERSION,
+		genesis_hash: current_state.genesis_hash.clone(),
+		from,
+		to,
+	};
+	write_record(&mut writer, &file_header)?;
+
+	for number in from..=to {
+		let block_data = get_block_data(&chain, number)?;
+		write_record(&mut writer, &block_data)?;
+	}
+	writer.flush().map_err(ErrorKind::IO)?;
+
+	info!(
+		"Exported blocks: from: {}, to: {}, file: {:?}",
+		from, to, export_config.file
+	);
+
+	Ok(())
+}
+
+pub async fn import(config: ServiceConfig, import_config: ImportConfig) -> CommonResult<()> {
+	let file_config = get_file_config(&config.home)?;
+	let chain_config = get_chain_config(&file_config, &config)?;
+	let chain = Arc::new(Chain::new(chain_config)?);
+
+	let txpool_config = get_txpool_config(&file_config)?;
+	let txpool_support = Arc::new(DefaultTxPoolSupport::new(chain.clone()));
+	let txpool = Arc::new(TxPool::new(txpool_config, txpool_support)?);
+
+	// the consensus only verifies the proofs, it should never generate blocks while importing
+	let mut consensus_config = get_consensus_config(&file_config, &config.home, chain.get_basic())?;
+	if let Some(poa) = &mut consensus_config.poa {
+		poa.secret_key = None;
+	}
+	if let Some(raft) = &mut consensus_config.raft {
+		raft.secret_key = None;
+	}
+	if let Some(instant) = &mut consensus_config.instant {
+		instant.secret_key = None;
+	}
+	let consensus_support = Arc::new(DefaultConsensusSupport::new(chain.clone(), txpool.clone()));
+	let consensus = Arc::new(Consensus::new(consensus_config, consensus_support)?);
+
+	let verifier = Verifier::new(Arc::new(DefaultCoordinatorSupport::new(
+		chain.clone(),
+		txpool,
+		consensus,
+	)))?;
+
+	let file = File::open(&import_config.file).map_err(ErrorKind::IO)?;
+	let mut reader = BufReader::new(file);
+
+	let mut magic = vec![0u8; BLOCK_FILE_MAGIC.len()];
+	reader.read_exact(&mut magic).map_err(ErrorKind::IO)?;
+	if magic != BLOCK_FILE_MAGIC {
+		return Err(ErrorKind::BlockFile("Invalid magic".to_string()).into());
+	}
+	let file_header: BlockFileHeader = read_record(&mut reader)?
+		.ok_or_else(|| ErrorKind::BlockFile("Missing file header".to_string()))?;
+	if file_header.version != BLOCK_FILE_VERSION {
+		return Err(
+			ErrorKind::BlockFile(format!("Unsupported version: {}", file_header.version)).into(),
+		);
+	}
+	let genesis_hash = chain.get_current_state().genesis_hash.clone();
+	if file_header.genesis_hash != genesis_hash {
+		return Err(ErrorKind::BlockFile(format!(
+			"Mismatched genesis hash: file: {}, chain: {}",
+			file_header.genesis_hash, genesis_hash
+		))
+		.into());
+	}
+
+	let mut chain_rx = chain.subscribe_message();
+
+	let mut imported = 0u64;
+	let mut skipped = 0u64;
+	while let Some(block_data) = read_record::<_, BlockData>(&mut reader)? {
+		let number = block_data.number;
+
+		// the blocks already in the chain are skipped
+		if let Some(block_hash) = chain.get_block_hash(&number)? {
+			if block_hash != block_data.block_hash {
+				return Err(ErrorKind::BlockFile(format!(
+					"Mismatched block hash: number: {}, file: {}, chain: {}",
+					number, block_data.block_hash, block_hash
+				))
+				.into());
+			}
+			skipped += 1;
+			continue;
+		}
+
+		// the block can only be verified after the execution it refers to,
+		// the execution beyond the confirmed number is left to the verifier to reject
+		let execution_number = match &block_data.header {
+			Some(header) => header
+				.number
+				.saturating_sub(header.payload_execution_gap as BlockNumber),
+			None => 0,
+		};
+		let confirmed_number = chain.get_current_state().confirmed_number;
+		wait_execution(
+			&chain,
+			&mut chain_rx,
+			execution_number.min(confirmed_number),
+		)
+		.await?;
+
+		let commit_block_params = verifier.verify_block(&mut Some(block_data))?;
+		chain.commit_block(commit_block_params)?;
+		imported += 1;
+	}
+
+	// make sure all the imported blocks are executed before exiting
+	let confirmed_number = chain.get_current_state().confirmed_number;
+	wait_execution(&chain, &mut chain_rx, confirmed_number).await?;
+
+	info!(
+		"Imported blocks: imported: {}, skipped: {}, confirmed number: {}",
+		imported, skipped, confirmed_number
+	);
+
+	Ok(())
+}
+
+fn get_block_data(chain: &Chain, number: BlockNumber) -> CommonResult<BlockData> {
+	let block_hash = chain
+		.get_block_hash(&number)?
+		.ok_or_else(|| ErrorKind::BlockFile(format!("Missing block: number: {}", number)))?;
+	let block = chain.get_block(&block_hash)?.ok_or_else(|| {
+		ErrorKind::BlockFile(format!("Missing block: block_hash: {}", block_hash))
+	})?;
+	let proof = chain.get_proof(&block_hash)?.unwrap_or_default();
+
+	let get_txs = |tx_hashes: Vec<Hash>| -> CommonResult<Vec<Transaction>> {
+		tx_hashes
+			.into_iter()
+			.map(|tx_hash| {
+				chain.get_transaction(&tx_hash)?.ok_or_else(|| {
+					ErrorKind::BlockFile(format!("Missing transaction: tx_hash: {}", tx_hash))
+						.into()
+				})
+			})
+			.collect()
+	};
+
+	let body = BodyData {
+		meta_txs: get_txs(block.body.meta_txs)?,
+		payload_txs: get_txs(block.body.payload_txs)?,
+	};
+
+	Ok(BlockData {
+		number,
+		block_hash,
+		header: Some(block.header),
+		body: Some(body),
+		proof: Some(proof),
+	})
+}
+
+/// Wait until the chain is executed to the given number
+pub(crate) async fn wait_execution(
+	chain: &Chain,
+	chain_rx: &mut UnboundedReceiver<ChainOutMessage>,
+	number: BlockNumber,
+) -> CommonResult<()> {
+	while chain.get_current_state().executed_number < number {
+		if chain_rx.next().await.is_none() {
+			return Err(ErrorKind::BlockFile("Chain message channel closed".to_string()).into());
+		}
+	}
+	Ok(())
+}
+
+pub(crate) fn write_record<W: Write, T: Encode>(writer: &mut W, data: &T) -> CommonResult<()> {
+	let data = codec::encode(data)?;
+	if data.len() > MAX_RECORD_LEN {
+		return Err(ErrorKind::IO(io::Error::new(
+			io::ErrorKind::InvalidInput,
+			"Record too large",
+		))
+		.into());
+	}
+	writer
+		.write_all(&(data.len() as u32).to_le_bytes())
+		.map_err(ErrorKind::IO)?;
+	writer.write_all(&data).map_err(ErrorKind::IO)?;
+	Ok(())
+}
+
+/// Read a record, return None at the end of the file
+pub(crate) fn read_record<R: Read, T: Decode>(reader: &mut R) -> CommonResult<Option<T>> {
+	let mut len = [0u8; 4];
+	match reader.read_exact(&mut len) {
+		Ok(_) => (),
+		Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+		Err(e) => return Err(ErrorKind::IO(e).into()),
+	}
+	let len = u32::from_le_bytes(len) as usize;
+	if len > MAX_RECORD_LEN {
+		return Err(ErrorKind::BlockFile(format!("Record too large: {}", len)).into());
+	}
+	let mut data = vec![0u8; len];
+	reader.read_exact(&mut data).map_err(ErrorKind::IO)?;
+	let data = codec::decode(&mut &data[..])?;
+	Ok(Some(data))
+}
@@ -0,0 +1,161 @@
+// Copyright 2019, 2020 Wingchain
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Verify the integrity of the chain from the blocks to the data they commit to
+
+use std::sync::Arc;
+
+use log::info;
+
+use node_chain::Chain;
+use node_consensus::Consensus;
+use node_consensus_base::support::DefaultConsensusSupport;
+use node_txpool::support::DefaultTxPoolSupport;
+use node_txpool::TxPool;
+use primitives::errors::{Catchable, CommonResult};
+use primitives::BlockNumber;
+
+use crate::config::{get_chain_config, get_consensus_config, get_file_config, get_txpool_config};
+use crate::errors::ErrorKind;
+use crate::ServiceConfig;
+
+/// Blocks between two progress logs
+const VERIFY_LOG_INTERVAL: u64 = 10000;
+
+pub struct VerifyConfig {
+	/// The first block number to verify
+	pub from: BlockNumber,
+	/// The last block number to verify, the confirmed number if not specified
+	pub to: Option<BlockNumber>,
+	/// Whether to execute the blocks again to verify the state roots
+	pub execute: bool,
+}
+
+pub async fn verify(config: ServiceConfig, verify_config: VerifyConfig) -> CommonResult<()> {
+	let file_config = get_file_config(&config.home)?;
+	let chain_config = get_chain_config(&file_config, &config)?;
+
+	// the db is locked by the running node
+	let chain = Arc::new(Chain::new(chain_config).map_err(|e| {
+		ErrorKind::Verify(format!(
+			"Failed to open the chain, make sure the node is not running: {}",
+			e
+		))
+	})?);
+
+	let txpool_config = get_txpool_config(&file_config)?;
+	let txpool_support = Arc::new(DefaultTxPoolSupport::new(chain.clone()));
+	let txpool = Arc::new(TxPool::new(txpool_config, txpool_support)?);
+
+	// the consensus only verifies the proofs, it should never generate blocks while verifying
+	let mut consensus_config = get_consensus_config(&file_config, &config.home, chain.get_basic())?;
+	if let Some(poa) = &mut consensus_config.poa {
+		poa.secret_key = None;
+	}
+	if let Some(raft) = &mut consensus_config.raft {
+		raft.secret_key = None;
+	}
+	if let Some(instant) = &mut consensus_config.instant {
+		instant.secret_key = None;
+	}
+	let consensus_support = Arc::new(DefaultConsensusSupport::new(chain.clone(), txpool));
+	let consensus = Consensus::new(consensus_config, consensus_support)?;
+
+	let confirmed_number = chain.get_current_state().confirmed_number;
+	let from = verify_config.from;
+	let to = verify_config.to.unwrap_or(confirmed_number);
+	if from > to || to > confirmed_number {
+		return Err(ErrorKind::Verify(format!(
+			"Invalid range: from: {}, to: {}, confirmed number: {}",
+			from, to, confirmed_number
+		))
+		.into());
+	}
+
+	info!(
+		"Verifying blocks: from: {}, to: {}, execute: {}",
+		from, to, verify_config.execute
+	);
+
+	for number in from..=to {
+		if let Some(reason) = verify_block(&chain, &consensus, number, verify_config.execute)? {
+			return Err(ErrorKind::Verify(format!(
+				"Inconsistent block: number: {}, verified blocks before it: {}, {}",
+				number,
+				number - from,
+				reason
+			))
+			.into());
+		}
+
+		if (number - from + 1) % VERIFY_LOG_INTERVAL == 0 {
+			info!("Verified blocks: from: {}, to: {}", from, number);
+		}
+	}
+
+	info!(
+		"Chain verified: from: {}, to: {}, blocks: {}, execute: {}",
+		from,
+		to,
+		to - from + 1,
+		verify_config.execute
+	);
+
+	Ok(())
+}
+
+/// Verify the block by the chain and its proof by the consensus,
+/// return the reason of the inconsistency if any
+fn verify_block(
+	chain: &Chain,
+	consensus: &Consensus<DefaultConsensusSupport>,
+	number: BlockNumber,
+	execute: bool,
+) -> CommonResult<Option<String>> {
+	let reason = chain
+		.verify_block(number, execute)
+		.map(|_| None)
+		.or_else_catch::<node_chain::errors::ErrorKind, _>(|e| match e {
+			node_chain::errors::ErrorKind::Verify(e) => Some(Ok(Some(e.clone()))),
+			_ => None,
+		})?;
+	if reason.is_some() {
+		return Ok(reason);
+	}
+
+	// the genesis block has no proof
+	if number == 0 {
+		return Ok(None);
+	}
+
+	let block_hash = chain
+		.get_block_hash(&number)?
+		.ok_or_else(|| ErrorKind::Verify(format!("Missing block: number: {}", number)))?;
+	let header = chain
+		.get_header(&block_hash)?
+		.ok_or_else(|| ErrorKind::Verify(format!("Missing block: block_hash: {}", block_hash)))?;
+	let proof = match chain.get_proof(&block_hash)? {
+		Some(proof) => proof,
+		None => return Ok(Some("Missing proof".to_string())),
+	};
+	consensus
+		.verify_proof(&header, &proof)
+		.map(|_| None)
+		.or_else_catch::<node_consensus_base::errors::ErrorKind, _>(|e| match e {
+			node_consensus_base::errors::ErrorKind::VerifyProofError(e) => {
+				Some(Ok(Some(format!("Invalid proof: {}", e))))
+			}
+			_ => None,
+		})
+}
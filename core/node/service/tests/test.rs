@@ -0,0 +1,256 @@
+// Copyright 2019, 2020 Wingchain
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use tempfile::tempdir;
+use tokio::runtime::Runtime;
+use tokio::time::Duration;
+
+use crypto::address::AddressImpl;
+use crypto::dsa::DsaImpl;
+use node_chain::{module, Chain, ChainConfig, DBBackend, DBConfig, StatePruning};
+use node_consensus::{Consensus, ConsensusConfig, PoaConfig};
+use node_consensus_base::support::DefaultConsensusSupport;
+use node_service::{ExportConfig, ImportConfig, ServiceConfig};
+use node_txpool::support::DefaultTxPoolSupport;
+use node_txpool::{TxPool, TxPoolConfig};
+use primitives::{BlockNumber, Execution, Hash};
+use utils_test::{test_accounts, TestAccount};
+
+#[test]
+fn test_export_import() {
+	let dsa = Arc::new(DsaImpl::Ed25519);
+	let address = Arc::new(AddressImpl::Blake2b160);
+
+	let test_accounts = test_accounts(dsa, address);
+	let (account1, account2) = (&test_accounts[0], &test_accounts[1]);
+
+	let home1 = tempdir().expect("Could not create a temp dir").into_path();
+	let home2 = tempdir().expect("Could not create a temp dir").into_path();
+	init(&home1, account1);
+	init(&home2, account1);
+
+	// generate blocks with a transfer
+	let number = {
+		let rt = Runtime::new().unwrap();
+		rt.block_on(async {
+			let chain = get_chain(&home1);
+			let txpool_config = TxPoolConfig {
+				pool_capacity: 32,
+				sender_capacity: None,
+				journal: false,
+			};
+			let txpool_support = Arc::new(DefaultTxPoolSupport::new(chain.clone()));
+			let txpool = Arc::new(TxPool::new(txpool_config, txpool_support).unwrap());
+			let consensus_config = ConsensusConfig {
+				poa: Some(PoaConfig {
+					secret_key: Some(account1.secret_key.clone()),
+				}),
+				raft: None,
+				instant: None,
+			};
+			let consensus_support =
+				Arc::new(DefaultConsensusSupport::new(chain.clone(), txpool.clone()));
+			let _consensus = Consensus::new(consensus_config, consensus_support).unwrap();
+
+			let tx = chain
+				.build_transaction(
+					Some((account1.secret_key.clone(), 0, 10)),
+					chain
+						.build_call(
+							"balance".to_string(),
+							"transfer".to_string(),
+							module::balance::TransferParams {
+								recipient: account2.address.clone(),
+								value: 2,
+							},
+						)
+						.unwrap(),
+				)
+				.unwrap();
+			txpool.insert(tx).unwrap();
+
+			loop {
+				let current_state = chain.get_current_state();
+				if current_state.confirmed_number >= 5 && current_state.executed_number >= 3 {
+					break current_state.executed_number;
+				}
+				tokio::time::sleep(Duration::from_millis(10)).await;
+			}
+		})
+	};
+
+	let file = home1.join("blocks.dat");
+	node_service::export(
+		service_config(&home1),
+		ExportConfig {
+			from: 0,
+			to: Some(number),
+			file: file.clone(),
+		},
+	)
+	.unwrap();
+	node_service::import(service_config(&home2), ImportConfig { file }).unwrap();
+
+	let rt = Runtime::new().unwrap();
+	rt.block_on(async {
+		let chain1 = get_chain(&home1);
+		let chain2 = get_chain(&home2);
+		assert_eq!(chain2.get_current_state().confirmed_number, number);
+		assert_eq!(get_blocks(&chain2, number), get_blocks(&chain1, number));
+	});
+}
+
+fn get_blocks(chain: &Chain, number: BlockNumber) -> Vec<(Hash, Option<Execution>)> {
+	(0..=number)
+		.map(|x| {
+			let block_hash = chain.get_block_hash(&x).unwrap().unwrap();
+			let execution = chain.get_execution(&block_hash).unwrap();
+			(block_hash, execution)
+		})
+		.collect()
+}
+
+fn service_config(home: &PathBuf) -> ServiceConfig {
+	ServiceConfig {
+		home: home.clone(),
+		agent_version: "test".to_string(),
+	}
+}
+
+fn get_chain(home: &PathBuf) -> Arc<Chain> {
+	let db = DBConfig {
+		memory_budget: 1024 * 1024,
+		path: home.join("data").join("db"),
+		partitions: vec![],
+		backend: DBBackend::RocksDB,
+	};
+	let chain_config = ChainConfig {
+		home: home.clone(),
+		db,
+		pruning: StatePruning::Archive,
+		index: false,
+	};
+	Arc::new(Chain::new(chain_config).unwrap())
+}
+
+fn init(home: &PathBuf, account: &TestAccount) {
+	let config_path = home.join("config");
+
+	fs::create_dir_all(&config_path).unwrap();
+
+	let spec = format!(
+		r#"
+[basic]
+hash = "blake2b_256"
+dsa = "ed25519"
+address = "blake2b_160"
+
+[genesis]
+
+[[genesis.txs]]
+module = "system"
+method = "init"
+params = '''
+{{
+    "chain_id": "chain-test",
+    "timestamp": "2020-04-29T15:51:36.502+08:00",
+    "max_until_gap": 20,
+    "max_execution_gap": 8,
+    "consensus": "poa",
+    "admin": {{
+    	"threshold": 1,
+    	"members": [["{}", 1]]
+    }}
+}}
+'''
+
+[[genesis.txs]]
+module = "balance"
+method = "init"
+params = '''
+{{
+    "endow": [
+    	["{}", 10]
+    ]
+}}
+'''
+
+[[genesis.txs]]
+module = "poa"
+method = "init"
+params = '''
+{{
+    "block_interval": 100,
+    "admin": {{
+    	"threshold": 1,
+    	"members": [["{}", 1]]
+    }},
+    "authorities": {{
+    	"members": ["{}"]
+    }}
+}}
+'''
+
+[[genesis.txs]]
+module = "contract"
+method = "init"
+params = '''
+{{
+}}
+'''
+	"#,
+		account.address, account.address, account.address, account.address
+	);
+
+	let config = r#"
+[txpool]
+pool_capacity = 32
+
+[api]
+rpc_addr = "127.0.0.1:0"
+rpc_workers = 1
+rpc_maxconn = 100
+
+[db]
+path = "data/db"
+memory_budget = 1_048_576
+
+[consensus]
+
+[consensus.poa]
+secret_key_file = "config/secret_key.dat"
+
+[network]
+max_in_peers = 32
+max_out_peers = 32
+listen_addresses = []
+external_addresses = []
+bootnodes = []
+reserved_nodes = []
+reserved_only = false
+secret_key_file = "config/network_secret_key.dat"
+"#;
+
+	fs::write(config_path.join("spec.toml"), &spec).unwrap();
+	fs::write(config_path.join("config.toml"), config).unwrap();
+	fs::write(
+		config_path.join("secret_key.dat"),
+		hex::encode(&account.secret_key.0),
+	)
+	.unwrap();
+}
@@ -21,7 +21,7 @@ use std::sync::Arc;
 use test::{black_box, Bencher};
 
 use crypto::hash::HashImpl;
-use node_db::{DBConfig, DB};
+use node_db::{DBBackend, DBConfig, DB};
 use node_statedb::StateDB;
 use primitives::DBKey;
 
@@ -71,6 +71,7 @@ fn prepare_statedb(records: usize) -> (StateDB, Vec<u8>) {
 		memory_budget: 128 * 1024 * 1024,
 		path,
 		partitions: vec![],
+		backend: DBBackend::RocksDB,
 	};
 
 	let db = Arc::new(DB::open(db_config).unwrap());
@@ -93,7 +94,7 @@ fn prepare_statedb(records: usize) -> (StateDB, Vec<u8>) {
 	.into_iter()
 	.collect::<HashMap<_, _>>();
 
-	let (update_1_root, transaction) = statedb.prepare_update(&root, data.iter()).unwrap();
+	let (update_1_root, transaction) = statedb.prepare_update(&root, data.iter(), 1).unwrap();
 	db.write(transaction).unwrap();
 	let result = statedb.get(&update_1_root, &b"abc"[..]).unwrap();
 
@@ -103,7 +104,9 @@ fn prepare_statedb(records: usize) -> (StateDB, Vec<u8>) {
 	let data = vec![(DBKey::from_slice(b"abc"), Some(vec![2u8; 1024]))]
 		.into_iter()
 		.collect::<HashMap<_, _>>();
-	let (update_2_root, transaction) = statedb.prepare_update(&update_1_root, data.iter()).unwrap();
+	let (update_2_root, transaction) = statedb
+		.prepare_update(&update_1_root, data.iter(), 2)
+		.unwrap();
 	db.write(transaction).unwrap();
 	let result = statedb.get(&update_2_root, &b"abc"[..]).unwrap();
 
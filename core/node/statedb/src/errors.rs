@@ -29,6 +29,9 @@ pub enum ErrorKind {
 
 	#[display(fmt = "Trie error: {}", _0)]
 	Trie(String),
+
+	#[display(fmt = "Invalid proof: {}", _0)]
+	InvalidProof(String),
 }
 
 impl Error for ErrorKind {}
@@ -12,8 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-//! State db maintaining all the historical trie
+//! State db maintaining the historical trie
+//! all the historical trie is kept unless the journal is enabled for pruning
 
+use std::cmp::Ordering;
 use std::iter::IntoIterator;
 use std::sync::Arc;
 
@@ -26,40 +28,58 @@ use trie_db::{Trie, TrieMut};
 use crypto::hash::{Hash as HashT, HashImpl};
 use crypto::HashLength;
 use node_db::{DBTransaction, DB};
-use primitives::codec;
+use primitives::codec::{self, Decode, Encode};
 use primitives::errors::CommonResult;
-use primitives::{DBKey, DBValue};
+use primitives::{BlockNumber, DBKey, DBValue};
 pub use trie::{
 	DefaultMemoryDB, DefaultTrieDB, DefaultTrieDBMut, TrieHasher20, TrieHasher32, TrieHasher64,
 	H512,
 };
 
 use crate::errors::parse_trie_error;
-use crate::trie::load_hasher;
+use crate::trie::{load_hasher, DefaultTrieLayout};
 
 pub mod errors;
 mod trie;
 
+/// Key of the pruned number in the journal column
+const PRUNED_NUMBER_KEY: &[u8] = b"pruned_number";
+
+/// Key prefix of the latest block number inserting a trie node in the journal column
+const INSERTED_NUMBER_PREFIX: &[u8] = b"inserted_number_";
+
 pub struct StateDB {
 	db: Arc<DB>,
 	pub db_column: u32,
+	/// column of the journal, the journal is disabled if not specified
+	journal_column: Option<u32>,
 	#[allow(dead_code)]
 	hasher: Arc<HashImpl>,
 	hash_length: HashLength,
 }
 
+/// Trie nodes inserted and deleted by the update of a block
+#[derive(Encode, Decode, Default)]
+struct StateJournal {
+	inserted: Vec<Vec<u8>>,
+	deleted: Vec<Vec<u8>>,
+}
+
 impl StateDB {
 	/// Create a new statedb
 	pub fn new(db: Arc<DB>, db_column: u32, hasher: Arc<HashImpl>) -> CommonResult<Self> {
-		load_hasher(hasher.clone())?;
+		Self::new_with_journal_column(db, db_column, None, hasher)
+	}
 
-		let hash_length = hasher.length();
-		Ok(Self {
-			db,
-			db_column,
-			hasher,
-			hash_length,
-		})
+	/// Create a new statedb with the journal enabled,
+	/// so that the trie nodes no longer used by the recent blocks can be pruned
+	pub fn new_with_journal(
+		db: Arc<DB>,
+		db_column: u32,
+		journal_column: u32,
+		hasher: Arc<HashImpl>,
+	) -> CommonResult<Self> {
+		Self::new_with_journal_column(db, db_column, Some(journal_column), hasher)
 	}
 
 	/// Get the default trie root
@@ -97,6 +117,82 @@ impl StateDB {
 		}
 	}
 
+	/// Get the key-values from the given start key in the order of key,
+	/// until the total size of the keys and values reaches max bytes,
+	/// by which the whole trie can be read chunk by chunk
+	pub fn iter_from(
+		&self,
+		root: &[u8],
+		start: &[u8],
+		max_bytes: usize,
+	) -> CommonResult<Vec<(DBKey, DBValue)>> {
+		match self.hash_length {
+			HashLength::HashLength20 => {
+				let mut typed_root = [0u8; 20];
+				typed_root.copy_from_slice(&root);
+				self.iter_from_for_hasher::<TrieHasher20>(typed_root, start, max_bytes)
+			}
+			HashLength::HashLength32 => {
+				let mut typed_root = [0u8; 32];
+				typed_root.copy_from_slice(&root);
+				self.iter_from_for_hasher::<TrieHasher32>(typed_root, start, max_bytes)
+			}
+			HashLength::HashLength64 => {
+				let mut typed_root = [0u8; 64];
+				typed_root.copy_from_slice(&root);
+				let typed_root = H512::from(typed_root);
+				self.iter_from_for_hasher::<TrieHasher64>(typed_root, start, max_bytes)
+			}
+		}
+	}
+
+	/// Get the key-values with the given key prefix from the trie
+	/// by trie root and key prefix, in the order of key
+	pub fn iter_prefix(&self, root: &[u8], prefix: &[u8]) -> CommonResult<Vec<(DBKey, DBValue)>> {
+		match self.hash_length {
+			HashLength::HashLength20 => {
+				let mut typed_root = [0u8; 20];
+				typed_root.copy_from_slice(&root);
+				self.iter_prefix_for_hasher::<TrieHasher20>(typed_root, prefix)
+			}
+			HashLength::HashLength32 => {
+				let mut typed_root = [0u8; 32];
+				typed_root.copy_from_slice(&root);
+				self.iter_prefix_for_hasher::<TrieHasher32>(typed_root, prefix)
+			}
+			HashLength::HashLength64 => {
+				let mut typed_root = [0u8; 64];
+				typed_root.copy_from_slice(&root);
+				let typed_root = H512::from(typed_root);
+				self.iter_prefix_for_hasher::<TrieHasher64>(typed_root, prefix)
+			}
+		}
+	}
+
+	/// Get the proof of the key from the trie
+	/// by trie root and key, the proof is the trie nodes on the path from the root to the key
+	/// and proves either the value of the key or the absence of the key
+	pub fn get_proof(&self, root: &[u8], key: &[u8]) -> CommonResult<Vec<Vec<u8>>> {
+		match self.hash_length {
+			HashLength::HashLength20 => {
+				let mut typed_root = [0u8; 20];
+				typed_root.copy_from_slice(&root);
+				self.get_proof_for_hasher::<TrieHasher20>(typed_root, key)
+			}
+			HashLength::HashLength32 => {
+				let mut typed_root = [0u8; 32];
+				typed_root.copy_from_slice(&root);
+				self.get_proof_for_hasher::<TrieHasher32>(typed_root, key)
+			}
+			HashLength::HashLength64 => {
+				let mut typed_root = [0u8; 64];
+				typed_root.copy_from_slice(&root);
+				let typed_root = H512::from(typed_root);
+				self.get_proof_for_hasher::<TrieHasher64>(typed_root, key)
+			}
+		}
+	}
+
 	/// Get the statedb statement by trie root
 	/// by which one can get a statedb getter
 	pub fn prepare_stmt(&self, root: &[u8]) -> CommonResult<StateDBStmt> {
@@ -128,10 +224,13 @@ impl StateDB {
 
 	/// Try update with batch key-values,
 	/// return new trie root and db transaction to update the state column of DB
+	/// if the journal is enabled, the transaction also records the inserted and deleted
+	/// trie nodes as the journal of the given block number
 	pub fn prepare_update<'a, I>(
 		&self,
 		root: &[u8],
 		data: I,
+		number: BlockNumber,
 	) -> CommonResult<(Vec<u8>, DBTransaction)>
 	where
 		I: Iterator<Item = (&'a DBKey, &'a Option<DBValue>)>,
@@ -141,14 +240,14 @@ impl StateDB {
 				let mut typed_root = [0u8; 20];
 				typed_root.copy_from_slice(&root);
 
-				self.prepare_update_for_hasher::<_, TrieHasher20>(typed_root, data)
+				self.prepare_update_for_hasher::<_, TrieHasher20>(typed_root, data, number)
 					.map(|(root, transaction)| (root.to_vec(), transaction))?
 			}
 			HashLength::HashLength32 => {
 				let mut typed_root = [0u8; 32];
 				typed_root.copy_from_slice(&root);
 
-				self.prepare_update_for_hasher::<_, TrieHasher32>(typed_root, data)
+				self.prepare_update_for_hasher::<_, TrieHasher32>(typed_root, data, number)
 					.map(|(root, transaction)| (root.to_vec(), transaction))?
 			}
 			HashLength::HashLength64 => {
@@ -156,16 +255,123 @@ impl StateDB {
 				typed_root.copy_from_slice(&root);
 				let typed_root = H512::from(typed_root);
 
-				self.prepare_update_for_hasher::<_, TrieHasher64>(typed_root, data)
+				self.prepare_update_for_hasher::<_, TrieHasher64>(typed_root, data, number)
 					.map(|(root, transaction)| (root.as_bytes().to_vec(), transaction))?
 			}
 		};
 		Ok(result)
 	}
+
+	/// Get the latest block number whose state has been pruned
+	pub fn get_pruned_number(&self) -> CommonResult<Option<BlockNumber>> {
+		let journal_column = match self.journal_column {
+			Some(journal_column) => journal_column,
+			None => return Ok(None),
+		};
+		self.db.get_with(journal_column, PRUNED_NUMBER_KEY, |x| {
+			codec::decode(&mut &x[..])
+		})
+	}
+
+	/// Prune the state of the given block number
+	/// remove the trie nodes deleted by the next block,
+	/// unless they are inserted again by the following blocks
+	/// should be called in the order of block number
+	pub fn prune(&self, number: BlockNumber) -> CommonResult<()> {
+		let journal_column = match self.journal_column {
+			Some(journal_column) => journal_column,
+			None => return Ok(()),
+		};
+
+		let deleted = match self.get_journal(journal_column, number + 1)? {
+			Some(journal) => journal.deleted,
+			None => vec![],
+		};
+
+		let mut transaction = self.db.transaction();
+		for key in deleted {
+			let reinserted = match self.get_inserted_number(journal_column, &key)? {
+				Some(inserted_number) => inserted_number >= number + 2,
+				None => false,
+			};
+			if !reinserted {
+				transaction.delete(self.db_column, &key);
+			}
+		}
+		// the latest inserting numbers not greater than the pruned are useless later
+		if let Some(journal) = self.get_journal(journal_column, number)? {
+			for key in journal.inserted {
+				if let Some(inserted_number) = self.get_inserted_number(journal_column, &key)? {
+					if inserted_number <= number {
+						transaction.delete(journal_column, &inserted_number_key(&key));
+					}
+				}
+			}
+		}
+		transaction.delete(journal_column, &codec::encode(&number)?);
+		transaction.put_owned(
+			journal_column,
+			DBKey::from_slice(PRUNED_NUMBER_KEY),
+			codec::encode(&number)?,
+		);
+		self.db.write(transaction)?;
+
+		Ok(())
+	}
+
+	/// Prepare the db transaction to remove the journal of the given block number
+	/// when the block is reverted, the trie nodes are kept as they may be shared
+	pub fn prepare_revert(&self, number: BlockNumber) -> CommonResult<DBTransaction> {
+		let mut transaction = self.db.transaction();
+		if let Some(journal_column) = self.journal_column {
+			transaction.delete(journal_column, &codec::encode(&number)?);
+		}
+		Ok(transaction)
+	}
 }
 
 /// private impl
 impl StateDB {
+	fn new_with_journal_column(
+		db: Arc<DB>,
+		db_column: u32,
+		journal_column: Option<u32>,
+		hasher: Arc<HashImpl>,
+	) -> CommonResult<Self> {
+		load_hasher(hasher.clone())?;
+
+		let hash_length = hasher.length();
+		Ok(Self {
+			db,
+			db_column,
+			journal_column,
+			hasher,
+			hash_length,
+		})
+	}
+
+	fn get_journal(
+		&self,
+		journal_column: u32,
+		number: BlockNumber,
+	) -> CommonResult<Option<StateJournal>> {
+		self.db
+			.get_with(journal_column, &codec::encode(&number)?, |x| {
+				codec::decode(&mut &x[..])
+			})
+	}
+
+	fn get_inserted_number(
+		&self,
+		journal_column: u32,
+		key: &[u8],
+	) -> CommonResult<Option<BlockNumber>> {
+		self.db
+			.get_with(journal_column, &inserted_number_key(key), |x| {
+				codec::decode(&mut &x[..])
+			})
+	}
+
 	fn default_root_for_hasher<H>(&self) -> H::Out
 	where
 		H: Hasher,
@@ -191,6 +397,78 @@ impl StateDB {
 		Ok(result)
 	}
 
+	fn iter_prefix_for_hasher<H>(
+		&self,
+		root: H::Out,
+		prefix: &[u8],
+	) -> CommonResult<Vec<(DBKey, DBValue)>>
+	where
+		H: Hasher,
+	{
+		let buffer = DefaultMemoryDB::<H>::default();
+		let proxy = ProxyHashDB {
+			db: self.db.clone(),
+			db_column: self.db_column,
+			buffer,
+		};
+
+		let triedb = DefaultTrieDB::<H>::new(&proxy, &root).map_err(parse_trie_error)?;
+
+		iter_prefix_for_triedb(&triedb, prefix)
+	}
+
+	fn iter_from_for_hasher<H>(
+		&self,
+		root: H::Out,
+		start: &[u8],
+		max_bytes: usize,
+	) -> CommonResult<Vec<(DBKey, DBValue)>>
+	where
+		H: Hasher,
+	{
+		let buffer = DefaultMemoryDB::<H>::default();
+		let proxy = ProxyHashDB {
+			db: self.db.clone(),
+			db_column: self.db_column,
+			buffer,
+		};
+
+		let triedb = DefaultTrieDB::<H>::new(&proxy, &root).map_err(parse_trie_error)?;
+
+		let mut iter = triedb.iter().map_err(parse_trie_error)?;
+		iter.seek(start).map_err(parse_trie_error)?;
+
+		let mut result = vec![];
+		let mut bytes = 0;
+		for item in iter {
+			let (key, value) = item.map_err(parse_trie_error)?;
+			bytes += key.len() + value.len();
+			result.push((DBKey::from_slice(&key), value));
+			if bytes >= max_bytes {
+				break;
+			}
+		}
+		Ok(result)
+	}
+
+	fn get_proof_for_hasher<H>(&self, root: H::Out, key: &[u8]) -> CommonResult<Vec<Vec<u8>>>
+	where
+		H: Hasher,
+	{
+		let buffer = DefaultMemoryDB::<H>::default();
+		let proxy = ProxyHashDB {
+			db: self.db.clone(),
+			db_column: self.db_column,
+			buffer,
+		};
+
+		let triedb = DefaultTrieDB::<H>::new(&proxy, &root).map_err(parse_trie_error)?;
+
+		let proof = trie_db::proof::generate_proof(&triedb, &[key]).map_err(parse_trie_error)?;
+
+		Ok(proof)
+	}
+
 	fn prepare_get_for_hasher<H>(
 		stmt: &StateDBStmtForHasher<H>,
 	) -> CommonResult<StateDBGetterForHasher<H>>
@@ -211,6 +489,7 @@ impl StateDB {
 		&self,
 		mut root: H::Out,
 		data: I,
+		number: BlockNumber,
 	) -> CommonResult<(H::Out, DBTransaction)>
 	where
 		I: Iterator<Item = (&'a DBKey, &'a Option<DBValue>)>,
@@ -247,11 +526,30 @@ impl StateDB {
 
 		// extract buffer to transaction
 		let mut transaction = self.db.transaction();
+		let mut journal = StateJournal::default();
 		for (k, (v, rc)) in proxy.buffer.drain() {
-			// only apply insert
-			if rc > 0 {
-				transaction.put_owned(self.db_column, DBKey::from_slice(&k), v);
+			// only apply insert, the deleted are kept in the journal until pruned
+			match rc.cmp(&0) {
+				Ordering::Greater => {
+					transaction.put_owned(self.db_column, DBKey::from_slice(&k), v);
+					journal.inserted.push(k);
+				}
+				Ordering::Less => journal.deleted.push(k),
+				Ordering::Equal => (),
+			}
+		}
+
+		if let Some(journal_column) = self.journal_column {
+			// the blocks are committed in order, so the latest inserting number is kept
+			let encoded_number = codec::encode(&number)?;
+			for k in &journal.inserted {
+				transaction.put(journal_column, &inserted_number_key(k), &encoded_number);
 			}
+			transaction.put_owned(
+				journal_column,
+				DBKey::from_slice(&encoded_number),
+				codec::encode(&journal)?,
+			);
 		}
 
 		Ok((root, transaction))
@@ -354,6 +652,50 @@ impl<'a> StateDBGetter<'a> {
 		};
 		Ok(result)
 	}
+
+	/// Get the key-values with the given key prefix, in the order of key
+	pub fn iter_prefix(&self, prefix: &[u8]) -> CommonResult<Vec<(DBKey, DBValue)>> {
+		let result = match self {
+			Self::Hasher20(g) => match &g.triedb {
+				Some(triedb) => iter_prefix_for_triedb(triedb, prefix)?,
+				None => vec![],
+			},
+			Self::Hasher32(g) => match &g.triedb {
+				Some(triedb) => iter_prefix_for_triedb(triedb, prefix)?,
+				None => vec![],
+			},
+			Self::Hasher64(g) => match &g.triedb {
+				Some(triedb) => iter_prefix_for_triedb(triedb, prefix)?,
+				None => vec![],
+			},
+		};
+		Ok(result)
+	}
+}
+
+fn inserted_number_key(key: &[u8]) -> Vec<u8> {
+	[INSERTED_NUMBER_PREFIX, key].concat()
+}
+
+fn iter_prefix_for_triedb<H>(
+	triedb: &DefaultTrieDB<H>,
+	prefix: &[u8],
+) -> CommonResult<Vec<(DBKey, DBValue)>>
+where
+	H: Hasher,
+{
+	let mut iter = triedb.iter().map_err(parse_trie_error)?;
+	iter.seek(prefix).map_err(parse_trie_error)?;
+
+	let mut result = vec![];
+	for item in iter {
+		let (key, value) = item.map_err(parse_trie_error)?;
+		if !key.starts_with(prefix) {
+			break;
+		}
+		result.push((DBKey::from_slice(&key), value));
+	}
+	Ok(result)
 }
 
 struct ProxyHashDB<H: Hasher> {
@@ -469,4 +811,138 @@ impl TrieRoot {
 
 		self.calc_trie_root(input)
 	}
+
+	/// Get the proof of the key in the trie built from the input
+	/// the proof can be verified by `ProofVerifier` against the root calculated by `calc_trie_root`
+	pub fn calc_trie_proof<I, A, B>(&self, input: I, key: &[u8]) -> CommonResult<Vec<Vec<u8>>>
+	where
+		I: IntoIterator<Item = (A, B)>,
+		A: AsRef<[u8]> + Ord,
+		B: AsRef<[u8]>,
+	{
+		match self.hash_length {
+			HashLength::HashLength20 => {
+				calc_trie_proof_for_hasher::<TrieHasher20, _, _, _>(input, key)
+			}
+			HashLength::HashLength32 => {
+				calc_trie_proof_for_hasher::<TrieHasher32, _, _, _>(input, key)
+			}
+			HashLength::HashLength64 => {
+				calc_trie_proof_for_hasher::<TrieHasher64, _, _, _>(input, key)
+			}
+		}
+	}
+
+	/// Get the proof of the item at the given index in the ordered trie built from the input
+	/// the key of the item in the trie is the encoded index (u32)
+	pub fn calc_ordered_trie_proof<I, A>(&self, input: I, index: u32) -> CommonResult<Vec<Vec<u8>>>
+	where
+		I: IntoIterator<Item = A>,
+		A: AsRef<[u8]>,
+	{
+		let input = input
+			.into_iter()
+			.enumerate()
+			.map(|(k, v)| {
+				(
+					codec::encode(&(k as u32)).expect("u32 can be serialized"),
+					v,
+				)
+			})
+			.collect::<Vec<_>>();
+
+		let key = codec::encode(&index)?;
+
+		self.calc_trie_proof(input, &key)
+	}
+}
+
+fn calc_trie_proof_for_hasher<H, I, A, B>(input: I, key: &[u8]) -> CommonResult<Vec<Vec<u8>>>
+where
+	H: Hasher,
+	I: IntoIterator<Item = (A, B)>,
+	A: AsRef<[u8]> + Ord,
+	B: AsRef<[u8]>,
+{
+	let mut memdb = DefaultMemoryDB::<H>::default();
+	let mut root = H::Out::default();
+	{
+		let mut triedb = DefaultTrieDBMut::<H>::new(&mut memdb, &mut root);
+		for (k, v) in input {
+			triedb
+				.insert(k.as_ref(), v.as_ref())
+				.map_err(parse_trie_error)?;
+		}
+	}
+
+	let triedb = DefaultTrieDB::<H>::new(&memdb, &root).map_err(parse_trie_error)?;
+
+	let proof = trie_db::proof::generate_proof(&triedb, &[key]).map_err(parse_trie_error)?;
+
+	Ok(proof)
+}
+
+/// Verify the trie proofs without the db
+pub struct ProofVerifier {
+	hash_length: HashLength,
+}
+
+impl ProofVerifier {
+	pub fn new(hasher: Arc<HashImpl>) -> CommonResult<Self> {
+		let hash_length = hasher.length();
+		load_hasher(hasher)?;
+
+		Ok(Self { hash_length })
+	}
+
+	/// Verify the proof generated by `StateDB::get_proof`
+	/// the value should be None if the proof is expected to prove the absence of the key
+	pub fn verify(
+		&self,
+		root: &[u8],
+		proof: &[Vec<u8>],
+		key: &[u8],
+		value: Option<&[u8]>,
+	) -> CommonResult<()> {
+		let hash_length: usize = self.hash_length.clone().into();
+		if root.len() != hash_length {
+			return Err(errors::ErrorKind::InvalidProof(format!(
+				"Invalid root length: {}",
+				root.len()
+			))
+			.into());
+		}
+		match self.hash_length {
+			HashLength::HashLength20 => {
+				let mut typed_root = [0u8; 20];
+				typed_root.copy_from_slice(&root);
+				verify_proof_for_hasher::<TrieHasher20>(&typed_root, proof, key, value)
+			}
+			HashLength::HashLength32 => {
+				let mut typed_root = [0u8; 32];
+				typed_root.copy_from_slice(&root);
+				verify_proof_for_hasher::<TrieHasher32>(&typed_root, proof, key, value)
+			}
+			HashLength::HashLength64 => {
+				let mut typed_root = [0u8; 64];
+				typed_root.copy_from_slice(&root);
+				let typed_root = H512::from(typed_root);
+				verify_proof_for_hasher::<TrieHasher64>(&typed_root, proof, key, value)
+			}
+		}
+	}
+}
+
+fn verify_proof_for_hasher<H>(
+	root: &H::Out,
+	proof: &[Vec<u8>],
+	key: &[u8],
+	value: Option<&[u8]>,
+) -> CommonResult<()>
+where
+	H: Hasher,
+{
+	let items = [(key, value)];
+	trie_db::proof::verify_proof::<DefaultTrieLayout<H>, _, _, _>(root, proof, &items)
+		.map_err(|e| errors::ErrorKind::InvalidProof(format!("{:?}", e)).into())
 }
@@ -22,8 +22,8 @@ use mut_static::MutStatic;
 use crypto::hash::Hash;
 use crypto::hash::HashImpl;
 use lazy_static::lazy_static;
-use node_db::{DBConfig, DB};
-use node_statedb::{StateDB, TrieRoot};
+use node_db::{DBBackend, DBConfig, DB};
+use node_statedb::{ProofVerifier, StateDB, TrieRoot};
 use primitives::{codec, DBKey};
 
 #[test]
@@ -109,6 +109,7 @@ fn test_statedb_256_reopen() {
 		memory_budget: 1 * 1024 * 1024,
 		path,
 		partitions: vec![],
+		backend: DBBackend::RocksDB,
 	};
 
 	let db = Arc::new(DB::open(db_config.clone()).unwrap());
@@ -131,7 +132,7 @@ fn test_statedb_256_reopen() {
 	let data = vec![(DBKey::from_slice(b"abc"), Some(vec![1u8; 1024]))]
 		.into_iter()
 		.collect::<HashMap<_, _>>();
-	let (update_1_root, transaction) = statedb.prepare_update(&root, data.iter()).unwrap();
+	let (update_1_root, transaction) = statedb.prepare_update(&root, data.iter(), 1).unwrap();
 	db.write(transaction).unwrap();
 	let result = statedb.get(&update_1_root, &b"abc"[..]).unwrap();
 
@@ -141,7 +142,9 @@ fn test_statedb_256_reopen() {
 	let data = vec![(DBKey::from_slice(b"abc"), Some(vec![2u8; 1024]))]
 		.into_iter()
 		.collect::<HashMap<_, _>>();
-	let (update_2_root, transaction) = statedb.prepare_update(&update_1_root, data.iter()).unwrap();
+	let (update_2_root, transaction) = statedb
+		.prepare_update(&update_1_root, data.iter(), 2)
+		.unwrap();
 	db.write(transaction).unwrap();
 	let result = statedb.get(&update_2_root, &b"abc"[..]).unwrap();
 
@@ -167,6 +170,151 @@ fn test_statedb_256_reopen() {
 	assert_eq!(Some(vec![1u8; 1024]), result);
 }
 
+#[test]
+fn test_statedb_prune() {
+	use tempfile::tempdir;
+
+	let path = tempdir().expect("Could not create a temp dir");
+	let path = path.into_path();
+
+	let db_config = DBConfig {
+		memory_budget: 1 * 1024 * 1024,
+		path,
+		partitions: vec![],
+		backend: DBBackend::Memory,
+	};
+
+	let db = Arc::new(DB::open(db_config).unwrap());
+
+	let hasher = Arc::new(HashImpl::Blake2b256);
+
+	let statedb = StateDB::new_with_journal(
+		db.clone(),
+		node_db::columns::PAYLOAD_STATE,
+		node_db::columns::PAYLOAD_STATE_JOURNAL,
+		hasher,
+	)
+	.unwrap();
+
+	let update = |root: &[u8], data: Vec<(&[u8], Vec<u8>)>, number| {
+		let data = data
+			.into_iter()
+			.map(|(k, v)| (DBKey::from_slice(k), Some(v)))
+			.collect::<HashMap<_, _>>();
+		let (root, transaction) = statedb.prepare_update(root, data.iter(), number).unwrap();
+		db.write(transaction).unwrap();
+		root
+	};
+
+	// update 0
+	let root = statedb.default_root();
+	let update_0_root = update(
+		&root,
+		vec![
+			(&b"abc"[..], vec![1u8; 1024]),
+			(&b"abd"[..], vec![1u8; 1024]),
+		],
+		0,
+	);
+
+	// update 1
+	let update_1_root = update(&update_0_root, vec![(&b"abc"[..], vec![2u8; 1024])], 1);
+
+	// update 2
+	let update_2_root = update(&update_1_root, vec![(&b"abd"[..], vec![2u8; 1024])], 2);
+
+	// update 3: insert the value of update 0 again
+	let update_3_root = update(&update_2_root, vec![(&b"abc"[..], vec![1u8; 1024])], 3);
+
+	let node_count = db.iter(node_db::columns::PAYLOAD_STATE).unwrap().len();
+
+	assert_eq!(statedb.get_pruned_number().unwrap(), None);
+
+	// prune 0
+	statedb.prune(0).unwrap();
+	assert_eq!(statedb.get_pruned_number().unwrap(), Some(0));
+	assert!(db.iter(node_db::columns::PAYLOAD_STATE).unwrap().len() < node_count);
+
+	assert!(statedb.get(&update_0_root, &b"abc"[..]).is_err());
+	let result = statedb.get(&update_1_root, &b"abc"[..]).unwrap();
+	assert_eq!(Some(vec![2u8; 1024]), result);
+
+	// the node deleted by update 1 but inserted again by update 3 is kept
+	let result = statedb.get(&update_3_root, &b"abc"[..]).unwrap();
+	assert_eq!(Some(vec![1u8; 1024]), result);
+
+	// prune 1
+	statedb.prune(1).unwrap();
+	assert_eq!(statedb.get_pruned_number().unwrap(), Some(1));
+
+	assert!(statedb.get(&update_1_root, &b"abc"[..]).is_err());
+	let result = statedb.get(&update_2_root, &b"abd"[..]).unwrap();
+	assert_eq!(Some(vec![2u8; 1024]), result);
+	let result = statedb.get(&update_3_root, &b"abc"[..]).unwrap();
+	assert_eq!(Some(vec![1u8; 1024]), result);
+	let result = statedb.get(&update_3_root, &b"abd"[..]).unwrap();
+	assert_eq!(Some(vec![2u8; 1024]), result);
+}
+
+#[test]
+fn test_statedb_proof() {
+	use tempfile::tempdir;
+
+	let path = tempdir().expect("Could not create a temp dir");
+	let path = path.into_path();
+
+	let db_config = DBConfig {
+		memory_budget: 1 * 1024 * 1024,
+		path,
+		partitions: vec![],
+		backend: DBBackend::Memory,
+	};
+
+	let db = Arc::new(DB::open(db_config).unwrap());
+
+	let hasher = Arc::new(HashImpl::Blake2b256);
+
+	let statedb =
+		StateDB::new(db.clone(), node_db::columns::PAYLOAD_STATE, hasher.clone()).unwrap();
+
+	let data = vec![
+		(DBKey::from_slice(b"abc"), Some(vec![1u8; 1024])),
+		(DBKey::from_slice(b"abd"), Some(vec![2u8; 1024])),
+	]
+	.into_iter()
+	.collect::<HashMap<_, _>>();
+
+	let root = statedb.default_root();
+	let (root, transaction) = statedb.prepare_update(&root, data.iter(), 1).unwrap();
+	db.write(transaction).unwrap();
+
+	let verifier = ProofVerifier::new(hasher).unwrap();
+
+	// existing key
+	let proof = statedb.get_proof(&root, &b"abc"[..]).unwrap();
+	assert!(!proof.is_empty());
+	verifier
+		.verify(&root, &proof, &b"abc"[..], Some(&[1u8; 1024][..]))
+		.unwrap();
+	assert!(verifier
+		.verify(&root, &proof, &b"abc"[..], Some(&[2u8; 1024][..]))
+		.is_err());
+	assert!(verifier.verify(&root, &proof, &b"abc"[..], None).is_err());
+
+	// absent key
+	let proof = statedb.get_proof(&root, &b"abe"[..]).unwrap();
+	verifier.verify(&root, &proof, &b"abe"[..], None).unwrap();
+	assert!(verifier
+		.verify(&root, &proof, &b"abe"[..], Some(&[1u8; 1024][..]))
+		.is_err());
+
+	// mismatched root
+	let other_root = statedb.default_root();
+	assert!(verifier
+		.verify(&other_root, &proof, &b"abe"[..], None)
+		.is_err());
+}
+
 fn test_statedb_for_hasher(hasher: HashImpl) {
 	use tempfile::tempdir;
 
@@ -177,6 +325,7 @@ fn test_statedb_for_hasher(hasher: HashImpl) {
 		memory_budget: 1 * 1024 * 1024,
 		path,
 		partitions: vec![],
+		backend: DBBackend::Memory,
 	};
 
 	let db = Arc::new(DB::open(db_config).unwrap());
@@ -199,7 +348,7 @@ fn test_statedb_for_hasher(hasher: HashImpl) {
 	.into_iter()
 	.collect::<HashMap<_, _>>();
 
-	let (update_1_root, transaction) = statedb.prepare_update(&root, data.iter()).unwrap();
+	let (update_1_root, transaction) = statedb.prepare_update(&root, data.iter(), 1).unwrap();
 	db.write(transaction).unwrap();
 	let result = statedb.get(&update_1_root, &b"abc"[..]).unwrap();
 
@@ -209,7 +358,9 @@ fn test_statedb_for_hasher(hasher: HashImpl) {
 	let data = vec![(DBKey::from_slice(b"abc"), Some(vec![2u8; 1024]))]
 		.into_iter()
 		.collect::<HashMap<_, _>>();
-	let (update_2_root, transaction) = statedb.prepare_update(&update_1_root, data.iter()).unwrap();
+	let (update_2_root, transaction) = statedb
+		.prepare_update(&update_1_root, data.iter(), 2)
+		.unwrap();
 	db.write(transaction).unwrap();
 	let result = statedb.get(&update_2_root, &b"abc"[..]).unwrap();
 
@@ -223,7 +374,9 @@ fn test_statedb_for_hasher(hasher: HashImpl) {
 	let data = vec![(DBKey::from_slice(b"abc"), None)]
 		.into_iter()
 		.collect::<HashMap<_, _>>();
-	let (update_3_root, transaction) = statedb.prepare_update(&update_2_root, data.iter()).unwrap();
+	let (update_3_root, transaction) = statedb
+		.prepare_update(&update_2_root, data.iter(), 3)
+		.unwrap();
 	db.write(transaction).unwrap();
 	let result = statedb.get(&update_3_root, &b"abc"[..]).unwrap();
 
@@ -248,6 +401,25 @@ fn test_statedb_for_hasher(hasher: HashImpl) {
 
 	let result = getter.get(&b"abd"[..]).unwrap();
 	assert_eq!(Some(vec![1u8; 1024]), result);
+
+	// iterate by prefix
+	let result = getter.iter_prefix(&b"ab"[..]).unwrap();
+	assert_eq!(
+		vec![
+			(DBKey::from_slice(b"abc"), vec![2u8; 1024]),
+			(DBKey::from_slice(b"abd"), vec![1u8; 1024]),
+		],
+		result
+	);
+
+	let result = getter.iter_prefix(&b"abd"[..]).unwrap();
+	assert_eq!(vec![(DBKey::from_slice(b"abd"), vec![1u8; 1024])], result);
+
+	let result = getter.iter_prefix(&b"b"[..]).unwrap();
+	assert_eq!(Vec::<(DBKey, Vec<u8>)>::new(), result);
+
+	let result = statedb.iter_prefix(&update_3_root, &b"ab"[..]).unwrap();
+	assert_eq!(vec![(DBKey::from_slice(b"abd"), vec![1u8; 1024])], result);
 }
 
 #[test]
@@ -289,6 +461,32 @@ fn test_calc_trie_root() {
 	);
 }
 
+#[test]
+fn test_calc_ordered_trie_proof() {
+	let hasher = Arc::new(HashImpl::Blake2b256);
+
+	let trie_root = TrieRoot::new(hasher.clone()).unwrap();
+	let verifier = ProofVerifier::new(hasher).unwrap();
+
+	let input = vec!["a", "b", "c"];
+
+	let root = trie_root.calc_ordered_trie_root(&input);
+
+	let proof = trie_root.calc_ordered_trie_proof(&input, 1).unwrap();
+	let key = codec::encode(&1u32).unwrap();
+	verifier
+		.verify(&root, &proof, &key, Some(&b"b"[..]))
+		.unwrap();
+	assert!(verifier
+		.verify(&root, &proof, &key, Some(&b"c"[..]))
+		.is_err());
+
+	// out of range
+	let proof = trie_root.calc_ordered_trie_proof(&input, 3).unwrap();
+	let key = codec::encode(&3u32).unwrap();
+	verifier.verify(&root, &proof, &key, None).unwrap();
+}
+
 #[cfg(feature = "build-dep-test")]
 mod build_dep_test {
 	use super::*;
@@ -27,7 +27,7 @@ use tokio::runtime::Runtime;
 
 use crypto::address::AddressImpl;
 use crypto::dsa::DsaImpl;
-use node_chain::{module, Chain, ChainConfig, DBConfig};
+use node_chain::{module, Chain, ChainConfig, DBBackend, DBConfig, StatePruning};
 use node_txpool::support::DefaultTxPoolSupport;
 use node_txpool::{TxPool, TxPoolConfig};
 use primitives::{Address, Transaction};
@@ -56,6 +56,8 @@ fn bench_txpool_insert_txs(b: &mut Bencher, address: &Address, txs: Vec<Transact
 		black_box({
 			let config = TxPoolConfig {
 				pool_capacity: 10240,
+				sender_capacity: Some(TXS_SIZE),
+				journal: false,
 			};
 
 			let runtime = Runtime::new().unwrap();
@@ -130,9 +132,15 @@ fn get_chain(address: &Address) -> Arc<Chain> {
 		memory_budget: 128 * 1024 * 1024,
 		path: home.join("data").join("db"),
 		partitions: vec![],
+		backend: DBBackend::RocksDB,
 	};
 
-	let chain_config = ChainConfig { home, db };
+	let chain_config = ChainConfig {
+		home,
+		db,
+		pruning: StatePruning::Archive,
+		index: false,
+	};
 
 	let chain = Arc::new(Chain::new(chain_config).unwrap());
 
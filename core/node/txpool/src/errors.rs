@@ -16,6 +16,7 @@ use std::error::Error;
 use std::fmt::Debug;
 
 use primitives::errors::{CommonError, CommonErrorKind, Display};
+use primitives::Nonce;
 
 #[derive(Debug, Display)]
 pub enum ErrorKind {
@@ -31,9 +32,15 @@ pub enum InsertError {
 	#[display(fmt = "Exceed capacity: {}", _0)]
 	ExceedCapacity(usize),
 
+	#[display(fmt = "Exceed sender capacity: {}", _0)]
+	ExceedSenderCapacity(usize),
+
 	#[display(fmt = "Duplicated tx: {}", _0)]
 	DuplicatedTx(String),
 
+	#[display(fmt = "Duplicated nonce: {}", _0)]
+	DuplicatedNonce(Nonce),
+
 	#[display(fmt = "Invalid tx: {}", _0)]
 	InvalidTx(node_chain::errors::ValidateTxError),
 }
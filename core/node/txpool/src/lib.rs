@@ -19,26 +19,113 @@ use std::sync::Arc;
 
 use chashmap::CHashMap;
 use futures::channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender};
-use futures::StreamExt;
-use log::{info, trace};
+use log::{debug, info, trace};
 use parking_lot::RwLock;
 
+use primitives::codec;
 use primitives::errors::{Catchable, CommonResult};
-use primitives::{FullTransaction, Hash, Transaction};
+use primitives::{FullTransaction, Hash, Nonce, PublicKey, Transaction};
 
 use crate::errors::InsertError;
+use crate::queue::SenderQueues;
+use crate::stream::ChainStream;
 use crate::support::TxPoolSupport;
 
 pub mod errors;
+mod queue;
+mod stream;
 pub mod support;
 
+/// Max transaction count of each sender if not specified
+pub const DEFAULT_SENDER_CAPACITY: usize = 64;
+
+/// Priority of a transaction, the higher goes first
+pub type Priority = u64;
+
 pub struct TxPoolConfig {
 	/// Max transaction count
 	pub pool_capacity: usize,
+	/// Max transaction count of each sender
+	pub sender_capacity: Option<usize>,
+	/// Keep the transactions in the journal, so that they are restored after restart
+	pub journal: bool,
 }
 
+/// Summary of the pool
+#[derive(Debug, Clone, PartialEq)]
+pub struct TxPoolStatus {
+	/// Transaction count
+	pub count: usize,
+	/// Max transaction count
+	pub capacity: usize,
+	/// Total size of the encoded transactions
+	pub bytes: usize,
+}
+
+#[derive(Clone)]
 pub enum TxPoolOutMessage {
 	TxInserted { tx_hash: Hash },
+	TxRemoved { tx_hash: Hash, reason: RemoveReason },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RemoveReason {
+	/// Included in a committed block
+	Included,
+	/// Until of the witness has passed
+	Expired,
+	/// Evicted by a transaction with higher priority when the pool is full
+	Evicted,
+	/// Replaced by a transaction of the same sender and nonce with higher priority
+	Replaced,
+	/// Found invalid when building a block
+	Invalid,
+	/// Removed on request, e.g. by the admin rpc
+	Removed,
+}
+
+/// Transactions in the pool, shared with the chain stream
+pub(crate) struct Pool {
+	map: CHashMap<Hash, Arc<FullTransaction>>,
+	queue: Arc<RwLock<Vec<Arc<FullTransaction>>>>,
+	senders: RwLock<SenderQueues>,
+	message_tx: UnboundedSender<TxPoolOutMessage>,
+	message_subscribers: RwLock<Vec<UnboundedSender<TxPoolOutMessage>>>,
+}
+
+impl Pool {
+	/// Remove transactions of given set of transaction hash
+	/// return the hashes of the transactions actually removed
+	pub(crate) fn remove(&self, tx_hash_set: &HashSet<Hash>) -> HashSet<Hash> {
+		let mut senders = self.senders.write();
+		senders.remove_all(tx_hash_set);
+
+		{
+			let mut queue = self.queue.write();
+			queue.retain(|x| !tx_hash_set.contains(&x.tx_hash));
+		}
+
+		tx_hash_set
+			.iter()
+			.filter(|x| self.map.remove(x).is_some())
+			.cloned()
+			.collect()
+	}
+
+	pub(crate) fn on_tx_removed(&self, tx_hash: Hash, reason: RemoveReason) -> CommonResult<()> {
+		trace!("Tx removed: {}, reason: {:?}", tx_hash, reason);
+		self.send_message(TxPoolOutMessage::TxRemoved { tx_hash, reason })
+	}
+
+	fn send_message(&self, message: TxPoolOutMessage) -> CommonResult<()> {
+		self.message_subscribers
+			.write()
+			.retain(|x| x.unbounded_send(message.clone()).is_ok());
+		self.message_tx
+			.unbounded_send(message)
+			.map_err(|e| errors::ErrorKind::Channel(Box::new(e)))?;
+		Ok(())
+	}
 }
 
 pub struct TxPool<S>
@@ -47,10 +134,7 @@ where
 {
 	config: TxPoolConfig,
 	support: Arc<S>,
-	map: CHashMap<Hash, Arc<FullTransaction>>,
-	queue: Arc<RwLock<Vec<Arc<FullTransaction>>>>,
-	buffer_tx: UnboundedSender<Arc<FullTransaction>>,
-	message_tx: UnboundedSender<TxPoolOutMessage>,
+	pool: Arc<Pool>,
 	message_rx: RwLock<Option<UnboundedReceiver<TxPoolOutMessage>>>,
 }
 
@@ -63,82 +147,105 @@ where
 		let map = CHashMap::with_capacity(config.pool_capacity);
 		let queue = Arc::new(RwLock::new(Vec::with_capacity(config.pool_capacity)));
 
-		let (buffer_tx, buffer_rx) = unbounded();
-
 		let (message_tx, message_rx) = unbounded();
 
+		let pool = Arc::new(Pool {
+			map,
+			queue,
+			senders: RwLock::new(SenderQueues::default()),
+			message_tx,
+			message_subscribers: RwLock::new(vec![]),
+		});
+
+		let chain_rx = support.subscribe_chain_message();
+		ChainStream::spawn(chain_rx, support.clone(), pool.clone(), config.journal);
+
 		let txpool = Self {
 			config,
 			support,
-			map,
-			queue: queue.clone(),
-			buffer_tx,
-			message_tx,
+			pool,
 			message_rx: RwLock::new(Some(message_rx)),
 		};
 
-		BufferStream::spawn(buffer_rx, queue);
-
 		info!("Initializing txpool");
 
+		if txpool.config.journal {
+			txpool.restore_journal()?;
+		}
+
 		Ok(txpool)
 	}
 
 	/// Get the queue of the pool
-	/// The queue keep the transaction in the insertion order
+	/// The queue keep the transactions in the order of priority,
+	/// while the transactions of each sender keep in the order of nonce
 	pub fn get_queue(&self) -> &Arc<RwLock<Vec<Arc<FullTransaction>>>> {
-		&self.queue
+		&self.pool.queue
 	}
 
 	/// Get the map of the pool
 	/// The map is used to check if the pool already contains a transaction
 	pub fn get_map(&self) -> &CHashMap<Hash, Arc<FullTransaction>> {
-		&self.map
+		&self.pool.map
+	}
+
+	/// Get the summary of the pool
+	pub fn get_status(&self) -> CommonResult<TxPoolStatus> {
+		let senders = self.pool.senders.read();
+		Ok(TxPoolStatus {
+			count: self.pool.map.len(),
+			capacity: self.config.pool_capacity,
+			bytes: senders.bytes(),
+		})
 	}
 
 	/// Insert a transaction into the pool
+	/// If the pool is full, the transaction with the lowest priority is evicted
 	pub fn insert(&self, tx: Transaction) -> CommonResult<()> {
-		self.check_capacity()?;
 		let tx_hash = self.support.hash_transaction(&tx)?;
 
 		self.check_pool_exist(&tx_hash)?;
 		self.validate_transaction(&tx_hash, &tx)?;
 
+		let priority = self.support.get_priority(&tx)?;
+		let bytes = codec::encode(&tx)?.len();
+
+		// witness is required by validation
+		let (sender, nonce) = {
+			let witness = tx.witness.as_ref().expect("qed");
+			(witness.public_key.clone(), witness.nonce)
+		};
+
 		let pool_tx = Arc::new(FullTransaction {
 			tx,
 			tx_hash: tx_hash.clone(),
 		});
 
-		if self.map.insert(tx_hash.clone(), pool_tx.clone()).is_some() {
-			return Err(
-				errors::ErrorKind::InsertError(InsertError::DuplicatedTx(format!("{}", tx_hash)))
-					.into(),
-			);
-		}
-
-		let result = self.buffer_tx.unbounded_send(pool_tx);
+		let removed = self.insert_pool_tx(sender, nonce, pool_tx.clone(), priority, bytes)?;
 
-		if let Err(e) = result {
-			self.map.remove(&tx_hash);
-			return Err(errors::ErrorKind::Channel(Box::new(e)).into());
+		if self.config.journal {
+			let removed = removed.iter().map(|(x, _)| x.clone()).collect();
+			self.support.update_journal(&[pool_tx], &removed)?;
 		}
 
 		self.on_tx_inserted(tx_hash)?;
+		for (tx_hash, reason) in removed {
+			self.pool.on_tx_removed(tx_hash, reason)?;
+		}
 
 		Ok(())
 	}
 
 	/// Remove transactions of given set of transaction hash
-	pub fn remove(&self, tx_hash_set: &HashSet<Hash>) -> CommonResult<()> {
-		{
-			let mut queue = self.queue.write();
-			queue.retain(|x| !tx_hash_set.contains(&x.tx_hash));
+	/// TxRemoved is sent with the reason for each transaction in the pool
+	pub fn remove(&self, tx_hash_set: &HashSet<Hash>, reason: RemoveReason) -> CommonResult<()> {
+		let removed = self.pool.remove(tx_hash_set);
+		if self.config.journal {
+			self.support.update_journal(&[], tx_hash_set)?;
 		}
-
-		{
-			self.map.retain(|k, _v| !tx_hash_set.contains(k));
+		for tx_hash in removed {
+			self.pool.on_tx_removed(tx_hash, reason.clone())?;
 		}
-
 		Ok(())
 	}
 
@@ -147,13 +254,160 @@ where
 		self.message_rx.write().take()
 	}
 
-	/// Check pool capacify
-	fn check_capacity(&self) -> CommonResult<()> {
-		if self.map.len() >= self.config.pool_capacity {
-			return Err(errors::ErrorKind::InsertError(InsertError::ExceedCapacity(
+	/// Subscribe the out messages besides message_rx
+	/// the subscriber is dropped once its receiver is dropped
+	pub fn subscribe_message(&self) -> UnboundedReceiver<TxPoolOutMessage> {
+		let (tx, rx) = unbounded();
+		self.pool.message_subscribers.write().push(tx);
+		rx
+	}
+
+	/// Re-insert the transactions in the journal
+	/// the transactions no longer valid are removed from the journal
+	fn restore_journal(&self) -> CommonResult<()> {
+		let txs = self.support.get_journal_transactions()?;
+		let count = txs.len();
+
+		let mut dropped = HashSet::new();
+		for tx in txs {
+			let tx_hash = self.support.hash_transaction(&tx)?;
+			if let Err(e) = self.insert(tx) {
+				debug!("Journal tx dropped: {}, error: {}", tx_hash, e);
+				dropped.insert(tx_hash);
+			}
+		}
+		self.support.update_journal(&[], &dropped)?;
+
+		info!(
+			"Restored txs from journal: {}, dropped: {}",
+			count - dropped.len(),
+			dropped.len()
+		);
+		Ok(())
+	}
+
+	/// Insert a transaction into the sender queues
+	/// return the transactions removed by eviction or replacement
+	fn insert_pool_tx(
+		&self,
+		sender: PublicKey,
+		nonce: Nonce,
+		pool_tx: Arc<FullTransaction>,
+		priority: Priority,
+		bytes: usize,
+	) -> CommonResult<Vec<(Hash, RemoveReason)>> {
+		let mut senders = self.pool.senders.write();
+
+		// a tx of the same sender and nonce is replaced only by a higher priority one
+		let replacing = match senders.get(&sender, nonce) {
+			Some(existing) if priority > existing.priority => true,
+			Some(_) => {
+				return Err(
+					errors::ErrorKind::InsertError(InsertError::DuplicatedNonce(nonce)).into(),
+				);
+			}
+			None => false,
+		};
+
+		// the tx after the last one of the sender is inserted into the queue incrementally
+		let appending = !replacing
+			&& match senders.last_nonce(&sender) {
+				Some(last_nonce) => nonce > last_nonce,
+				None => true,
+			};
+
+		let to_evict = match replacing {
+			true => None,
+			false => {
+				self.check_sender_capacity(&senders, &sender)?;
+				self.check_capacity(&senders, &sender, priority)?
+			}
+		};
+
+		let tx_hash = pool_tx.tx_hash.clone();
+		if self
+			.pool
+			.map
+			.insert(tx_hash.clone(), pool_tx.clone())
+			.is_some()
+		{
+			return Err(
+				errors::ErrorKind::InsertError(InsertError::DuplicatedTx(format!("{}", tx_hash)))
+					.into(),
+			);
+		}
+
+		let mut removed = vec![];
+		if let Some((evict_sender, evict_nonce)) = to_evict {
+			if let Some(evicted) = senders.remove(&evict_sender, evict_nonce) {
+				self.pool.map.remove(&evicted.tx.tx_hash);
+				removed.push((evicted.tx.tx_hash.clone(), RemoveReason::Evicted));
+			}
+		}
+
+		if let Some(replaced) = senders.insert(sender.clone(), nonce, pool_tx, priority, bytes) {
+			self.pool.map.remove(&replaced.tx.tx_hash);
+			removed.push((replaced.tx.tx_hash.clone(), RemoveReason::Replaced));
+		}
+
+		let mut queue = self.pool.queue.write();
+		match appending {
+			true => {
+				if !removed.is_empty() {
+					queue.retain(|x| removed.iter().all(|(tx_hash, _)| tx_hash != &x.tx_hash));
+				}
+				senders.insert_ordered(&mut queue, &sender, nonce);
+			}
+			false => *queue = senders.ordered(),
+		}
+
+		Ok(removed)
+	}
+
+	/// Check pool capacity
+	/// return the transaction to evict if the pool is full
+	fn check_capacity(
+		&self,
+		senders: &SenderQueues,
+		sender: &PublicKey,
+		priority: Priority,
+	) -> CommonResult<Option<(PublicKey, Nonce)>> {
+		if self.pool.map.len() < self.config.pool_capacity {
+			return Ok(None);
+		}
+
+		// evict a transaction of another sender with lower priority,
+		// or with the same priority but from a sender with more transactions
+		let sender_len = senders.sender_len(sender);
+		match senders.eviction_candidate(sender) {
+			Some((evict_sender, evict_nonce, evict_tx, evict_sender_len))
+				if evict_tx.priority < priority
+					|| (evict_tx.priority == priority && evict_sender_len > sender_len + 1) =>
+			{
+				Ok(Some((evict_sender, evict_nonce)))
+			}
+			_ => Err(errors::ErrorKind::InsertError(InsertError::ExceedCapacity(
 				self.config.pool_capacity,
 			))
-			.into());
+			.into()),
+		}
+	}
+
+	/// Check transaction count of the sender
+	fn check_sender_capacity(
+		&self,
+		senders: &SenderQueues,
+		sender: &PublicKey,
+	) -> CommonResult<()> {
+		let sender_capacity = self
+			.config
+			.sender_capacity
+			.unwrap_or(DEFAULT_SENDER_CAPACITY);
+		if senders.sender_len(sender) >= sender_capacity {
+			return Err(
+				errors::ErrorKind::InsertError(InsertError::ExceedSenderCapacity(sender_capacity))
+					.into(),
+			);
 		}
 		Ok(())
 	}
@@ -171,10 +425,8 @@ where
 
 	fn on_tx_inserted(&self, tx_hash: Hash) -> CommonResult<()> {
 		trace!("Tx inserted: {}", tx_hash);
-		self.message_tx
-			.unbounded_send(TxPoolOutMessage::TxInserted { tx_hash })
-			.map_err(|e| errors::ErrorKind::Channel(Box::new(e)))?;
-		Ok(())
+		self.pool
+			.send_message(TxPoolOutMessage::TxInserted { tx_hash })
 	}
 
 	/// Validate a transaction
@@ -193,30 +445,6 @@ where
 
 	/// Check if the pool contains the transaction
 	fn contain(&self, tx_hash: &Hash) -> bool {
-		self.map.contains_key(tx_hash)
-	}
-}
-
-struct BufferStream {
-	buffer_rx: UnboundedReceiver<Arc<FullTransaction>>,
-	queue: Arc<RwLock<Vec<Arc<FullTransaction>>>>,
-}
-
-impl BufferStream {
-	fn spawn(
-		buffer_rx: UnboundedReceiver<Arc<FullTransaction>>,
-		queue: Arc<RwLock<Vec<Arc<FullTransaction>>>>,
-	) {
-		let this = Self { buffer_rx, queue };
-		tokio::spawn(this.start());
-	}
-	async fn start(mut self) {
-		loop {
-			let tx = self.buffer_rx.next().await;
-			match tx {
-				Some(tx) => self.queue.write().push(tx),
-				None => break,
-			}
-		}
+		self.pool.map.contains_key(tx_hash)
 	}
 }
@@ -0,0 +1,214 @@
+// Copyright 2019, 2020 Wingchain
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Per-sender nonce queues of the transaction pool
+
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap, HashMap, HashSet};
+use std::sync::Arc;
+
+use primitives::{FullTransaction, Hash, Nonce, PublicKey};
+
+use crate::Priority;
+
+pub struct PoolTx {
+	pub tx: Arc<FullTransaction>,
+	pub priority: Priority,
+	/// insertion sequence, the earlier inserted goes first among the same priority
+	pub seq: u64,
+	/// encoded size of the transaction
+	pub bytes: usize,
+}
+
+/// Transactions grouped by sender (public key) and ordered by nonce
+#[derive(Default)]
+pub struct SenderQueues {
+	senders: HashMap<PublicKey, BTreeMap<Nonce, PoolTx>>,
+	next_seq: u64,
+	/// total encoded size of the transactions
+	bytes: usize,
+}
+
+impl SenderQueues {
+	pub fn get(&self, sender: &PublicKey, nonce: Nonce) -> Option<&PoolTx> {
+		self.senders.get(sender).and_then(|x| x.get(&nonce))
+	}
+
+	/// Total encoded size of the transactions
+	pub fn bytes(&self) -> usize {
+		self.bytes
+	}
+
+	/// Transaction count of the sender
+	pub fn sender_len(&self, sender: &PublicKey) -> usize {
+		self.senders.get(sender).map(|x| x.len()).unwrap_or(0)
+	}
+
+	/// Max nonce of the sender
+	pub fn last_nonce(&self, sender: &PublicKey) -> Option<Nonce> {
+		self.senders
+			.get(sender)
+			.and_then(|x| x.keys().next_back().cloned())
+	}
+
+	/// Insert a transaction, return the replaced one of the same sender and nonce
+	pub fn insert(
+		&mut self,
+		sender: PublicKey,
+		nonce: Nonce,
+		tx: Arc<FullTransaction>,
+		priority: Priority,
+		bytes: usize,
+	) -> Option<PoolTx> {
+		let seq = self.next_seq;
+		self.next_seq += 1;
+		let pool_tx = PoolTx {
+			tx,
+			priority,
+			seq,
+			bytes,
+		};
+		self.bytes += bytes;
+		let replaced = self
+			.senders
+			.entry(sender)
+			.or_insert_with(BTreeMap::new)
+			.insert(nonce, pool_tx);
+		if let Some(replaced) = &replaced {
+			self.bytes -= replaced.bytes;
+		}
+		replaced
+	}
+
+	pub fn remove(&mut self, sender: &PublicKey, nonce: Nonce) -> Option<PoolTx> {
+		let queue = self.senders.get_mut(sender)?;
+		let pool_tx = queue.remove(&nonce);
+		if queue.is_empty() {
+			self.senders.remove(sender);
+		}
+		if let Some(pool_tx) = &pool_tx {
+			self.bytes -= pool_tx.bytes;
+		}
+		pool_tx
+	}
+
+	pub fn remove_all(&mut self, tx_hash_set: &HashSet<Hash>) {
+		for queue in self.senders.values_mut() {
+			let nonces = queue
+				.iter()
+				.filter(|(_, v)| tx_hash_set.contains(&v.tx.tx_hash))
+				.map(|(nonce, _)| *nonce)
+				.collect::<Vec<_>>();
+			for nonce in nonces {
+				if let Some(pool_tx) = queue.remove(&nonce) {
+					self.bytes -= pool_tx.bytes;
+				}
+			}
+		}
+		self.senders.retain(|_, v| !v.is_empty());
+	}
+
+	/// The transaction to evict when the pool is full:
+	/// the last (max nonce) transaction of a sender other than `exclude`, with the lowest priority,
+	/// then of the sender with the most transactions, then the latest inserted
+	pub fn eviction_candidate(
+		&self,
+		exclude: &PublicKey,
+	) -> Option<(PublicKey, Nonce, &PoolTx, usize)> {
+		self.senders
+			.iter()
+			.filter(|(sender, _)| *sender != exclude)
+			.filter_map(|(sender, queue)| {
+				queue
+					.iter()
+					.next_back()
+					.map(|(nonce, pool_tx)| (sender.clone(), *nonce, pool_tx, queue.len()))
+			})
+			.min_by_key(|(_, _, pool_tx, len)| {
+				(pool_tx.priority, Reverse(*len), Reverse(pool_tx.seq))
+			})
+	}
+
+	/// All the transactions ordered by priority,
+	/// while the transactions of each sender keep in the order of nonce
+	pub fn ordered(&self) -> Vec<Arc<FullTransaction>> {
+		let mut iters = self
+			.senders
+			.values()
+			.map(|queue| queue.values().peekable())
+			.collect::<Vec<_>>();
+
+		// heads of the sender queues
+		let mut heap = BinaryHeap::new();
+		for (i, iter) in iters.iter_mut().enumerate() {
+			if let Some(pool_tx) = iter.peek() {
+				heap.push((pool_tx.priority, Reverse(pool_tx.seq), i));
+			}
+		}
+
+		let mut result = Vec::new();
+		while let Some((_, _, i)) = heap.pop() {
+			let iter = &mut iters[i];
+			let pool_tx = iter.next().expect("qed");
+			result.push(pool_tx.tx.clone());
+			if let Some(pool_tx) = iter.peek() {
+				heap.push((pool_tx.priority, Reverse(pool_tx.seq), i));
+			}
+		}
+		result
+	}
+
+	/// Insert the last transaction of the sender into the ordered transactions,
+	/// at the same position as `ordered` gives, without rebuilding the whole queue
+	pub fn insert_ordered(
+		&self,
+		ordered: &mut Vec<Arc<FullTransaction>>,
+		sender: &PublicKey,
+		nonce: Nonce,
+	) {
+		let queue = match self.senders.get(sender) {
+			Some(queue) => queue,
+			None => return,
+		};
+		let pool_tx = match queue.get(&nonce) {
+			Some(pool_tx) => pool_tx,
+			None => return,
+		};
+		let key = (pool_tx.priority, Reverse(pool_tx.seq));
+
+		// the transaction goes after the previous one of the same sender,
+		// and before the first one popped later with a lower key
+		let start = queue
+			.range(..nonce)
+			.next_back()
+			.and_then(|(_, prev)| ordered.iter().position(|x| x.tx_hash == prev.tx.tx_hash))
+			.map(|i| i + 1)
+			.unwrap_or(0);
+		let index = ordered[start..]
+			.iter()
+			.position(|x| match self.get_key(x) {
+				Some(x_key) => key > x_key,
+				None => false,
+			})
+			.map(|i| start + i)
+			.unwrap_or_else(|| ordered.len());
+		ordered.insert(index, pool_tx.tx.clone());
+	}
+
+	fn get_key(&self, tx: &FullTransaction) -> Option<(Priority, Reverse<u64>)> {
+		let witness = tx.tx.witness.as_ref()?;
+		let pool_tx = self.get(&witness.public_key, witness.nonce)?;
+		Some((pool_tx.priority, Reverse(pool_tx.seq)))
+	}
+}
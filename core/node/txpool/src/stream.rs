@@ -0,0 +1,124 @@
+// Copyright 2019, 2020 Wingchain
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Prune the transaction pool on the chain messages
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use futures::channel::mpsc::UnboundedReceiver;
+use futures::StreamExt;
+use log::{debug, error};
+
+use node_chain::ChainOutMessage;
+use primitives::errors::CommonResult;
+use primitives::{BlockNumber, Hash};
+
+use crate::support::TxPoolSupport;
+use crate::{Pool, RemoveReason};
+
+pub(crate) struct ChainStream<S>
+where
+	S: TxPoolSupport,
+{
+	chain_rx: UnboundedReceiver<ChainOutMessage>,
+	support: Arc<S>,
+	pool: Arc<Pool>,
+	journal: bool,
+}
+
+impl<S> ChainStream<S>
+where
+	S: TxPoolSupport,
+{
+	pub(crate) fn spawn(
+		chain_rx: UnboundedReceiver<ChainOutMessage>,
+		support: Arc<S>,
+		pool: Arc<Pool>,
+		journal: bool,
+	) {
+		let this = Self {
+			chain_rx,
+			support,
+			pool,
+			journal,
+		};
+		tokio::spawn(this.start());
+	}
+
+	async fn start(mut self) {
+		loop {
+			let message = self.chain_rx.next().await;
+			match message {
+				Some(ChainOutMessage::BlockCommitted { number, hash }) => {
+					self.on_block_committed(number, hash)
+						.unwrap_or_else(|e| error!("TxPool handle block committed error: {}", e));
+				}
+				Some(ChainOutMessage::ExecutionCommitted { .. }) => (),
+				None => break,
+			}
+		}
+	}
+
+	/// Remove the transactions included in the block or expired
+	fn on_block_committed(&self, number: BlockNumber, block_hash: Hash) -> CommonResult<()> {
+		let included = match self.support.get_block(&block_hash)? {
+			Some(block) => block
+				.body
+				.meta_txs
+				.into_iter()
+				.chain(block.body.payload_txs.into_iter())
+				.collect::<HashSet<_>>(),
+			None => HashSet::new(),
+		};
+
+		let removed = self
+			.pool
+			.queue
+			.read()
+			.iter()
+			.filter_map(|tx| {
+				if included.contains(&tx.tx_hash) {
+					return Some((tx.tx_hash.clone(), RemoveReason::Included));
+				}
+				match &tx.tx.witness {
+					// a tx is valid only before its until
+					Some(witness) if witness.until <= number => {
+						Some((tx.tx_hash.clone(), RemoveReason::Expired))
+					}
+					_ => None,
+				}
+			})
+			.collect::<Vec<_>>();
+
+		if removed.is_empty() {
+			return Ok(());
+		}
+		debug!("TxPool pruned txs count: {}", removed.len());
+
+		let tx_hash_set = removed.iter().map(|(x, _)| x.clone()).collect();
+		// the txs may have been removed meanwhile, e.g. by the consensus
+		let actually_removed = self.pool.remove(&tx_hash_set);
+		if self.journal {
+			self.support.update_journal(&[], &tx_hash_set)?;
+		}
+
+		for (tx_hash, reason) in removed {
+			if actually_removed.contains(&tx_hash) {
+				self.pool.on_tx_removed(tx_hash, reason)?;
+			}
+		}
+		Ok(())
+	}
+}
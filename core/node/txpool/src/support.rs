@@ -12,14 +12,18 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use node_chain::Chain;
-use primitives::codec::{Decode, Encode};
+use futures::channel::mpsc::UnboundedReceiver;
+use node_chain::{Chain, ChainOutMessage};
+use primitives::codec::{self, Decode, Encode};
 use primitives::errors::CommonResult;
 use primitives::types::CallResult;
-use primitives::{Address, BlockNumber, Hash, Transaction};
+use primitives::{Address, Block, BlockNumber, FullTransaction, Hash, Transaction};
+use std::collections::HashSet;
 use std::sync::Arc;
 
-pub trait TxPoolSupport {
+use crate::Priority;
+
+pub trait TxPoolSupport: Send + Sync + 'static {
 	fn hash_transaction(&self, tx: &Transaction) -> CommonResult<Hash>;
 	fn validate_transaction(
 		&self,
@@ -28,6 +32,9 @@ pub trait TxPoolSupport {
 		witness_required: bool,
 	) -> CommonResult<()>;
 	fn get_transaction(&self, tx_hash: &Hash) -> CommonResult<Option<Transaction>>;
+	fn get_block(&self, block_hash: &Hash) -> CommonResult<Option<Block>>;
+	/// Priority of the transaction in the pool, the higher goes first
+	fn get_priority(&self, tx: &Transaction) -> CommonResult<Priority>;
 	fn execute_call_with_block_number<P: Encode, R: Decode>(
 		&self,
 		block_number: &BlockNumber,
@@ -36,6 +43,13 @@ pub trait TxPoolSupport {
 		method: String,
 		params: P,
 	) -> CommonResult<CallResult<R>>;
+	fn subscribe_chain_message(&self) -> UnboundedReceiver<ChainOutMessage>;
+	fn get_journal_transactions(&self) -> CommonResult<Vec<Transaction>>;
+	fn update_journal(
+		&self,
+		inserted: &[Arc<FullTransaction>],
+		removed: &HashSet<Hash>,
+	) -> CommonResult<()>;
 }
 
 pub struct DefaultTxPoolSupport {
@@ -64,6 +78,19 @@ impl TxPoolSupport for DefaultTxPoolSupport {
 	fn get_transaction(&self, tx_hash: &Hash) -> CommonResult<Option<Transaction>> {
 		self.chain.get_transaction(tx_hash)
 	}
+	fn get_block(&self, block_hash: &Hash) -> CommonResult<Option<Block>> {
+		self.chain.get_block(block_hash)
+	}
+	/// The fee the transaction pays, known before execution (base + per_byte * len)
+	fn get_priority(&self, tx: &Transaction) -> CommonResult<Priority> {
+		let fee = match &self.chain.get_current_state().system_meta.fee {
+			Some(fee) => fee.clone(),
+			None => return Ok(0),
+		};
+		let len = codec::encode(tx)?.len() as u64;
+		let priority = fee.base.saturating_add(fee.per_byte.saturating_mul(len));
+		Ok(priority)
+	}
 	fn execute_call_with_block_number<P: Encode, R: Decode>(
 		&self,
 		block_number: &BlockNumber,
@@ -75,4 +102,17 @@ impl TxPoolSupport for DefaultTxPoolSupport {
 		self.chain
 			.execute_call_with_block_number(block_number, sender, module, method, params)
 	}
+	fn subscribe_chain_message(&self) -> UnboundedReceiver<ChainOutMessage> {
+		self.chain.subscribe_message()
+	}
+	fn get_journal_transactions(&self) -> CommonResult<Vec<Transaction>> {
+		self.chain.get_txpool_journal()
+	}
+	fn update_journal(
+		&self,
+		inserted: &[Arc<FullTransaction>],
+		removed: &HashSet<Hash>,
+	) -> CommonResult<()> {
+		self.chain.update_txpool_journal(inserted, removed)
+	}
 }
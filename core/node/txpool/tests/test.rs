@@ -16,16 +16,18 @@ use std::fs;
 use std::path::PathBuf;
 use std::sync::Arc;
 
+use futures::StreamExt;
 use std::time::Duration;
 use tempfile::tempdir;
 
 use crypto::address::AddressImpl;
 use crypto::dsa::DsaImpl;
-use node_chain::{module, Chain, ChainConfig, DBConfig};
+use node_chain::{module, Chain, ChainConfig, DBBackend, DBConfig, StatePruning};
 use node_txpool::support::DefaultTxPoolSupport;
-use node_txpool::{TxPool, TxPoolConfig};
-use primitives::{Address, FullTransaction};
-use utils_test::test_accounts;
+use node_txpool::{RemoveReason, TxPool, TxPoolConfig, TxPoolOutMessage};
+use primitives::codec;
+use primitives::{Address, BuildBlockParams, FullTransaction, Nonce, Transaction};
+use utils_test::{test_accounts, TestAccount};
 
 #[tokio::test]
 async fn test_txpool() {
@@ -38,6 +40,8 @@ async fn test_txpool() {
 	let chain = get_chain(&account1.address);
 	let config = TxPoolConfig {
 		pool_capacity: 1024,
+		sender_capacity: None,
+		journal: false,
 	};
 	let txpool_support = Arc::new(DefaultTxPoolSupport::new(chain.clone()));
 	let txpool = TxPool::new(config, txpool_support).unwrap();
@@ -88,6 +92,8 @@ async fn test_txpool_dup() {
 	let chain = get_chain(&account1.address);
 	let config = TxPoolConfig {
 		pool_capacity: 1024,
+		sender_capacity: None,
+		journal: false,
 	};
 	let txpool_support = Arc::new(DefaultTxPoolSupport::new(chain.clone()));
 	let txpool = TxPool::new(config, txpool_support).unwrap();
@@ -125,6 +131,8 @@ async fn test_txpool_validate() {
 	let chain = get_chain(&account1.address);
 	let config = TxPoolConfig {
 		pool_capacity: 1024,
+		sender_capacity: None,
+		journal: false,
 	};
 	let txpool_support = Arc::new(DefaultTxPoolSupport::new(chain.clone()));
 	let txpool = TxPool::new(config, txpool_support).unwrap();
@@ -204,7 +212,11 @@ async fn test_txpool_capacity() {
 	let (account1, account2) = (&test_accounts[0], &test_accounts[1]);
 
 	let chain = get_chain(&account1.address);
-	let config = TxPoolConfig { pool_capacity: 2 };
+	let config = TxPoolConfig {
+		pool_capacity: 2,
+		sender_capacity: None,
+		journal: false,
+	};
 	let txpool_support = Arc::new(DefaultTxPoolSupport::new(chain.clone()));
 	let txpool = TxPool::new(config, txpool_support).unwrap();
 
@@ -262,6 +274,298 @@ async fn test_txpool_capacity() {
 	assert!(format!("{}", result.unwrap_err()).contains("Exceed capacity"));
 }
 
+#[tokio::test]
+async fn test_txpool_sender_capacity() {
+	let dsa = Arc::new(DsaImpl::Ed25519);
+	let address = Arc::new(AddressImpl::Blake2b160);
+
+	let test_accounts = test_accounts(dsa, address);
+	let (account1, account2) = (&test_accounts[0], &test_accounts[1]);
+
+	let chain = get_chain(&account1.address);
+	let config = TxPoolConfig {
+		pool_capacity: 1024,
+		sender_capacity: Some(1),
+		journal: false,
+	};
+	let txpool_support = Arc::new(DefaultTxPoolSupport::new(chain.clone()));
+	let txpool = TxPool::new(config, txpool_support).unwrap();
+
+	let tx = build_transfer_tx(&chain, account1, 0, account2);
+	let tx2 = build_transfer_tx(&chain, account1, 1, account2);
+	let tx3 = build_transfer_tx(&chain, account2, 0, account1);
+
+	txpool.insert(tx).unwrap();
+	let result = txpool.insert(tx2);
+	assert!(format!("{}", result.unwrap_err()).contains("Exceed sender capacity"));
+	txpool.insert(tx3).unwrap();
+}
+
+#[tokio::test]
+async fn test_txpool_evict() {
+	let dsa = Arc::new(DsaImpl::Ed25519);
+	let address = Arc::new(AddressImpl::Blake2b160);
+
+	let test_accounts = test_accounts(dsa, address);
+	let (account1, account2) = (&test_accounts[0], &test_accounts[1]);
+
+	let chain = get_chain(&account1.address);
+	let config = TxPoolConfig {
+		pool_capacity: 2,
+		sender_capacity: None,
+		journal: false,
+	};
+	let txpool_support = Arc::new(DefaultTxPoolSupport::new(chain.clone()));
+	let txpool = TxPool::new(config, txpool_support).unwrap();
+
+	let tx = build_transfer_tx(&chain, account1, 0, account2);
+	let tx2 = build_transfer_tx(&chain, account1, 1, account2);
+	let tx3 = build_transfer_tx(&chain, account2, 0, account1);
+	let tx_hash = chain.hash_transaction(&tx).unwrap();
+	let tx2_hash = chain.hash_transaction(&tx2).unwrap();
+	let tx3_hash = chain.hash_transaction(&tx3).unwrap();
+
+	txpool.insert(tx).unwrap();
+	txpool.insert(tx2).unwrap();
+
+	// the last tx of the sender with the most txs is evicted
+	txpool.insert(tx3).unwrap();
+
+	let queue = txpool
+		.get_queue()
+		.read()
+		.iter()
+		.map(|x| x.tx_hash.clone())
+		.collect::<Vec<_>>();
+	assert_eq!(queue, vec![tx_hash, tx3_hash]);
+	assert!(!txpool.get_map().contains_key(&tx2_hash));
+
+	// a tx of the same sender and nonce with the same priority is rejected
+	let tx4 = build_transfer_tx(&chain, account1, 0, account1);
+	let result = txpool.insert(tx4);
+	assert!(format!("{}", result.unwrap_err()).contains("Duplicated nonce"));
+}
+
+#[tokio::test]
+async fn test_txpool_order() {
+	let dsa = Arc::new(DsaImpl::Ed25519);
+	let address = Arc::new(AddressImpl::Blake2b160);
+
+	let test_accounts = test_accounts(dsa, address);
+	let (account1, account2) = (&test_accounts[0], &test_accounts[1]);
+
+	let chain = get_chain(&account1.address);
+	let config = TxPoolConfig {
+		pool_capacity: 1024,
+		sender_capacity: None,
+		journal: false,
+	};
+	let txpool_support = Arc::new(DefaultTxPoolSupport::new(chain.clone()));
+	let txpool = TxPool::new(config, txpool_support).unwrap();
+
+	let txs = vec![
+		build_transfer_tx(&chain, account1, 1, account2),
+		build_transfer_tx(&chain, account2, 0, account1),
+		build_transfer_tx(&chain, account1, 0, account2),
+		build_transfer_tx(&chain, account2, 1, account1),
+	];
+	let tx_hashes = txs
+		.iter()
+		.map(|x| chain.hash_transaction(x).unwrap())
+		.collect::<Vec<_>>();
+
+	for tx in txs {
+		txpool.insert(tx).unwrap();
+	}
+
+	// the same priority goes in the order of insertion,
+	// while the txs of each sender keep in the order of nonce
+	let queue = txpool
+		.get_queue()
+		.read()
+		.iter()
+		.map(|x| x.tx_hash.clone())
+		.collect::<Vec<_>>();
+	assert_eq!(
+		queue,
+		vec![
+			tx_hashes[1].clone(),
+			tx_hashes[2].clone(),
+			tx_hashes[0].clone(),
+			tx_hashes[3].clone()
+		]
+	);
+}
+
+#[tokio::test]
+async fn test_txpool_prune_expired() {
+	let dsa = Arc::new(DsaImpl::Ed25519);
+	let address = Arc::new(AddressImpl::Blake2b160);
+
+	let test_accounts = test_accounts(dsa, address);
+	let (account1, account2) = (&test_accounts[0], &test_accounts[1]);
+
+	let chain = get_chain(&account1.address);
+	let config = TxPoolConfig {
+		pool_capacity: 1024,
+		sender_capacity: None,
+		journal: false,
+	};
+	let txpool_support = Arc::new(DefaultTxPoolSupport::new(chain.clone()));
+	let txpool = TxPool::new(config, txpool_support).unwrap();
+	let mut message_rx = txpool.subscribe_message();
+
+	// until = 1
+	let tx = build_transfer_tx(&chain, account1, 0, account2);
+	let tx_hash = chain.hash_transaction(&tx).unwrap();
+	txpool.insert(tx).unwrap();
+
+	// commit block 1 without txs
+	let build_block_params = BuildBlockParams {
+		number: 1,
+		timestamp: 1587052962189,
+		author: None,
+		meta_txs: vec![],
+		payload_txs: vec![],
+		execution_number: 0,
+	};
+	let commit_block_params = chain.build_block(build_block_params).unwrap();
+	chain.commit_block(commit_block_params).unwrap();
+
+	loop {
+		match message_rx.next().await {
+			Some(TxPoolOutMessage::TxRemoved {
+				tx_hash: removed_tx_hash,
+				reason,
+			}) => {
+				assert_eq!(removed_tx_hash, tx_hash);
+				assert_eq!(reason, RemoveReason::Expired);
+				break;
+			}
+			Some(_) => (),
+			None => unreachable!(),
+		}
+	}
+	assert!(txpool.get_queue().read().is_empty());
+	assert!(!txpool.get_map().contains_key(&tx_hash));
+}
+
+#[tokio::test]
+async fn test_txpool_remove() {
+	let dsa = Arc::new(DsaImpl::Ed25519);
+	let address = Arc::new(AddressImpl::Blake2b160);
+
+	let test_accounts = test_accounts(dsa, address);
+	let (account1, account2) = (&test_accounts[0], &test_accounts[1]);
+
+	let chain = get_chain(&account1.address);
+	let config = TxPoolConfig {
+		pool_capacity: 1024,
+		sender_capacity: None,
+		journal: false,
+	};
+	let txpool_support = Arc::new(DefaultTxPoolSupport::new(chain.clone()));
+	let txpool = TxPool::new(config, txpool_support).unwrap();
+	let mut message_rx = txpool.subscribe_message();
+
+	let tx = build_transfer_tx(&chain, account1, 0, account2);
+	let tx_hash = chain.hash_transaction(&tx).unwrap();
+	let tx_bytes = codec::encode(&tx).unwrap().len();
+	txpool.insert(tx).unwrap();
+	assert_eq!(txpool.get_status().unwrap().bytes, tx_bytes);
+
+	let tx_hash_set = vec![tx_hash.clone()].into_iter().collect();
+	txpool.remove(&tx_hash_set, RemoveReason::Removed).unwrap();
+	assert_eq!(txpool.get_status().unwrap().bytes, 0);
+
+	loop {
+		match message_rx.next().await {
+			Some(TxPoolOutMessage::TxRemoved {
+				tx_hash: removed_tx_hash,
+				reason,
+			}) => {
+				assert_eq!(removed_tx_hash, tx_hash);
+				assert_eq!(reason, RemoveReason::Removed);
+				break;
+			}
+			Some(_) => (),
+			None => unreachable!(),
+		}
+	}
+	assert!(txpool.get_queue().read().is_empty());
+	assert!(!txpool.get_map().contains_key(&tx_hash));
+}
+
+#[tokio::test]
+async fn test_txpool_journal() {
+	let dsa = Arc::new(DsaImpl::Ed25519);
+	let address = Arc::new(AddressImpl::Blake2b160);
+
+	let test_accounts = test_accounts(dsa, address);
+	let (account1, account2) = (&test_accounts[0], &test_accounts[1]);
+
+	let chain = get_chain(&account1.address);
+	let get_txpool = || {
+		let config = TxPoolConfig {
+			pool_capacity: 1024,
+			sender_capacity: None,
+			journal: true,
+		};
+		let txpool_support = Arc::new(DefaultTxPoolSupport::new(chain.clone()));
+		TxPool::new(config, txpool_support).unwrap()
+	};
+
+	let tx = build_transfer_tx(&chain, account1, 0, account2);
+	let tx2 = build_transfer_tx(&chain, account2, 0, account1);
+	let tx_hash = chain.hash_transaction(&tx).unwrap();
+	let tx2_hash = chain.hash_transaction(&tx2).unwrap();
+
+	let txpool = get_txpool();
+	txpool.insert(tx).unwrap();
+	txpool.insert(tx2).unwrap();
+	txpool
+		.remove(
+			&vec![tx2_hash.clone()].into_iter().collect(),
+			RemoveReason::Removed,
+		)
+		.unwrap();
+	drop(txpool);
+
+	// restart
+	let txpool = get_txpool();
+	let queue = txpool
+		.get_queue()
+		.read()
+		.iter()
+		.map(|x| x.tx_hash.clone())
+		.collect::<Vec<_>>();
+	assert_eq!(queue, vec![tx_hash]);
+	assert!(!txpool.get_map().contains_key(&tx2_hash));
+}
+
+fn build_transfer_tx(
+	chain: &Arc<Chain>,
+	sender: &TestAccount,
+	nonce: Nonce,
+	recipient: &TestAccount,
+) -> Transaction {
+	chain
+		.build_transaction(
+			Some((sender.secret_key.clone(), nonce, 1)),
+			chain
+				.build_call(
+					"balance".to_string(),
+					"transfer".to_string(),
+					module::balance::TransferParams {
+						recipient: recipient.address.clone(),
+						value: 2,
+					},
+				)
+				.unwrap(),
+		)
+		.unwrap()
+}
+
 fn get_chain(address: &Address) -> Arc<Chain> {
 	let path = tempdir().expect("Could not create a temp dir");
 	let home = path.into_path();
@@ -272,8 +576,14 @@ fn get_chain(address: &Address) -> Arc<Chain> {
 		memory_budget: 1 * 1024 * 1024,
 		path: home.join("data").join("db"),
 		partitions: vec![],
+		backend: DBBackend::RocksDB,
+	};
+	let chain_config = ChainConfig {
+		home,
+		db,
+		pruning: StatePruning::Archive,
+		index: false,
 	};
-	let chain_config = ChainConfig { home, db };
 
 	let chain = Arc::new(Chain::new(chain_config).unwrap());
 
@@ -60,6 +60,7 @@ fn pre_compile(code: &[u8], config: &VMConfig) -> VMResult<Vec<u8>> {
 
 	let module = import_memory(module, config)?;
 	let module = validate_memory(module)?;
+	let module = inject_gas_metering(module)?;
 	let module = inject_stack_height_metering(module, config)?;
 	let module = validate_imports(module, config)?;
 
@@ -101,6 +102,13 @@ fn validate_memory(module: elements::Module) -> VMResult<elements::Module> {
 	}
 }
 
+fn inject_gas_metering(module: elements::Module) -> VMResult<elements::Module> {
+	let rules = pwasm_utils::rules::Set::default();
+	let module = pwasm_utils::inject_gas_counter(module, &rules)
+		.map_err(|_| PreCompileError::GasMetering)?;
+	Ok(module)
+}
+
 fn inject_stack_height_metering(
 	module: elements::Module,
 	config: &VMConfig,
@@ -58,6 +58,8 @@ pub enum ApplicationError {
 	RuntimeError(RuntimeError),
 	#[display(fmt = "ContractError: {}", _0)]
 	ContractError(ContractError),
+	#[display(fmt = "OutOfGas")]
+	OutOfGas,
 }
 
 #[derive(Debug, Clone, Display)]
@@ -70,6 +72,8 @@ pub enum PreCompileError {
 	Deserialize,
 	#[display(fmt = "InternalMemoryDeclared")]
 	InternalMemoryDeclared,
+	#[display(fmt = "GasMetering")]
+	GasMetering,
 	#[display(fmt = "StackHeightMetering")]
 	StackHeightMetering,
 	#[display(fmt = "Imports")]
@@ -114,6 +114,7 @@ pub fn import(state: &mut State, memory: Memory) -> VMResult<ImportObject> {
 		move || (state_ref.0, |_a| {}),
 		"env" => {
 			"memory" => memory,
+			"gas" => func!(gas),
 			"share_read" => func!(share_read),
 			"share_len" => func!(share_len),
 			"share_write" => func!(share_write),
@@ -146,6 +147,16 @@ pub fn import(state: &mut State, memory: Memory) -> VMResult<ImportObject> {
 	Ok(import_object)
 }
 
+fn gas(ctx: &mut Ctx, gas: u32) -> VMResult<()> {
+	let state = State::from_ctx(ctx);
+	let gas_used = state.context.gas_used_get()?.saturating_add(gas as u64);
+	state.context.gas_used_set(gas_used)?;
+	if gas_used > state.config.max_gas {
+		return Err(VMError::Application(ApplicationError::OutOfGas));
+	}
+	Ok(())
+}
+
 fn share_read(ctx: &mut Ctx, share_id: u64, ptr: u64) -> VMResult<()> {
 	let state = State::from_ctx(ctx);
 	let value = state.share_to_vec(share_id)?;
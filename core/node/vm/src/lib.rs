@@ -41,6 +41,7 @@ pub struct VMConfig {
 	pub max_share_value_len: u64,
 	pub max_share_size: u64,
 	pub max_nest_depth: u32,
+	pub max_gas: u64,
 }
 
 impl Default for VMConfig {
@@ -52,6 +53,7 @@ impl Default for VMConfig {
 			max_share_value_len: 2u64.pow(20) * 100,
 			max_share_size: 1024,
 			max_nest_depth: 8,
+			max_gas: 2u64.pow(28),
 		}
 	}
 }
@@ -169,6 +171,8 @@ pub trait VMContext {
 	fn env(&self) -> Rc<VMContextEnv>;
 	fn call_env(&self) -> Rc<VMCallEnv>;
 	fn contract_env(&self) -> Rc<VMContractEnv>;
+	fn gas_used_get(&self) -> VMResult<u64>;
+	fn gas_used_set(&self, gas_used: u64) -> VMResult<()>;
 	fn payload_get(&self, key: &[u8]) -> VMResult<Option<DBValue>>;
 	fn payload_set(&self, key: &[u8], value: Option<DBValue>) -> VMResult<()>;
 	fn payload_drain_buffer(&self) -> VMResult<Vec<(DBKey, Option<DBValue>)>>;
@@ -13,7 +13,7 @@
 // limitations under the License.
 
 use std::borrow::Cow;
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::collections::{HashMap, VecDeque};
 use std::rc::Rc;
 use std::sync::Arc;
@@ -73,8 +73,21 @@ pub fn vm_execute(
 	params: &[u8],
 	pay_value: Balance,
 ) -> VMResult<String> {
-	let hash = Arc::new(HashImpl::Blake2b256);
 	let config = VMConfig::default();
+	vm_execute_with_config(code, context, config, mode, method, params, pay_value)
+}
+
+#[allow(dead_code)]
+pub fn vm_execute_with_config(
+	code: &[u8],
+	context: &dyn VMContext,
+	config: VMConfig,
+	mode: Mode,
+	method: &str,
+	params: &[u8],
+	pay_value: Balance,
+) -> VMResult<String> {
+	let hash = Arc::new(HashImpl::Blake2b256);
 
 	let vm = VM::new(config);
 
@@ -110,6 +123,7 @@ pub fn vm_execute(
 }
 
 const SEPARATOR: &[u8] = b"_";
+const MAP_SEPARATOR: &[u8] = b"\0";
 
 pub trait ExecutorContext: Clone {
 	fn payload_get(&self, key: &[u8]) -> VMResult<Option<DBValue>>;
@@ -298,6 +312,7 @@ pub struct TestVMContext<EC: ExecutorContext> {
 	env: Rc<VMContextEnv>,
 	call_env: Rc<VMCallEnv>,
 	contract_env: Rc<VMContractEnv>,
+	gas_used: Rc<Cell<u64>>,
 	base_context: StackedExecutorContext<EC>,
 	hash: Arc<HashImpl>,
 	address: Arc<AddressImpl>,
@@ -314,11 +329,13 @@ impl<EC: ExecutorContext> TestVMContext<EC> {
 		context: EC,
 	) -> Self {
 		let base_context = StackedExecutorContext::new(context);
+		let gas_used = Rc::new(Cell::new(0));
 		Self::new_with_base_context(
 			Rc::new(config),
 			tx_hash,
 			contract_address,
 			sender_address,
+			gas_used,
 			base_context,
 		)
 	}
@@ -327,6 +344,7 @@ impl<EC: ExecutorContext> TestVMContext<EC> {
 		tx_hash: Option<Hash>,
 		contract_address: Option<Address>,
 		sender_address: Option<Address>,
+		gas_used: Rc<Cell<u64>>,
 		base_context: StackedExecutorContext<EC>,
 	) -> Self {
 		let hash = Arc::new(HashImpl::Blake2b256);
@@ -347,6 +365,7 @@ impl<EC: ExecutorContext> TestVMContext<EC> {
 				contract_address,
 				sender_address,
 			}),
+			gas_used,
 			base_context,
 			hash: hash.clone(),
 			address: address.clone(),
@@ -405,6 +424,13 @@ impl<EC: ExecutorContext> VMContext for TestVMContext<EC> {
 	fn contract_env(&self) -> Rc<VMContractEnv> {
 		self.contract_env.clone()
 	}
+	fn gas_used_get(&self) -> VMResult<u64> {
+		Ok(self.gas_used.get())
+	}
+	fn gas_used_set(&self, gas_used: u64) -> VMResult<()> {
+		self.gas_used.set(gas_used);
+		Ok(())
+	}
 	fn payload_get(&self, key: &[u8]) -> VMResult<Option<DBValue>> {
 		let key = &self.vm_to_module_key(key)?;
 		self.base_context.payload_get(key)
@@ -528,6 +554,7 @@ impl<EC: ExecutorContext> VMContext for TestVMContext<EC> {
 					self.call_env.tx_hash.clone(),
 					Some(contract_address.clone()),
 					self.contract_env.contract_address.clone(),
+					self.gas_used.clone(),
 					base_context,
 				)),
 			};
@@ -613,6 +640,12 @@ impl<EC: ExecutorContext> VMContext for ClonableTestVMContext<EC> {
 	fn contract_env(&self) -> Rc<VMContractEnv> {
 		self.inner.contract_env()
 	}
+	fn gas_used_get(&self) -> VMResult<u64> {
+		self.inner.gas_used_get()
+	}
+	fn gas_used_set(&self, gas_used: u64) -> VMResult<()> {
+		self.inner.gas_used_set(gas_used)
+	}
 	fn payload_get(&self, key: &[u8]) -> VMResult<Option<DBValue>> {
 		self.inner.payload_get(key)
 	}
@@ -763,7 +796,7 @@ fn storage_map_raw_get<V: Decode, EC: ExecutorContext>(
 	storage_name: &[u8],
 	key: &[u8],
 ) -> VMResult<Option<V>> {
-	let key = &[module_name, SEPARATOR, storage_name, SEPARATOR, &key].concat();
+	let key = &[module_name, SEPARATOR, storage_name, MAP_SEPARATOR, &key].concat();
 	let value = executor_context.payload_get(key)?;
 	let value = match value {
 		Some(value) => {
@@ -794,7 +827,7 @@ fn storage_map_raw_set<V: Encode, EC: ExecutorContext>(
 	key: &[u8],
 	value: &V,
 ) -> VMResult<()> {
-	let key = &[module_name, SEPARATOR, storage_name, SEPARATOR, &key].concat();
+	let key = &[module_name, SEPARATOR, storage_name, MAP_SEPARATOR, &key].concat();
 
 	let value = codec::encode(value)?;
 	executor_context.payload_set(key, Some(value))?;
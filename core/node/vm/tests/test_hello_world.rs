@@ -15,7 +15,7 @@
 use std::borrow::Cow;
 use std::rc::Rc;
 
-use node_vm::errors::{ContractError, PreCompileError, VMError, VMResult};
+use node_vm::errors::{ApplicationError, ContractError, PreCompileError, VMError, VMResult};
 use node_vm::{Mode, VMConfig, VMContext};
 use primitives::{Address, Balance, Hash};
 
@@ -128,6 +128,41 @@ fn test_vm_hw_hello() {
 	let result = vm_execute(&context, Mode::Call, "hello", params, 0).unwrap();
 
 	assert_eq!(result, r#""hello world""#.to_string());
+	assert!(context.gas_used_get().unwrap() > 0);
+}
+
+#[test]
+fn test_vm_hw_out_of_gas() {
+	let test_accounts = base::test_accounts();
+	let (account1, _account2) = (&test_accounts[0], &test_accounts[1]);
+
+	let contract_address = Address(vec![1]);
+
+	let config = VMConfig {
+		max_gas: 100,
+		..Default::default()
+	};
+
+	let executor_context = TestExecutorContext::new();
+	let tx_hash = Some(Hash(vec![1]));
+	let context = TestVMContext::new(
+		config.clone(),
+		tx_hash,
+		Some(contract_address),
+		Some(account1.address.clone()),
+		executor_context,
+	);
+
+	let params = r#"{"name": "world"}"#.as_bytes();
+	let code = get_code();
+	let error =
+		base::vm_execute_with_config(code, &context, config, Mode::Call, "hello", params, 0)
+			.unwrap_err();
+
+	let expected_error = VMError::Application(ApplicationError::OutOfGas);
+
+	assert_eq!(format!("{:?}", error), format!("{:?}", expected_error));
+	assert!(context.gas_used_get().unwrap() > 100);
 }
 
 #[test]
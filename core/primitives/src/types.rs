@@ -20,7 +20,7 @@ use crate::codec::{Decode, Encode};
 #[derive(Clone, Encode, Decode, PartialEq, Hash, Eq)]
 pub struct Address(pub Vec<u8>);
 
-#[derive(Clone, Debug, Encode, Decode, PartialEq)]
+#[derive(Clone, Debug, Encode, Decode, PartialEq, Hash, Eq)]
 pub struct PublicKey(pub Vec<u8>);
 
 #[derive(Clone, Debug, Encode, Decode, PartialEq)]
@@ -69,6 +69,7 @@ pub type Balance = u64;
 pub struct Header {
 	pub number: BlockNumber,
 	pub timestamp: u64,
+	pub author: Option<Address>,
 	pub parent_hash: Hash,
 	pub meta_txs_root: Hash,
 	pub meta_state_root: Hash,
@@ -121,6 +122,7 @@ pub struct Receipt {
 	pub block_number: BlockNumber,
 	pub events: Vec<Event>,
 	pub result: OpaqueCallResult,
+	pub gas_used: u64,
 }
 
 #[derive(Debug, PartialEq)]
@@ -139,6 +141,7 @@ pub struct CommitBlockParams<T> {
 pub struct BuildBlockParams {
 	pub number: BlockNumber,
 	pub timestamp: u64,
+	pub author: Option<Address>,
 	pub meta_txs: Vec<Arc<FullTransaction>>,
 	pub payload_txs: Vec<Arc<FullTransaction>>,
 	pub execution_number: BlockNumber,
@@ -157,6 +160,7 @@ pub struct CommitExecutionParams<T> {
 pub struct BuildExecutionParams {
 	pub number: BlockNumber,
 	pub timestamp: u64,
+	pub author: Option<Address>,
 	pub block_hash: Hash,
 	pub meta_state_root: Hash,
 	pub payload_state_root: Hash,